@@ -0,0 +1,20 @@
+#include <assert.h>
+#include <shogi_core.h>
+#include <stddef.h>
+
+int main(void) {
+    size_t initial = shogi_core_arena_remaining();
+    Position *p = Position_startpos();
+    assert (p != NULL);
+    assert (shogi_core_arena_remaining() < initial);
+    // The most recently allocated block is given back.
+    Position_destruct(p);
+    assert (shogi_core_arena_remaining() == initial);
+
+    p = Position_startpos();
+    Position *q = Position_startpos();
+    assert (p != NULL && q != NULL);
+    shogi_core_arena_reset();
+    assert (shogi_core_arena_remaining() == initial);
+    return 0;
+}
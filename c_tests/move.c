@@ -0,0 +1,45 @@
+#include <assert.h>
+#include <shogi_core.h>
+#include <stdio.h>
+#include <string.h>
+
+int main(void) {
+    Square sq77 = Square_from_u8_unchecked(Square_new(7, 7));
+    Square sq76 = Square_from_u8_unchecked(Square_new(7, 6));
+    CMove mv = Move_normal_c(sq77, sq76, false);
+    assert (mv.kind == Normal);
+    assert (mv.from == sq77);
+    assert (mv.to == sq76);
+    assert (!mv.promote);
+
+    assert (OptionSquare_is_some(Square_new(7, 7)));
+    assert (OptionSquare_is_none(Square_new(0, 7)));
+    assert (OptionSquare_unwrap_unchecked(Square_new(7, 7)) == sq77);
+
+    OptionCompactMove compact = CMove_to_compact_move(mv);
+    assert (OptionCompactMove_is_some(compact));
+    CMove mv2 = CMove_from_compact_move(compact);
+    assert (memcmp(&mv, &mv2, sizeof(CMove)) == 0);
+
+    mv = Move_drop_c(Piece_new(Silver, White), sq76);
+    assert (mv.kind == Drop);
+    assert (mv.piece == Piece_new(Silver, White));
+    assert (CMove_to_compact_move(mv) != 0);
+
+    mv.piece = 0;
+    assert (OptionCompactMove_is_none(CMove_to_compact_move(mv)));
+
+    mv = Move_normal_c(sq77, 82, false);
+    assert (mv.kind == 0);
+    assert (OptionCompactMove_is_none(CMove_to_compact_move(mv)));
+    mv = Move_drop_c(15, sq76);
+    assert (mv.kind == 0);
+
+    mv = Move_normal_c(sq77, sq76, false);
+    mv.kind = 3;
+    assert (OptionCompactMove_is_none(CMove_to_compact_move(mv)));
+    mv.kind = Normal;
+    mv.to = 0;
+    assert (OptionCompactMove_is_none(CMove_to_compact_move(mv)));
+    return 0;
+}
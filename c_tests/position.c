@@ -12,9 +12,41 @@ int main(void) {
 
     memset(buf, 0, sizeof(buf));
 
+    size_t written = PartialPosition_to_sfen_bounded(&pos, (uint8_t *) buf, sizeof(buf));
+    assert (written == strlen("lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1"));
+    assert (strcmp(buf, "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1") == 0);
+    assert (PartialPosition_to_sfen_bounded(&pos, (uint8_t *) buf, 10) == 0);
+
+    OptionPiece board[81];
+    PartialPosition_board(&pos, board);
+    Square sq28 = Square_from_u8_unchecked(Square_new(2, 8));
+    assert (board[Square_index(sq28) - 1] == Piece_new(Rook, Black));
+    assert (board[40] == 0); // 5e
+
+    // Set up an arbitrary position from startpos.
+    PartialPosition custom;
+    PartialPosition_startpos(&custom);
+    for (uint8_t index = 1; index <= 81; ++index) {
+        PartialPosition_piece_set_c(&custom, Square_from_u8_unchecked(index), 0);
+    }
+    PartialPosition_piece_set_c(&custom, Square_from_u8_unchecked(Square_new(1, 1)), Piece_new(King, White));
+    PartialPosition_piece_set_c(&custom, Square_from_u8_unchecked(Square_new(9, 9)), Piece_new(King, Black));
+    assert (PartialPosition_hand_set_c(&custom, Black, Gold, 2));
+    assert (!PartialPosition_hand_set_c(&custom, Black, King, 1));
+    PartialPosition_side_to_move_set(&custom, White);
+    assert (PartialPosition_ply_set(&custom, 10));
+    assert (!PartialPosition_ply_set(&custom, 0));
+    PartialPosition_to_sfen_c(&custom, (uint8_t *) buf);
+    assert (strcmp(buf, "8k/9/9/9/9/9/9/9/K8 w 2G 10") == 0);
+
+    memset(buf, 0, sizeof(buf));
+
     Position *p = Position_startpos();
     Position_to_sfen_c(p, (uint8_t *) buf);
     Position_destruct(p);
     assert (strcmp(buf, "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1") == 0);
+
+    assert (strcmp(IllegalMoveKind_message(TwoPawns), "two pawns on the same file") == 0);
+    assert (strcmp(Ki2Error_message(Ambiguous), "ambiguous move") == 0);
     return 0;
 }
@@ -0,0 +1,52 @@
+#include <cassert>
+#include <shogi_core.hpp>
+#include <string>
+#include <utility>
+
+int main() {
+    shogi::Position pos = shogi::Position::startpos();
+    assert (pos.to_sfen() == "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1");
+    assert (pos.side_to_move() == Black);
+    assert (pos.ply() == 1);
+
+    Square sq77 = Square_from_u8_unchecked(Square_new(7, 7));
+    Square sq76 = Square_from_u8_unchecked(Square_new(7, 6));
+    assert (pos.make_compact_move(CompactMove_normal(sq77, sq76, false)));
+    assert (pos.piece_at(sq76) == Piece_new(Pawn, Black));
+    assert (pos.to_sfen() == "lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL w - 2");
+
+    // Moving transfers the ownership; only `moved` calls Position_destruct.
+    shogi::Position moved = std::move(pos);
+    assert (pos.get() == nullptr);
+    assert (moved.ply() == 2);
+
+    int count = 0;
+    Square previous = 0;
+    for (Square square : moved.player_bitboard(Black)) {
+        assert (square > previous);
+        assert (moved.piece_at(square) != 0);
+        previous = square;
+        ++count;
+    }
+    assert (count == 20);
+    assert (moved.player_bitboard(Black).count() == 20);
+
+    shogi::Bitboard bb = moved.occupied_bitboard() & shogi::Bitboard::single(sq76);
+    assert (bb == shogi::Bitboard::single(sq76));
+    assert (*bb.begin() == sq76);
+    assert ((~moved.occupied_bitboard()) == moved.vacant_bitboard());
+    assert (shogi::Bitboard().begin() == shogi::Bitboard().end());
+
+    ::Position *raw = moved.release();
+    assert (moved.get() == nullptr);
+    shogi::Position owned(raw);
+    assert (owned.last_compact_move() != 0);
+
+    // Move assignment destructs the old position and leaves the source empty.
+    shogi::Position assigned = shogi::Position::startpos();
+    assigned = std::move(owned);
+    assert (owned.get() == nullptr);
+    assert (assigned.get() == raw);
+    assert (assigned.ply() == 2);
+    return 0;
+}
@@ -0,0 +1,37 @@
+#include <assert.h>
+#include <shogi_core.h>
+#include <stdio.h>
+#include <string.h>
+
+int main(void) {
+    char buf[16];
+    Square sq76 = Square_from_u8_unchecked(Square_new(7, 6));
+    assert (Square_to_usi_c(sq76, (uint8_t *) buf, sizeof(buf)) == 2);
+    assert (strcmp(buf, "7f") == 0);
+    assert (Square_to_usi_c(sq76, (uint8_t *) buf, 2) == 0);
+    assert (Square_parse_c((const uint8_t *) "7f", 2) == sq76);
+    assert (Square_parse_c((const uint8_t *) "7j", 2) == 0);
+
+    Piece piece = Piece_new(ProRook, White);
+    assert (Piece_to_usi_c(piece, (uint8_t *) buf, sizeof(buf)) == 2);
+    assert (strcmp(buf, "+r") == 0);
+    assert (Piece_parse_c((const uint8_t *) "+r", 2) == piece);
+    assert (Piece_parse_c((const uint8_t *) "+k", 2) == 0);
+
+    CMove mv;
+    const char *usi = "S*5b";
+    assert (Move_parse_c((const uint8_t *) usi, strlen(usi), White, &mv));
+    assert (mv.kind == Drop);
+    assert (mv.piece == Piece_new(Silver, White));
+    assert (Move_to_usi_c(mv, (uint8_t *) buf, sizeof(buf)) == 4);
+    assert (strcmp(buf, usi) == 0);
+
+    usi = "8h2b+";
+    assert (Move_parse_c((const uint8_t *) usi, strlen(usi), Black, &mv));
+    assert (mv.kind == Normal);
+    assert (mv.promote);
+    assert (Move_to_usi_c(mv, (uint8_t *) buf, sizeof(buf)) == 5);
+    assert (strcmp(buf, usi) == 0);
+    assert (!Move_parse_c((const uint8_t *) "8h2b=", 5, Black, &mv));
+    return 0;
+}
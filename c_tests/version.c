@@ -0,0 +1,12 @@
+#include <assert.h>
+#include <shogi_core.h>
+#include <stdint.h>
+#include <string.h>
+
+int main(void) {
+    assert (SHOGI_CORE_ABI_VERSION >= 1);
+    const char *version = shogi_core_version();
+    assert (strlen(version) >= 5);
+    assert (strchr(version, '.') != NULL);
+    return 0;
+}
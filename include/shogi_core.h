@@ -10,6 +10,21 @@
 #include <stdlib.h>
 
 
+/**
+ * The number of moves after which a game is drawn in floodgate.
+ */
+#define Game_MOVE_LIMIT_FLOODGATE 256
+
+/**
+ * A longer move limit adopted by some tournaments.
+ */
+#define Game_MOVE_LIMIT_LONG 512
+
+/**
+ * The number of moves after which a game is drawn in the World Computer Shogi Championship (WCSC).
+ */
+#define Game_MOVE_LIMIT_WCSC 320
+
 /**
  * How many elements should an array indexed by [`Color`] have?
  *
@@ -29,13 +44,47 @@
 #define PieceKind_OPTION_NUM 15
 #endif
 
+/**
+ * The size of a record in bytes.
+ */
+#define PackedSfenValue_SIZE 40
+
+/**
+ * Kinds of [`CMove`].
+ */
+enum CMoveKind
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+ {
+  /**
+   * A normal move. Corresponds to [`Move::Normal`].
+   *
+   * Discriminant = 1.
+   */
+  Normal = 1,
+  /**
+   * A drop move. Corresponds to [`Move::Drop`].
+   *
+   * Discriminant = 2.
+   */
+  Drop = 2,
+};
+#ifndef __cplusplus
+typedef uint8_t CMoveKind;
+#endif // __cplusplus
+
 /**
  * A player.
  *
  * [`Color`] and <code>[Option]<[Color]></code> are both 1-byte data types.
  * Because they are cheap to copy, they implement [`Copy`].
  */
-enum Color {
+enum Color
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+ {
   /**
    * Black, who plays first. Known as `先手` (*sente*).
    *
@@ -49,7 +98,9 @@ enum Color {
    */
   White = 2,
 };
+#ifndef __cplusplus
 typedef uint8_t Color;
+#endif // __cplusplus
 
 /**
  * How a game is resolved.
@@ -57,7 +108,11 @@ typedef uint8_t Color;
  * [`GameResolution`] and <code>[Option]<[GameResolution]></code> are both 1-byte data types.
  * Because they are cheap to copy, they implement [`Copy`].
  */
-enum GameResolution {
+enum GameResolution
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+ {
   /**
    * White's king was mated or white resigned.
    *
@@ -89,7 +144,9 @@ enum GameResolution {
    */
   Aborted = 5,
 };
+#ifndef __cplusplus
 typedef uint8_t GameResolution;
+#endif // __cplusplus
 
 /**
  * Kinds of illegal moves.
@@ -99,7 +156,11 @@ typedef uint8_t GameResolution;
  *
  * Note: the equality of sizes are not guaranteed, but assumed to be correct.
  */
-enum IllegalMoveKind {
+enum IllegalMoveKind
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+ {
   /**
    * A player has two pawns in the same file. Promoted pawns are not counted.
    *
@@ -143,7 +204,40 @@ enum IllegalMoveKind {
    */
   IncorrectMove = 7,
 };
+#ifndef __cplusplus
 typedef uint8_t IllegalMoveKind;
+#endif // __cplusplus
+
+/**
+ * Errors that can happen in resolving a move in KI2 notation.
+ */
+enum Ki2Error
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+ {
+  /**
+   * The text is not a move in KI2 notation.
+   *
+   * Discriminant = 1.
+   */
+  Syntax = 1,
+  /**
+   * No legal move matches the text.
+   *
+   * Discriminant = 2.
+   */
+  NoCandidate = 2,
+  /**
+   * Several legal moves match the text.
+   *
+   * Discriminant = 3.
+   */
+  Ambiguous = 3,
+};
+#ifndef __cplusplus
+typedef uint8_t Ki2Error;
+#endif // __cplusplus
 
 /**
  * Kinds of pieces.
@@ -151,7 +245,11 @@ typedef uint8_t IllegalMoveKind;
  * [`PieceKind`] and <code>[Option]<[PieceKind]></code> are both 1-byte data types.
  * Because they are cheap to copy, they implement [`Copy`].
  */
-enum PieceKind {
+enum PieceKind
+#ifdef __cplusplus
+  : uint8_t
+#endif // __cplusplus
+ {
   /**
    * A pawn. Unlike in chess, it always moves one square forward,
    * even if the destination square is occuipied by an enemy piece.
@@ -279,7 +377,9 @@ enum PieceKind {
    */
   ProRook = 14,
 };
+#ifndef __cplusplus
 typedef uint8_t PieceKind;
+#endif // __cplusplus
 
 #if defined(DEFINE_ALLOC)
 /**
@@ -298,6 +398,10 @@ typedef struct PartialGame PartialGame;
 #if defined(DEFINE_ALLOC)
 /**
  * A position. It provides sufficient data for legality checking.
+ *
+ * With the `shared-moves` feature, clones of a [`Position`] share their moves made so far,
+ * so cloning a [`Position`] takes constant time regardless of the number of moves.
+ * The moves are copied when a move is made in a clone whose moves are shared with another.
  */
 typedef struct Position Position;
 #endif
@@ -319,6 +423,22 @@ typedef struct Bitboard {
  * Because they are cheap to copy, they implement [`Copy`].
  */
 typedef uint8_t Square;
+/**
+ * The minimum value of [`Square::index`].
+ */
+#define Square_MIN_INDEX 1
+/**
+ * The maximum value of [`Square::index`].
+ */
+#define Square_MAX_INDEX 81
+/**
+ * The minimum value of [`Square::index0`].
+ */
+#define Square_MIN_INDEX0 0
+/**
+ * The maximum value of [`Square::index0`].
+ */
+#define Square_MAX_INDEX0 80
 /**
  * How many elements should an array indexed by [`Square`] have?
  *
@@ -344,6 +464,58 @@ typedef uint8_t Square;
  */
 typedef uint8_t OptionSquare;
 
+/**
+ * C-compatible type for <code>[Option]<[Piece]></code> with defined representations.
+ *
+ * Valid representations are `0..=14`, and `17..=30`. `0` represents [`None`], `1..=14` represents a black [`Piece`] and `17..=30` represents a white [`Piece`].
+ *
+ * cbindgen cannot deduce that <code>[Option]<[Piece]></code> can be represented by `uint8_t` in C, so we need to define the bridge type.
+ * See: <https://github.com/eqrion/cbindgen/issues/326>
+ */
+typedef uint8_t OptionPiece;
+
+/**
+ * C-compatible version of [`Move`] with named fields.
+ *
+ * Unlike [`CompactMove`], C callers can create and inspect values of this type without bit manipulation.
+ * All fields are plain integers on the C side, so C callers can set any value; invalid values are rejected by [`CMove::to_move`].
+ * Which fields are meaningful depends on `kind`:
+ * - [`CMoveKind::Normal`]: `from`, `to` and `promote` are meaningful. `piece` is `0` (`None`) if created by this crate.
+ * - [`CMoveKind::Drop`]: `piece` and `to` are meaningful. `from` is `0` (`None`) and `promote` is `false` if created by this crate.
+ *
+ * Examples:
+ * ```
+ * # use shogi_core::{Move, Piece, Square};
+ * # use shogi_core::c_compat::{CMove, CMoveKind};
+ * let mv = Move::Drop { piece: Piece::B_S, to: Square::SQ_5B };
+ * let cmove = CMove::from(mv);
+ * assert_eq!(cmove.kind, CMoveKind::Drop as u8);
+ * assert_eq!(cmove.to_move(), Some(mv));
+ * ```
+ */
+typedef struct CMove {
+  /**
+   * The kind of this move, which is the discriminant of a [`CMoveKind`].
+   */
+  uint8_t kind;
+  /**
+   * The source square. Meaningful only if `kind` is [`CMoveKind::Normal`].
+   */
+  OptionSquare from;
+  /**
+   * The destination square. Never `0` (`None`) if created by this crate.
+   */
+  OptionSquare to;
+  /**
+   * Whether this piece is promoted. Meaningful only if `kind` is [`CMoveKind::Normal`].
+   */
+  bool promote;
+  /**
+   * The piece to be placed. Meaningful only if `kind` is [`CMoveKind::Drop`].
+   */
+  OptionPiece piece;
+} CMove;
+
 /**
  * A move packed in two bytes. C-compatible version of [`Move`].
  *
@@ -355,6 +527,14 @@ typedef uint8_t OptionSquare;
  */
 typedef uint16_t CompactMove;
 
+/**
+ * C-compatible type for <code>[Option]<[CompactMove]></code>.
+ *
+ * cbindgen cannot deduce that <code>[Option]<[CompactMove]></code> can be represented by `uint16_t` in C, so we need to define the bridge type.
+ * See: <https://github.com/eqrion/cbindgen/issues/326>.
+ */
+typedef uint16_t OptionCompactMove;
+
 /**
  * A piece + who owns it.
  *
@@ -388,21 +568,6 @@ typedef uint8_t Piece;
 #define Piece_NUM 31
 #endif
 
-/**
- * <code>[Option]<[GameResolution]></code> with defined representation.
- *
- * The representation is:
- * [`None`] => `0`, <code>[Some]\(x\)</code> => `x`.
- * Therefore, valid representations of this type are precisely `0..=5`.
- *
- * This type is provided for C interoperability.
- * cbindgen cannot deduce that <code>[Option]<[GameResolution]></code> can be represented by `uint8_t` in C, so we need to define the bridge type.
- * Users of this type should convert to/from <code>[Option]<[GameResolution]></code>.
- *
- * See: <https://github.com/eqrion/cbindgen/issues/326>.
- */
-typedef uint8_t OptionGameResolution;
-
 /**
  * A hand of a single player. A hand is a multiset of unpromoted pieces (except a king).
  *
@@ -426,24 +591,6 @@ typedef struct Hand {
  */
 #define Hand_NUM_HAND_PIECES 7
 
-/**
- * C-compatible type for <code>[Option]<[Piece]></code> with defined representations.
- *
- * Valid representations are `0..=14`, and `17..=30`. `0` represents [`None`], `1..=14` represents a black [`Piece`] and `17..=30` represents a white [`Piece`].
- *
- * cbindgen cannot deduce that <code>[Option]<[Piece]></code> can be represented by `uint8_t` in C, so we need to define the bridge type.
- * See: <https://github.com/eqrion/cbindgen/issues/326>
- */
-typedef uint8_t OptionPiece;
-
-/**
- * C-compatible type for <code>[Option]<[CompactMove]></code>.
- *
- * cbindgen cannot deduce that <code>[Option]<[CompactMove]></code> can be represented by `uint16_t` in C, so we need to define the bridge type.
- * See: <https://github.com/eqrion/cbindgen/issues/326>.
- */
-typedef uint16_t OptionCompactMove;
-
 /**
  * A position with its move sequence omitted.
  *
@@ -461,8 +608,25 @@ typedef struct PartialPosition {
   struct Bitboard piece_bb[14];
   OptionCompactMove last_move;
   OptionSquare king_square[2];
+  uint8_t material[2][14];
+  uint64_t board_key;
 } PartialPosition;
 
+/**
+ * <code>[Option]<[GameResolution]></code> with defined representation.
+ *
+ * The representation is:
+ * [`None`] => `0`, <code>[Some]\(x\)</code> => `x`.
+ * Therefore, valid representations of this type are precisely `0..=5`.
+ *
+ * This type is provided for C interoperability.
+ * cbindgen cannot deduce that <code>[Option]<[GameResolution]></code> can be represented by `uint8_t` in C, so we need to define the bridge type.
+ * Users of this type should convert to/from <code>[Option]<[GameResolution]></code>.
+ *
+ * See: <https://github.com/eqrion/cbindgen/issues/326>.
+ */
+typedef uint8_t OptionGameResolution;
+
 /**
  * <code>[Option]<[PieceKind]></code> with defined representation.
  *
@@ -478,6 +642,51 @@ typedef struct PartialPosition {
  */
 typedef uint8_t OptionPieceKind;
 
+/**
+ * A position packed in 256 bits, in the format of YaneuraOu's `PackedSfen`.
+ *
+ * The side to move, the squares of the kings, the pieces on the other squares and the pieces in hand are
+ * written in this order with Huffman codes. The ply is not recorded.
+ * Because the codes are assigned so that all 40 pieces take exactly 256 bits,
+ * only positions with all 40 pieces (on the board or in hand) can be packed.
+ *
+ * Examples:
+ * ```
+ * # use shogi_core::{PackedSfen, PartialPosition};
+ * let position = PartialPosition::startpos();
+ * let packed = PackedSfen::from_position(&position).unwrap();
+ * assert_eq!(packed.to_position(), Some(position));
+ * ```
+ *
+ * Since: 0.1.6
+ */
+typedef struct PackedSfen {
+  uint8_t _0[32];
+} PackedSfen;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * The version of the C ABI of this library.
+ *
+ * It is incremented whenever a change that breaks existing C callers is made,
+ * e.g. a change of the layout of an exported type or the signature of an exported function.
+ * Consumers that load this library dynamically should check it before calling other functions.
+ */
+extern const uint32_t SHOGI_CORE_ABI_VERSION;
+
+/**
+ * C interface of [`Bitboard::behind`].
+ */
+struct Bitboard Bitboard_behind(Color color, uint8_t rank);
+
+/**
+ * C interface to [`Bitboard::bishop_pseudo_attacks`].
+ */
+struct Bitboard Bitboard_bishop_pseudo_attacks(Square square);
+
 struct Bitboard Bitboard_bitand(struct Bitboard a, struct Bitboard b);
 
 void Bitboard_bitand_assign(struct Bitboard *a, struct Bitboard b);
@@ -521,6 +730,11 @@ uint8_t Bitboard_count(struct Bitboard self);
  */
 struct Bitboard Bitboard_empty(void);
 
+/**
+ * C interface of [`Bitboard::files_occupied`].
+ */
+uint16_t Bitboard_files_occupied(struct Bitboard self);
+
 /**
  * Finds the flipped version of `self`.
  *
@@ -534,6 +748,11 @@ struct Bitboard Bitboard_empty(void);
  */
 struct Bitboard Bitboard_flip(struct Bitboard self);
 
+/**
+ * C interface of [`Bitboard::front_of`].
+ */
+struct Bitboard Bitboard_front_of(Color color, uint8_t rank);
+
 /**
  * Checks if `self` is an empty set.
  *
@@ -550,6 +769,11 @@ struct Bitboard Bitboard_flip(struct Bitboard self);
  */
 bool Bitboard_is_empty(struct Bitboard self);
 
+/**
+ * C interface to [`Bitboard::lance_pseudo_attacks`].
+ */
+struct Bitboard Bitboard_lance_pseudo_attacks(Color color, Square square);
+
 /**
  * C interface of `Bitboard::not`.
  */
@@ -560,11 +784,41 @@ struct Bitboard Bitboard_not(struct Bitboard a);
  */
 OptionSquare Bitboard_pop(struct Bitboard *self);
 
+/**
+ * C interface of [`Bitboard::ranks_occupied`].
+ */
+uint16_t Bitboard_ranks_occupied(struct Bitboard self);
+
+/**
+ * C interface of [`Bitboard::rect`]. The bounds are inclusive.
+ */
+struct Bitboard Bitboard_rect(uint8_t file_from,
+                              uint8_t file_to,
+                              uint8_t rank_from,
+                              uint8_t rank_to);
+
+/**
+ * C interface to [`Bitboard::rook_pseudo_attacks`].
+ */
+struct Bitboard Bitboard_rook_pseudo_attacks(Square square);
+
 /**
  * C interface to [`Bitboard::single`].
  */
 struct Bitboard Bitboard_single(Square square);
 
+/**
+ * Converts a [`CompactMove`] to a [`CMove`].
+ */
+struct CMove CMove_from_compact_move(CompactMove mv);
+
+/**
+ * Converts a [`CMove`] to a [`CompactMove`].
+ *
+ * If `self` does not represent a valid move (see [`CMove::to_move`]), this function returns `0` (`None`).
+ */
+OptionCompactMove CMove_to_compact_move(struct CMove self);
+
 /**
  * Flips the player.
  *
@@ -578,18 +832,9 @@ struct Bitboard Bitboard_single(Square square);
 Color Color_flip(Color self);
 
 /**
- * Creates a drop move.
- *
- * Examples:
- * ```
- * # use shogi_core::{Color, CompactMove, Move, Piece, PieceKind, Square};
- * let piece = Piece::W_G;
- * let to = Square::SQ_3D;
- * assert_eq!(<CompactMove as From<Move>>::from(Move::Drop { piece, to }), CompactMove::drop(piece, to));
- * ```
+ * C interface to [`CompactMove::drop`].
  */
-CompactMove CompactMove_drop(Piece piece,
-                             Square to);
+CompactMove CompactMove_drop(Piece piece, Square to);
 
 /**
  * C interface of [`CompactMove::from`].
@@ -607,20 +852,14 @@ bool CompactMove_is_drop(CompactMove self);
 bool CompactMove_is_promoting(CompactMove self);
 
 /**
- * Creates a normal move.
- *
- * Examples:
- * ```
- * # use shogi_core::{CompactMove, Move, Square};
- * let from = Square::SQ_1B;
- * let to = Square::SQ_3D;
- * let promote = false;
- * assert_eq!(<CompactMove as From<Move>>::from(Move::Normal { from, to, promote }), CompactMove::normal(from, to, promote));
- * ```
+ * C interface to [`CompactMove::normal`].
+ */
+CompactMove CompactMove_normal(Square from, Square to, bool promote);
+
+/**
+ * C interface of [`CompactMove::ordering_key`].
  */
-CompactMove CompactMove_normal(Square from,
-                               Square to,
-                               bool promote);
+uint16_t CompactMove_ordering_key(CompactMove self, const struct PartialPosition *position);
 
 /**
  * Finds the `to` square.
@@ -648,6 +887,8 @@ OptionGameResolution Game_resolution(const struct Game *self);
 
 /**
  * Sets the resolution of this game.
+ *
+ * The [`ResolutionDetail`] of this game is cleared.
  */
 void Game_resolve(struct Game *self, GameResolution resolution);
 
@@ -673,16 +914,24 @@ uint8_t Hand_count(struct Hand self, PieceKind piece_kind);
 bool Hand_is_hand_piece(PieceKind piece_kind);
 
 /**
- * Creates an empty instance of [`Hand`].
- *
- * Examples:
- * ```
- * use shogi_core::Hand;
- * assert_eq!(Hand::new(), Hand::default());
- * ```
+ * C interface of [`Hand::new`].
  */
 struct Hand Hand_new(void);
 
+/**
+ * C interface of [`Hand::parse_sfen_hands`].
+ * The hand field is read from `len` bytes starting at `ptr`, which need not be nul-terminated.
+ *
+ * If the bytes are a valid hand field, this function writes the hands of black and white to `out` and returns `true`.
+ * Otherwise, this function returns `false` and `out` is left untouched.
+ *
+ * # Safety
+ * `ptr` must be null or valid for reads of `len` bytes. `out` must be valid for writes of two [`Hand`]s.
+ */
+bool Hand_parse_sfen_hands_c(const uint8_t *ptr,
+                             size_t len,
+                             struct Hand *out);
+
 /**
  * C interface of [`Hand::removed`].
  *
@@ -700,87 +949,457 @@ bool Hand_remove(struct Hand *self, PieceKind piece_kind);
 IllegalMoveKind IllegalMoveKind_from_u8_unchecked(uint8_t repr);
 
 /**
- * Returns the inner position.
+ * C interface of [`IllegalMoveKind::description`].
+ *
+ * This function returns a pointer to a static nul-terminated string in UTF-8, which must not be freed.
+ *
+ * Since: 0.1.6
  */
-const struct PartialPosition *PartialGame_position(const struct PartialGame *self);
+const char *IllegalMoveKind_message(IllegalMoveKind self);
 
 /**
- * C interface to [`PartialGame::resolution`].
+ * C interface of [`IllegalMoveKind::description_ja`].
+ *
+ * This function returns a pointer to a static nul-terminated string in UTF-8, which must not be freed.
+ *
+ * Since: 0.1.6
  */
-OptionGameResolution PartialGame_resolution(const struct PartialGame *self);
+const char *IllegalMoveKind_message_ja(IllegalMoveKind self);
 
 /**
- * Sets the resolution of this game.
+ * C interface of [`Ki2Error::description`].
+ *
+ * This function returns a pointer to a static nul-terminated string in UTF-8, which must not be freed.
  */
-void PartialGame_resolve(struct PartialGame *self, GameResolution resolution);
+const char *Ki2Error_message(Ki2Error self);
 
 /**
- * Unsets the resolution of this game.
+ * C interface for creating a drop move.
+ *
+ * `piece` and `to` are the representations of a piece and a square (see [`Piece::as_u8`] and [`Square::index`]).
+ * If either of them is not valid, this function returns a [`CMove`] whose fields are all zero,
+ * which [`CMove::to_move`] rejects.
  */
-void PartialGame_unresolve(struct PartialGame *self);
+struct CMove Move_drop_c(uint8_t piece,
+                         uint8_t to);
 
 /**
- * Returns the [`Hand`] of a player.
+ * C interface for creating a normal move.
+ *
+ * `from` and `to` are the representations of squares (see [`Square::index`]).
+ * If either of them is not a valid square, this function returns a [`CMove`] whose fields are all zero,
+ * which [`CMove::to_move`] rejects.
  */
-struct Hand PartialPosition_hand_of_a_player(const struct PartialPosition *self, Color color);
+struct CMove Move_normal_c(uint8_t from,
+                           uint8_t to,
+                           bool promote);
 
 /**
- * C interface to [`PartialPosition::last_compact_move`].
+ * Parses a move in USI format (e.g. `7g7f`, `8h2b+`, `S*5b`) from `len` bytes starting at `ptr`.
+ * The bytes need not be nul-terminated.
+ * `side` is the player who makes the move, which determines the color of a dropped piece.
+ *
+ * If the bytes are a valid move, this function writes it to `out` and returns `true`.
+ * Otherwise, this function returns `false` and `out` is left untouched.
+ *
+ * # Safety
+ * `ptr` must be null or valid for reads of `len` bytes. `out` must be valid for writes.
  */
-OptionCompactMove PartialPosition_last_compact_move(const struct PartialPosition *self);
+bool Move_parse_c(const uint8_t *ptr, size_t len, Color side, struct CMove *out);
 
 /**
- * Makes a move. This function is a C-compatible counterpart of `make_move`.
- * Note that this function will never check legality.
+ * C interface of [`ToUsi::to_usi`].
+ * Writes the USI representation of `mv` to `ptr` followed by a nul byte.
  *
- * Returns true if the given move makes sense, i.e.,
- * moves a piece to another square or drops a piece on a vacant square.
+ * Returns the number of bytes written excluding the nul byte.
+ * If `capacity` is not large enough or `mv` is not a valid move (see [`CMove::to_move`]), this function returns 0.
+ * A capacity of 6 is always sufficient.
  *
- * If it returns false, it is guaranteed that self is not modified.
+ * # Safety
+ * `ptr` must be valid for writes of `capacity` bytes.
  */
-bool PartialPosition_make_compact_move(struct PartialPosition *self, CompactMove mv);
+size_t Move_to_usi_c(struct CMove mv,
+                     uint8_t *ptr,
+                     size_t capacity);
 
 /**
- * Finds the subset of squares with a piece.
+ * Returns true if and only if `self` is the representation of [`None`].
  *
- * Since: 0.1.4
+ * Since: 0.1.6
  */
-struct Bitboard PartialPosition_occupied_bitboard(const struct PartialPosition *self);
+bool OptionCompactMove_is_none(OptionCompactMove self);
 
 /**
- * C interface to [`PartialPosition::piece_at`].
+ * Returns true if and only if `self` holds a move, i.e., `self` is not the representation of [`None`].
+ *
+ * Since: 0.1.6
  */
-OptionPiece PartialPosition_piece_at(const struct PartialPosition *self, Square square);
+bool OptionCompactMove_is_some(OptionCompactMove self);
 
 /**
- * Finds the subset of squares where a piece is placed.
+ * Returns the [`CompactMove`] held by `self` without checking.
  *
- * Examples:
- * ```
- * # use shogi_core::{Bitboard, Color, PartialPosition, Piece, PieceKind, Square};
- * let pos = PartialPosition::startpos();
- * let black_rook = pos.piece_bitboard(Piece::B_R);
- * assert_eq!(black_rook, Bitboard::single(Square::SQ_2H));
- * let white_rook = pos.piece_bitboard(Piece::W_R);
- * assert_eq!(white_rook, Bitboard::single(Square::SQ_8B));
- * ```
+ * # Safety
+ * `self` must hold a move, i.e., [`OptionCompactMove::OptionCompactMove_is_some`] must return true.
+ *
+ * Since: 0.1.6
  */
-struct Bitboard PartialPosition_piece_bitboard(const struct PartialPosition *self, Piece piece);
+CompactMove OptionCompactMove_unwrap_unchecked(OptionCompactMove self);
 
 /**
- * Finds the subset of squares where a [`PieceKind`] is placed.
+ * Returns true if and only if `self` is the representation of [`None`].
  *
- * Examples:
- * ```
- * # use shogi_core::{Bitboard, Color, PartialPosition, PieceKind, Square};
- * let pos = PartialPosition::startpos();
- * let rooks = pos.piece_kind_bitboard(PieceKind::Rook);
- * assert_eq!(rooks, Bitboard::single(Square::SQ_2H) | Bitboard::single(Square::SQ_8B));
- * ```
+ * Since: 0.1.6
+ */
+bool OptionGameResolution_is_none(OptionGameResolution self);
+
+/**
+ * Returns true if and only if `self` holds a resolution, i.e., `self` is not the representation of [`None`].
+ *
+ * Since: 0.1.6
+ */
+bool OptionGameResolution_is_some(OptionGameResolution self);
+
+/**
+ * Returns the [`GameResolution`] held by `self` without checking.
+ *
+ * # Safety
+ * `self` must hold a resolution, i.e., [`OptionGameResolution::OptionGameResolution_is_some`] must return true.
+ *
+ * Since: 0.1.6
+ */
+GameResolution OptionGameResolution_unwrap_unchecked(OptionGameResolution self);
+
+/**
+ * Returns true if and only if `self` is the representation of [`None`].
+ *
+ * Since: 0.1.6
+ */
+bool OptionPieceKind_is_none(OptionPieceKind self);
+
+/**
+ * Returns true if and only if `self` holds a piece kind, i.e., `self` is not the representation of [`None`].
+ *
+ * Since: 0.1.6
+ */
+bool OptionPieceKind_is_some(OptionPieceKind self);
+
+/**
+ * Returns the [`PieceKind`] held by `self` without checking.
+ *
+ * # Safety
+ * `self` must hold a piece kind, i.e., [`OptionPieceKind::OptionPieceKind_is_some`] must return true.
+ *
+ * Since: 0.1.6
+ */
+PieceKind OptionPieceKind_unwrap_unchecked(OptionPieceKind self);
+
+/**
+ * Returns true if and only if `self` is the representation of [`None`].
+ *
+ * Since: 0.1.6
+ */
+bool OptionPiece_is_none(OptionPiece self);
+
+/**
+ * Returns true if and only if `self` holds a piece, i.e., `self` is not the representation of [`None`].
+ *
+ * Since: 0.1.6
+ */
+bool OptionPiece_is_some(OptionPiece self);
+
+/**
+ * Returns the [`Piece`] held by `self` without checking.
+ *
+ * # Safety
+ * `self` must hold a piece, i.e., [`OptionPiece::OptionPiece_is_some`] must return true.
+ *
+ * Since: 0.1.6
+ */
+Piece OptionPiece_unwrap_unchecked(OptionPiece self);
+
+/**
+ * Returns true if and only if `self` is the representation of [`None`].
+ *
+ * Since: 0.1.6
+ */
+bool OptionSquare_is_none(OptionSquare self);
+
+/**
+ * Returns true if and only if `self` holds a square, i.e., `self` is not the representation of [`None`].
+ *
+ * Since: 0.1.6
+ */
+bool OptionSquare_is_some(OptionSquare self);
+
+/**
+ * Returns the [`Square`] held by `self` without checking.
+ *
+ * # Safety
+ * `self` must hold a square, i.e., [`OptionSquare::OptionSquare_is_some`] must return true.
+ *
+ * Since: 0.1.6
+ */
+Square OptionSquare_unwrap_unchecked(OptionSquare self);
+
+/**
+ * C interface of [`PackedSfen::from_position`]. Returns `false` if `position` cannot be packed.
+ *
+ * # Safety
+ * `out` must be valid for writes of a [`PackedSfen`].
+ */
+bool PackedSfen_from_position(const struct PartialPosition *position, struct PackedSfen *out);
+
+/**
+ * Returns the inner position.
+ */
+const struct PartialPosition *PartialGame_position(const struct PartialGame *self);
+
+/**
+ * C interface to [`PartialGame::resolution`].
+ */
+OptionGameResolution PartialGame_resolution(const struct PartialGame *self);
+
+/**
+ * Sets the resolution of this game.
+ */
+void PartialGame_resolve(struct PartialGame *self, GameResolution resolution);
+
+/**
+ * Unsets the resolution of this game.
+ */
+void PartialGame_unresolve(struct PartialGame *self);
+
+/**
+ * Writes the pieces on all squares to `out`. `out[square.array_index()]` is the piece on `square`.
+ *
+ * This function is provided so that C callers can find the whole board in one call.
+ *
+ * # Safety
+ * `out` must be valid for writes of 81 elements.
+ *
+ * Examples:
+ * ```
+ * # use shogi_core::{PartialPosition, Piece, Square};
+ * # use shogi_core::c_compat::OptionPiece;
+ * let pos = PartialPosition::startpos();
+ * let mut board = [OptionPiece::from(None); 81];
+ * unsafe { PartialPosition::PartialPosition_board(&pos, board.as_mut_ptr()) };
+ * assert_eq!(Option::<Piece>::from(board[Square::SQ_2H.array_index()]), Some(Piece::B_R));
+ * assert_eq!(Option::<Piece>::from(board[Square::SQ_5E.array_index()]), None);
+ * ```
+ */
+void PartialPosition_board(const struct PartialPosition *self, OptionPiece *out);
+
+/**
+ * Finds the squares where `piece` may be dropped, ignoring checks:
+ * vacant squares, except those from which `piece` could never move
+ * and, for a pawn, those in files that already have an unpromoted pawn of the same color (二歩, *nifu*).
+ *
+ * This function does not check whether `piece` is in hand or whose turn it is,
+ * so that legality checkers and GUIs can refine the result as they need.
+ * Returns an empty [`Bitboard`] if `piece` cannot be in hand (e.g. a king or a promoted piece).
+ *
+ * Examples:
+ * ```
+ * # use shogi_core::{PartialPosition, Piece, Square};
+ * let position = PartialPosition::startpos();
+ * // every file has a pawn
+ * assert!(position.drop_mask(Piece::B_P).is_empty());
+ * // 27 squares on ranks 4 to 6 and 7 on rank 8; a black knight cannot be dropped on ranks 1 and 2
+ * assert_eq!(position.drop_mask(Piece::B_N).count(), 34);
+ * assert_eq!(position.drop_mask(Piece::W_G).count(), 41);
+ * assert!(position.drop_mask(Piece::W_G).contains(Square::SQ_1B));
+ * assert!(position.drop_mask(Piece::B_PR).is_empty());
+ * ```
+ *
+ * Since: 0.1.6
+ */
+struct Bitboard PartialPosition_drop_mask(const struct PartialPosition *self,
+                                          Piece piece);
+
+/**
+ * Finds the destination squares of moves that may get the king of the side to move out of check:
+ * for non-king pieces, the square of the checker and the squares between it and the king,
+ * and for the king, adjacent squares that are neither occupied by its own pieces nor attacked by the opponent.
+ *
+ * If two pieces give check, only the squares for the king are returned, because blocking or capturing cannot help.
+ * Returns an empty [`Bitboard`] if the side to move is not in check or has no king.
+ *
+ * Squares are checked as if the king had already left its square,
+ * so that the king cannot flee along the line of a sliding checker.
+ * Pins and drop-pawn-mates (打ち歩詰め, *uchifu-zume*) are not taken into account;
+ * this function is intended as a building block for generating evasions.
+ *
+ * Examples:
+ * ```
+ * # use shogi_core::{Bitboard, PartialPosition, Square};
+ * let position = PartialPosition::from_sfen("4k4/9/9/9/9/9/9/4G4/r3K4 b - 1").unwrap();
+ * let targets = position.evasion_targets();
+ * // capture or block the rook on 9i
+ * assert!(targets.contains(Square::SQ_9I));
+ * assert!(targets.contains(Square::SQ_6I));
+ * // 4i is still on the rook's line once the king leaves 5i
+ * assert!(!targets.contains(Square::SQ_4I));
+ * assert!(targets.contains(Square::SQ_4H));
+ * // the gold is on 5h
+ * assert!(!targets.contains(Square::SQ_5H));
+ * assert!(PartialPosition::startpos().evasion_targets().is_empty());
+ * ```
+ *
+ * Since: 0.1.6
+ */
+struct Bitboard PartialPosition_evasion_targets(const struct PartialPosition *self);
+
+/**
+ * Returns the [`Hand`] of a player.
+ */
+struct Hand PartialPosition_hand_of_a_player(const struct PartialPosition *self, Color color);
+
+/**
+ * Sets how many pieces of `piece_kind` `color` has in hand. Returns whether this operation was successful.
+ * This operation succeeds iff `piece_kind` is a valid piece in hand.
+ *
+ * This function is provided so that C callers can set up arbitrary positions.
+ * It makes no guarantee about the consistency of the position.
+ *
+ * Since: 0.1.6
+ */
+bool PartialPosition_hand_set_c(struct PartialPosition *self,
+                                Color color,
+                                PieceKind piece_kind,
+                                uint8_t count);
+
+/**
+ * Finds the squares within `radius` files and ranks of `color`'s king, clipped to the board.
+ * The square of the king is included, so a radius of 1 gives the king and its neighbors.
+ *
+ * Returns an empty [`Bitboard`] if `color` has no king.
+ *
+ * Examples:
+ * ```
+ * # use shogi_core::{Bitboard, Color, PartialPosition, Square};
+ * let position = PartialPosition::startpos();
+ * let zone = position.king_zone(Color::Black, 1);
+ * assert_eq!(zone, Bitboard::rect(4..=6, 8..=9));
+ * assert_eq!(position.king_zone(Color::White, 2).count(), 15);
+ * assert_eq!(position.king_zone(Color::White, 0), Bitboard::single(Square::SQ_5A));
+ * assert_eq!(position.king_zone(Color::White, u8::MAX).count(), 81);
+ * assert!(PartialPosition::empty().king_zone(Color::Black, 1).is_empty());
+ * ```
+ *
+ * Since: 0.1.6
+ */
+struct Bitboard PartialPosition_king_zone(const struct PartialPosition *self,
+                                          Color color,
+                                          uint8_t radius);
+
+/**
+ * C interface to [`PartialPosition::last_compact_move`].
+ */
+OptionCompactMove PartialPosition_last_compact_move(const struct PartialPosition *self);
+
+/**
+ * Makes a move. This function is a C-compatible counterpart of `make_move`.
+ * Note that this function will never check legality.
+ *
+ * Returns true if the given move makes sense, i.e.,
+ * moves a piece to another square or drops a piece on a vacant square.
+ *
+ * If it returns false, it is guaranteed that self is not modified.
+ */
+bool PartialPosition_make_compact_move(struct PartialPosition *self, CompactMove mv);
+
+/**
+ * Makes a null move (pass), which is used in null move pruning. Note that null moves are illegal in shogi.
+ *
+ * The side to move is flipped and the ply is incremented, so that the SFEN of the resulting position makes sense.
+ * The last move is cleared, because a null move is not a [`Move`].
+ * To undo a null move, pass the last move before it to [`PartialPosition::unmake_null_move`].
+ *
+ * Returns `false` if the ply would overflow. If it returns `false`, it is guaranteed that `self` is not modified.
+ *
+ * Examples:
+ * ```
+ * # use shogi_core::{Move, PartialPosition, Square};
+ * let mut pos = PartialPosition::startpos();
+ * pos.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+ * let last_move = pos.last_compact_move();
+ * assert!(pos.make_null_move());
+ * assert_eq!(pos.to_sfen_owned(), "lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL b - 3");
+ * assert_eq!(pos.last_move(), None);
+ * assert!(pos.unmake_null_move(last_move));
+ * assert_eq!(pos.to_sfen_owned(), "lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL w - 2");
+ * assert_eq!(pos.last_compact_move(), last_move);
+ * ```
+ *
+ * Since: 0.1.6
+ */
+bool PartialPosition_make_null_move(struct PartialPosition *self);
+
+/**
+ * C interface of [`PartialPosition::move_from_csa_str`].
+ * The move is read from `len` bytes starting at `ptr`, which need not be nul-terminated.
+ *
+ * If the bytes are a valid move, this function writes it to `out` and returns `true`.
+ * Otherwise, this function returns `false` and `out` is left untouched.
+ *
+ * # Safety
+ * `ptr` must be null or valid for reads of `len` bytes. `out` must be valid for writes.
+ */
+bool PartialPosition_move_from_csa_c(const struct PartialPosition *self,
+                                     const uint8_t *ptr,
+                                     size_t len,
+                                     struct CMove *out);
+
+/**
+ * Finds the subset of squares with a piece.
+ *
+ * Since: 0.1.4
+ */
+struct Bitboard PartialPosition_occupied_bitboard(const struct PartialPosition *self);
+
+/**
+ * C interface to [`PartialPosition::piece_at`].
+ */
+OptionPiece PartialPosition_piece_at(const struct PartialPosition *self, Square square);
+
+/**
+ * Finds the subset of squares where a piece is placed.
+ *
+ * Examples:
+ * ```
+ * # use shogi_core::{Bitboard, Color, PartialPosition, Piece, PieceKind, Square};
+ * let pos = PartialPosition::startpos();
+ * let black_rook = pos.piece_bitboard(Piece::B_R);
+ * assert_eq!(black_rook, Bitboard::single(Square::SQ_2H));
+ * let white_rook = pos.piece_bitboard(Piece::W_R);
+ * assert_eq!(white_rook, Bitboard::single(Square::SQ_8B));
+ * ```
+ */
+struct Bitboard PartialPosition_piece_bitboard(const struct PartialPosition *self, Piece piece);
+
+/**
+ * Finds the subset of squares where a [`PieceKind`] is placed.
+ *
+ * Examples:
+ * ```
+ * # use shogi_core::{Bitboard, Color, PartialPosition, PieceKind, Square};
+ * let pos = PartialPosition::startpos();
+ * let rooks = pos.piece_kind_bitboard(PieceKind::Rook);
+ * assert_eq!(rooks, Bitboard::single(Square::SQ_2H) | Bitboard::single(Square::SQ_8B));
+ * ```
  */
 struct Bitboard PartialPosition_piece_kind_bitboard(const struct PartialPosition *self,
                                                     PieceKind piece_kind);
 
+/**
+ * C interface to [`PartialPosition::piece_set`].
+ *
+ * Since: 0.1.6
+ */
+void PartialPosition_piece_set_c(struct PartialPosition *self, Square square, OptionPiece piece);
+
 /**
  * Finds the subset of squares where a piece of the specified player is placed.
  */
@@ -791,6 +1410,23 @@ struct Bitboard PartialPosition_player_bitboard(const struct PartialPosition *se
  */
 uint16_t PartialPosition_ply(const struct PartialPosition *self);
 
+/**
+ * Sets how many moves are made. Returns whether this operation was successful.
+ * This operation succeeds iff `ply != 0`.
+ */
+bool PartialPosition_ply_set(struct PartialPosition *self, uint16_t ply);
+
+/**
+ * C interface of [`PartialPosition::sfen_set`]. The SFEN is read from `len` bytes starting at `ptr`,
+ * which need not be nul-terminated.
+ *
+ * # Safety
+ * `ptr` must be null or valid for reads of `len` bytes.
+ */
+bool PartialPosition_sfen_set_c(struct PartialPosition *self,
+                                const uint8_t *ptr,
+                                size_t len);
+
 /**
  * Finds which player is to move.
  *
@@ -805,11 +1441,41 @@ uint16_t PartialPosition_ply(const struct PartialPosition *self);
  */
 Color PartialPosition_side_to_move(const struct PartialPosition *self);
 
+/**
+ * Sets which player is to move.
+ */
+void PartialPosition_side_to_move_set(struct PartialPosition *self, Color side);
+
 /**
  * C interface of `startpos`.
  */
 void PartialPosition_startpos(struct PartialPosition *buf);
 
+/**
+ * C interface of `to_sfen` with bounds checking.
+ *
+ * This function writes the SFEN representation of `self` followed by a nul byte to `ptr`,
+ * and returns the number of bytes written excluding the nul byte.
+ * If `capacity` is not large enough to hold them, this function returns 0. In that case, the contents of `ptr` are unspecified.
+ *
+ * # Safety
+ * `ptr` must be valid for writes of `capacity` bytes.
+ *
+ * Examples:
+ * ```
+ * # use shogi_core::PartialPosition;
+ * let pos = PartialPosition::startpos();
+ * let mut buf = [0u8; 100];
+ * let written = unsafe { pos.to_sfen_bounded(buf.as_mut_ptr(), buf.len()) };
+ * assert_eq!(&buf[..written], b"lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1");
+ * assert_eq!(buf[written], 0);
+ * assert_eq!(unsafe { pos.to_sfen_bounded(buf.as_mut_ptr(), written) }, 0);
+ * ```
+ */
+size_t PartialPosition_to_sfen_bounded(const struct PartialPosition *self,
+                                       uint8_t *ptr,
+                                       size_t capacity);
+
 /**
  * C interface of `to_sfen`.
  *
@@ -819,11 +1485,46 @@ void PartialPosition_startpos(struct PartialPosition *buf);
  */
 void PartialPosition_to_sfen_c(const struct PartialPosition *self, uint8_t *ptr);
 
+/**
+ * C interface of [`PartialPosition::unmake_null_move`].
+ *
+ * Since: 0.1.6
+ */
+bool PartialPosition_unmake_null_move(struct PartialPosition *self, OptionCompactMove last_move);
+
 /**
  * Finds the subset of squares with no pieces.
  */
 struct Bitboard PartialPosition_vacant_bitboard(const struct PartialPosition *self);
 
+/**
+ * Returns the Zobrist key of this position.
+ *
+ * The key depends only on the pieces on the board, the pieces in hand and the player to move;
+ * the number of moves made and the last move are not taken into account.
+ * The key of the pieces on the board is cached and updated by [`PartialPosition::piece_set`],
+ * so this function is cheap.
+ * Keys are stable across releases; see [`zobrist`](crate::zobrist) for how they are computed.
+ *
+ * Examples:
+ * ```
+ * # use shogi_core::{Move, PartialPosition, Square};
+ * let mut pos = PartialPosition::startpos();
+ * let key = pos.zobrist_key();
+ * for (from, to) in [
+ *     (Square::SQ_2H, Square::SQ_3H),
+ *     (Square::SQ_8B, Square::SQ_7B),
+ *     (Square::SQ_3H, Square::SQ_2H),
+ *     (Square::SQ_7B, Square::SQ_8B),
+ * ] {
+ *     pos.make_move(Move::Normal { from, to, promote: false }).unwrap();
+ * }
+ * assert_eq!(pos.zobrist_key(), key);
+ * assert_ne!(pos, PartialPosition::startpos()); // the ply differs
+ * ```
+ */
+uint64_t PartialPosition_zobrist_key(const struct PartialPosition *self);
+
 /**
  * C interface of [`PieceKind::from_u8`].
  */
@@ -858,6 +1559,17 @@ Color Piece_color(Piece self);
  */
 Piece Piece_new(PieceKind piece_kind, Color color);
 
+/**
+ * Parses a piece in USI format (e.g. `P`, `+r`) from `len` bytes starting at `ptr`.
+ * The bytes need not be nul-terminated.
+ *
+ * If the bytes are not a valid piece, this function returns `0` (`None`).
+ *
+ * # Safety
+ * `ptr` must be null or valid for reads of `len` bytes.
+ */
+OptionPiece Piece_parse_c(const uint8_t *ptr, size_t len);
+
 /**
  * Finds the [`PieceKind`] of this piece.
  */
@@ -868,11 +1580,31 @@ PieceKind Piece_piece_kind(Piece self);
  */
 OptionPiece Piece_promote(Piece self);
 
+/**
+ * C interface of [`ToUsi::to_usi`].
+ * Writes the USI representation of `self` to `ptr` followed by a nul byte.
+ *
+ * Returns the number of bytes written excluding the nul byte.
+ * If `capacity` is not large enough, this function returns 0. A capacity of 3 is always sufficient.
+ *
+ * # Safety
+ * `ptr` must be valid for writes of `capacity` bytes.
+ */
+size_t Piece_to_usi_c(Piece self, uint8_t *ptr, size_t capacity);
+
 /**
  * C interface of [`Piece::unpromote`].
  */
 OptionPiece Piece_unpromote(Piece self);
 
+/**
+ * C interface to [`PartialPosition::PartialPosition_board`].
+ *
+ * # Safety
+ * `out` must be valid for writes of 81 elements.
+ */
+void Position_board(const struct Position *self, OptionPiece *out);
+
 /**
  * Destructs a [`Position`].
  *
@@ -902,15 +1634,19 @@ const struct PartialPosition *Position_inner(const struct Position *self);
 OptionCompactMove Position_last_compact_move(const struct Position *self);
 
 /**
- * Makes a move. This function is a C-compatible counterpart of `make_move`.
+ * C interface to [`Position::make_compact_move`].
  * Note that this function will never check legality.
  *
  * Returns true if the given move makes sense, i.e.,
  * moves a piece to another square or drops a piece on a vacant square.
  *
  * If it returns false, it is guaranteed that self is not modified.
+ * This function also returns false if memory allocation for the move history fails.
+ * With the `shared-moves` feature, the shared block that holds the move history is allocated infallibly,
+ * so this function aborts the process if that allocation fails.
  */
-bool Position_make_compact_move(struct Position *self, CompactMove mv);
+bool Position_make_compact_move(struct Position *self,
+                                CompactMove mv);
 
 /**
  * Finds the subset of squares with a piece.
@@ -965,9 +1701,22 @@ Color Position_side_to_move(const struct Position *self);
 
 /**
  * C interface of [`Position::startpos`].
+ *
+ * If memory allocation fails, this function returns a null pointer.
+ * The returned pointer must be released with [`Position::Position_destruct`].
  */
 struct Position *Position_startpos(void);
 
+/**
+ * C interface of `to_sfen` with bounds checking. See [`PartialPosition::to_sfen_bounded`] for details.
+ *
+ * # Safety
+ * `ptr` must be valid for writes of `capacity` bytes.
+ */
+size_t Position_to_sfen_bounded(const struct Position *self,
+                                uint8_t *ptr,
+                                size_t capacity);
+
 /**
  * C interface of `to_sfen`.
  *
@@ -993,6 +1742,20 @@ struct Bitboard Position_vacant_bitboard(const struct Position *self);
  */
 uint8_t Square_file(Square self);
 
+/**
+ * Finds the distance between the files of `self` and `other`, in range `0..=8`.
+ *
+ * Examples:
+ * ```
+ * use shogi_core::Square;
+ * assert_eq!(Square::SQ_3C.file_abs_diff(Square::SQ_7A), 4);
+ * assert_eq!(Square::SQ_7A.file_abs_diff(Square::SQ_3C), 4);
+ * ```
+ *
+ * Since: 0.1.6
+ */
+uint8_t Square_file_abs_diff(Square self, Square other);
+
 /**
  * Finds the reflected square of `self`.
  *
@@ -1005,6 +1768,20 @@ uint8_t Square_file(Square self);
  */
 Square Square_flip(Square self);
 
+/**
+ * Converts a zero-based index to a [`Square`]. This is the inverse of [`Square::index0`].
+ * If `index0` is not in range `0..81`, this function returns [`None`].
+ *
+ * Examples:
+ * ```
+ * use shogi_core::Square;
+ * assert_eq!(Square::from_index0(21), Some(Square::SQ_3D));
+ * assert_eq!(Square::from_index0(81), None);
+ * assert_eq!(Square::from_index0(255), None);
+ * ```
+ */
+struct Option_Square Square_from_index0(uint8_t index0);
+
 /**
  * Converts a [`u8`] to a [`Square`]. If `value` is not in range `1..=81`, this function returns [`None`].
  *
@@ -1038,6 +1815,21 @@ Square Square_from_u8_unchecked(uint8_t value);
  */
 uint8_t Square_index(Square self);
 
+/**
+ * Finds the zero-based index of `self` in range `0..81`, which is `self.index() - 1`.
+ *
+ * Tables indexed by squares should use this function (or [`Square::array_index`]) rather than [`Square::index`].
+ *
+ * Examples:
+ * ```
+ * use shogi_core::Square;
+ * assert_eq!(Square::SQ_1A.index0(), Square::MIN_INDEX0);
+ * assert_eq!(Square::SQ_3D.index0(), 21);
+ * assert_eq!(Square::SQ_9I.index0(), Square::MAX_INDEX0);
+ * ```
+ */
+uint8_t Square_index0(Square self);
+
 /**
  * C interface to [`Square::new`].
  */
@@ -1048,6 +1840,17 @@ OptionSquare Square_new(uint8_t file, uint8_t rank);
  */
 OptionSquare Square_new_relative(uint8_t file, uint8_t rank, Color color);
 
+/**
+ * Parses a square in USI format from `len` bytes starting at `ptr`.
+ * The bytes need not be nul-terminated.
+ *
+ * If the bytes are not a valid square, this function returns `0` (`None`).
+ *
+ * # Safety
+ * `ptr` must be null or valid for reads of `len` bytes.
+ */
+OptionSquare Square_parse_c(const uint8_t *ptr, size_t len);
+
 /**
  * Finds the rank in range `1..=9`.
  *
@@ -1059,6 +1862,19 @@ OptionSquare Square_new_relative(uint8_t file, uint8_t rank, Color color);
  */
 uint8_t Square_rank(Square self);
 
+/**
+ * Finds the distance between the ranks of `self` and `other`, in range `0..=8`.
+ *
+ * Examples:
+ * ```
+ * use shogi_core::Square;
+ * assert_eq!(Square::SQ_3C.rank_abs_diff(Square::SQ_7A), 2);
+ * ```
+ *
+ * Since: 0.1.6
+ */
+uint8_t Square_rank_abs_diff(Square self, Square other);
+
 /**
  * Finds the file from the perspective of `color`.
  */
@@ -1069,6 +1885,50 @@ uint8_t Square_relative_file(Square self, Color color);
  */
 uint8_t Square_relative_rank(Square self, Color color);
 
+/**
+ * Checks if `self` and `other` are on the same diagonal line, in either direction.
+ * A square is on the same diagonal as itself.
+ *
+ * Examples:
+ * ```
+ * use shogi_core::Square;
+ * assert!(Square::SQ_3C.same_diagonal(Square::SQ_7G));
+ * assert!(Square::SQ_3C.same_diagonal(Square::SQ_1E));
+ * assert!(!Square::SQ_3C.same_diagonal(Square::SQ_4E));
+ * ```
+ *
+ * Since: 0.1.6
+ */
+bool Square_same_diagonal(Square self, Square other);
+
+/**
+ * Checks if `self` and `other` are on the same file.
+ *
+ * Examples:
+ * ```
+ * use shogi_core::Square;
+ * assert!(Square::SQ_3C.same_file(Square::SQ_3I));
+ * assert!(!Square::SQ_3C.same_file(Square::SQ_4C));
+ * ```
+ *
+ * Since: 0.1.6
+ */
+bool Square_same_file(Square self, Square other);
+
+/**
+ * Checks if `self` and `other` are on the same rank.
+ *
+ * Examples:
+ * ```
+ * use shogi_core::Square;
+ * assert!(Square::SQ_3C.same_rank(Square::SQ_9C));
+ * assert!(!Square::SQ_3C.same_rank(Square::SQ_3D));
+ * ```
+ *
+ * Since: 0.1.6
+ */
+bool Square_same_rank(Square self, Square other);
+
 /**
  * Shifts `self` by the given arguments. If the result would be out of the board, this function returns [`None`].
  *
@@ -1084,4 +1944,52 @@ struct Option_Square Square_shift(Square self,
                                   int8_t file_delta,
                                   int8_t rank_delta);
 
-#endif /* shogi_core_bindings_h */
+/**
+ * C interface of [`ToUsi::to_usi`].
+ * Writes the USI representation of `self` to `ptr` followed by a nul byte.
+ *
+ * Returns the number of bytes written excluding the nul byte.
+ * If `capacity` is not large enough, this function returns 0. A capacity of 3 is always sufficient.
+ *
+ * # Safety
+ * `ptr` must be valid for writes of `capacity` bytes.
+ */
+size_t Square_to_usi_c(Square self, uint8_t *ptr, size_t capacity);
+
+#if (!defined(DEFINE_STD) && (defined(DEFINE_ALLOC) && defined(DEFINE_EXTERNAL_ALLOCATOR)))
+extern uint8_t *shogi_core_alloc(size_t size, size_t align);
+#endif
+
+#if (!defined(DEFINE_STD) && (defined(DEFINE_ALLOC) && !defined(DEFINE_EXTERNAL_ALLOCATOR)))
+/**
+ * Returns how many bytes are left in the arena.
+ */
+size_t shogi_core_arena_remaining(void);
+#endif
+
+#if (!defined(DEFINE_STD) && (defined(DEFINE_ALLOC) && !defined(DEFINE_EXTERNAL_ALLOCATOR)))
+/**
+ * Frees everything allocated in the arena at once.
+ *
+ * # Safety
+ * Every object allocated by this library (e.g. `Position`s) must not be used after calling this function.
+ */
+void shogi_core_arena_reset(void);
+#endif
+
+#if (!defined(DEFINE_STD) && (defined(DEFINE_ALLOC) && defined(DEFINE_EXTERNAL_ALLOCATOR)))
+extern void shogi_core_dealloc(uint8_t *ptr, size_t size, size_t align);
+#endif
+
+/**
+ * Returns the version of `shogi_core` this library is built from (e.g. `"0.1.5"`) as a nul-terminated string.
+ *
+ * The returned pointer points to a static buffer and must not be freed.
+ */
+const char *shogi_core_version(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* shogi_core_bindings_h */
@@ -0,0 +1,271 @@
+#ifndef shogi_core_hpp
+#define shogi_core_hpp
+
+/*
+ * Thin C++11 wrappers of the C interface in shogi_core.h.
+ *
+ * Unlike shogi_core.h, this file is written by hand. It only calls functions declared in shogi_core.h,
+ * so it adds nothing to the shared object.
+ * Classes that own memory allocated by the library release it in their destructors.
+ */
+
+#include <cstddef>
+#include <cstdint>
+#include <iterator>
+#include <new>
+#include <string>
+#include <utility>
+#include <vector>
+
+#include "shogi_core.h"
+
+namespace shogi {
+
+/* An input iterator over the squares in a bitboard, in increasing order of Square_index. */
+class BitboardIterator {
+public:
+    using iterator_category = std::input_iterator_tag;
+    using value_type = Square;
+    using difference_type = std::ptrdiff_t;
+    using pointer = const Square *;
+    using reference = Square;
+
+    /* Creates the past-the-end iterator. */
+    BitboardIterator() noexcept : rest_(Bitboard_empty()), current_(0) {}
+
+    explicit BitboardIterator(::Bitboard bitboard) noexcept : rest_(bitboard), current_(0) {
+        ++*this;
+    }
+
+    Square operator*() const noexcept {
+        return OptionSquare_unwrap_unchecked(current_);
+    }
+
+    BitboardIterator &operator++() noexcept {
+        current_ = Bitboard_pop(&rest_);
+        return *this;
+    }
+
+    BitboardIterator operator++(int) noexcept {
+        BitboardIterator copy = *this;
+        ++*this;
+        return copy;
+    }
+
+    /* Squares are visited in increasing order, so the current square identifies the position of an iterator. */
+    friend bool operator==(const BitboardIterator &a, const BitboardIterator &b) noexcept {
+        return a.current_ == b.current_;
+    }
+
+    friend bool operator!=(const BitboardIterator &a, const BitboardIterator &b) noexcept {
+        return !(a == b);
+    }
+
+private:
+    ::Bitboard rest_;
+    /* 0 (None) if exhausted */
+    OptionSquare current_;
+};
+
+/* A value wrapper of ::Bitboard that can be used in range-based for loops. */
+class Bitboard {
+public:
+    Bitboard() noexcept : raw_(Bitboard_empty()) {}
+
+    /* Implicit conversion from the C type, so that results of C functions can be used directly. */
+    Bitboard(::Bitboard raw) noexcept : raw_(raw) {}
+
+    static Bitboard single(Square square) noexcept {
+        return Bitboard_single(square);
+    }
+
+    const ::Bitboard &raw() const noexcept {
+        return raw_;
+    }
+
+    bool empty() const noexcept {
+        return Bitboard_is_empty(raw_);
+    }
+
+    std::uint8_t count() const noexcept {
+        return Bitboard_count(raw_);
+    }
+
+    bool contains(Square square) const noexcept {
+        return Bitboard_contains(raw_, square);
+    }
+
+    BitboardIterator begin() const noexcept {
+        return BitboardIterator(raw_);
+    }
+
+    BitboardIterator end() const noexcept {
+        return BitboardIterator();
+    }
+
+    friend Bitboard operator&(const Bitboard &a, const Bitboard &b) noexcept {
+        return Bitboard_bitand(a.raw_, b.raw_);
+    }
+
+    friend Bitboard operator|(const Bitboard &a, const Bitboard &b) noexcept {
+        return Bitboard_bitor(a.raw_, b.raw_);
+    }
+
+    friend Bitboard operator^(const Bitboard &a, const Bitboard &b) noexcept {
+        return Bitboard_bitxor(a.raw_, b.raw_);
+    }
+
+    Bitboard operator~() const noexcept {
+        return Bitboard_not(raw_);
+    }
+
+    Bitboard &operator&=(const Bitboard &other) noexcept {
+        Bitboard_bitand_assign(&raw_, other.raw_);
+        return *this;
+    }
+
+    Bitboard &operator|=(const Bitboard &other) noexcept {
+        Bitboard_bitor_assign(&raw_, other.raw_);
+        return *this;
+    }
+
+    Bitboard &operator^=(const Bitboard &other) noexcept {
+        Bitboard_bitxor_assign(&raw_, other.raw_);
+        return *this;
+    }
+
+    friend bool operator==(const Bitboard &a, const Bitboard &b) noexcept {
+        return Bitboard_is_empty(Bitboard_bitxor(a.raw_, b.raw_));
+    }
+
+    friend bool operator!=(const Bitboard &a, const Bitboard &b) noexcept {
+        return !(a == b);
+    }
+
+private:
+    ::Bitboard raw_;
+};
+
+/*
+ * An owning handle of ::Position, which calls Position_destruct in its destructor.
+ * It can be moved but not copied. A moved-from Position holds no position and must not be used
+ * except for being destroyed or assigned to.
+ */
+class Position {
+public:
+    /* Takes the ownership of `ptr`, which must be null or created by a function in shogi_core.h. */
+    explicit Position(::Position *ptr) noexcept : ptr_(ptr) {}
+
+    /* Returns the starting position of a game. Throws std::bad_alloc if memory allocation fails. */
+    static Position startpos() {
+        ::Position *ptr = Position_startpos();
+        if (ptr == nullptr) {
+            throw std::bad_alloc();
+        }
+        return Position(ptr);
+    }
+
+    Position(const Position &) = delete;
+    Position &operator=(const Position &) = delete;
+
+    Position(Position &&other) noexcept : ptr_(other.ptr_) {
+        other.ptr_ = nullptr;
+    }
+
+    Position &operator=(Position &&other) noexcept {
+        if (this != &other) {
+            if (ptr_ != nullptr) {
+                Position_destruct(ptr_);
+            }
+            ptr_ = other.ptr_;
+            other.ptr_ = nullptr;
+        }
+        return *this;
+    }
+
+    ~Position() {
+        if (ptr_ != nullptr) {
+            Position_destruct(ptr_);
+        }
+    }
+
+    ::Position *get() noexcept {
+        return ptr_;
+    }
+
+    const ::Position *get() const noexcept {
+        return ptr_;
+    }
+
+    /* Gives up the ownership. The caller becomes responsible for calling Position_destruct. */
+    ::Position *release() noexcept {
+        ::Position *ptr = ptr_;
+        ptr_ = nullptr;
+        return ptr;
+    }
+
+    const PartialPosition &inner() const noexcept {
+        return *Position_inner(ptr_);
+    }
+
+    const PartialPosition &initial_position() const noexcept {
+        return *Position_initial_position(ptr_);
+    }
+
+    Color side_to_move() const noexcept {
+        return Position_side_to_move(ptr_);
+    }
+
+    std::uint16_t ply() const noexcept {
+        return Position_ply(ptr_);
+    }
+
+    OptionPiece piece_at(Square square) const noexcept {
+        return Position_piece_at(ptr_, square);
+    }
+
+    Hand hand_of_a_player(Color color) const noexcept {
+        return Position_hand_of_a_player(ptr_, color);
+    }
+
+    OptionCompactMove last_compact_move() const noexcept {
+        return Position_last_compact_move(ptr_);
+    }
+
+    /* Makes a move without checking legality. See Position_make_compact_move for the return value. */
+    bool make_compact_move(CompactMove mv) noexcept {
+        return Position_make_compact_move(ptr_, mv);
+    }
+
+    Bitboard occupied_bitboard() const noexcept {
+        return Position_occupied_bitboard(ptr_);
+    }
+
+    Bitboard vacant_bitboard() const noexcept {
+        return Position_vacant_bitboard(ptr_);
+    }
+
+    Bitboard player_bitboard(Color color) const noexcept {
+        return Position_player_bitboard(ptr_, color);
+    }
+
+    Bitboard piece_bitboard(Piece piece) const noexcept {
+        return Position_piece_bitboard(ptr_, piece);
+    }
+
+    std::string to_sfen() const {
+        std::vector<std::uint8_t> buf(256);
+        std::size_t written;
+        while ((written = Position_to_sfen_bounded(ptr_, buf.data(), buf.size())) == 0) {
+            buf.resize(buf.size() * 2);
+        }
+        return std::string(buf.begin(), buf.begin() + written);
+    }
+
+private:
+    ::Position *ptr_;
+};
+
+} // namespace shogi
+
+#endif // shogi_core_hpp
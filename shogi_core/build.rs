@@ -110,6 +110,74 @@ pub mod square {
     Ok(())
 }
 
+// The representation of a bitboard with the given squares, where a square is (file, rank).
+fn bitboard_repr(squares: &[(i8, i8)]) -> u128 {
+    let mut result = 0u128;
+    for &(file, rank) in squares {
+        let index = (file - 1) as u32 * 9 + (rank - 1) as u32;
+        // Bit 63 is unused.
+        let bit = if index < 63 { index } else { index + 1 };
+        result |= 1 << bit;
+    }
+    result
+}
+
+// Squares reachable from (file, rank) by sliding in the given directions on an empty board.
+fn slide(file: i8, rank: i8, directions: &[(i8, i8)]) -> Vec<(i8, i8)> {
+    let mut result = Vec::new();
+    for &(file_delta, rank_delta) in directions {
+        let (mut current_file, mut current_rank) = (file + file_delta, rank + rank_delta);
+        while (1..=9).contains(&current_file) && (1..=9).contains(&current_rank) {
+            result.push((current_file, current_rank));
+            current_file += file_delta;
+            current_rank += rank_delta;
+        }
+    }
+    result
+}
+
+fn write_bitboard_table(
+    dest_file: &mut fs::File,
+    directions: &[(i8, i8)],
+    indent: &str,
+) -> std::io::Result<()> {
+    writeln!(dest_file, "[")?;
+    for index in 0..81 {
+        let file = index / 9 + 1;
+        let rank = index % 9 + 1;
+        writeln!(
+            dest_file,
+            "{}    unsafe {{ Bitboard::from_u128_unchecked({:#x}) }},",
+            indent,
+            bitboard_repr(&slide(file, rank, directions)),
+        )?;
+    }
+    write!(dest_file, "{}]", indent)?;
+    Ok(())
+}
+
+fn generate_bitboard_tables(dest_file: &mut fs::File) -> std::io::Result<()> {
+    writeln!(
+        dest_file,
+        "// Attacks of sliders on an empty board, indexed by the array index of a square.
+const LANCE_PSEUDO_ATTACKS: [[Bitboard; 81]; 2] = ["
+    )?;
+    // Black's lances move toward rank 1, white's toward rank 9.
+    for rank_delta in [-1, 1] {
+        write!(dest_file, "    ")?;
+        write_bitboard_table(dest_file, &[(0, rank_delta)], "    ")?;
+        writeln!(dest_file, ",")?;
+    }
+    writeln!(dest_file, "];")?;
+    write!(dest_file, "const BISHOP_PSEUDO_ATTACKS: [Bitboard; 81] = ")?;
+    write_bitboard_table(dest_file, &[(1, 1), (1, -1), (-1, 1), (-1, -1)], "")?;
+    writeln!(dest_file, ";")?;
+    write!(dest_file, "const ROOK_PSEUDO_ATTACKS: [Bitboard; 81] = ")?;
+    write_bitboard_table(dest_file, &[(1, 0), (-1, 0), (0, 1), (0, -1)], "")?;
+    writeln!(dest_file, ";")?;
+    Ok(())
+}
+
 fn main() -> std::io::Result<()> {
     let out_dir = env::var_os("OUT_DIR").unwrap();
     let dest_path = Path::new(&out_dir).join("piece_consts.rs");
@@ -118,6 +186,10 @@ fn main() -> std::io::Result<()> {
     let dest_path = Path::new(&out_dir).join("square_consts.rs");
     let mut dest_file = fs::File::create(dest_path)?;
     generate_square_consts(&mut dest_file)?;
+    let dest_path = Path::new(&out_dir).join("bitboard_tables.rs");
+    let mut dest_file = fs::File::create(dest_path)?;
+    generate_bitboard_tables(&mut dest_file)?;
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rustc-check-cfg=cfg(bench)");
     Ok(())
 }
@@ -42,6 +42,17 @@ macro_rules! impl_hash_for_fieldless_enum {
     };
 }
 
+// Defines a Hash implementation for a unit struct.
+macro_rules! impl_hash_for_fieldless_struct {
+    ($ty:ty) => {
+        #[cfg(feature = "hash")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "hash")))]
+        impl core::hash::Hash for $ty {
+            fn hash<H: core::hash::Hasher>(&self, _state: &mut H) {}
+        }
+    };
+}
+
 // impl PartialOrd and Ord for a tuple struct with a single field
 macro_rules! impl_ord_for_single_field {
     ($ty:ty) => {
@@ -49,7 +60,7 @@ macro_rules! impl_ord_for_single_field {
         #[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
         impl core::cmp::PartialOrd for $ty {
             fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-                self.0.partial_cmp(&other.0)
+                Some(self.cmp(other))
             }
         }
 
@@ -90,7 +101,7 @@ macro_rules! impl_ord_for_fieldless_enum {
         #[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
         impl core::cmp::PartialOrd for $ty {
             fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-                (*self as u8).partial_cmp(&(*other as u8))
+                Some(self.cmp(other))
             }
         }
 
@@ -103,3 +114,60 @@ macro_rules! impl_ord_for_fieldless_enum {
         }
     };
 }
+
+// impl PartialOrd and Ord for a unit struct
+macro_rules! impl_ord_for_fieldless_struct {
+    ($ty:ty) => {
+        #[cfg(feature = "ord")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+        impl core::cmp::PartialOrd for $ty {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        #[cfg(feature = "ord")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+        impl core::cmp::Ord for $ty {
+            fn cmp(&self, _other: &Self) -> core::cmp::Ordering {
+                core::cmp::Ordering::Equal
+            }
+        }
+    };
+}
+
+// impl rkyv's Archive, Serialize and Deserialize for a `Copy` type with a stable layout (`repr(u8)`, `repr(C)` or `repr(transparent)`),
+// so that the archived representation is the type itself and can be used in place.
+macro_rules! impl_rkyv_as_self {
+    ($ty:ty) => {
+        #[cfg(feature = "rkyv")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "rkyv")))]
+        impl rkyv::Archive for $ty {
+            type Archived = Self;
+            type Resolver = ();
+
+            #[inline(always)]
+            unsafe fn resolve(&self, _pos: usize, _resolver: (), out: *mut Self) {
+                out.write(self.clone());
+            }
+        }
+
+        #[cfg(feature = "rkyv")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "rkyv")))]
+        impl<S: rkyv::Fallible + ?Sized> rkyv::Serialize<S> for $ty {
+            #[inline(always)]
+            fn serialize(&self, _serializer: &mut S) -> Result<(), S::Error> {
+                Ok(())
+            }
+        }
+
+        #[cfg(feature = "rkyv")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "rkyv")))]
+        impl<D: rkyv::Fallible + ?Sized> rkyv::Deserialize<$ty, D> for $ty {
+            #[inline(always)]
+            fn deserialize(&self, _deserializer: &mut D) -> Result<$ty, D::Error> {
+                Ok(self.clone())
+            }
+        }
+    };
+}
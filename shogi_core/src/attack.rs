@@ -0,0 +1,783 @@
+//! Squares attacked by pieces.
+
+use crate::attacks;
+use crate::{Bitboard, Color, Move, PartialPosition, PerSquare, Piece, PieceKind, Square};
+
+// Steps of a piece from black's point of view. Sliding directions are not included.
+pub(crate) fn steps(piece_kind: PieceKind) -> &'static [(i8, i8)] {
+    const GOLD: &[(i8, i8)] = &[(0, -1), (1, -1), (-1, -1), (1, 0), (-1, 0), (0, 1)];
+    const KING: &[(i8, i8)] = &[
+        (0, -1),
+        (1, -1),
+        (-1, -1),
+        (1, 0),
+        (-1, 0),
+        (0, 1),
+        (1, 1),
+        (-1, 1),
+    ];
+    match piece_kind {
+        PieceKind::Pawn => &[(0, -1)],
+        PieceKind::Lance | PieceKind::Bishop | PieceKind::Rook => &[],
+        PieceKind::Knight => &[(1, -2), (-1, -2)],
+        PieceKind::Silver => &[(0, -1), (1, -1), (-1, -1), (1, 1), (-1, 1)],
+        PieceKind::Gold
+        | PieceKind::ProPawn
+        | PieceKind::ProLance
+        | PieceKind::ProKnight
+        | PieceKind::ProSilver => GOLD,
+        PieceKind::King | PieceKind::ProBishop | PieceKind::ProRook => KING,
+    }
+}
+
+impl PartialPosition {
+    /// Finds the squares attacked by the piece on `square`.
+    ///
+    /// Sliding pieces attack up to and including the first occupied square in each direction.
+    /// Returns an empty [`Bitboard`] if `square` is vacant.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Bitboard, PartialPosition, Square};
+    /// let position = PartialPosition::startpos();
+    /// assert_eq!(position.attacks_from(Square::SQ_7G), Bitboard::single(Square::SQ_7F));
+    /// // the bishop on 8h is blocked by the pawn on 7g and the silver on 7i
+    /// assert_eq!(position.attacks_from(Square::SQ_8H).count(), 4);
+    /// assert!(position.attacks_from(Square::SQ_5E).is_empty());
+    /// ```
+    pub fn attacks_from(&self, square: Square) -> Bitboard {
+        let (piece_kind, color) = match self.piece_at(square) {
+            Some(piece) => piece.to_parts(),
+            None => return Bitboard::empty(),
+        };
+        let forward = match color {
+            Color::Black => 1,
+            Color::White => -1,
+        };
+        let mut result = Bitboard::empty();
+        for &(file_delta, rank_delta) in steps(piece_kind) {
+            if let Some(to) = square.shift(file_delta, rank_delta * forward) {
+                result |= to;
+            }
+        }
+        let occupied = self.occupied_bitboard();
+        result |= match piece_kind {
+            PieceKind::Lance => attacks::lance_attacks(color, square, occupied),
+            PieceKind::Bishop | PieceKind::ProBishop => attacks::bishop_attacks(square, occupied),
+            PieceKind::Rook | PieceKind::ProRook => attacks::rook_attacks(square, occupied),
+            _ => Bitboard::empty(),
+        };
+        result
+    }
+
+    /// Counts how many of `color`'s pieces attack each square (利きの数, *kiki no kazu*).
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, PartialPosition, Square};
+    /// let position = PartialPosition::startpos();
+    /// let counts = position.effect_counts(Color::Black);
+    /// assert_eq!(counts[Square::SQ_7F], 1);
+    /// // the gold on 4i, the silver on 3i, the king on 5i and the rook on 2h
+    /// assert_eq!(counts[Square::SQ_4H], 4);
+    /// assert_eq!(counts[Square::SQ_5E], 0);
+    /// ```
+    pub fn effect_counts(&self, color: Color) -> PerSquare<u8> {
+        let mut result = PerSquare::new(0);
+        for from in self.player_bitboard(color) {
+            for to in self.attacks_from(from) {
+                result[to] += 1;
+            }
+        }
+        result
+    }
+
+    /// Checks if `mv` is pseudo-legal in `self`, i.e., legal except that it may leave the king in check.
+    ///
+    /// A normal move is pseudo-legal if a piece of the side to move is on the source square,
+    /// the piece attacks the destination square (see [`PartialPosition::attacks_from`]),
+    /// the destination square is not occupied by a piece of the side to move,
+    /// the piece promotes only if it can and the move starts or ends in the promotion zone,
+    /// and the piece does not end up on a square from which it can never move.
+    /// A drop is pseudo-legal if the side to move has the piece in hand, the destination square is vacant,
+    /// the piece does not end up on a square from which it can never move, and it does not put two pawns in the same file.
+    ///
+    /// Checks and drop-pawn-mates (打ち歩詰め, *uchifu-zume*) are not taken into account.
+    /// This function is intended as a cheap filter for moves from outside, e.g. GUIs or opening books.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Move, PartialPosition, Piece, Square};
+    /// let position = PartialPosition::startpos();
+    /// assert!(position.is_pseudo_legal(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }));
+    /// // a pawn moves only one square forward
+    /// assert!(!position.is_pseudo_legal(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7E, promote: false }));
+    /// // the bishop is blocked by the pawn on 7g
+    /// assert!(!position.is_pseudo_legal(Move::Normal { from: Square::SQ_8H, to: Square::SQ_2B, promote: true }));
+    /// // white is not to move
+    /// assert!(!position.is_pseudo_legal(Move::Normal { from: Square::SQ_3C, to: Square::SQ_3D, promote: false }));
+    /// // black has no pieces in hand
+    /// assert!(!position.is_pseudo_legal(Move::Drop { piece: Piece::B_G, to: Square::SQ_5E }));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn is_pseudo_legal(&self, mv: Move) -> bool {
+        let side = self.side_to_move();
+        match mv {
+            Move::Normal { from, to, promote } => {
+                let piece = match self.piece_at(from) {
+                    Some(piece) => piece,
+                    None => return false,
+                };
+                if piece.color() != side
+                    || !self.attacks_from(from).contains(to)
+                    || self.player_bitboard(side).contains(to)
+                {
+                    return false;
+                }
+                if promote {
+                    piece.promote().is_some()
+                        && (from.relative_rank(side) <= 3 || to.relative_rank(side) <= 3)
+                } else {
+                    !is_stuck(piece.piece_kind(), side, to)
+                }
+            }
+            Move::Drop { piece, to } => {
+                let piece_kind = piece.piece_kind();
+                piece.color() == side
+                    && matches!(
+                        self.hand_of_a_player(side).count(piece_kind),
+                        Some(1..=u8::MAX)
+                    )
+                    && self.drop_mask(piece).contains(to)
+            }
+        }
+    }
+
+    /// Finds the squares where `piece` may be dropped, ignoring checks:
+    /// vacant squares, except those from which `piece` could never move
+    /// and, for a pawn, those in files that already have an unpromoted pawn of the same color (二歩, *nifu*).
+    ///
+    /// This function does not check whether `piece` is in hand or whose turn it is,
+    /// so that legality checkers and GUIs can refine the result as they need.
+    /// Returns an empty [`Bitboard`] if `piece` cannot be in hand (e.g. a king or a promoted piece).
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{PartialPosition, Piece, Square};
+    /// let position = PartialPosition::startpos();
+    /// // every file has a pawn
+    /// assert!(position.drop_mask(Piece::B_P).is_empty());
+    /// // 27 squares on ranks 4 to 6 and 7 on rank 8; a black knight cannot be dropped on ranks 1 and 2
+    /// assert_eq!(position.drop_mask(Piece::B_N).count(), 34);
+    /// assert_eq!(position.drop_mask(Piece::W_G).count(), 41);
+    /// assert!(position.drop_mask(Piece::W_G).contains(Square::SQ_1B));
+    /// assert!(position.drop_mask(Piece::B_PR).is_empty());
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_drop_mask")]
+    pub extern "C" fn drop_mask(&self, piece: Piece) -> Bitboard {
+        let (piece_kind, color) = piece.to_parts();
+        // The number of ranks, counted from the far end, where `piece` could never move.
+        let dead_ranks = match piece_kind {
+            PieceKind::Pawn | PieceKind::Lance => 1,
+            PieceKind::Knight => 2,
+            PieceKind::Silver | PieceKind::Gold | PieceKind::Bishop | PieceKind::Rook => 0,
+            _ => return Bitboard::empty(),
+        };
+        let ranks = match color {
+            Color::Black => dead_ranks + 1..=9,
+            Color::White => 1..=9 - dead_ranks,
+        };
+        let mut result = self.vacant_bitboard() & Bitboard::rect(1..=9, ranks);
+        if piece_kind == PieceKind::Pawn {
+            let files = self.piece_bitboard(piece).files_occupied();
+            for file in 1..=9 {
+                if files & 1 << (file - 1) != 0 {
+                    result &= !Bitboard::rect(file..=file, 1..=9);
+                }
+            }
+        }
+        result
+    }
+
+    /// Finds the destination squares of moves that may get the king of the side to move out of check:
+    /// for non-king pieces, the square of the checker and the squares between it and the king,
+    /// and for the king, adjacent squares that are neither occupied by its own pieces nor attacked by the opponent.
+    ///
+    /// If two pieces give check, only the squares for the king are returned, because blocking or capturing cannot help.
+    /// Returns an empty [`Bitboard`] if the side to move is not in check or has no king.
+    ///
+    /// Squares are checked as if the king had already left its square,
+    /// so that the king cannot flee along the line of a sliding checker.
+    /// Pins and drop-pawn-mates (打ち歩詰め, *uchifu-zume*) are not taken into account;
+    /// this function is intended as a building block for generating evasions.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Bitboard, PartialPosition, Square};
+    /// let position = PartialPosition::from_sfen("4k4/9/9/9/9/9/9/4G4/r3K4 b - 1").unwrap();
+    /// let targets = position.evasion_targets();
+    /// // capture or block the rook on 9i
+    /// assert!(targets.contains(Square::SQ_9I));
+    /// assert!(targets.contains(Square::SQ_6I));
+    /// // 4i is still on the rook's line once the king leaves 5i
+    /// assert!(!targets.contains(Square::SQ_4I));
+    /// assert!(targets.contains(Square::SQ_4H));
+    /// // the gold is on 5h
+    /// assert!(!targets.contains(Square::SQ_5H));
+    /// assert!(PartialPosition::startpos().evasion_targets().is_empty());
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_evasion_targets")]
+    pub extern "C" fn evasion_targets(&self) -> Bitboard {
+        let side = self.side_to_move();
+        let king = match self.king_position(side) {
+            Some(king) => king,
+            None => return Bitboard::empty(),
+        };
+        let opponent = self.player_bitboard(side.flip());
+        let mut checkers = opponent.filter(|&from| self.attacks_from(from).contains(king));
+        let checker = match checkers.next() {
+            Some(checker) => checker,
+            None => return Bitboard::empty(),
+        };
+        let mut result = Bitboard::empty();
+        if checkers.next().is_none() {
+            result |= checker;
+            // A checker on the same line as the king and not adjacent to it is a slider,
+            // and the squares between them are vacant.
+            if king.same_file(checker) || king.same_rank(checker) || king.same_diagonal(checker) {
+                let file_delta = (checker.file() as i8 - king.file() as i8).signum();
+                let rank_delta = (checker.rank() as i8 - king.rank() as i8).signum();
+                let mut square = king.shift(file_delta, rank_delta);
+                while let Some(between) = square {
+                    if between == checker {
+                        break;
+                    }
+                    result |= between;
+                    square = between.shift(file_delta, rank_delta);
+                }
+            }
+        }
+        let mut without_king = self.clone();
+        without_king.piece_set(king, None);
+        let mut attacked = Bitboard::empty();
+        for from in opponent {
+            attacked |= without_king.attacks_from(from);
+        }
+        let mut flights = self.attacks_from(king);
+        flights &= !self.player_bitboard(side);
+        flights &= !attacked;
+        result | flights
+    }
+
+    // Checks if the king of the side to move is attacked.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn is_in_check(&self) -> bool {
+        let side = self.side_to_move();
+        let king = match self.king_position(side) {
+            Some(king) => king,
+            None => return false,
+        };
+        self.player_bitboard(side.flip())
+            .any(|from| self.attacks_from(from).contains(king))
+    }
+}
+
+// Checks if a piece of `color` on `square` can never move.
+fn is_stuck(piece_kind: PieceKind, color: Color, square: Square) -> bool {
+    let rank = square.relative_rank(color);
+    match piece_kind {
+        PieceKind::Pawn | PieceKind::Lance => rank <= 1,
+        PieceKind::Knight => rank <= 2,
+        _ => false,
+    }
+}
+
+/// A [`PartialPosition`] that keeps track of which pieces attack each square.
+///
+/// Attacks are updated incrementally in [`EffectPosition::piece_set`] and [`EffectPosition::make_move`]:
+/// only the moved pieces and the sliders whose lines pass through the changed squares are recomputed,
+/// so that [`EffectPosition::attackers_to`] answers in O(1) at every node of a search.
+/// [`EffectPosition::make_move`] returns a [`MoveDelta`], which [`EffectPosition::unmake_move`] takes to undo the move incrementally as well.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Bitboard, Color, EffectPosition, Move, PartialPosition, Square};
+/// let mut position = EffectPosition::new(PartialPosition::startpos());
+/// assert_eq!(position.attackers_to(Square::SQ_7F, Color::Black), Bitboard::single(Square::SQ_7G));
+/// let delta = position.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+/// // the bishop on 8h now sees the pawn on 3c
+/// assert!(position.attackers_to(Square::SQ_3C, Color::Black).contains(Square::SQ_8H));
+/// position.unmake_move(delta).unwrap();
+/// assert!(position.attackers_to(Square::SQ_3C, Color::Black).is_empty());
+/// assert_eq!(position, EffectPosition::new(PartialPosition::startpos()));
+/// ```
+#[cfg(feature = "long-effect")]
+#[cfg_attr(docsrs, doc(cfg(feature = "long-effect")))]
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct EffectPosition {
+    inner: PartialPosition,
+    // The squares attacked by the piece on each square.
+    attacks: PerSquare<Bitboard>,
+    // The squares of the pieces attacking each square.
+    attackers: PerSquare<Bitboard>,
+}
+
+#[cfg(feature = "long-effect")]
+impl EffectPosition {
+    /// Creates an [`EffectPosition`], computing the attacks of every piece in `position`.
+    pub fn new(position: PartialPosition) -> Self {
+        let mut result = Self {
+            inner: position,
+            attacks: PerSquare::new(Bitboard::empty()),
+            attackers: PerSquare::new(Bitboard::empty()),
+        };
+        result.recompute(result.inner.occupied_bitboard());
+        result
+    }
+
+    /// Returns a reference to the underlying [`PartialPosition`].
+    #[inline(always)]
+    pub fn inner(&self) -> &PartialPosition {
+        &self.inner
+    }
+
+    /// Converts `self` into the underlying [`PartialPosition`].
+    #[inline(always)]
+    pub fn into_inner(self) -> PartialPosition {
+        self.inner
+    }
+
+    /// Finds the squares of `color`'s pieces that attack `square`.
+    ///
+    /// Equivalent to filtering [`PartialPosition::attacks_from`] over all pieces of `color`, but runs in O(1).
+    #[inline]
+    pub fn attackers_to(&self, square: Square, color: Color) -> Bitboard {
+        self.attackers[square] & self.inner.player_bitboard(color)
+    }
+
+    /// Finds the squares attacked by the piece on `square`.
+    ///
+    /// Equivalent to [`PartialPosition::attacks_from`], but runs in O(1).
+    #[inline]
+    pub fn attacks_from(&self, square: Square) -> Bitboard {
+        self.attacks[square]
+    }
+
+    /// Sets a piece on `square`. See [`PartialPosition::piece_set`].
+    pub fn piece_set(&mut self, square: Square, piece: Option<Piece>) {
+        let affected = self.affected_by(Bitboard::single(square));
+        self.inner.piece_set(square, piece);
+        self.recompute(affected);
+    }
+
+    /// Makes a move. See [`PartialPosition::make_move`].
+    ///
+    /// Returns a [`MoveDelta`] to undo the move with [`EffectPosition::unmake_move`].
+    /// If it returns None, it is guaranteed that self is not modified.
+    pub fn make_move(&mut self, mv: Move) -> Option<MoveDelta> {
+        let delta = MoveDelta {
+            mv,
+            captured: match mv {
+                Move::Normal { to, .. } => self.inner.piece_at(to),
+                Move::Drop { .. } => None,
+            },
+            last_move: self.inner.last_compact_move(),
+        };
+        let affected = self.affected_by(changed_squares(mv));
+        self.inner.make_move(mv)?;
+        self.recompute(affected);
+        Some(delta)
+    }
+
+    /// Undoes the move made by [`EffectPosition::make_move`] that returned `delta`.
+    /// Attacks are recomputed only for the same pieces as in [`EffectPosition::make_move`].
+    ///
+    /// Returns None if the last move of `self` is not the move of `delta`
+    /// or the position cannot be restored, e.g. the ply would become 0.
+    /// If it returns None, it is guaranteed that self is not modified.
+    ///
+    /// Since: 0.1.6
+    pub fn unmake_move(&mut self, delta: MoveDelta) -> Option<()> {
+        let mv = delta.mv;
+        if self.inner.last_move() != Some(mv) {
+            return None;
+        }
+        let ply = self.inner.ply().wrapping_sub(1);
+        if ply == 0 {
+            return None;
+        }
+        let side = self.inner.side_to_move().flip();
+        let hand = self.inner.hand_of_a_player(side);
+        // The hand of the player who made the move before it
+        let hand = match mv {
+            Move::Normal { to, promote, .. } => {
+                let piece = self.inner.piece_at(to)?;
+                if promote && piece.unpromote().is_none() {
+                    return None;
+                }
+                match delta.captured {
+                    Some(captured) => hand.removed(captured.piece_kind().unpromoted_or_self())?,
+                    None => hand,
+                }
+            }
+            Move::Drop { piece, to } => {
+                if self.inner.piece_at(to) != Some(piece) {
+                    return None;
+                }
+                hand.added(piece.piece_kind())?
+            }
+        };
+        let affected = self.affected_by(changed_squares(mv));
+        match mv {
+            Move::Normal { from, to, promote } => {
+                let piece = self.inner.piece_at(to);
+                let piece = if promote {
+                    piece.and_then(Piece::unpromote)
+                } else {
+                    piece
+                };
+                self.inner.piece_set(from, piece);
+                self.inner.piece_set(to, delta.captured);
+            }
+            Move::Drop { to, .. } => self.inner.piece_set(to, None),
+        }
+        *self.inner.hand_of_a_player_mut(side) = hand;
+        self.inner.side_to_move_set(side);
+        let result = self.inner.ply_set(ply);
+        debug_assert!(result);
+        self.inner.last_move_set(delta.last_move);
+        self.recompute(affected);
+        Some(())
+    }
+
+    // The squares of pieces whose attacks may change if pieces on `changed` change.
+    // Sliders whose lines pass through a changed square attack that square, so they are found in `attackers`.
+    fn affected_by(&self, changed: Bitboard) -> Bitboard {
+        let mut result = changed;
+        for square in changed {
+            result |= self.attackers[square];
+        }
+        result
+    }
+
+    // Recomputes the attacks of the pieces on `squares`.
+    fn recompute(&mut self, squares: Bitboard) {
+        for from in squares {
+            for to in self.attacks[from] {
+                self.attackers[to] &= !Bitboard::single(from);
+            }
+            let attacks = self.inner.attacks_from(from);
+            for to in attacks {
+                self.attackers[to] |= from;
+            }
+            self.attacks[from] = attacks;
+        }
+    }
+}
+
+// The squares whose pieces change when `mv` is made or unmade.
+#[cfg(feature = "long-effect")]
+fn changed_squares(mv: Move) -> Bitboard {
+    match mv {
+        Move::Normal { from, to, .. } => Bitboard::single(from) | Bitboard::single(to),
+        Move::Drop { to, .. } => Bitboard::single(to),
+    }
+}
+
+/// What is needed to undo a move made by [`EffectPosition::make_move`], which returns it.
+/// Pass it to [`EffectPosition::unmake_move`] to undo the move.
+///
+/// Since: 0.1.6
+#[cfg(feature = "long-effect")]
+#[cfg_attr(docsrs, doc(cfg(feature = "long-effect")))]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct MoveDelta {
+    mv: Move,
+    // The piece captured by the move, if any.
+    captured: Option<Piece>,
+    // The last move before the move.
+    last_move: Option<crate::CompactMove>,
+}
+
+#[cfg(feature = "long-effect")]
+impl MoveDelta {
+    /// Returns the move to be undone.
+    #[inline(always)]
+    pub fn mv(&self) -> Move {
+        self.mv
+    }
+
+    /// Returns the piece captured by the move, if any.
+    #[inline(always)]
+    pub fn captured(&self) -> Option<Piece> {
+        self.captured
+    }
+}
+
+#[cfg(feature = "long-effect")]
+impl_ord_with_fields!(MoveDelta; mv, captured, last_move);
+#[cfg(feature = "long-effect")]
+impl_hash_with_fields!(MoveDelta; mv, captured, last_move);
+
+#[cfg(feature = "long-effect")]
+impl From<PartialPosition> for EffectPosition {
+    #[inline]
+    fn from(position: PartialPosition) -> Self {
+        Self::new(position)
+    }
+}
+
+#[cfg(feature = "long-effect")]
+impl_ord_with_fields!(EffectPosition; inner);
+#[cfg(feature = "long-effect")]
+impl_hash_with_fields!(EffectPosition; inner);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effect_counts_work() {
+        let position = PartialPosition::startpos();
+        let black = position.effect_counts(Color::Black);
+        let white = position.effect_counts(Color::White);
+        for square in Square::all() {
+            // startpos is symmetric
+            assert_eq!(black[square], white[square.flip()]);
+        }
+        let total: u32 = black.as_array().iter().map(|&count| count as u32).sum();
+        let expected: u32 = position
+            .player_bitboard(Color::Black)
+            .map(|from| position.attacks_from(from).count() as u32)
+            .sum();
+        assert_eq!(total, expected);
+
+        let mut position = PartialPosition::empty();
+        position.piece_set(Square::SQ_5E, Some(Piece::B_PR));
+        position.piece_set(Square::SQ_5C, Some(Piece::W_P));
+        position.piece_set(Square::SQ_5A, Some(Piece::B_L));
+        let counts = position.effect_counts(Color::Black);
+        // the dragon moves one square diagonally and is blocked by the pawn on 5c
+        assert_eq!(counts[Square::SQ_4D], 1);
+        assert_eq!(counts[Square::SQ_5C], 1);
+        assert_eq!(counts[Square::SQ_5B], 0);
+        assert_eq!(counts[Square::SQ_1E], 1);
+        assert_eq!(counts[Square::SQ_3C], 0);
+        assert_eq!(position.attacks_from(Square::SQ_5A), Bitboard::empty());
+    }
+
+    #[test]
+    fn is_pseudo_legal_works() {
+        let mut position = PartialPosition::empty();
+        position.piece_set(Square::SQ_5I, Some(Piece::B_K));
+        position.piece_set(Square::SQ_5A, Some(Piece::W_K));
+        position.piece_set(Square::SQ_2D, Some(Piece::B_P));
+        position.piece_set(Square::SQ_9D, Some(Piece::B_N));
+        position.piece_set(Square::SQ_1E, Some(Piece::B_S));
+        position.piece_set(Square::SQ_4B, Some(Piece::W_G));
+        position.piece_set(Square::SQ_4F, Some(Piece::B_P));
+        *position.hand_of_a_player_mut(Color::Black) = crate::Hand::new()
+            .added(PieceKind::Pawn)
+            .and_then(|hand| hand.added(PieceKind::Lance))
+            .unwrap();
+        let normal =
+            |from, to, promote| position.is_pseudo_legal(Move::Normal { from, to, promote });
+        let drop = |piece, to| position.is_pseudo_legal(Move::Drop { piece, to });
+
+        // Promotion is mandatory for a pawn moving to the last rank, but optional on the third rank.
+        assert!(normal(Square::SQ_2D, Square::SQ_2C, true));
+        assert!(normal(Square::SQ_2D, Square::SQ_2C, false));
+        assert!(!normal(Square::SQ_9D, Square::SQ_8B, false));
+        assert!(normal(Square::SQ_9D, Square::SQ_8B, true));
+        // Outside the promotion zone
+        assert!(!normal(Square::SQ_1E, Square::SQ_1D, true));
+        assert!(normal(Square::SQ_1E, Square::SQ_1D, false));
+        // Kings and golds never promote.
+        assert!(!normal(Square::SQ_5I, Square::SQ_5H, true));
+        // Own pieces, opponent's pieces and vacant squares
+        assert!(!normal(Square::SQ_5I, Square::SQ_5I, false));
+        assert!(!normal(Square::SQ_4B, Square::SQ_4C, false));
+        assert!(!normal(Square::SQ_5E, Square::SQ_5D, false));
+        assert!(!normal(Square::SQ_1E, Square::SQ_2D, false));
+
+        assert!(drop(Piece::B_L, Square::SQ_5B));
+        assert!(!drop(Piece::B_L, Square::SQ_5A));
+        assert!(!drop(Piece::B_L, Square::SQ_9A));
+        assert!(!drop(Piece::B_L, Square::SQ_4B));
+        assert!(!drop(Piece::B_G, Square::SQ_5E));
+        assert!(!drop(Piece::W_P, Square::SQ_5E));
+        // Two pawns
+        assert!(drop(Piece::B_P, Square::SQ_5E));
+        assert!(!drop(Piece::B_P, Square::SQ_2E));
+        assert!(!drop(Piece::B_P, Square::SQ_4H));
+        // Promoted pieces are never in hand.
+        assert!(!drop(Piece::B_PP, Square::SQ_5E));
+    }
+
+    #[test]
+    fn drop_mask_works() {
+        let mut position = PartialPosition::empty();
+        position.piece_set(Square::SQ_5I, Some(Piece::B_K));
+        position.piece_set(Square::SQ_5A, Some(Piece::W_K));
+        position.piece_set(Square::SQ_2D, Some(Piece::B_P));
+        position.piece_set(Square::SQ_3D, Some(Piece::B_PP));
+        position.piece_set(Square::SQ_7C, Some(Piece::W_P));
+        let vacant = position.vacant_bitboard();
+
+        let black_pawn = position.drop_mask(Piece::B_P);
+        // 8 files (except file 2) x 8 ranks (except rank 1), minus the occupied 5i, 3d and 7c
+        assert_eq!(black_pawn.count(), 8 * 8 - 3);
+        assert!(!black_pawn.contains(Square::SQ_2H));
+        assert!(black_pawn.contains(Square::SQ_3H));
+        let white_pawn = position.drop_mask(Piece::W_P);
+        assert_eq!(white_pawn.count(), 8 * 8 - 3);
+        assert!(!white_pawn.contains(Square::SQ_7G));
+        assert!(!white_pawn.contains(Square::SQ_1I));
+
+        assert_eq!(
+            position.drop_mask(Piece::B_N),
+            vacant & Bitboard::rect(1..=9, 3..=9),
+        );
+        assert_eq!(
+            position.drop_mask(Piece::W_L),
+            vacant & Bitboard::rect(1..=9, 1..=8),
+        );
+        assert_eq!(position.drop_mask(Piece::W_R), vacant);
+        assert!(position.drop_mask(Piece::B_K).is_empty());
+        assert!(position.drop_mask(Piece::W_PB).is_empty());
+    }
+
+    #[test]
+    fn evasion_targets_work() {
+        let mut position = PartialPosition::empty();
+        position.piece_set(Square::SQ_5E, Some(Piece::B_K));
+        position.piece_set(Square::SQ_5A, Some(Piece::W_K));
+        position.piece_set(Square::SQ_2B, Some(Piece::W_B));
+        position.piece_set(Square::SQ_6C, Some(Piece::W_N));
+        // Double check: only the king can move, and not along the bishop's diagonal.
+        let targets = position.evasion_targets();
+        assert!(!targets.contains(Square::SQ_2B));
+        assert!(!targets.contains(Square::SQ_4D));
+        assert!(!targets.contains(Square::SQ_6F));
+        assert!(targets.contains(Square::SQ_5D));
+        assert_eq!(targets, targets & position.attacks_from(Square::SQ_5E));
+
+        // A knight cannot be blocked.
+        position.piece_set(Square::SQ_2B, None);
+        let targets = position.evasion_targets();
+        assert!(targets.contains(Square::SQ_6C));
+        assert_eq!(
+            targets & !position.attacks_from(Square::SQ_5E),
+            Bitboard::single(Square::SQ_6C),
+        );
+
+        // An adjacent checker: squares it attacks are not flights.
+        position.piece_set(Square::SQ_6C, None);
+        position.piece_set(Square::SQ_5D, Some(Piece::W_G));
+        position.piece_set(Square::SQ_5C, Some(Piece::W_P));
+        let targets = position.evasion_targets();
+        assert!(targets.contains(Square::SQ_5D));
+        assert!(!targets.contains(Square::SQ_4D));
+        assert!(!targets.contains(Square::SQ_4E));
+        assert!(targets.contains(Square::SQ_4F));
+        // 5d, 4f, 5f and 6f
+        assert_eq!(targets.count(), 4);
+
+        // No check
+        position.side_to_move_set(Color::White);
+        assert!(position.evasion_targets().is_empty());
+    }
+
+    #[cfg(feature = "long-effect")]
+    fn assert_consistent(position: &EffectPosition) {
+        let fresh = EffectPosition::new(position.inner().clone());
+        assert_eq!(position.attacks, fresh.attacks);
+        assert_eq!(position.attackers, fresh.attackers);
+        for square in Square::all() {
+            assert_eq!(
+                position.attacks_from(square),
+                position.inner().attacks_from(square),
+            );
+        }
+    }
+
+    #[cfg(feature = "long-effect")]
+    #[test]
+    fn effect_position_works() {
+        use crate::Move;
+
+        let mut position = EffectPosition::new(PartialPosition::startpos());
+        assert_consistent(&position);
+        let moves = [
+            Move::Normal {
+                from: Square::SQ_7G,
+                to: Square::SQ_7F,
+                promote: false,
+            },
+            Move::Normal {
+                from: Square::SQ_3C,
+                to: Square::SQ_3D,
+                promote: false,
+            },
+            Move::Normal {
+                from: Square::SQ_8H,
+                to: Square::SQ_2B,
+                promote: true,
+            },
+            Move::Normal {
+                from: Square::SQ_3A,
+                to: Square::SQ_2B,
+                promote: false,
+            },
+            Move::Drop {
+                piece: Piece::B_B,
+                to: Square::SQ_5E,
+            },
+        ];
+        let mut history = alloc::vec::Vec::new();
+        for mv in moves {
+            let before = position.clone();
+            let delta = position.make_move(mv).unwrap();
+            assert_consistent(&position);
+            history.push((before, delta));
+        }
+        // The bishop on 2b captured the one of black, which is now in white's hand.
+        assert_eq!(history[3].1.captured(), Some(Piece::B_PB));
+        let after = position.clone();
+        for (before, delta) in history.into_iter().rev() {
+            position.unmake_move(delta).unwrap();
+            assert_eq!(position, before);
+            assert_consistent(&position);
+            // `delta` no longer matches the last move.
+            assert!(position.clone().unmake_move(delta).is_none());
+        }
+        position = after;
+        let counts = position.inner().effect_counts(Color::Black);
+        for square in Square::all() {
+            assert_eq!(
+                position.attackers_to(square, Color::Black).count(),
+                counts[square],
+            );
+        }
+        // An invalid move does not modify `position`.
+        let before = position.clone();
+        assert!(position
+            .make_move(Move::Drop {
+                piece: Piece::B_R,
+                to: Square::SQ_5D,
+            })
+            .is_none());
+        assert_eq!(position.attacks, before.attacks);
+        assert_eq!(position.attackers, before.attackers);
+
+        position.piece_set(Square::SQ_5C, None);
+        assert_consistent(&position);
+        position.piece_set(Square::SQ_5C, Some(Piece::W_R));
+        assert_consistent(&position);
+    }
+}
@@ -0,0 +1,297 @@
+//! Attacks of sliding pieces (lances, bishops and rooks) on a board with given occupied squares.
+//!
+//! The functions in this module are a common facade over the backends that compute attacks.
+//! By default, attacks are computed by scanning each direction up to the first occupied square.
+//! With the `pext` feature, lookup tables indexed with the `PEXT` instruction of BMI2 are used instead
+//! if the running x86_64 CPU supports it, which is detected at run time.
+//! The tables (about 650 KiB) are built at the first call of a function in this module.
+//! On other targets or CPUs, the `pext` feature has no effect.
+//!
+//! Both backends give the same results. Attacks include the first occupied square in each direction,
+//! regardless of the color of the piece on it.
+//!
+//! Examples:
+//! ```
+//! # use shogi_core::attacks;
+//! # use shogi_core::{Bitboard, Color, Square};
+//! let occupied = Bitboard::single(Square::SQ_5C) | Bitboard::single(Square::SQ_2E);
+//! let rook = attacks::rook_attacks(Square::SQ_5E, occupied);
+//! assert!(rook.contains(Square::SQ_5C) && !rook.contains(Square::SQ_5B));
+//! assert!(rook.contains(Square::SQ_2E) && !rook.contains(Square::SQ_1E));
+//! assert_eq!(rook.count(), 13);
+//! let lance = attacks::lance_attacks(Color::Black, Square::SQ_5E, occupied);
+//! assert_eq!(lance, Bitboard::single(Square::SQ_5D) | Bitboard::single(Square::SQ_5C));
+//! ```
+
+use crate::{Bitboard, Color, Square};
+
+/// A backend of the functions in this module.
+///
+/// Since: 0.1.6
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Backend {
+    /// Scans each direction up to the first occupied square.
+    Portable,
+    /// Looks up tables indexed with the `PEXT` instruction. Available only with the `pext` feature on x86_64 CPUs with BMI2.
+    Pext,
+}
+
+impl_ord_for_fieldless_enum!(Backend);
+impl_hash_for_fieldless_enum!(Backend);
+
+/// Returns the backend used by the functions in this module.
+///
+/// The result is the same throughout the execution of a program.
+///
+/// Since: 0.1.6
+pub fn backend() -> Backend {
+    #[cfg(all(feature = "pext", target_arch = "x86_64"))]
+    if pext::tables().is_some() {
+        return Backend::Pext;
+    }
+    Backend::Portable
+}
+
+/// Finds the squares a lance of `color` on `square` attacks, where the squares in `occupied` are occupied.
+///
+/// Since: 0.1.6
+pub fn lance_attacks(color: Color, square: Square, occupied: Bitboard) -> Bitboard {
+    #[cfg(all(feature = "pext", target_arch = "x86_64"))]
+    if let Some(tables) = pext::tables() {
+        // Safety: `tables` is `Some` only if the CPU supports BMI2.
+        let file = unsafe { pext::lookup(tables, square, pext::FILE, occupied) };
+        return file & Bitboard::lance_pseudo_attacks(color, square);
+    }
+    let forward = match color {
+        Color::Black => -1,
+        Color::White => 1,
+    };
+    ray_attacks(square, occupied, &[(0, forward)])
+}
+
+/// Finds the squares a bishop on `square` attacks, where the squares in `occupied` are occupied.
+///
+/// Since: 0.1.6
+pub fn bishop_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    #[cfg(all(feature = "pext", target_arch = "x86_64"))]
+    if let Some(tables) = pext::tables() {
+        // Safety: `tables` is `Some` only if the CPU supports BMI2.
+        return unsafe {
+            pext::lookup(tables, square, pext::DIAGONAL, occupied)
+                | pext::lookup(tables, square, pext::ANTI_DIAGONAL, occupied)
+        };
+    }
+    ray_attacks(square, occupied, &DIAGONAL_DIRECTIONS)
+}
+
+/// Finds the squares a rook on `square` attacks, where the squares in `occupied` are occupied.
+///
+/// Since: 0.1.6
+pub fn rook_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    #[cfg(all(feature = "pext", target_arch = "x86_64"))]
+    if let Some(tables) = pext::tables() {
+        // Safety: `tables` is `Some` only if the CPU supports BMI2.
+        return unsafe {
+            pext::lookup(tables, square, pext::FILE, occupied)
+                | pext::lookup(tables, square, pext::RANK, occupied)
+        };
+    }
+    ray_attacks(square, occupied, &ORTHOGONAL_DIRECTIONS)
+}
+
+const DIAGONAL_DIRECTIONS: [(i8, i8); 4] = [(1, 1), (-1, -1), (1, -1), (-1, 1)];
+const ORTHOGONAL_DIRECTIONS: [(i8, i8); 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+
+// The portable backend: scans each direction in `directions` up to the first occupied square.
+fn ray_attacks(square: Square, occupied: Bitboard, directions: &[(i8, i8)]) -> Bitboard {
+    let mut result = Bitboard::empty();
+    for &(file_delta, rank_delta) in directions {
+        let mut current = square;
+        while let Some(next) = current.shift(file_delta, rank_delta) {
+            result |= next;
+            if occupied.contains(next) {
+                break;
+            }
+            current = next;
+        }
+    }
+    result
+}
+
+#[cfg(all(feature = "pext", target_arch = "x86_64"))]
+mod pext {
+    use core::arch::x86_64::_pext_u64;
+    use core::sync::atomic::{AtomicPtr, Ordering};
+    use std::boxed::Box;
+    use std::sync::Once;
+    use std::vec::Vec;
+
+    use super::ray_attacks;
+    use crate::{Bitboard, Square};
+
+    // Lines through a square, each of which consists of two opposite directions.
+    pub(super) const FILE: usize = 0;
+    pub(super) const RANK: usize = 1;
+    pub(super) const DIAGONAL: usize = 2;
+    pub(super) const ANTI_DIAGONAL: usize = 3;
+    const LINES: [[(i8, i8); 2]; 4] = [
+        [(0, 1), (0, -1)],
+        [(1, 0), (-1, 0)],
+        [(1, 1), (-1, -1)],
+        [(1, -1), (-1, 1)],
+    ];
+    // A line has at most 7 squares that can block a slider, so 7 bits suffice for an index.
+    const STRIDE: usize = 1 << 7;
+
+    pub(super) struct Tables {
+        // masks[square][line]: the squares on `line` through `square` that can block a slider,
+        // i.e., those except `square` and the squares on the edge, as the lower and upper halves of `Bitboard::to_u128`.
+        masks: [[[u64; 2]; 4]; 81],
+        // attacks[(square * 4 + line) * STRIDE + index]
+        attacks: Vec<Bitboard>,
+    }
+
+    impl Tables {
+        fn new() -> Self {
+            let mut masks = [[[0; 2]; 4]; 81];
+            let mut attacks = Vec::with_capacity(81 * 4 * STRIDE);
+            for square in Square::all() {
+                for (line, directions) in LINES.iter().enumerate() {
+                    let mut mask = Bitboard::empty();
+                    for &(file_delta, rank_delta) in directions {
+                        let mut current = square;
+                        while let Some(next) = current.shift(file_delta, rank_delta) {
+                            if next.shift(file_delta, rank_delta).is_none() {
+                                break;
+                            }
+                            mask |= next;
+                            current = next;
+                        }
+                    }
+                    let repr = mask.to_u128();
+                    masks[square.array_index()][line] = [repr as u64, (repr >> 64) as u64];
+                    // Squares in `mask`, in the order of bits extracted by PEXT.
+                    let blockers: Vec<Square> = mask.collect();
+                    for index in 0..STRIDE {
+                        let mut occupied = Bitboard::empty();
+                        for (bit, &blocker) in blockers.iter().enumerate() {
+                            if index & 1 << bit != 0 {
+                                occupied |= blocker;
+                            }
+                        }
+                        attacks.push(ray_attacks(square, occupied, directions));
+                    }
+                }
+            }
+            Self { masks, attacks }
+        }
+    }
+
+    static TABLES: AtomicPtr<Tables> = AtomicPtr::new(core::ptr::null_mut());
+    static INIT: Once = Once::new();
+
+    // Returns the tables if the CPU supports BMI2, building them at the first call.
+    pub(super) fn tables() -> Option<&'static Tables> {
+        INIT.call_once(|| {
+            if std::is_x86_feature_detected!("bmi2") {
+                TABLES.store(Box::into_raw(Box::new(Tables::new())), Ordering::Release);
+            }
+        });
+        // Safety: the pointer is either null or made from a `Box` that is never freed.
+        unsafe { TABLES.load(Ordering::Acquire).as_ref() }
+    }
+
+    // Safety: the CPU must support BMI2.
+    #[target_feature(enable = "bmi2")]
+    pub(super) unsafe fn lookup(
+        tables: &Tables,
+        square: Square,
+        line: usize,
+        occupied: Bitboard,
+    ) -> Bitboard {
+        let [mask0, mask1] = tables.masks[square.array_index()][line];
+        let repr = occupied.to_u128();
+        let index = _pext_u64(repr as u64, mask0)
+            | _pext_u64((repr >> 64) as u64, mask1) << mask0.count_ones();
+        tables.attacks[(square.array_index() * 4 + line) * STRIDE + index as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reference implementation
+    fn slide(square: Square, occupied: Bitboard, directions: &[(i8, i8)]) -> Bitboard {
+        let mut result = Bitboard::empty();
+        for &(file_delta, rank_delta) in directions {
+            for distance in 1..9 {
+                let next = match square.shift(file_delta * distance, rank_delta * distance) {
+                    Some(next) => next,
+                    None => break,
+                };
+                result |= next;
+                if occupied.contains(next) {
+                    break;
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn attacks_work() {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..64 {
+            // xorshift64
+            let mut occupied = Bitboard::empty();
+            for square in Square::all() {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                if state % 3 == 0 {
+                    occupied |= square;
+                }
+            }
+            for square in Square::all() {
+                assert_eq!(
+                    lance_attacks(Color::Black, square, occupied),
+                    slide(square, occupied, &[(0, -1)]),
+                );
+                assert_eq!(
+                    lance_attacks(Color::White, square, occupied),
+                    slide(square, occupied, &[(0, 1)]),
+                );
+                assert_eq!(
+                    bishop_attacks(square, occupied),
+                    slide(square, occupied, &DIAGONAL_DIRECTIONS),
+                );
+                assert_eq!(
+                    rook_attacks(square, occupied),
+                    slide(square, occupied, &ORTHOGONAL_DIRECTIONS),
+                );
+            }
+        }
+        for square in Square::all() {
+            assert_eq!(
+                bishop_attacks(square, Bitboard::empty()),
+                Bitboard::bishop_pseudo_attacks(square),
+            );
+            assert_eq!(
+                rook_attacks(square, Bitboard::empty()),
+                Bitboard::rook_pseudo_attacks(square),
+            );
+        }
+    }
+
+    #[cfg(all(feature = "pext", target_arch = "x86_64"))]
+    #[test]
+    fn backend_works() {
+        let expected = if std::is_x86_feature_detected!("bmi2") {
+            Backend::Pext
+        } else {
+            Backend::Portable
+        };
+        assert_eq!(backend(), expected);
+    }
+}
@@ -1,6 +1,8 @@
-use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use core::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Index, Not, RangeInclusive,
+};
 
-use crate::{c_compat::OptionSquare, Square};
+use crate::{c_compat::OptionSquare, Color, Square};
 
 /// A subset of all squares.
 ///
@@ -30,7 +32,8 @@ impl Bitboard {
     }
 
     /// C interface to [`Bitboard::empty`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Bitboard_empty() -> Self {
         Self::empty()
     }
@@ -61,7 +64,8 @@ impl Bitboard {
     }
 
     /// C interface to [`Bitboard::single`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Bitboard_single(square: Square) -> Self {
         Self::single(square)
     }
@@ -75,8 +79,8 @@ impl Bitboard {
     /// let sq55 = Bitboard::single(Square::SQ_5E);
     /// assert_eq!((sq11 | sq55).count(), 2);
     /// ```
-    #[export_name = "Bitboard_count"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "Bitboard_count")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn count(self) -> u8 {
         (self.0[0].count_ones() + self.0[1].count_ones()) as u8
     }
@@ -93,8 +97,8 @@ impl Bitboard {
     /// assert!(!(sq11 | sq55).is_empty());
     /// assert!(Bitboard::empty().is_empty());
     /// ```
-    #[export_name = "Bitboard_is_empty"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "Bitboard_is_empty")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn is_empty(self) -> bool {
         self.0 == [0; 2]
     }
@@ -108,7 +112,7 @@ impl Bitboard {
     /// assert!(sq11.contains(Square::SQ_1A));
     /// assert!(!sq11.contains(Square::SQ_9I));
     /// ```
-    #[export_name = "Bitboard_contains"]
+    #[cfg_attr(feature = "c-exports", export_name = "Bitboard_contains")]
     pub extern "C" fn contains(self, square: Square) -> bool {
         let index = square.index() - 1;
         let overlap = if index < 63 {
@@ -128,7 +132,7 @@ impl Bitboard {
     /// let sq99 = Bitboard::single(Square::SQ_9I);
     /// assert_eq!(sq11.flip(), sq99);
     /// ```
-    #[export_name = "Bitboard_flip"]
+    #[cfg_attr(feature = "c-exports", export_name = "Bitboard_flip")]
     pub extern "C" fn flip(self) -> Self {
         let fst_rev = ((self.0[0] >> 17) | (self.0[1] << 46)) & !1;
         let snd_rev = self.0[0] << 46;
@@ -171,7 +175,8 @@ impl Bitboard {
     }
 
     /// C interface of [`Bitboard::pop`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Bitboard_pop(&mut self) -> OptionSquare {
         self.pop().into()
     }
@@ -242,6 +247,209 @@ impl Bitboard {
         pattern as u16 & 0x1ff
     }
 
+    /// Finds the files that contain at least one square in `self`.
+    ///
+    /// Bit `file - 1` of the result is set if and only if the file `file` contains a square in `self`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Bitboard, Square};
+    /// let bitboard = Bitboard::single(Square::SQ_7G) | Bitboard::single(Square::SQ_1A);
+    /// assert_eq!(bitboard.files_occupied(), 1 << 6 | 1 << 0);
+    /// assert_eq!(Bitboard::empty().files_occupied(), 0);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub const fn files_occupied(self) -> u16 {
+        let mut result = 0;
+        let mut file = 1;
+        while file <= 9 {
+            // Safety: 1 <= file <= 9
+            if unsafe { self.get_file_unchecked(file) } != 0 {
+                result |= 1 << (file - 1);
+            }
+            file += 1;
+        }
+        result
+    }
+
+    /// C interface of [`Bitboard::files_occupied`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn Bitboard_files_occupied(self) -> u16 {
+        self.files_occupied()
+    }
+
+    /// Finds the ranks that contain at least one square in `self`.
+    ///
+    /// Bit `rank - 1` of the result is set if and only if the rank `rank` contains a square in `self`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Bitboard, Square};
+    /// let bitboard = Bitboard::single(Square::SQ_7G) | Bitboard::single(Square::SQ_1A);
+    /// assert_eq!(bitboard.ranks_occupied(), 1 << 6 | 1 << 0);
+    /// assert_eq!(Bitboard::empty().ranks_occupied(), 0);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub const fn ranks_occupied(self) -> u16 {
+        let mut result = 0;
+        let mut file = 1;
+        while file <= 9 {
+            // Safety: 1 <= file <= 9
+            result |= unsafe { self.get_file_unchecked(file) };
+            file += 1;
+        }
+        result
+    }
+
+    /// C interface of [`Bitboard::ranks_occupied`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn Bitboard_ranks_occupied(self) -> u16 {
+        self.ranks_occupied()
+    }
+
+    /// Creates a [`Bitboard`] with all squares whose file is in `files` and whose rank is in `ranks`.
+    ///
+    /// Files and ranks outside `1..=9` are ignored, so an empty or out-of-range range gives an empty [`Bitboard`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Bitboard, Square};
+    /// // The camp of black
+    /// let camp = Bitboard::rect(1..=9, 7..=9);
+    /// assert_eq!(camp.count(), 27);
+    /// assert!(camp.contains(Square::SQ_5I) && !camp.contains(Square::SQ_5F));
+    /// let corner = Bitboard::rect(8..=9, 8..=10);
+    /// assert_eq!(corner, Bitboard::single(Square::SQ_8H) | Bitboard::single(Square::SQ_8I) | Bitboard::single(Square::SQ_9H) | Bitboard::single(Square::SQ_9I));
+    /// assert!(Bitboard::rect(3..=2, 1..=9).is_empty());
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub const fn rect(files: RangeInclusive<u8>, ranks: RangeInclusive<u8>) -> Self {
+        let file_from = if *files.start() < 1 {
+            1
+        } else {
+            *files.start()
+        };
+        let file_to = if *files.end() > 9 { 9 } else { *files.end() };
+        let rank_from = if *ranks.start() < 1 {
+            1
+        } else {
+            *ranks.start()
+        };
+        let rank_to = if *ranks.end() > 9 { 9 } else { *ranks.end() };
+        if file_from > file_to || rank_from > rank_to {
+            return Self::empty();
+        }
+        // bits rank_from - 1 ..= rank_to - 1
+        let pattern = (1u16 << rank_to) - (1u16 << (rank_from - 1));
+        let mut result = Self::empty();
+        let mut file = file_from;
+        while file <= file_to {
+            // Safety: 1 <= file <= 9, 0 <= pattern < 512
+            result = result.or(unsafe { Self::from_file_unchecked(file, pattern) });
+            file += 1;
+        }
+        result
+    }
+
+    /// C interface of [`Bitboard::rect`]. The bounds are inclusive.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn Bitboard_rect(
+        file_from: u8,
+        file_to: u8,
+        rank_from: u8,
+        rank_to: u8,
+    ) -> Self {
+        Self::rect(file_from..=file_to, rank_from..=rank_to)
+    }
+
+    /// Creates a [`Bitboard`] with all squares strictly in front of the rank `rank`, as seen from `color`.
+    ///
+    /// `rank` is an absolute rank, i.e., rank 1 is the farthest from black.
+    /// For example, the squares in front of rank 7 are those in ranks 1 to 6 for black and those in ranks 8 and 9 for white.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Bitboard, Color};
+    /// assert_eq!(Bitboard::front_of(Color::Black, 7), Bitboard::rect(1..=9, 1..=6));
+    /// assert_eq!(Bitboard::front_of(Color::White, 7), Bitboard::rect(1..=9, 8..=9));
+    /// assert!(Bitboard::front_of(Color::Black, 1).is_empty());
+    /// // Evaluated at compile time
+    /// const BLACK_ENEMY_CAMP: Bitboard = Bitboard::front_of(Color::Black, 4);
+    /// assert_eq!(BLACK_ENEMY_CAMP.count(), 27);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub const fn front_of(color: Color, rank: u8) -> Self {
+        match color {
+            Color::Black => Self::rect(1..=9, 1..=rank.saturating_sub(1)),
+            Color::White => Self::rect(1..=9, rank.saturating_add(1)..=9),
+        }
+    }
+
+    /// C interface of [`Bitboard::front_of`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn Bitboard_front_of(color: Color, rank: u8) -> Self {
+        Self::front_of(color, rank)
+    }
+
+    /// Creates a [`Bitboard`] with all squares strictly behind the rank `rank`, as seen from `color`.
+    ///
+    /// `rank` is an absolute rank. `Bitboard::behind(color, rank)` is the mirror image of
+    /// `Bitboard::front_of(color, rank)` with respect to the rank `rank`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Bitboard, Color};
+    /// assert_eq!(Bitboard::behind(Color::Black, 7), Bitboard::rect(1..=9, 8..=9));
+    /// assert_eq!(Bitboard::behind(Color::White, 7), Bitboard::rect(1..=9, 1..=6));
+    /// assert!(Bitboard::behind(Color::White, 1).is_empty());
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub const fn behind(color: Color, rank: u8) -> Self {
+        match color {
+            Color::Black => Self::front_of(Color::White, rank),
+            Color::White => Self::front_of(Color::Black, rank),
+        }
+    }
+
+    /// C interface of [`Bitboard::behind`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn Bitboard_behind(color: Color, rank: u8) -> Self {
+        Self::behind(color, rank)
+    }
+
+    /// Creates a [`Bitboard`] with all squares in the given files.
+    ///
+    /// Files outside `1..=9` are ignored.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Bitboard, Color, Square};
+    /// let edges = Bitboard::files_of([1, 9]);
+    /// assert_eq!(edges, Bitboard::rect(1..=1, 1..=9) | Bitboard::rect(9..=9, 1..=9));
+    /// // Squares in front of pawns of black, in files with a pawn
+    /// let pawns = Bitboard::single(Square::SQ_7G) | Bitboard::single(Square::SQ_2F);
+    /// let mask = Bitboard::files_of(pawns.map(|sq| sq.file())) & Bitboard::front_of(Color::Black, 5);
+    /// assert_eq!(mask.count(), 8);
+    /// assert!(Bitboard::files_of([0, 10]).is_empty());
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn files_of<I: IntoIterator<Item = u8>>(files: I) -> Self {
+        files.into_iter().fold(Self::empty(), |acc, file| {
+            acc | Self::rect(file..=file, 1..=9)
+        })
+    }
+
     /// Bitwise or.
     ///
     /// Since: 0.1.3
@@ -353,8 +561,76 @@ impl Bitboard {
         };
         Self(data)
     }
+
+    /// Finds the squares a lance of `color` on `square` attacks on an empty board.
+    ///
+    /// The result is read from a precomputed table.
+    /// Squares actually attacked are found by cutting it at the first occupied square.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::{Bitboard, Color, Square};
+    /// let attacks = Bitboard::lance_pseudo_attacks(Color::Black, Square::SQ_1C);
+    /// assert_eq!(attacks, Bitboard::single(Square::SQ_1A) | Bitboard::single(Square::SQ_1B));
+    /// assert!(Bitboard::lance_pseudo_attacks(Color::White, Square::SQ_1I).is_empty());
+    /// ```
+    #[inline(always)]
+    pub const fn lance_pseudo_attacks(color: Color, square: Square) -> Self {
+        LANCE_PSEUDO_ATTACKS[color.array_index()][square.array_index()]
+    }
+
+    /// C interface to [`Bitboard::lance_pseudo_attacks`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn Bitboard_lance_pseudo_attacks(color: Color, square: Square) -> Self {
+        Self::lance_pseudo_attacks(color, square)
+    }
+
+    /// Finds the squares a bishop on `square` attacks on an empty board.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::{Bitboard, Square};
+    /// assert_eq!(Bitboard::bishop_pseudo_attacks(Square::SQ_1A).count(), 8);
+    /// assert_eq!(Bitboard::bishop_pseudo_attacks(Square::SQ_5E).count(), 16);
+    /// assert!(Bitboard::bishop_pseudo_attacks(Square::SQ_5E).contains(Square::SQ_9A));
+    /// ```
+    #[inline(always)]
+    pub const fn bishop_pseudo_attacks(square: Square) -> Self {
+        BISHOP_PSEUDO_ATTACKS[square.array_index()]
+    }
+
+    /// C interface to [`Bitboard::bishop_pseudo_attacks`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn Bitboard_bishop_pseudo_attacks(square: Square) -> Self {
+        Self::bishop_pseudo_attacks(square)
+    }
+
+    /// Finds the squares a rook on `square` attacks on an empty board.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::{Bitboard, Square};
+    /// assert_eq!(Bitboard::rook_pseudo_attacks(Square::SQ_1A).count(), 16);
+    /// assert!(Bitboard::rook_pseudo_attacks(Square::SQ_5E).contains(Square::SQ_5I));
+    /// assert!(!Bitboard::rook_pseudo_attacks(Square::SQ_5E).contains(Square::SQ_5E));
+    /// ```
+    #[inline(always)]
+    pub const fn rook_pseudo_attacks(square: Square) -> Self {
+        ROOK_PSEUDO_ATTACKS[square.array_index()]
+    }
+
+    /// C interface to [`Bitboard::rook_pseudo_attacks`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn Bitboard_rook_pseudo_attacks(square: Square) -> Self {
+        Self::rook_pseudo_attacks(square)
+    }
 }
 
+include!(concat!(env!("OUT_DIR"), "/bitboard_tables.rs"));
+
 impl Iterator for Bitboard {
     type Item = Square;
 
@@ -449,29 +725,35 @@ define_bit_trait!(
 // We need to define them manually for cbindgen to find and make bindings of them.
 #[doc(hidden)]
 impl Bitboard {
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Bitboard_bitand(a: Bitboard, b: Bitboard) -> Bitboard {
         a & b
     }
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Bitboard_bitand_assign(a: &mut Bitboard, b: Bitboard) {
         *a &= b;
     }
 
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Bitboard_bitor(a: Bitboard, b: Bitboard) -> Bitboard {
         a | b
     }
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Bitboard_bitor_assign(a: &mut Bitboard, b: Bitboard) {
         *a |= b;
     }
 
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Bitboard_bitxor(a: Bitboard, b: Bitboard) -> Bitboard {
         a ^ b
     }
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Bitboard_bitxor_assign(a: &mut Bitboard, b: Bitboard) {
         *a ^= b;
     }
@@ -513,14 +795,42 @@ impl Not for &'_ Bitboard {
     }
 }
 
+impl Index<Square> for Bitboard {
+    type Output = bool;
+
+    /// Finds if `self` as a subset contains a [`Square`]. Same as [`Bitboard::contains`].
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::{Bitboard, Square};
+    /// let sq11 = Bitboard::single(Square::SQ_1A);
+    /// assert!(sq11[Square::SQ_1A]);
+    /// assert!(!sq11[Square::SQ_9I]);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[inline(always)]
+    fn index(&self, square: Square) -> &bool {
+        // Constants are promoted to `'static`.
+        if self.contains(square) {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
 /// C interface of `Bitboard::not`.
-#[no_mangle]
+#[allow(non_snake_case)]
+#[cfg_attr(feature = "c-exports", no_mangle)]
+#[cfg_attr(not(feature = "c-exports"), allow(dead_code))]
 pub extern "C" fn Bitboard_not(a: Bitboard) -> Bitboard {
     !a
 }
 
 impl_ord_for_single_field!(Bitboard);
 impl_hash_for_single_field!(Bitboard);
+impl_rkyv_as_self!(Bitboard);
 
 /// A [`Bitboard`] with its all bytes reversed.
 ///
@@ -629,6 +939,7 @@ mod tests {
                     for orank in 1..=9 {
                         let osq = Square::new(ofile, orank).unwrap();
                         assert_eq!(Bitboard::single(sq).contains(osq), sq == osq);
+                        assert_eq!(Bitboard::single(sq)[osq], sq == osq);
                     }
                 }
             }
@@ -645,6 +956,95 @@ mod tests {
         }
     }
 
+    #[test]
+    fn occupied_works() {
+        for square in Square::all() {
+            let bitboard = Bitboard::single(square);
+            assert_eq!(bitboard.files_occupied(), 1 << (square.file() - 1));
+            assert_eq!(bitboard.ranks_occupied(), 1 << (square.rank() - 1));
+        }
+        let bitboard = from_strs([
+            b".........",
+            b"*........",
+            b".........",
+            b"....*....",
+            b".........",
+            b".........",
+            b"....*...*",
+            b".........",
+            b".........",
+        ]);
+        assert_eq!(bitboard.files_occupied(), 0b100010001);
+        assert_eq!(bitboard.ranks_occupied(), 0b001001010);
+        assert_eq!(Bitboard::empty().files_occupied(), 0);
+        assert_eq!((!Bitboard::empty()).ranks_occupied(), 0x1ff);
+    }
+
+    #[test]
+    fn rect_works() {
+        for file_from in 0..=10 {
+            for file_to in 0..=10 {
+                for rank_from in 0..=10 {
+                    for rank_to in 0..=10 {
+                        let mut expected = Bitboard::empty();
+                        for square in Square::all() {
+                            if (file_from..=file_to).contains(&square.file())
+                                && (rank_from..=rank_to).contains(&square.rank())
+                            {
+                                expected |= square;
+                            }
+                        }
+                        assert_eq!(
+                            Bitboard::rect(file_from..=file_to, rank_from..=rank_to),
+                            expected,
+                        );
+                    }
+                }
+            }
+        }
+        assert_eq!(Bitboard::rect(1..=9, 1..=9), !Bitboard::empty());
+    }
+
+    #[test]
+    fn front_of_and_behind_work() {
+        for color in Color::all() {
+            for rank in 0..=10 {
+                let mut front = Bitboard::empty();
+                let mut behind = Bitboard::empty();
+                for square in Square::all() {
+                    let is_front = match color {
+                        Color::Black => square.rank() < rank,
+                        Color::White => square.rank() > rank,
+                    };
+                    let is_behind = match color {
+                        Color::Black => square.rank() > rank,
+                        Color::White => square.rank() < rank,
+                    };
+                    if is_front {
+                        front |= square;
+                    }
+                    if is_behind {
+                        behind |= square;
+                    }
+                }
+                assert_eq!(Bitboard::front_of(color, rank), front);
+                assert_eq!(Bitboard::behind(color, rank), behind);
+            }
+        }
+        assert_eq!(Bitboard::front_of(Color::White, 255), Bitboard::empty());
+        assert_eq!(Bitboard::behind(Color::Black, 255), Bitboard::empty());
+    }
+
+    #[test]
+    fn files_of_works() {
+        assert_eq!(Bitboard::files_of(1..=9), !Bitboard::empty());
+        assert_eq!(Bitboard::files_of([]), Bitboard::empty());
+        assert_eq!(Bitboard::files_of([5, 5]).count(), 9);
+        for square in Bitboard::files_of([2, 4, 11]) {
+            assert!(square.file() == 2 || square.file() == 4);
+        }
+    }
+
     #[test]
     fn pop_works() {
         for square in Square::all() {
@@ -666,6 +1066,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn pseudo_attacks_work() {
+        fn slide(square: Square, directions: &[(i8, i8)]) -> Bitboard {
+            let mut result = Bitboard::empty();
+            for &(file_delta, rank_delta) in directions {
+                let mut current = square;
+                while let Some(next) = current.shift(file_delta, rank_delta) {
+                    result |= next;
+                    current = next;
+                }
+            }
+            result
+        }
+        for square in Square::all() {
+            assert_eq!(
+                Bitboard::lance_pseudo_attacks(Color::Black, square),
+                slide(square, &[(0, -1)]),
+            );
+            assert_eq!(
+                Bitboard::lance_pseudo_attacks(Color::White, square),
+                slide(square, &[(0, 1)]),
+            );
+            assert_eq!(
+                Bitboard::bishop_pseudo_attacks(square),
+                slide(square, &[(1, 1), (1, -1), (-1, 1), (-1, -1)]),
+            );
+            assert_eq!(
+                Bitboard::rook_pseudo_attacks(square),
+                slide(square, &[(1, 0), (-1, 0), (0, 1), (0, -1)]),
+            );
+        }
+        let sample = from_strs([
+            b"....*....",
+            b"....*....",
+            b"....*....",
+            b"....*....",
+            b"****.****",
+            b"....*....",
+            b"....*....",
+            b"....*....",
+            b"....*....",
+        ]);
+        assert_eq!(Bitboard::rook_pseudo_attacks(Square::SQ_5E), sample);
+    }
+
     #[cfg(bench)]
     #[bench]
     fn pop_bench(b: &mut test::Bencher) {
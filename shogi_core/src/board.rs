@@ -0,0 +1,240 @@
+use core::fmt::{Result as FmtResult, Write};
+use core::ops::{Index, IndexMut};
+
+use crate::common::{write_ascii_byte, write_u8};
+use crate::{Flip, PartialPosition, Piece, Square, ToUsi};
+
+/// The pieces on the 81 squares of a board, without hands, the side to move or the ply.
+///
+/// This is useful where a whole [`PartialPosition`] is unnecessary, e.g. for drawing diagrams.
+/// Indexing a [`Board`] with a [`Square`] gives the piece on it.
+///
+/// Because [`Board`] is cheap to copy, it implements [`Copy`].
+/// Its [`Default`] value is an empty board.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Board, Piece, Square};
+/// let mut board = Board::startpos();
+/// assert_eq!(board[Square::SQ_5I], Some(Piece::B_K));
+/// board[Square::SQ_7F] = board[Square::SQ_7G].take();
+/// assert_eq!(
+///     board.to_sfen_owned(),
+///     "lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL",
+/// );
+/// assert_eq!(board.pieces().count(), 40);
+/// ```
+///
+/// Since: 0.1.6
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Board([Option<Piece>; Square::NUM]);
+
+impl Board {
+    /// Creates an empty [`Board`].
+    #[inline]
+    pub const fn empty() -> Self {
+        Self([None; Square::NUM])
+    }
+
+    /// Returns the board of the starting position of a game.
+    pub fn startpos() -> Self {
+        PartialPosition::startpos().board()
+    }
+
+    /// Creates a [`Board`] from an array indexed by [`Square::array_index`].
+    #[inline]
+    pub const fn from_array(pieces: [Option<Piece>; Square::NUM]) -> Self {
+        Self(pieces)
+    }
+
+    /// Returns the pieces as an array indexed by [`Square::array_index`].
+    #[inline]
+    pub const fn as_array(&self) -> &[Option<Piece>; Square::NUM] {
+        &self.0
+    }
+
+    /// Returns an iterator over all squares and the pieces on them, in the order of [`Square::all`].
+    pub fn iter(&self) -> impl Iterator<Item = (Square, Option<Piece>)> + '_ {
+        Square::all().zip(self.0.iter().copied())
+    }
+
+    /// Returns an iterator over the occupied squares and the pieces on them, in the order of [`Square::all`].
+    pub fn pieces(&self) -> impl Iterator<Item = (Square, Piece)> + '_ {
+        self.iter()
+            .filter_map(|(square, piece)| piece.map(|piece| (square, piece)))
+    }
+
+    /// Returns the board mirrored horizontally, i.e., the piece on file `f` is moved to file `10 - f`.
+    /// The colors of pieces are kept.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Board, Piece, Square};
+    /// let board = Board::from_sfen("8k/9/9/9/9/9/9/9/KL7").unwrap().mirror();
+    /// assert_eq!(board[Square::SQ_1I], Some(Piece::B_K));
+    /// assert_eq!(board[Square::SQ_2I], Some(Piece::B_L));
+    /// assert_eq!(board[Square::SQ_9A], Some(Piece::W_K));
+    /// ```
+    #[must_use]
+    pub fn mirror(self) -> Self {
+        let mut result = Self::empty();
+        for (square, piece) in self.iter() {
+            // Safety: 1 <= 10 - square.file() <= 9
+            let mirrored = unsafe {
+                crate::common::unwrap_unchecked(Square::new(10 - square.file(), square.rank()))
+            };
+            result[mirrored] = piece;
+        }
+        result
+    }
+
+    /// Parses the board field of SFEN, e.g. `lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL`.
+    ///
+    /// Only the syntax is checked, as in [`PartialPosition::parse_sfen_board`].
+    /// If `board` is not a valid board field, this function returns [`None`].
+    pub fn from_sfen(board: &str) -> Option<Self> {
+        PartialPosition::parse_sfen_board(board).map(Self)
+    }
+
+    /// Writes `self` as the board field of SFEN.
+    ///
+    /// This function returns Err(core::fmt::Error) if and only if it fails to write to `sink`.
+    pub fn to_sfen<W: Write>(&self, sink: &mut W) -> FmtResult {
+        for rank in 1..=9 {
+            let mut vacant = 0;
+            for file in (1..=9).rev() {
+                // Safety: 1 <= file, rank <= 9
+                let square = unsafe { crate::common::unwrap_unchecked(Square::new(file, rank)) };
+                if let Some(piece) = self[square] {
+                    if vacant > 0 {
+                        write_u8(sink, vacant)?;
+                        vacant = 0;
+                    }
+                    piece.to_usi(sink)?;
+                } else {
+                    vacant += 1;
+                }
+            }
+            if vacant > 0 {
+                write_u8(sink, vacant)?;
+            }
+            if rank < 9 {
+                // Safety: '/' is in ASCII
+                unsafe { write_ascii_byte(sink, b'/') }?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `self` as the board field of SFEN. See [`Board::to_sfen`].
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn to_sfen_owned(&self) -> alloc::string::String {
+        let mut s = alloc::string::String::new();
+        // guaranteed to be Ok(())
+        let result = self.to_sfen(&mut s);
+        debug_assert_eq!(result, Ok(()));
+        s
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl Index<Square> for Board {
+    type Output = Option<Piece>;
+
+    #[inline(always)]
+    fn index(&self, square: Square) -> &Option<Piece> {
+        // Safety: square.array_index() < Square::NUM
+        unsafe { crate::common::get_unchecked(&self.0, square.array_index()) }
+    }
+}
+
+impl IndexMut<Square> for Board {
+    #[inline(always)]
+    fn index_mut(&mut self, square: Square) -> &mut Option<Piece> {
+        // Safety: square.array_index() < Square::NUM
+        unsafe { crate::common::get_unchecked_mut(&mut self.0, square.array_index()) }
+    }
+}
+
+impl Flip for Board {
+    fn flip(self) -> Self {
+        let mut result = Self::empty();
+        for (square, piece) in self.iter() {
+            result[square.flip()] = piece.map(Flip::flip);
+        }
+        result
+    }
+}
+
+impl_ord_for_single_field!(Board);
+impl_hash_for_single_field!(Board);
+
+impl PartialPosition {
+    /// Returns the pieces on the board of `self`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{PartialPosition, Piece, Square};
+    /// let board = PartialPosition::startpos().board();
+    /// assert_eq!(board[Square::SQ_2H], Some(Piece::B_R));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn board(&self) -> Board {
+        let mut result = Board::empty();
+        for square in Square::all() {
+            result[square] = self.piece_at(square);
+        }
+        result
+    }
+
+    /// Replaces the pieces on the board of `self` with those in `board`.
+    ///
+    /// As with [`PartialPosition::piece_set`], this function makes no guarantee about the consistency of the position.
+    ///
+    /// Since: 0.1.6
+    pub fn board_set(&mut self, board: &Board) {
+        for (square, piece) in board.iter() {
+            if self.piece_at(square) != piece {
+                self.piece_set(square, piece);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn board_works() {
+        let sfen = "l6nl/5+P1gk/2np1S3/p1p4Pp/3P2Sp1/1PPb2P1P/P5GS1/R8/LN4bKL w RGgsn5p 75";
+        let position = PartialPosition::from_sfen(sfen).unwrap();
+        let board = position.board();
+        let board_field = sfen.split(' ').next().unwrap();
+        assert_eq!(Board::from_sfen(board_field), Some(board));
+        assert_eq!(board.to_sfen_owned(), board_field);
+
+        assert_eq!(board.flip(), position.clone().flip().board());
+        assert_eq!(board.flip().flip(), board);
+        assert_eq!(board.mirror().mirror(), board);
+        assert_ne!(board.mirror(), board);
+        assert_eq!(
+            board.pieces().count(),
+            position.occupied_bitboard().count() as usize
+        );
+
+        let mut other = PartialPosition::startpos();
+        other.board_set(&board);
+        assert_eq!(other.board(), board);
+        assert_eq!(other.occupied_bitboard(), position.occupied_bitboard());
+        assert_eq!(Board::default().pieces().count(), 0);
+        assert_eq!(Board::from_sfen("9/9/9/9/9/9/9/9/8"), None);
+    }
+}
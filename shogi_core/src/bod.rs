@@ -0,0 +1,227 @@
+//! BOD, the board diagram of KIF.
+//!
+//! A diagram looks like this:
+//! ```text
+//! 後手の持駒：なし
+//!   ９ ８ ７ ６ ５ ４ ３ ２ １
+//! +---------------------------+
+//! |v香v桂v銀v金v玉v金v銀v桂v香|一
+//! | ・v飛 ・ ・ ・ ・ ・v角 ・|二
+//! |v歩v歩v歩v歩v歩v歩v歩v歩v歩|三
+//! | ・ ・ ・ ・ ・ ・ ・ ・ ・|四
+//! | ・ ・ ・ ・ ・ ・ ・ ・ ・|五
+//! | ・ ・ ・ ・ ・ ・ ・ ・ ・|六
+//! | 歩 歩 歩 歩 歩 歩 歩 歩 歩|七
+//! | ・ 角 ・ ・ ・ ・ ・ 飛 ・|八
+//! | 香 桂 銀 金 玉 金 銀 桂 香|九
+//! +---------------------------+
+//! 先手の持駒：なし
+//! 先手番
+//! ```
+
+use crate::kanji::{piece_kind_from_char, RANKS};
+use crate::{Color, Hand, PartialPosition, Piece, Square};
+
+// Parses a number in kanji from 1 to 19, e.g. `十八`.
+fn parse_kanji_number(s: &str) -> Option<u8> {
+    let digit = |c: char| RANKS.iter().position(|&x| x == c).map(|i| i as u8 + 1);
+    let mut chars = s.chars();
+    let result = match (chars.next()?, chars.next()) {
+        ('十', None) => 10,
+        ('十', Some(c)) => 10 + digit(c)?,
+        (c, None) => digit(c)?,
+        _ => return None,
+    };
+    if chars.next().is_some() {
+        return None;
+    }
+    Some(result)
+}
+
+// Parses the pieces in hand, e.g. `飛　金二　歩十八`.
+fn parse_hand(s: &str) -> Option<Hand> {
+    let mut hand = Hand::new();
+    let s = s.trim_matches(['　', ' ']);
+    if s == "なし" {
+        return Some(hand);
+    }
+    for item in s.split(['　', ' ']).filter(|item| !item.is_empty()) {
+        let mut chars = item.chars();
+        let piece_kind = piece_kind_from_char(chars.next()?)?;
+        let rest = chars.as_str();
+        let count = if rest.is_empty() {
+            1
+        } else {
+            parse_kanji_number(rest)?
+        };
+        for _ in 0..count {
+            hand = hand.added(piece_kind)?;
+        }
+    }
+    Some(hand)
+}
+
+// Parses a row of the board, e.g. `|v香v桂 ・ ・ ・ ・ ・v桂v香|一`, from file 9 to file 1.
+fn parse_row(line: &str) -> Option<[Option<Piece>; 9]> {
+    let mut chars = line.strip_prefix('|')?.chars();
+    let mut result = [None; 9];
+    for cell in result.iter_mut() {
+        let color = match chars.next()? {
+            ' ' => Color::Black,
+            'v' => Color::White,
+            _ => return None,
+        };
+        let c = chars.next()?;
+        if c == '・' {
+            continue;
+        }
+        *cell = Some(Piece::new(piece_kind_from_char(c)?, color));
+    }
+    if chars.next()? != '|' {
+        return None;
+    }
+    Some(result)
+}
+
+impl PartialPosition {
+    /// Parses a board diagram in BOD format, the format used in KIF files.
+    ///
+    /// The diagram consists of the board between two lines of `+---...---+`, the pieces in hand of each player
+    /// (`先手の持駒：` and `後手の持駒：`, or `下手の持駒：` and `上手の持駒：`), the player to move (`先手番` or `後手番`),
+    /// and optionally the number of moves made (`手数＝`). Other lines are ignored.
+    /// If the player to move is not given, black is to move.
+    ///
+    /// Returns [`None`] if the board is malformed or has not exactly 9 rows, or the pieces in hand are invalid.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PartialPosition;
+    /// let bod = "\
+    /// 後手の持駒：飛　金二　歩十八
+    ///   ９ ８ ７ ６ ５ ４ ３ ２ １
+    /// +---------------------------+
+    /// | ・ ・ ・ ・ ・ ・ ・ ・v玉|一
+    /// | ・ ・ ・ ・ ・ ・ ・ ・ ・|二
+    /// | ・ ・ ・ ・ ・ ・ ・ 龍 ・|三
+    /// | ・ ・ ・ ・ ・ ・ ・ ・ ・|四
+    /// | ・ ・ ・ ・ ・ ・ ・ ・ ・|五
+    /// | ・ ・ ・ ・ ・ ・ ・ ・ ・|六
+    /// | ・ ・ ・ ・ ・ ・ ・ ・ ・|七
+    /// | ・ ・ ・ ・ ・ ・ ・ ・ ・|八
+    /// | ・ ・ ・ ・ 玉 ・ ・ ・ ・|九
+    /// +---------------------------+
+    /// 先手の持駒：金
+    /// 後手番
+    /// ";
+    /// let position = PartialPosition::from_bod(bod).unwrap();
+    /// assert_eq!(position.to_sfen_owned(), "8k/9/7+R1/9/9/9/9/9/4K4 w Gr2g18p 1");
+    /// ```
+    pub fn from_bod(bod: &str) -> Option<Self> {
+        let mut result = Self::empty();
+        let mut rows = 0;
+        for line in bod.lines() {
+            let line = line.trim_end();
+            if line.starts_with('|') {
+                if rows >= 9 {
+                    return None;
+                }
+                for (file, piece) in (1..=9).rev().zip(parse_row(line)?) {
+                    // Safety: 1 <= file <= 9 and 1 <= rows + 1 <= 9
+                    let square =
+                        unsafe { crate::common::unwrap_unchecked(Square::new(file, rows + 1)) };
+                    result.piece_set(square, piece);
+                }
+                rows += 1;
+            } else if let Some(hand) = line
+                .strip_prefix("先手の持駒：")
+                .or_else(|| line.strip_prefix("下手の持駒："))
+            {
+                *result.hand_of_a_player_mut(Color::Black) = parse_hand(hand)?;
+            } else if let Some(hand) = line
+                .strip_prefix("後手の持駒：")
+                .or_else(|| line.strip_prefix("上手の持駒："))
+            {
+                *result.hand_of_a_player_mut(Color::White) = parse_hand(hand)?;
+            } else if line.starts_with("先手番") || line.starts_with("下手番") {
+                result.side_to_move_set(Color::Black);
+            } else if line.starts_with("後手番") || line.starts_with("上手番") {
+                result.side_to_move_set(Color::White);
+            } else if let Some(rest) = line.strip_prefix("手数＝") {
+                let digits = rest.split(|c: char| !c.is_ascii_digit()).next()?;
+                let moves: u16 = digits.parse().ok()?;
+                if !result.ply_set(moves.checked_add(1)?) {
+                    return None;
+                }
+            }
+        }
+        if rows != 9 {
+            return None;
+        }
+        Some(result)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_bod_works() {
+        let bod = "\
+後手の持駒：なし
+  ９ ８ ７ ６ ５ ４ ３ ２ １
++---------------------------+
+|v香v桂v銀v金v玉v金v銀v桂v香|一
+| ・v飛 ・ ・ ・ ・ ・v角 ・|二
+|v歩v歩v歩v歩v歩v歩v歩v歩v歩|三
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|四
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|五
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|六
+| 歩 歩 歩 歩 歩 歩 歩 歩 歩|七
+| ・ 角 ・ ・ ・ ・ ・ 飛 ・|八
+| 香 桂 銀 金 玉 金 銀 桂 香|九
++---------------------------+
+先手の持駒：なし
+先手番
+";
+        let position = PartialPosition::from_bod(bod).unwrap();
+        assert_eq!(position, PartialPosition::startpos());
+
+        let bod = "\
+上手の持駒：歩二
+  ９ ８ ７ ６ ５ ４ ３ ２ １
++---------------------------+
+| ・ ・ ・ ・ ・ ・ ・ ・v王|一
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|二
+| ・ ・ ・ ・ ・ ・ ・ 杏v全|三
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|四
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|五
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|六
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|七
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|八
+| ・ ・ ・ ・ 玉 ・ ・ ・ ・|九
++---------------------------+
+下手の持駒：角　桂二
+手数＝41  ▲２三香成  まで
+上手番
+";
+        let position = PartialPosition::from_bod(bod).unwrap();
+        assert_eq!(
+            position.to_sfen_owned(),
+            "8k/9/7+L+s/9/9/9/9/9/4K4 w B2N2p 42",
+        );
+        assert_eq!(position.ply(), 42);
+
+        // Eight rows
+        let short = bod.replace("| ・ ・ ・ ・ ・ ・ ・ ・ ・|八\n", "");
+        assert_eq!(PartialPosition::from_bod(&short), None);
+        // An unknown piece
+        let unknown = bod.replace("杏v全", "杏v犬");
+        assert_eq!(PartialPosition::from_bod(&unknown), None);
+        // Invalid pieces in hand
+        let invalid = bod.replace("歩二", "歩百");
+        assert_eq!(PartialPosition::from_bod(&invalid), None);
+        let invalid = bod.replace("歩二", "玉");
+        assert_eq!(PartialPosition::from_bod(&invalid), None);
+    }
+}
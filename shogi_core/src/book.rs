@@ -0,0 +1,311 @@
+//! Opening books in the standard format of YaneuraOu (`YANEURAOU-DB2016`).
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Result as FmtResult, Write};
+
+use crate::common::{write_i32, write_u32, write_u64};
+use crate::usi::{next_number, next_token};
+use crate::{Move, PartialPosition, ToUsi};
+
+/// The header written at the beginning of a book.
+const HEADER: &str = "#YANEURAOU-DB2016 1.00";
+
+/// A move registered in a [`Book`].
+///
+/// Since: 0.1.6
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct BookMove {
+    /// The move.
+    pub mv: Move,
+    /// The expected reply of the opponent, if any.
+    pub ponder: Option<Move>,
+    /// The evaluation after the move from the viewpoint of the side that makes the move.
+    pub score: i32,
+    /// The depth of the search that produced `score`.
+    pub depth: u32,
+    /// How many times the move was chosen, e.g. in the games the book was made from.
+    pub count: u64,
+}
+
+impl BookMove {
+    /// Creates a [`BookMove`] without a ponder move, whose score, depth and count are 0.
+    pub fn new(mv: Move) -> Self {
+        Self {
+            mv,
+            ponder: None,
+            score: 0,
+            depth: 0,
+            count: 0,
+        }
+    }
+}
+
+impl_ord_with_fields!(BookMove; mv, ponder, score, depth, count);
+impl_hash_with_fields!(BookMove; mv, ponder, score, depth, count);
+
+/// An opening book, which maps positions to candidate moves.
+///
+/// Positions are identified by their SFEN without the ply, so the same position reached at different plies
+/// shares its moves. Moves of a position keep the order in which they were added.
+///
+/// A book is read from and written to text in YaneuraOu's standard format:
+/// ```text
+/// #YANEURAOU-DB2016 1.00
+/// sfen lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1
+/// 7g7f 3c3d 30 20 5
+/// 2g2f none 25 20 3
+/// ```
+/// Each line after a `sfen` line consists of a move, the ponder move (`none` if absent), the score,
+/// the depth and the count. Missing trailing fields are read as 0.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Book, BookMove, Move, PartialPosition, Square};
+/// let text = "#YANEURAOU-DB2016 1.00\nsfen lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1\n7g7f 3c3d 30 20 5\n";
+/// let book = Book::from_db(text).unwrap();
+/// let moves = book.get(&PartialPosition::startpos()).unwrap();
+/// assert_eq!(moves[0].mv, Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false });
+/// assert_eq!(moves[0].count, 5);
+/// assert_eq!(book.to_db_owned(), text);
+/// ```
+///
+/// Since: 0.1.6
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct Book {
+    // Keyed by SFEN without the ply.
+    positions: BTreeMap<String, (PartialPosition, Vec<BookMove>)>,
+}
+
+// Returns the SFEN of `position` without the ply.
+fn key(position: &PartialPosition) -> String {
+    let mut sfen = position.to_sfen_owned();
+    if let Some(index) = sfen.rfind(' ') {
+        sfen.truncate(index);
+    }
+    sfen
+}
+
+impl Book {
+    /// Creates an empty [`Book`].
+    pub fn new() -> Self {
+        Self {
+            positions: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of positions in `self`.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Checks if `self` has no positions.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns the moves registered for `position`, if any. The ply of `position` is ignored.
+    pub fn get(&self, position: &PartialPosition) -> Option<&[BookMove]> {
+        self.positions
+            .get(&key(position))
+            .map(|(_, moves)| moves.as_slice())
+    }
+
+    /// Registers a move for `position`.
+    /// If the same move is already registered for `position`, it is replaced with `book_move`.
+    pub fn insert(&mut self, position: &PartialPosition, book_move: BookMove) {
+        let (_, moves) = self
+            .positions
+            .entry(key(position))
+            .or_insert_with(|| (position.clone(), Vec::new()));
+        match moves
+            .iter_mut()
+            .find(|existing| existing.mv == book_move.mv)
+        {
+            Some(existing) => *existing = book_move,
+            None => moves.push(book_move),
+        }
+    }
+
+    /// Removes `position` from `self` and returns its moves, if any.
+    pub fn remove(&mut self, position: &PartialPosition) -> Option<Vec<BookMove>> {
+        self.positions
+            .remove(&key(position))
+            .map(|(_, moves)| moves)
+    }
+
+    /// Returns an iterator over the positions and their moves, in the lexicographical order of their SFEN.
+    ///
+    /// The ply of each position is that of the position when it was first added.
+    pub fn iter(&self) -> impl Iterator<Item = (&PartialPosition, &[BookMove])> + '_ {
+        self.positions
+            .values()
+            .map(|(position, moves)| (position, moves.as_slice()))
+    }
+
+    /// Parses a book in YaneuraOu's standard format.
+    ///
+    /// Empty lines and lines starting with `#` or `//` are ignored.
+    /// If the same position appears more than once, its moves are merged.
+    ///
+    /// Returns [`None`] if `s` is not a valid book, e.g. if a move line precedes all `sfen` lines.
+    pub fn from_db(s: &str) -> Option<Self> {
+        let mut book = Self::new();
+        let mut current: Option<PartialPosition> = None;
+        for line in s.lines() {
+            let mut rest = line.trim();
+            if rest.is_empty() || rest.starts_with('#') || rest.starts_with("//") {
+                continue;
+            }
+            if let Some(sfen) = rest.strip_prefix("sfen ") {
+                current = Some(PartialPosition::from_sfen(sfen.trim())?);
+                continue;
+            }
+            let position = current.as_ref()?;
+            let side = position.side_to_move();
+            let mv = Move::parse_usi_bytes(next_token(&mut rest)?.as_bytes(), side)?;
+            let ponder = match next_token(&mut rest) {
+                Some("none") | None => None,
+                Some(token) => Some(Move::parse_usi_bytes(token.as_bytes(), side.flip())?),
+            };
+            let score = match next_token(&mut rest) {
+                Some(token) => token.parse().ok()?,
+                None => 0,
+            };
+            let depth = if rest.trim().is_empty() {
+                0
+            } else {
+                next_number(&mut rest)?
+            };
+            let count = if rest.trim().is_empty() {
+                0
+            } else {
+                next_number(&mut rest)?
+            };
+            if next_token(&mut rest).is_some() {
+                return None;
+            }
+            let (_, moves) = book
+                .positions
+                .entry(key(position))
+                .or_insert_with(|| (position.clone(), Vec::new()));
+            moves.push(BookMove {
+                mv,
+                ponder,
+                score,
+                depth,
+                count,
+            });
+        }
+        Some(book)
+    }
+
+    /// Writes `self` in YaneuraOu's standard format, including the header.
+    ///
+    /// This function returns Err(core::fmt::Error) if and only if it fails to write to `sink`.
+    pub fn to_db<W: Write>(&self, sink: &mut W) -> FmtResult {
+        sink.write_str(HEADER)?;
+        sink.write_str("\n")?;
+        for (position, moves) in self.positions.values() {
+            sink.write_str("sfen ")?;
+            position.to_sfen(sink)?;
+            sink.write_str("\n")?;
+            for book_move in moves {
+                book_move.mv.to_usi(sink)?;
+                sink.write_str(" ")?;
+                match book_move.ponder {
+                    Some(ponder) => ponder.to_usi(sink)?,
+                    None => sink.write_str("none")?,
+                }
+                sink.write_str(" ")?;
+                write_i32(sink, book_move.score)?;
+                sink.write_str(" ")?;
+                write_u32(sink, book_move.depth)?;
+                sink.write_str(" ")?;
+                write_u64(sink, book_move.count)?;
+                sink.write_str("\n")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `self` in YaneuraOu's standard format. See [`Book::to_db`].
+    pub fn to_db_owned(&self) -> String {
+        let mut s = String::new();
+        // guaranteed to be Ok(())
+        let result = self.to_db(&mut s);
+        debug_assert_eq!(result, Ok(()));
+        s
+    }
+}
+
+impl_ord_with_fields!(Book; positions);
+impl_hash_with_fields!(Book; positions);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, Piece, Square};
+
+    #[test]
+    fn book_works() {
+        let text = "\
+#YANEURAOU-DB2016 1.00
+// comment
+sfen lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1
+7g7f 3c3d -10 18
+2g2f
+
+sfen lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 3
+5g5f none 0 1 2
+sfen 8k/9/9/9/9/9/9/9/K8 w P 10
+P*5e
+";
+        let mut book = Book::from_db(text).unwrap();
+        assert_eq!(book.len(), 2);
+        let moves = book.get(&PartialPosition::startpos()).unwrap();
+        assert_eq!(moves.len(), 3);
+        assert_eq!(
+            moves[0].ponder,
+            Some(Move::Normal {
+                from: Square::SQ_3C,
+                to: Square::SQ_3D,
+                promote: false,
+            }),
+        );
+        assert_eq!(
+            (moves[0].score, moves[0].depth, moves[0].count),
+            (-10, 18, 0)
+        );
+        assert_eq!(moves[2].count, 2);
+
+        let mut position = PartialPosition::from_sfen("8k/9/9/9/9/9/9/9/K8 w P 1").unwrap();
+        let drop = Move::Drop {
+            piece: Piece::W_P,
+            to: Square::SQ_5E,
+        };
+        assert_eq!(book.get(&position), Some(&[BookMove::new(drop)][..]));
+
+        let mut book_move = BookMove::new(drop);
+        book_move.count = 1;
+        book.insert(&position, book_move);
+        assert_eq!(book.get(&position), Some(&[book_move][..]));
+        position.side_to_move_set(Color::Black);
+        assert_eq!(book.get(&position), None);
+
+        let reread = Book::from_db(&book.to_db_owned()).unwrap();
+        assert_eq!(reread, book);
+        assert!(book.remove(&PartialPosition::startpos()).is_some());
+        assert_eq!(book.len(), 1);
+
+        assert_eq!(Book::from_db("7g7f 3c3d 0 0 0\n"), None);
+        assert_eq!(
+            Book::from_db("sfen 8k/9/9/9/9/9/9/9/K8 w P 10\nP*5e none 0 x\n"),
+            None
+        );
+    }
+}
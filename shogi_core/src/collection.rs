@@ -0,0 +1,267 @@
+//! Collections of games, such as the contents of a file that records multiple games.
+
+use alloc::vec::Vec;
+
+use crate::Game;
+
+/// A sequence of games, such as the games recorded in a single file.
+///
+/// Games keep the order in which they were added.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Game, GameCollection, GameResolution, Position};
+/// let mut collection = GameCollection::new();
+/// collection.push(Game::new(Position::startpos()));
+/// let mut other: GameCollection = (0..2).map(|_| Game::new(Position::startpos())).collect();
+/// collection.append(&mut other);
+/// assert_eq!(collection.len(), 3);
+/// assert!(other.is_empty());
+/// assert!(collection.iter().all(|game| game.resolution().is_none()));
+/// ```
+///
+/// Since: 0.1.6
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct GameCollection {
+    games: Vec<Game>,
+}
+
+impl GameCollection {
+    /// Creates an empty [`GameCollection`].
+    pub fn new() -> Self {
+        Self { games: Vec::new() }
+    }
+
+    /// Returns the number of games in `self`.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    /// Checks if `self` has no games.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    /// Returns the games in `self`.
+    #[inline(always)]
+    pub fn games(&self) -> &[Game] {
+        &self.games
+    }
+
+    /// Returns the `index`-th game, if it exists.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<&Game> {
+        self.games.get(index)
+    }
+
+    /// Returns an iterator over the games in `self`.
+    pub fn iter(&self) -> core::slice::Iter<'_, Game> {
+        self.games.iter()
+    }
+
+    /// Appends a game to the end of `self`.
+    pub fn push(&mut self, game: Game) {
+        self.games.push(game);
+    }
+
+    /// Moves all games in `other` to the end of `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self) {
+        self.games.append(&mut other.games);
+    }
+
+    /// Returns the games in `self` followed by those in `other`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Game, GameCollection, Position};
+    /// let a: GameCollection = [Game::new(Position::startpos())].into_iter().collect();
+    /// let b = a.clone();
+    /// assert_eq!(a.merged(b).len(), 2);
+    /// ```
+    #[must_use]
+    pub fn merged(mut self, mut other: Self) -> Self {
+        self.append(&mut other);
+        self
+    }
+
+    /// Returns the games in `self` as a [`Vec`].
+    pub fn into_games(self) -> Vec<Game> {
+        self.games
+    }
+}
+
+impl From<Vec<Game>> for GameCollection {
+    fn from(games: Vec<Game>) -> Self {
+        Self { games }
+    }
+}
+
+impl FromIterator<Game> for GameCollection {
+    fn from_iter<I: IntoIterator<Item = Game>>(iter: I) -> Self {
+        Self {
+            games: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Game> for GameCollection {
+    fn extend<I: IntoIterator<Item = Game>>(&mut self, iter: I) {
+        self.games.extend(iter);
+    }
+}
+
+impl IntoIterator for GameCollection {
+    type Item = Game;
+    type IntoIter = alloc::vec::IntoIter<Game>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.games.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a GameCollection {
+    type Item = &'a Game;
+    type IntoIter = core::slice::Iter<'a, Game>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.games.iter()
+    }
+}
+
+impl core::ops::Index<usize> for GameCollection {
+    type Output = Game;
+
+    #[inline(always)]
+    fn index(&self, index: usize) -> &Game {
+        &self.games[index]
+    }
+}
+
+impl_ord_with_fields!(GameCollection; games);
+impl_hash_with_fields!(GameCollection; games);
+
+#[cfg(feature = "csa")]
+#[cfg_attr(docsrs, doc(cfg(feature = "csa")))]
+impl GameCollection {
+    /// Parses the games in a file in CSA format lazily.
+    ///
+    /// In CSA format, games in a file are separated by lines consisting of `/`.
+    /// The returned iterator parses a game only when it is requested,
+    /// and yields [`None`] for a game that cannot be parsed or converted with [`Game::from_csa`].
+    /// Empty records (e.g. after a trailing `/`) are skipped.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{GameCollection, GameResolution};
+    /// let text = "V2.2\nPI\n+\n+7776FU\n%TORYO\n/\nV2.2\nPI\n+\n+2726FU\n-8384FU\n/\nbroken\n";
+    /// let mut games = GameCollection::csa_games(text);
+    /// assert_eq!(games.next().unwrap().unwrap().resolution(), Some(GameResolution::BlackWins));
+    /// assert_eq!(games.next().unwrap().unwrap().position().moves().len(), 2);
+    /// assert_eq!(games.next(), Some(None));
+    /// assert_eq!(games.next(), None);
+    /// ```
+    pub fn csa_games(s: &str) -> impl Iterator<Item = Option<Game>> + '_ {
+        CsaRecords { rest: s }
+            .filter(|record| !record.trim().is_empty())
+            .map(|record| Game::from_csa(&csa::parse_csa(record).ok()?))
+    }
+
+    /// Parses all games in a file in CSA format. See [`GameCollection::csa_games`].
+    ///
+    /// Returns [`None`] if any of the games cannot be parsed.
+    pub fn from_csa(s: &str) -> Option<Self> {
+        Self::csa_games(s).collect()
+    }
+
+    /// Writes all games in `self` in CSA format, separated by lines consisting of `/`.
+    ///
+    /// Returns [`None`] if any of the games cannot be converted with [`Game::to_csa`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Game, GameCollection, Position};
+    /// let collection: GameCollection = (0..2).map(|_| Game::new(Position::startpos())).collect();
+    /// let text = collection.to_csa_owned().unwrap();
+    /// assert_eq!(text, "V2.2\nPI\n+\n/\nV2.2\nPI\n+\n");
+    /// assert_eq!(GameCollection::from_csa(&text), Some(collection));
+    /// ```
+    pub fn to_csa_owned(&self) -> Option<alloc::string::String> {
+        use core::fmt::Write;
+
+        let mut result = alloc::string::String::new();
+        for (index, game) in self.games.iter().enumerate() {
+            if index != 0 {
+                result.push_str("/\n");
+            }
+            write!(result, "{}", game.to_csa()?).ok()?;
+        }
+        Some(result)
+    }
+}
+
+// Splits a file in CSA format into records of games.
+#[cfg(feature = "csa")]
+struct CsaRecords<'a> {
+    rest: &'a str,
+}
+
+#[cfg(feature = "csa")]
+impl<'a> Iterator for CsaRecords<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let mut offset = 0;
+        for line in self.rest.split_inclusive('\n') {
+            if line.trim_end() == "/" {
+                let record = &self.rest[..offset];
+                self.rest = &self.rest[offset + line.len()..];
+                return Some(record);
+            }
+            offset += line.len();
+        }
+        let record = self.rest;
+        self.rest = "";
+        Some(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameResolution, Position};
+
+    #[test]
+    fn game_collection_works() {
+        let mut resolved = Game::new(Position::startpos());
+        resolved.resolve(GameResolution::Draw);
+        let mut collection = GameCollection::from(alloc::vec![Game::new(Position::startpos())]);
+        collection.extend([resolved.clone()]);
+        assert_eq!(collection.len(), 2);
+        assert_eq!(collection[1], resolved);
+        assert_eq!(collection.get(2), None);
+        let resolutions: Vec<_> = (&collection).into_iter().map(Game::resolution).collect();
+        assert_eq!(resolutions, [None, Some(GameResolution::Draw)]);
+
+        let merged = collection.clone().merged(collection.clone());
+        assert_eq!(merged.len(), 4);
+        assert_eq!(merged.games()[..2], merged.games()[2..]);
+        assert_eq!(merged.into_games().len(), 4);
+    }
+
+    #[cfg(feature = "csa")]
+    #[test]
+    fn csa_games_work() {
+        let text = "V2.2\r\nPI\r\n+\r\n/\r\n\r\n/\r\nV2.2\r\nPI\r\n-\r\n";
+        let collection = GameCollection::from_csa(text).unwrap();
+        assert_eq!(collection.len(), 2);
+        assert_eq!(collection[1].position().side_to_move(), crate::Color::White);
+        assert_eq!(GameCollection::from_csa(""), Some(GameCollection::new()));
+        assert_eq!(GameCollection::from_csa("V2.2\nPI\n+\n/\nbroken\n"), None);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ToUsi;
+use crate::{OutOfRangeError, ToUsi};
 
 /// A player.
 ///
@@ -6,6 +6,7 @@ use crate::ToUsi;
 /// Because they are cheap to copy, they implement [`Copy`].
 #[repr(u8)]
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Color {
     /// Black, who plays first. Known as `先手` (*sente*).
     ///
@@ -26,7 +27,7 @@ impl Color {
     /// assert_eq!(Color::Black.flip(), Color::White);
     /// assert_eq!(Color::White.flip(), Color::Black);
     /// ```
-    #[export_name = "Color_flip"]
+    #[cfg_attr(feature = "c-exports", export_name = "Color_flip")]
     pub extern "C" fn flip(self) -> Self {
         // The shortest possible machine code for this function in x86_64 (System V AMD64 ABI) is:
         // 89 f8  movl %edi, %eax
@@ -42,7 +43,7 @@ impl Color {
         let result = self as usize - 1;
         // Safety: 0 <= self as usize - 1 < 2 always holds.
         if result >= 2 {
-            unsafe { core::hint::unreachable_unchecked() };
+            unsafe { crate::common::unreachable_unchecked() };
         }
         result
     }
@@ -64,10 +65,65 @@ impl Color {
     pub fn all() -> [Self; 2] {
         [Color::Black, Color::White]
     }
+
+    /// Parses a color written as in USI (`b` or `w`) or in English (`black` or `white`), ignoring ASCII case.
+    ///
+    /// This is intended for configuration files and command-line arguments written by hand.
+    /// For USI streams, which accept only `b` and `w`, use [`FromStr`](core::str::FromStr).
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, ColorParseError};
+    /// assert_eq!(Color::from_name("b"), Ok(Color::Black));
+    /// assert_eq!(Color::from_name("White"), Ok(Color::White));
+    /// assert_eq!(Color::from_name("BLACK"), Ok(Color::Black));
+    /// assert_eq!(Color::from_name("sente"), Err(ColorParseError));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn from_name(s: &str) -> Result<Self, ColorParseError> {
+        for (color, usi, name) in [(Color::Black, "b", "black"), (Color::White, "w", "white")] {
+            if s.eq_ignore_ascii_case(usi) || s.eq_ignore_ascii_case(name) {
+                return Ok(color);
+            }
+        }
+        Err(ColorParseError)
+    }
 }
 
 impl_ord_for_fieldless_enum!(Color);
 impl_hash_for_fieldless_enum!(Color);
+impl_rkyv_as_self!(Color);
+
+/// Converts a [`u8`] to a [`Color`]. Fails if `value` is neither `1` nor `2`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Color, OutOfRangeError};
+/// assert_eq!(Color::try_from(2u8), Ok(Color::White));
+/// assert_eq!(Color::try_from(0u8), Err(OutOfRangeError));
+/// assert_eq!(u8::from(Color::Black), 1);
+/// ```
+impl TryFrom<u8> for Color {
+    type Error = OutOfRangeError;
+
+    #[inline]
+    fn try_from(value: u8) -> Result<Self, OutOfRangeError> {
+        match value {
+            1 => Ok(Color::Black),
+            2 => Ok(Color::White),
+            _ => Err(OutOfRangeError),
+        }
+    }
+}
+
+/// Converts a [`Color`] to its representation.
+impl From<Color> for u8 {
+    #[inline(always)]
+    fn from(color: Color) -> Self {
+        color as u8
+    }
+}
 
 impl ToUsi for Color {
     fn to_usi<W: core::fmt::Write>(&self, sink: &mut W) -> core::fmt::Result {
@@ -78,6 +134,64 @@ impl ToUsi for Color {
     }
 }
 
+/// Displays a color in USI format, e.g. `b` or `w`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::Color;
+/// assert_eq!(format!("{}", Color::White), "w");
+/// ```
+///
+/// Since: 0.1.6
+impl core::fmt::Display for Color {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.to_usi(f)
+    }
+}
+
+/// Parses a color in USI format, `b` or `w`.
+/// To accept `black` and `white` as well, use [`Color::from_name`].
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Color, ColorParseError};
+/// assert_eq!("w".parse::<Color>(), Ok(Color::White));
+/// assert_eq!("W".parse::<Color>(), Err(ColorParseError));
+/// assert_eq!("white".parse::<Color>(), Err(ColorParseError));
+/// ```
+///
+/// Since: 0.1.6
+impl core::str::FromStr for Color {
+    type Err = ColorParseError;
+
+    fn from_str(s: &str) -> Result<Self, ColorParseError> {
+        match s {
+            "b" => Ok(Color::Black),
+            "w" => Ok(Color::White),
+            _ => Err(ColorParseError),
+        }
+    }
+}
+
+/// An error returned when a string is not a color, by [`Color::from_name`] and the [`FromStr`](core::str::FromStr) implementation of [`Color`].
+///
+/// Since: 0.1.6
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct ColorParseError;
+
+impl_ord_for_fieldless_struct!(ColorParseError);
+impl_hash_for_fieldless_struct!(ColorParseError);
+
+impl core::fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("invalid color")
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for ColorParseError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,6 +203,18 @@ mod tests {
         assert_eq!(colors[1].flip(), colors[0]);
     }
 
+    #[test]
+    fn from_name_works() {
+        for color in Color::all() {
+            assert_eq!(Color::from_name(&color.to_usi_owned()), Ok(color));
+            assert_eq!(Color::from_name(&alloc::format!("{:?}", color)), Ok(color),);
+        }
+        for invalid in ["", "bw", "blac", "black ", " w"] {
+            assert_eq!(Color::from_name(invalid), Err(ColorParseError));
+            assert_eq!(invalid.parse::<Color>(), Err(ColorParseError));
+        }
+    }
+
     #[test]
     fn array_index_works() {
         for i in 0..2 {
@@ -1,3 +1,6 @@
+use core::num::{NonZeroU16, NonZeroU8};
+use core::slice::SliceIndex;
+
 // To avoid calling `format_args!("{}", u8_value)` and `write_fmt`, which results in code bloating
 // core::str::count::do_count_chars and core::fmt::Formatter::pad_integral take 3.2KiB
 pub(crate) fn write_u8<W: core::fmt::Write>(sink: &mut W, value: u8) -> core::fmt::Result {
@@ -44,6 +47,37 @@ pub(crate) fn write_u16<W: core::fmt::Write>(sink: &mut W, mut value: u16) -> co
     Ok(())
 }
 
+pub(crate) fn write_u32<W: core::fmt::Write>(sink: &mut W, value: u32) -> core::fmt::Result {
+    write_u64(sink, value as u64)
+}
+
+pub(crate) fn write_u64<W: core::fmt::Write>(sink: &mut W, mut value: u64) -> core::fmt::Result {
+    if value == 0 {
+        return unsafe { write_ascii_byte(sink, b'0') };
+    }
+    let mut base = 10_000_000_000_000_000_000;
+    let mut displayed = false;
+    while base != 0 {
+        let digit = value / base; // always < 10
+        if displayed || digit != 0 {
+            // Safety: b'0' <= digit + b'0' <= b'9'
+            unsafe { write_ascii_byte(sink, digit as u8 + b'0') }?;
+            displayed = true;
+        }
+        value -= digit * base;
+        base /= 10;
+    }
+    Ok(())
+}
+
+pub(crate) fn write_i32<W: core::fmt::Write>(sink: &mut W, value: i32) -> core::fmt::Result {
+    if value < 0 {
+        // Safety: '-' is in ASCII
+        unsafe { write_ascii_byte(sink, b'-') }?;
+    }
+    write_u32(sink, value.unsigned_abs())
+}
+
 /// # Safety
 /// `ascii_byte` must be an ASCII byte, i.e., 0 <= ascii_byte < 128 must hold.
 #[inline(always)]
@@ -52,13 +86,237 @@ pub(crate) unsafe fn write_ascii_byte<W: core::fmt::Write>(
     ascii_byte: u8,
 ) -> core::fmt::Result {
     // `[ascii_byte]` is always a valid UTF-8 encoding.
-    let str = core::str::from_utf8_unchecked(core::slice::from_ref(&ascii_byte));
+    let str = str_from_utf8_unchecked(core::slice::from_ref(&ascii_byte));
     sink.write_str(str)
 }
 
+/// Writes a nul-terminated string to `ptr` with bounds checking.
+///
+/// Returns the number of bytes written excluding the nul byte.
+/// If `capacity` is not large enough to hold them or `f` fails, this function returns 0.
+///
+/// # Safety
+/// `ptr` must be valid for writes of `capacity` bytes.
+pub(crate) unsafe fn write_nul_terminated<F>(ptr: *mut u8, capacity: usize, f: F) -> usize
+where
+    F: FnOnce(&mut BoundedWriter) -> core::fmt::Result,
+{
+    let mut sink = BoundedWriter {
+        ptr,
+        written: 0,
+        capacity,
+    };
+    if f(&mut sink).is_err() {
+        return 0;
+    }
+    let length = sink.written;
+    // Safety: nul is in ASCII
+    if write_ascii_byte(&mut sink, b'\0').is_err() {
+        return 0;
+    }
+    length
+}
+
+/// A sink that writes to a buffer of fixed capacity. Writing fails if the capacity is exceeded.
+pub(crate) struct BoundedWriter {
+    ptr: *mut u8,
+    written: usize,
+    capacity: usize,
+}
+
+impl core::fmt::Write for BoundedWriter {
+    #[inline(always)]
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let slice = s.as_bytes();
+        if self.capacity - self.written < slice.len() {
+            return Err(core::fmt::Error);
+        }
+        // Safety: `self.ptr` is valid for writes of `self.capacity` bytes
+        // and self.written + slice.len() <= self.capacity holds.
+        unsafe {
+            for (i, &byte) in slice.iter().enumerate() {
+                core::ptr::write(self.ptr.add(self.written + i), byte);
+            }
+        }
+        self.written += slice.len();
+        Ok(())
+    }
+}
+
+/// Calls `f`, aborting the process if it panics.
+///
+/// Unwinding out of an `extern "C"` function is undefined behavior,
+/// so C interfaces that can panic call their bodies through this function.
+/// Only the C interfaces that allocate, namely `Position_startpos` and `Position_make_compact_move`, can panic.
+/// Rust counterparts of them are not wrapped, so that Rust callers see panics as usual.
+/// Without `std`, panics cannot be caught and `f` is simply called.
+#[cfg(feature = "std")]
+#[inline(always)]
+pub(crate) fn abort_on_panic<R, F: FnOnce() -> R>(f: F) -> R {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        Ok(value) => value,
+        Err(_) => std::process::abort(),
+    }
+}
+
+/// Calls `f`, aborting the process if it panics.
+///
+/// Without `std`, panics cannot be caught and `f` is simply called.
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+#[inline(always)]
+pub(crate) fn abort_on_panic<R, F: FnOnce() -> R>(f: F) -> R {
+    f()
+}
+
+/// Creates a byte slice from a C buffer. A null `ptr` is treated as an empty buffer.
+///
+/// # Safety
+/// If `ptr` is not null, `ptr` must be valid for reads of `len` bytes.
+#[inline(always)]
+pub(crate) unsafe fn c_bytes<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if ptr.is_null() {
+        return &[];
+    }
+    core::slice::from_raw_parts(ptr, len)
+}
+
+/// `char` in C, i.e. the pointee type of C strings.
+///
+/// This is the same type as `core::ffi::c_char`, which is not available in Rust 1.60.
+/// Functions returning C strings use this type, so that they are declared with `const char *` in C.
+///
+/// Since: 0.1.6
+#[cfg(all(
+    not(windows),
+    not(target_vendor = "apple"),
+    any(
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "hexagon",
+        target_arch = "msp430",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+        target_arch = "s390x",
+    ),
+))]
+#[allow(non_camel_case_types)]
+pub type c_char = u8;
+
+/// `char` in C, i.e. the pointee type of C strings.
+///
+/// This is the same type as `core::ffi::c_char`, which is not available in Rust 1.60.
+/// Functions returning C strings use this type, so that they are declared with `const char *` in C.
+///
+/// Since: 0.1.6
+#[cfg(not(all(
+    not(windows),
+    not(target_vendor = "apple"),
+    any(
+        target_arch = "aarch64",
+        target_arch = "arm",
+        target_arch = "hexagon",
+        target_arch = "msp430",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+        target_arch = "s390x",
+    ),
+)))]
+#[allow(non_camel_case_types)]
+pub type c_char = i8;
+
+/// Removes the nul byte at the end of `s`, which is an entry of a table of nul-terminated strings.
+///
+/// The tables hold nul-terminated strings so that C interfaces can return pointers to them as they are.
+#[inline]
+pub(crate) fn strip_nul(s: &'static str) -> &'static str {
+    debug_assert!(s.ends_with('\0'));
+    &s[..s.len() - 1]
+}
+
+// Unchecked operations. With the `checked` feature, they check their preconditions and panic if violated,
+// so that a bug in this crate or a misuse of an unsafe function cannot cause undefined behavior.
+
+/// # Safety
+/// This function must not be reached.
+#[inline(always)]
+pub(crate) const unsafe fn unreachable_unchecked() -> ! {
+    #[cfg(feature = "checked")]
+    panic!("entered unreachable code");
+    #[cfg(not(feature = "checked"))]
+    core::hint::unreachable_unchecked()
+}
+
+/// # Safety
+/// `value` must not be 0.
+#[inline(always)]
+pub(crate) const unsafe fn nonzero_u8_unchecked(value: u8) -> NonZeroU8 {
+    match NonZeroU8::new(value) {
+        Some(result) => result,
+        None => unreachable_unchecked(),
+    }
+}
+
+/// # Safety
+/// `value` must not be 0.
+#[inline(always)]
+pub(crate) const unsafe fn nonzero_u16_unchecked(value: u16) -> NonZeroU16 {
+    match NonZeroU16::new(value) {
+        Some(result) => result,
+        None => unreachable_unchecked(),
+    }
+}
+
+/// # Safety
+/// `option` must be `Some`.
+#[inline(always)]
+pub(crate) unsafe fn unwrap_unchecked<T>(option: Option<T>) -> T {
+    match option {
+        Some(result) => result,
+        None => unreachable_unchecked(),
+    }
+}
+
+/// # Safety
+/// `index` must be in bounds.
+#[inline(always)]
+pub(crate) unsafe fn get_unchecked<T, I: SliceIndex<[T]>>(slice: &[T], index: I) -> &I::Output {
+    #[cfg(feature = "checked")]
+    return &slice[index];
+    #[cfg(not(feature = "checked"))]
+    slice.get_unchecked(index)
+}
+
+/// # Safety
+/// `index` must be in bounds.
+#[inline(always)]
+pub(crate) unsafe fn get_unchecked_mut<T, I: SliceIndex<[T]>>(
+    slice: &mut [T],
+    index: I,
+) -> &mut I::Output {
+    #[cfg(feature = "checked")]
+    return &mut slice[index];
+    #[cfg(not(feature = "checked"))]
+    slice.get_unchecked_mut(index)
+}
+
+/// # Safety
+/// `bytes` must be valid UTF-8.
+#[inline(always)]
+pub(crate) unsafe fn str_from_utf8_unchecked(bytes: &[u8]) -> &str {
+    #[cfg(feature = "checked")]
+    return core::str::from_utf8(bytes).expect("invalid UTF-8");
+    #[cfg(not(feature = "checked"))]
+    core::str::from_utf8_unchecked(bytes)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use core::fmt::Write;
 
     #[test]
     fn write_u8_test() {
@@ -69,6 +327,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn write_u32_test() {
+        for value in [0, 1, 9, 10, 65535, 600_000, 1_000_000_000, u32::MAX] {
+            let mut string = String::new();
+            write_u32(&mut string, value).unwrap();
+            assert_eq!(string, format!("{}", value));
+        }
+        for value in [0, 1, 10_000_000_000_000_000_000, u64::MAX] {
+            let mut string = String::new();
+            write_u64(&mut string, value).unwrap();
+            assert_eq!(string, format!("{}", value));
+        }
+        for value in [0, -1, 100, i32::MIN, i32::MAX] {
+            let mut string = String::new();
+            write_i32(&mut string, value).unwrap();
+            assert_eq!(string, format!("{}", value));
+        }
+    }
+
+    #[test]
+    fn write_nul_terminated_works() {
+        let mut buf = [0xffu8; 8];
+        let written =
+            unsafe { write_nul_terminated(buf.as_mut_ptr(), 4, |sink| sink.write_str("abc")) };
+        assert_eq!(written, 3);
+        assert_eq!(&buf, b"abc\0\xff\xff\xff\xff");
+        let written =
+            unsafe { write_nul_terminated(buf.as_mut_ptr(), 3, |sink| sink.write_str("abc")) };
+        assert_eq!(written, 0);
+        let written = unsafe { write_nul_terminated(buf.as_mut_ptr(), 0, |_| Ok(())) };
+        assert_eq!(written, 0);
+    }
+
     #[test]
     fn write_u16_test() {
         for value in 0..=65535 {
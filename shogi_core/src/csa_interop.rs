@@ -0,0 +1,618 @@
+//! Conversions between the types of this crate and those of the [`csa`] crate.
+
+use core::convert::TryFrom;
+use core::time::Duration;
+
+use alloc::vec::Vec;
+
+use crate::{
+    Color, Game, GameResolution, Hand, Move, PartialPosition, Piece, PieceKind, Position,
+    ResolutionDetail, Square, TimeControl,
+};
+
+impl From<Color> for csa::Color {
+    #[inline]
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black => csa::Color::Black,
+            Color::White => csa::Color::White,
+        }
+    }
+}
+
+impl From<csa::Color> for Color {
+    #[inline]
+    fn from(color: csa::Color) -> Self {
+        match color {
+            csa::Color::Black => Color::Black,
+            csa::Color::White => Color::White,
+        }
+    }
+}
+
+impl From<Square> for csa::Square {
+    #[inline]
+    fn from(square: Square) -> Self {
+        csa::Square::new(square.file(), square.rank())
+    }
+}
+
+/// Fails if `square` is `00`, which denotes a hand in CSA format, or is out of the board.
+impl TryFrom<csa::Square> for Square {
+    type Error = ();
+
+    #[inline]
+    fn try_from(square: csa::Square) -> Result<Self, ()> {
+        Square::new(square.file, square.rank).ok_or(())
+    }
+}
+
+impl From<PieceKind> for csa::PieceType {
+    fn from(piece_kind: PieceKind) -> Self {
+        match piece_kind {
+            PieceKind::Pawn => csa::PieceType::Pawn,
+            PieceKind::Lance => csa::PieceType::Lance,
+            PieceKind::Knight => csa::PieceType::Knight,
+            PieceKind::Silver => csa::PieceType::Silver,
+            PieceKind::Gold => csa::PieceType::Gold,
+            PieceKind::Bishop => csa::PieceType::Bishop,
+            PieceKind::Rook => csa::PieceType::Rook,
+            PieceKind::King => csa::PieceType::King,
+            PieceKind::ProPawn => csa::PieceType::ProPawn,
+            PieceKind::ProLance => csa::PieceType::ProLance,
+            PieceKind::ProKnight => csa::PieceType::ProKnight,
+            PieceKind::ProSilver => csa::PieceType::ProSilver,
+            PieceKind::ProBishop => csa::PieceType::Horse,
+            PieceKind::ProRook => csa::PieceType::Dragon,
+        }
+    }
+}
+
+/// Fails if `piece_type` is [`csa::PieceType::All`], which is not a kind of piece.
+impl TryFrom<csa::PieceType> for PieceKind {
+    type Error = ();
+
+    fn try_from(piece_type: csa::PieceType) -> Result<Self, ()> {
+        Ok(match piece_type {
+            csa::PieceType::Pawn => PieceKind::Pawn,
+            csa::PieceType::Lance => PieceKind::Lance,
+            csa::PieceType::Knight => PieceKind::Knight,
+            csa::PieceType::Silver => PieceKind::Silver,
+            csa::PieceType::Gold => PieceKind::Gold,
+            csa::PieceType::Bishop => PieceKind::Bishop,
+            csa::PieceType::Rook => PieceKind::Rook,
+            csa::PieceType::King => PieceKind::King,
+            csa::PieceType::ProPawn => PieceKind::ProPawn,
+            csa::PieceType::ProLance => PieceKind::ProLance,
+            csa::PieceType::ProKnight => PieceKind::ProKnight,
+            csa::PieceType::ProSilver => PieceKind::ProSilver,
+            csa::PieceType::Horse => PieceKind::ProBishop,
+            csa::PieceType::Dragon => PieceKind::ProRook,
+            csa::PieceType::All => return Err(()),
+        })
+    }
+}
+
+fn hand_square() -> csa::Square {
+    csa::Square::new(0, 0)
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "csa")))]
+impl PartialPosition {
+    /// Converts a starting position in CSA format.
+    ///
+    /// Returns [`None`] if `position` refers to a square out of the board,
+    /// removes a piece that is not there with `PI`,
+    /// or puts a piece that cannot be in hand in hand.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::PartialPosition;
+    /// let position = PartialPosition::from_csa(&csa::Position::default()).unwrap();
+    /// assert_eq!(position, PartialPosition::startpos());
+    /// ```
+    pub fn from_csa(position: &csa::Position) -> Option<Self> {
+        let mut result = match position.bulk {
+            Some(ref bulk) => {
+                let mut result = PartialPosition::empty();
+                for (rank_index, row) in bulk.iter().enumerate() {
+                    for (file_index, entry) in row.iter().enumerate() {
+                        if let Some((color, piece_type)) = *entry {
+                            let square = Square::new(9 - file_index as u8, rank_index as u8 + 1)?;
+                            let piece_kind = PieceKind::try_from(piece_type).ok()?;
+                            result.piece_set(square, Some(Piece::new(piece_kind, color.into())));
+                        }
+                    }
+                }
+                result
+            }
+            None => {
+                let mut result = PartialPosition::startpos();
+                for &(square, piece_type) in &position.drop_pieces {
+                    let square = Square::try_from(square).ok()?;
+                    let piece_kind = PieceKind::try_from(piece_type).ok()?;
+                    if result.piece_at(square).map(|piece| piece.piece_kind()) != Some(piece_kind) {
+                        return None;
+                    }
+                    result.piece_set(square, None);
+                }
+                result
+            }
+        };
+        for &(color, square, piece_type) in &position.add_pieces {
+            let color = Color::from(color);
+            if square != hand_square() {
+                let square = Square::try_from(square).ok()?;
+                let piece_kind = PieceKind::try_from(piece_type).ok()?;
+                result.piece_set(square, Some(Piece::new(piece_kind, color)));
+                continue;
+            }
+            if piece_type == csa::PieceType::All {
+                result.remaining_pieces_to_hand(color)?;
+                continue;
+            }
+            let piece_kind = PieceKind::try_from(piece_type).ok()?;
+            let hand = result.hand_of_a_player_mut(color);
+            *hand = hand.added(piece_kind)?;
+        }
+        result.side_to_move_set(position.side_to_move.into());
+        Some(result)
+    }
+
+    /// Converts `self` to a starting position in CSA format.
+    ///
+    /// The board is written in bulk (`P1` to `P9`) and pieces in hand are written one by one (e.g. `P+00FU`),
+    /// except that the starting position of shogi is written as `PI`.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::PartialPosition;
+    /// let position = PartialPosition::startpos();
+    /// assert_eq!(position.to_csa(), csa::Position::default());
+    /// ```
+    pub fn to_csa(&self) -> csa::Position {
+        let mut result = csa::Position {
+            side_to_move: self.side_to_move().into(),
+            ..csa::Position::default()
+        };
+        let startpos = PartialPosition::startpos();
+        if Square::all().all(|square| startpos.piece_at(square) == self.piece_at(square))
+            && Color::all()
+                .iter()
+                .all(|&color| self.hand_of_a_player(color) == Hand::new())
+        {
+            return result;
+        }
+        let mut bulk = [[None; 9]; 9];
+        for square in Square::all() {
+            if let Some(piece) = self.piece_at(square) {
+                let (piece_kind, color) = piece.to_parts();
+                bulk[square.rank() as usize - 1][9 - square.file() as usize] =
+                    Some((color.into(), piece_kind.into()));
+            }
+        }
+        result.bulk = Some(bulk);
+        for color in Color::all() {
+            let hand = self.hand_of_a_player(color);
+            for piece_kind in Hand::all_hand_pieces() {
+                for _ in 0..hand.count(piece_kind).unwrap_or(0) {
+                    result
+                        .add_pieces
+                        .push((color.into(), hand_square(), piece_kind.into()));
+                }
+            }
+        }
+        result
+    }
+
+    /// Converts a move in CSA format, which is made in `self`.
+    ///
+    /// Returns [`None`] if `action` is not a move or is inconsistent with `self`,
+    /// e.g. the moved piece is not on the source square or does not belong to the player to move.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::{Move, PartialPosition, Square};
+    /// let position = PartialPosition::startpos();
+    /// let action = csa::Action::Move(
+    ///     csa::Color::Black,
+    ///     csa::Square::new(7, 7),
+    ///     csa::Square::new(7, 6),
+    ///     csa::PieceType::Pawn,
+    /// );
+    /// assert_eq!(
+    ///     position.move_from_csa(&action),
+    ///     Some(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }),
+    /// );
+    /// ```
+    pub fn move_from_csa(&self, action: &csa::Action) -> Option<Move> {
+        let (color, from, to, piece_type) = match *action {
+            csa::Action::Move(color, from, to, piece_type) => (color, from, to, piece_type),
+            _ => return None,
+        };
+        let color = Color::from(color);
+        if color != self.side_to_move() {
+            return None;
+        }
+        let to = Square::try_from(to).ok()?;
+        let piece_kind = PieceKind::try_from(piece_type).ok()?;
+        if from == hand_square() {
+            return Some(Move::Drop {
+                piece: Piece::new(piece_kind, color),
+                to,
+            });
+        }
+        let from = Square::try_from(from).ok()?;
+        let (original, owner) = self.piece_at(from)?.to_parts();
+        if owner != color {
+            return None;
+        }
+        let promote = if original == piece_kind {
+            false
+        } else if original.promote() == Some(piece_kind) {
+            true
+        } else {
+            return None;
+        };
+        Some(Move::Normal { from, to, promote })
+    }
+
+    /// Converts a move made in `self` to CSA format.
+    ///
+    /// Returns [`None`] if the source square of `mv` is vacant.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::{Move, PartialPosition, Square};
+    /// let position = PartialPosition::startpos();
+    /// let mv = Move::Normal { from: Square::SQ_8H, to: Square::SQ_2B, promote: true };
+    /// assert_eq!(
+    ///     position.move_to_csa(mv),
+    ///     Some(csa::Action::Move(
+    ///         csa::Color::Black,
+    ///         csa::Square::new(8, 8),
+    ///         csa::Square::new(2, 2),
+    ///         csa::PieceType::Horse,
+    ///     )),
+    /// );
+    /// ```
+    pub fn move_to_csa(&self, mv: Move) -> Option<csa::Action> {
+        let color = self.side_to_move().into();
+        match mv {
+            Move::Normal { from, to, promote } => {
+                let piece_kind = self.piece_at(from)?.piece_kind();
+                let piece_kind = if promote {
+                    piece_kind.promote()?
+                } else {
+                    piece_kind
+                };
+                Some(csa::Action::Move(
+                    color,
+                    from.into(),
+                    to.into(),
+                    piece_kind.into(),
+                ))
+            }
+            Move::Drop { piece, to } => Some(csa::Action::Move(
+                color,
+                hand_square(),
+                to.into(),
+                piece.piece_kind().into(),
+            )),
+        }
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "csa")))]
+impl Game {
+    /// Converts a game record in CSA format.
+    ///
+    /// The time limit (`$TIME_LIMIT`) is converted to [`Game::time_control`].
+    /// The time spent on each move is converted to [`Game::move_time`]. Other metadata (e.g. players' names) is discarded.
+    /// The game is resolved according to the special move that ends the record, if any:
+    /// `%TORYO`, `%TIME_UP`, `%ILLEGAL_MOVE` and `%TSUMI` mean the player to move lost,
+    /// `%KACHI` means the player to move won, `%±ILLEGAL_ACTION` means the player `±` lost,
+    /// `%JISHOGI` and `%HIKIWAKE` mean a draw, `%SENNICHITE` means a rematch and `%CHUDAN` means the game was aborted.
+    /// The special move is also recorded as [`Game::resolution_detail`], except for `%HIKIWAKE`.
+    ///
+    /// Returns [`None`] if the starting position or a move does not make sense.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::{Game, GameResolution};
+    /// let record = csa::parse_csa("V2.2\nPI\n+\n+7776FU\n-3334FU\n%TORYO\n").unwrap();
+    /// let game = Game::from_csa(&record).unwrap();
+    /// assert_eq!(game.position().moves().len(), 2);
+    /// assert_eq!(game.resolution(), Some(GameResolution::WhiteWins));
+    /// ```
+    pub fn from_csa(record: &csa::GameRecord) -> Option<Self> {
+        let mut position =
+            Position::arbitrary_position(PartialPosition::from_csa(&record.start_pos)?);
+        let mut resolution = None;
+        let mut move_times = Vec::new();
+        for record in &record.moves {
+            if let csa::Action::Move(..) = record.action {
+                let mv = position.inner().move_from_csa(&record.action)?;
+                position.make_move(mv)?;
+                move_times.push(record.time.map(|time| time.as_millis() as u32));
+                continue;
+            }
+            let side = position.side_to_move();
+            resolution = special_from_csa(&record.action, side).or(resolution);
+        }
+        let mut game = Game::new(position);
+        for (index, time) in move_times.into_iter().enumerate() {
+            // `index` is less than the number of moves.
+            let _ = game.move_time_set(index, time);
+        }
+        match resolution {
+            Some(Ok(detail)) => game.resolve_with_detail(detail),
+            Some(Err(resolution)) => game.resolve(resolution),
+            None => {}
+        }
+        game.time_control_set(record.time_limit.as_ref().map(|time_limit| {
+            TimeControl::with_byoyomi(
+                time_limit.main_time.as_millis() as u32,
+                time_limit.byoyomi.as_millis() as u32,
+            )
+        }));
+        Some(game)
+    }
+
+    /// Converts `self` to a game record in CSA format.
+    ///
+    /// If `self` is resolved, a special move that represents the resolution is appended.
+    /// If [`Game::resolution_detail`] is known, the special move corresponding to it (e.g. `%TIME_UP` or `%KACHI`) is used where possible.
+    /// Otherwise, the special move is
+    /// `%TORYO` if the loser is to move, `%±ILLEGAL_ACTION` if the winner is to move,
+    /// `%JISHOGI` for a draw, `%SENNICHITE` for a rematch and `%CHUDAN` for an aborted game.
+    /// The time control is written as the time limit, where increments and per-move limits are dropped.
+    /// The time spent on each move is written if known.
+    ///
+    /// Returns [`None`] if a move does not make sense.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::{Game, GameResolution, Move, Position, Square};
+    /// let mut position = Position::startpos();
+    /// position.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// let mut game = Game::new(position);
+    /// game.resolve(GameResolution::BlackWins);
+    /// let record = game.to_csa().unwrap();
+    /// assert_eq!(record.to_string(), "V2.2\nPI\n+\n+7776FU\n%TORYO\n");
+    /// ```
+    pub fn to_csa(&self) -> Option<csa::GameRecord> {
+        let position = self.position();
+        let mut current = position.initial_position().clone();
+        let mut moves = Vec::with_capacity(position.moves().len() + 1);
+        for (index, &mv) in position.moves().iter().enumerate() {
+            moves.push(csa::MoveRecord {
+                action: current.move_to_csa(mv)?,
+                time: self
+                    .move_time(index)
+                    .map(|time| Duration::from_millis(time as u64)),
+            });
+            current.make_move(mv)?;
+        }
+        let side = current.side_to_move();
+        let detail = self
+            .resolution_detail()
+            .and_then(|detail| detail_to_csa(detail, side));
+        let action = detail.or_else(|| {
+            self.resolution().map(|resolution| match resolution {
+                GameResolution::BlackWins | GameResolution::WhiteWins => {
+                    let loser = if resolution == GameResolution::BlackWins {
+                        Color::White
+                    } else {
+                        Color::Black
+                    };
+                    if loser == side {
+                        csa::Action::Toryo
+                    } else {
+                        csa::Action::IllegalAction(loser.into())
+                    }
+                }
+                GameResolution::Draw => csa::Action::Jishogi,
+                GameResolution::Rematch => csa::Action::Sennichite,
+                GameResolution::Aborted => csa::Action::Chudan,
+            })
+        });
+        if let Some(action) = action {
+            moves.push(csa::MoveRecord { action, time: None });
+        }
+        Some(csa::GameRecord {
+            start_pos: position.initial_position().to_csa(),
+            moves,
+            time_limit: self.time_control().map(|time_control| csa::TimeLimit {
+                main_time: Duration::from_millis(time_control.main_time as u64),
+                byoyomi: Duration::from_millis(time_control.byoyomi as u64),
+            }),
+            ..csa::GameRecord::default()
+        })
+    }
+}
+
+// How a game is resolved by the special move `action`, where `side` is to move.
+// Returns `Err` if only the resolution is known, and `None` if `action` does not resolve a game.
+pub(crate) fn special_from_csa(
+    action: &csa::Action,
+    side: Color,
+) -> Option<Result<ResolutionDetail, GameResolution>> {
+    Some(match *action {
+        csa::Action::Toryo => Ok(ResolutionDetail::Resignation(side)),
+        csa::Action::TimeUp => Ok(ResolutionDetail::TimeUp(side)),
+        csa::Action::IllegalMove => Ok(ResolutionDetail::IllegalMove(side, None)),
+        csa::Action::Tsumi => Ok(ResolutionDetail::Mated(side)),
+        csa::Action::Kachi => Ok(ResolutionDetail::Declaration(side)),
+        csa::Action::IllegalAction(loser) => Ok(ResolutionDetail::IllegalMove(loser.into(), None)),
+        csa::Action::Jishogi => Ok(ResolutionDetail::Impasse),
+        csa::Action::Hikiwake => Err(GameResolution::Draw),
+        csa::Action::Sennichite => Ok(ResolutionDetail::Repetition),
+        csa::Action::Chudan => Ok(ResolutionDetail::Aborted),
+        _ => return None,
+    })
+}
+
+// The special move that represents `detail`, where `side` is to move.
+// Returns `None` if no special move represents it, e.g., if a player who is not to move resigned.
+fn detail_to_csa(detail: ResolutionDetail, side: Color) -> Option<csa::Action> {
+    let to_move = detail.player() == Some(side);
+    Some(match detail {
+        ResolutionDetail::Resignation(_) if to_move => csa::Action::Toryo,
+        ResolutionDetail::TimeUp(_) if to_move => csa::Action::TimeUp,
+        ResolutionDetail::IllegalMove(_, _) if to_move => csa::Action::IllegalMove,
+        ResolutionDetail::IllegalMove(loser, _) | ResolutionDetail::PerpetualCheck(loser) => {
+            csa::Action::IllegalAction(loser.into())
+        }
+        ResolutionDetail::Mated(_) if to_move => csa::Action::Tsumi,
+        ResolutionDetail::Declaration(_) if to_move => csa::Action::Kachi,
+        ResolutionDetail::Repetition => csa::Action::Sennichite,
+        ResolutionDetail::Impasse | ResolutionDetail::MoveLimit => csa::Action::Jishogi,
+        ResolutionDetail::Aborted => csa::Action::Chudan,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_csa_works() {
+        let record = csa::parse_csa(
+            "\
+V2.2
+$TIME_LIMIT:00:25+10
+P1-KY-KE-GI-KI-OU-KI-GI-KE-KY
+P2 * -HI *  *  *  *  * -KA * 
+P3-FU-FU-FU-FU-FU-FU-FU-FU-FU
+P4 *  *  *  *  *  *  *  *  * 
+P5 *  *  *  *  *  *  *  *  * 
+P6 *  *  *  *  *  *  *  *  * 
+P7+FU+FU+FU+FU+FU+FU+FU+FU+FU
+P8 * +KA *  *  *  *  * +HI * 
+P9+KY+KE+GI+KI+OU+KI+GI+KE+KY
++
++7776FU
+T12
+-3334FU
++8822UM
+T3
+-3122GI
++0045KA
+%TORYO
+",
+        )
+        .unwrap();
+        let game = Game::from_csa(&record).unwrap();
+        assert_eq!(
+            game.position().initial_position(),
+            &PartialPosition::startpos()
+        );
+        assert_eq!(game.position().moves().len(), 5);
+        assert_eq!(
+            game.position().to_sfen_owned(),
+            "lnsgkg1nl/1r5s1/pppppp1pp/6p2/5B3/2P6/PP1PPPPPP/7R1/LNSGKGSNL w b 6",
+        );
+        assert_eq!(game.resolution(), Some(GameResolution::BlackWins));
+        assert_eq!(
+            game.resolution_detail(),
+            Some(ResolutionDetail::Resignation(Color::White)),
+        );
+        assert_eq!(
+            game.time_control(),
+            Some(TimeControl::with_byoyomi(1_500_000, 10_000)),
+        );
+        assert_eq!(game.move_time(0), Some(12_000));
+        assert_eq!(game.move_time(1), None);
+        assert_eq!(game.move_time(2), Some(3_000));
+        assert_eq!(game.remaining_time(Color::Black, 1), Some(1_488_000));
+        assert_eq!(game.remaining_time(Color::Black, 2), None);
+
+        let record = game.to_csa().unwrap();
+        assert_eq!(record.start_pos, csa::Position::default());
+        assert_eq!(Game::from_csa(&record), Some(game));
+    }
+
+    #[test]
+    fn resolution_detail_works() {
+        let mut game = Game::from_csa(&csa::parse_csa("V2.2\nPI\n+\n+7776FU\n").unwrap()).unwrap();
+        let cases = [
+            (ResolutionDetail::TimeUp(Color::White), "%TIME_UP"),
+            (ResolutionDetail::Declaration(Color::White), "%KACHI"),
+            (ResolutionDetail::Mated(Color::White), "%TSUMI"),
+            (
+                ResolutionDetail::IllegalMove(Color::Black, None),
+                "%+ILLEGAL_ACTION",
+            ),
+            (ResolutionDetail::Repetition, "%SENNICHITE"),
+        ];
+        for (detail, special) in cases {
+            game.resolve_with_detail(detail);
+            let record = game.to_csa().unwrap();
+            assert!(record.to_string().ends_with(&format!("{}\n", special)));
+            assert_eq!(Game::from_csa(&record), Some(game.clone()));
+        }
+        // Black cannot resign when white is to move
+        game.resolve_with_detail(ResolutionDetail::Resignation(Color::Black));
+        let record = game.to_csa().unwrap();
+        assert!(record.to_string().ends_with("%+ILLEGAL_ACTION\n"));
+    }
+
+    #[test]
+    fn hand_works() {
+        let record = csa::parse_csa(
+            "\
+V2.2
+P1 *  *  *  *  *  *  *  * -OU
+P2 *  *  *  *  *  *  *  *  * 
+P3 *  *  *  *  *  *  *  *  * 
+P4 *  *  *  *  *  *  *  *  * 
+P5 *  *  *  *  *  *  *  *  * 
+P6 *  *  *  *  *  *  *  *  * 
+P7 *  *  *  *  *  *  *  *  * 
+P8 *  *  *  *  *  *  *  *  * 
+P9 *  *  *  *  *  *  *  *  * 
+P+00HI
+P-00AL
++
+",
+        )
+        .unwrap();
+        let game = Game::from_csa(&record).unwrap();
+        let initial = game.position().initial_position();
+        assert_eq!(
+            initial
+                .hand_of_a_player(Color::Black)
+                .count(PieceKind::Rook),
+            Some(1),
+        );
+        let white = initial.hand_of_a_player(Color::White);
+        assert_eq!(white.count(PieceKind::Rook), Some(1));
+        assert_eq!(white.count(PieceKind::Pawn), Some(18));
+        assert_eq!(
+            PartialPosition::from_csa(&initial.to_csa()).as_ref(),
+            Some(initial)
+        );
+    }
+
+    #[test]
+    fn inconsistent_moves_are_rejected() {
+        let position = PartialPosition::startpos();
+        let wrong_piece = csa::Action::Move(
+            csa::Color::Black,
+            csa::Square::new(7, 7),
+            csa::Square::new(7, 6),
+            csa::PieceType::Lance,
+        );
+        assert_eq!(position.move_from_csa(&wrong_piece), None);
+        let wrong_color = csa::Action::Move(
+            csa::Color::White,
+            csa::Square::new(3, 3),
+            csa::Square::new(3, 4),
+            csa::PieceType::Pawn,
+        );
+        assert_eq!(position.move_from_csa(&wrong_color), None);
+        assert_eq!(position.move_from_csa(&csa::Action::Toryo), None);
+    }
+}
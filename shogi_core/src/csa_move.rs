@@ -0,0 +1,202 @@
+//! Moves in CSA format (e.g. `+7776FU`, `-0055KA`), without depending on the [`csa`](https://docs.rs/csa) crate.
+
+use crate::c_compat::CMove;
+use crate::common::c_bytes;
+use crate::{Color, Hand, Move, PartialPosition, Piece, PieceKind, Square};
+
+// Names of piece kinds in CSA format, indexed by `PieceKind::array_index`.
+const NAMES: [[u8; 2]; PieceKind::NUM] = [
+    *b"FU", *b"KY", *b"KE", *b"GI", *b"KI", *b"KA", *b"HI", *b"OU", *b"TO", *b"NY", *b"NK", *b"NG",
+    *b"UM", *b"RY",
+];
+
+impl PartialPosition {
+    /// Parses a move in CSA format, e.g. `+7776FU` or `-0055KA`, which is made in `self`.
+    ///
+    /// A move in CSA format consists of the sign of the player (`+` or `-`), the source square (`00` for a drop),
+    /// the destination square and the piece after the move.
+    /// Whether the move promotes is found by comparing the piece with the one on the source square in `self`.
+    /// Suffixes sent by CSA servers, e.g. `,T12`, must be removed beforehand.
+    ///
+    /// Returns [`None`] if `s` is not a move in CSA format or is inconsistent with `self`,
+    /// e.g. the moved piece is not on the source square or does not belong to the player to move.
+    /// Legality of the move is not checked.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Move, PartialPosition, Piece, Square};
+    /// let position = PartialPosition::from_sfen("lnsgkgsnl/1r5b1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL b - 3").unwrap();
+    /// assert_eq!(
+    ///     position.move_from_csa_str("+8822UM"),
+    ///     Some(Move::Normal { from: Square::SQ_8H, to: Square::SQ_2B, promote: true }),
+    /// );
+    /// assert_eq!(position.move_from_csa_str("+8822KA"), Some(Move::Normal { from: Square::SQ_8H, to: Square::SQ_2B, promote: false }));
+    /// // It is black's turn.
+    /// assert_eq!(position.move_from_csa_str("-3334FU"), None);
+    /// // The piece on 8h is not a rook.
+    /// assert_eq!(position.move_from_csa_str("+8822RY"), None);
+    ///
+    /// let position = PartialPosition::from_sfen("4k4/9/9/9/9/9/9/9/4K4 w b 1").unwrap();
+    /// assert_eq!(
+    ///     position.move_from_csa_str("-0055KA"),
+    ///     Some(Move::Drop { piece: Piece::W_B, to: Square::SQ_5E }),
+    /// );
+    /// assert_eq!(position.move_from_csa_str("-0055UM"), None);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn move_from_csa_str(&self, s: &str) -> Option<Move> {
+        self.move_from_csa_bytes(s.as_bytes())
+    }
+
+    fn move_from_csa_bytes(&self, bytes: &[u8]) -> Option<Move> {
+        let (sign, from, to, name) = match *bytes {
+            [sign, from_file, from_rank, to_file, to_rank, name0, name1] => (
+                sign,
+                [from_file, from_rank],
+                [to_file, to_rank],
+                [name0, name1],
+            ),
+            _ => return None,
+        };
+        let color = match sign {
+            b'+' => Color::Black,
+            b'-' => Color::White,
+            _ => return None,
+        };
+        if color != self.side_to_move() {
+            return None;
+        }
+        let to = parse_square(to)?;
+        let index = NAMES.iter().position(|&candidate| candidate == name)?;
+        let piece_kind = PieceKind::from_u8(index as u8 + 1)?;
+        if from == *b"00" {
+            if !Hand::is_hand_piece(piece_kind) {
+                return None;
+            }
+            return Some(Move::Drop {
+                piece: Piece::new(piece_kind, color),
+                to,
+            });
+        }
+        let from = parse_square(from)?;
+        let (original, owner) = self.piece_at(from)?.to_parts();
+        if owner != color {
+            return None;
+        }
+        let promote = if original == piece_kind {
+            false
+        } else if original.promote() == Some(piece_kind) {
+            true
+        } else {
+            return None;
+        };
+        Some(Move::Normal { from, to, promote })
+    }
+
+    /// C interface of [`PartialPosition::move_from_csa_str`].
+    /// The move is read from `len` bytes starting at `ptr`, which need not be nul-terminated.
+    ///
+    /// If the bytes are a valid move, this function writes it to `out` and returns `true`.
+    /// Otherwise, this function returns `false` and `out` is left untouched.
+    ///
+    /// # Safety
+    /// `ptr` must be null or valid for reads of `len` bytes. `out` must be valid for writes.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn PartialPosition_move_from_csa_c(
+        &self,
+        ptr: *const u8,
+        len: usize,
+        out: *mut CMove,
+    ) -> bool {
+        match self.move_from_csa_bytes(c_bytes(ptr, len)) {
+            Some(mv) => {
+                *out = mv.into();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// Parses a square in CSA format, e.g. `76`.
+fn parse_square([file, rank]: [u8; 2]) -> Option<Square> {
+    if !file.is_ascii_digit() || !rank.is_ascii_digit() {
+        return None;
+    }
+    Square::new(file - b'0', rank - b'0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_from_csa_str_works() {
+        let position = PartialPosition::startpos();
+        assert_eq!(
+            position.move_from_csa_str("+7776FU"),
+            Some(Move::Normal {
+                from: Square::SQ_7G,
+                to: Square::SQ_7F,
+                promote: false,
+            }),
+        );
+        for s in [
+            "",
+            "+7776F",
+            "+7776FUU",
+            "*7776FU",
+            "+7776fu",
+            "+7776XX",
+            "+7a76FU",
+            "+7770FU",
+            "+7076FU",
+            "+5576FU",
+            "+7776KY",
+            "+3334FU",
+            "+7776FU,T1",
+        ] {
+            assert_eq!(position.move_from_csa_str(s), None, "{}", s);
+        }
+
+        // Every piece kind can be parsed.
+        let position =
+            PartialPosition::from_sfen("+R+B+S+N+L+P3/9/9/9/9/9/9/4K4/9 b RBGSNLPrbgsnlp 1")
+                .unwrap();
+        for (index, name) in NAMES.iter().enumerate() {
+            let piece_kind = PieceKind::from_u8(index as u8 + 1).unwrap();
+            let name = core::str::from_utf8(name).unwrap();
+            let drop = position.move_from_csa_str(&format!("+0055{}", name));
+            assert_eq!(drop.is_some(), Hand::is_hand_piece(piece_kind));
+            let from = position.piece_bitboard(Piece::new(piece_kind, Color::Black));
+            if let Some(from) = from.into_iter().next() {
+                let s = format!("+{}{}55{}", from.file(), from.rank(), name);
+                assert_eq!(
+                    position.move_from_csa_str(&s),
+                    Some(Move::Normal {
+                        from,
+                        to: Square::SQ_5E,
+                        promote: false,
+                    }),
+                );
+            }
+        }
+        let mut buf: CMove = Move::Normal {
+            from: Square::SQ_1A,
+            to: Square::SQ_1B,
+            promote: false,
+        }
+        .into();
+        let s = b"+0055KI";
+        assert!(unsafe { position.PartialPosition_move_from_csa_c(s.as_ptr(), s.len(), &mut buf) });
+        assert_eq!(
+            buf.to_move(),
+            Some(Move::Drop {
+                piece: Piece::B_G,
+                to: Square::SQ_5E,
+            }),
+        );
+    }
+}
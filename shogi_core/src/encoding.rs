@@ -0,0 +1,163 @@
+/// Text encodings in which game records are commonly saved.
+///
+/// KIF and KI2 files are often saved in Shift_JIS (`.kif`, `.ki2`) or UTF-8 (`.kifu`, `.ki2u`),
+/// and older files in EUC-JP. This crate reads only UTF-8, so records in other encodings must be converted beforehand,
+/// e.g. with [`encoding_rs`](https://crates.io/crates/encoding_rs), whose labels are given by [`TextEncoding::label`].
+///
+/// Since: 0.1.6
+#[repr(u8)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum TextEncoding {
+    /// UTF-8 without a byte order mark.
+    ///
+    /// Discriminant = 1.
+    Utf8 = 1,
+    /// UTF-8 with a byte order mark (`EF BB BF`).
+    ///
+    /// Discriminant = 2.
+    Utf8Bom = 2,
+    /// Shift_JIS, including its extension by Microsoft (CP932).
+    ///
+    /// Discriminant = 3.
+    ShiftJis = 3,
+    /// EUC-JP.
+    ///
+    /// Discriminant = 4.
+    EucJp = 4,
+}
+
+impl TextEncoding {
+    /// Guesses the encoding of `bytes`.
+    ///
+    /// UTF-8 is preferred if `bytes` is valid in UTF-8 (e.g. if it consists only of ASCII characters).
+    /// Otherwise, Shift_JIS and EUC-JP are tried. If `bytes` is valid in both, the one which does not need
+    /// half-width katakana is chosen, because they are rare in game records; ties are resolved in favor of Shift_JIS.
+    /// `bytes` may end in the middle of a character, so that the beginning of a file can be passed.
+    ///
+    /// Returns [`None`] if `bytes` is valid in none of them.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::TextEncoding;
+    /// assert_eq!(TextEncoding::detect("先手：Alice".as_bytes()), Some(TextEncoding::Utf8));
+    /// assert_eq!(TextEncoding::detect(b"\xef\xbb\xbf#KIF"), Some(TextEncoding::Utf8Bom));
+    /// // "先手" in Shift_JIS and EUC-JP
+    /// assert_eq!(TextEncoding::detect(b"\x90\xe6\x8e\xe8"), Some(TextEncoding::ShiftJis));
+    /// assert_eq!(TextEncoding::detect(b"\xc0\xe8\xbc\xea"), Some(TextEncoding::EucJp));
+    /// assert_eq!(TextEncoding::detect(b"\xff\xff"), None);
+    /// ```
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"\xef\xbb\xbf") {
+            return Some(TextEncoding::Utf8Bom);
+        }
+        match core::str::from_utf8(bytes) {
+            Ok(_) => return Some(TextEncoding::Utf8),
+            // `bytes` ends in the middle of a character.
+            Err(error) if error.error_len().is_none() => return Some(TextEncoding::Utf8),
+            Err(_) => {}
+        }
+        match (shift_jis_kana_count(bytes), euc_jp_kana_count(bytes)) {
+            (Some(sjis), Some(euc)) if euc < sjis => Some(TextEncoding::EucJp),
+            (Some(_), _) => Some(TextEncoding::ShiftJis),
+            (None, Some(_)) => Some(TextEncoding::EucJp),
+            (None, None) => None,
+        }
+    }
+
+    /// Returns the label of `self` defined in the [Encoding Standard](https://encoding.spec.whatwg.org/),
+    /// e.g. `"Shift_JIS"`. Both [`TextEncoding::Utf8`] and [`TextEncoding::Utf8Bom`] are labeled `"UTF-8"`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::TextEncoding;
+    /// assert_eq!(TextEncoding::EucJp.label(), "EUC-JP");
+    /// ```
+    pub const fn label(self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 | TextEncoding::Utf8Bom => "UTF-8",
+            TextEncoding::ShiftJis => "Shift_JIS",
+            TextEncoding::EucJp => "EUC-JP",
+        }
+    }
+}
+
+impl_ord_for_fieldless_enum!(TextEncoding);
+impl_hash_for_fieldless_enum!(TextEncoding);
+
+// Counts half-width katakana in `bytes` interpreted in Shift_JIS. Returns `None` if `bytes` is invalid.
+fn shift_jis_kana_count(bytes: &[u8]) -> Option<usize> {
+    let mut count = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            0x00..=0x7f => index += 1,
+            0xa1..=0xdf => {
+                count += 1;
+                index += 1;
+            }
+            0x81..=0x9f | 0xe0..=0xfc => {
+                match bytes.get(index + 1) {
+                    Some(0x40..=0x7e | 0x80..=0xfc) | None => {}
+                    Some(_) => return None,
+                }
+                index += 2;
+            }
+            _ => return None,
+        }
+    }
+    Some(count)
+}
+
+// Counts half-width katakana in `bytes` interpreted in EUC-JP. Returns `None` if `bytes` is invalid.
+fn euc_jp_kana_count(bytes: &[u8]) -> Option<usize> {
+    let mut count = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        let length = match bytes[index] {
+            0x00..=0x7f => 1,
+            0x8e => {
+                count += 1;
+                2
+            }
+            0x8f => 3,
+            0xa1..=0xfe => 2,
+            _ => return None,
+        };
+        let trail = &bytes[(index + 1).min(bytes.len())..(index + length).min(bytes.len())];
+        let valid = if bytes[index] == 0x8e {
+            trail.iter().all(|byte| matches!(byte, 0xa1..=0xdf))
+        } else {
+            trail.iter().all(|byte| matches!(byte, 0xa1..=0xfe))
+        };
+        if !valid {
+            return None;
+        }
+        index += length;
+    }
+    Some(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_works() {
+        assert_eq!(TextEncoding::detect(b""), Some(TextEncoding::Utf8));
+        // "手合割：平手" in Shift_JIS and EUC-JP
+        let sjis = b"\x8e\xe8\x8d\x87\x8a\x84\x81\x46\x95\xbd\x8e\xe8";
+        let euc = b"\xbc\xea\xb9\xe7\xb3\xe4\xa1\xa7\xca\xbf\xbc\xea";
+        assert_eq!(TextEncoding::detect(sjis), Some(TextEncoding::ShiftJis));
+        assert_eq!(TextEncoding::detect(euc), Some(TextEncoding::EucJp));
+        // Truncated in the middle of a character
+        assert_eq!(
+            TextEncoding::detect(&sjis[..3]),
+            Some(TextEncoding::ShiftJis)
+        );
+        assert_eq!(TextEncoding::detect(&euc[..3]), Some(TextEncoding::EucJp));
+        assert_eq!(
+            TextEncoding::detect(&"▲７六歩".as_bytes()[..5]),
+            Some(TextEncoding::Utf8)
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ops::Range;
+
+use crate::{Color, PieceKind, Square};
+
+/// A type with finitely many values, each of which has an index for array accesses.
+///
+/// This trait makes it possible to write tables and containers indexed by [`Color`], [`Square`], [`PieceKind`] or [`Piece`](crate::Piece) only once.
+/// It is implemented for [`Piece`](crate::Piece) only with the `experimental` feature, because the indices of pieces are experimental.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Color, EnumLike, PieceKind, Square};
+/// fn count<T: EnumLike>() -> usize {
+///     T::all().count()
+/// }
+/// assert_eq!(count::<Color>(), 2);
+/// assert_eq!(count::<Square>(), 81);
+/// assert_eq!(count::<PieceKind>(), 14);
+/// ```
+///
+/// Since: 0.1.6
+pub trait EnumLike: Copy + Eq {
+    /// How many elements should an array indexed by `Self` have?
+    const NUM: usize;
+
+    /// Returns the index of `self` for array accesses. This function returns an integer in range `0..Self::NUM`.
+    fn array_index(self) -> usize;
+
+    /// Finds the value whose index for array accesses is `index`, if any.
+    ///
+    /// This function is the inverse of [`EnumLike::array_index`].
+    fn from_array_index(index: usize) -> Option<Self>;
+
+    /// Returns an iterator over all values of `Self`, in ascending order of [`EnumLike::array_index`].
+    fn all() -> EnumLikeIter<Self> {
+        EnumLikeIter {
+            indices: 0..Self::NUM,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// An iterator over all values of an [`EnumLike`] type. Created by [`EnumLike::all`].
+///
+/// Since: 0.1.6
+#[derive(Clone, Debug)]
+pub struct EnumLikeIter<T> {
+    indices: Range<usize>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: EnumLike> Iterator for EnumLikeIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.indices.by_ref().find_map(T::from_array_index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.indices.len()))
+    }
+}
+
+impl<T: EnumLike> DoubleEndedIterator for EnumLikeIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.indices.by_ref().rev().find_map(T::from_array_index)
+    }
+}
+
+impl<T: EnumLike> FusedIterator for EnumLikeIter<T> {}
+
+impl EnumLike for Color {
+    const NUM: usize = Color::NUM;
+
+    #[inline(always)]
+    fn array_index(self) -> usize {
+        Color::array_index(self)
+    }
+
+    #[inline]
+    fn from_array_index(index: usize) -> Option<Self> {
+        match index {
+            0 => Some(Color::Black),
+            1 => Some(Color::White),
+            _ => None,
+        }
+    }
+}
+
+impl EnumLike for Square {
+    const NUM: usize = Square::NUM;
+
+    #[inline(always)]
+    fn array_index(self) -> usize {
+        Square::array_index(self)
+    }
+
+    #[inline]
+    fn from_array_index(index: usize) -> Option<Self> {
+        if index < Square::NUM {
+            Square::from_u8(index as u8 + 1)
+        } else {
+            None
+        }
+    }
+}
+
+impl EnumLike for PieceKind {
+    const NUM: usize = PieceKind::NUM;
+
+    #[inline(always)]
+    fn array_index(self) -> usize {
+        PieceKind::array_index(self)
+    }
+
+    #[inline]
+    fn from_array_index(index: usize) -> Option<Self> {
+        if index < PieceKind::NUM {
+            PieceKind::from_u8(index as u8 + 1)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "experimental")]
+#[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
+impl EnumLike for crate::Piece {
+    const NUM: usize = crate::Piece::NUM;
+
+    #[inline(always)]
+    fn array_index(self) -> usize {
+        crate::Piece::array_index(self)
+    }
+
+    #[inline]
+    fn from_array_index(index: usize) -> Option<Self> {
+        if index < crate::Piece::NUM {
+            crate::Piece::try_from(index as u8 + 1).ok()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check<T: EnumLike + core::fmt::Debug>(expected: usize) {
+        let mut count = 0;
+        let mut last = None;
+        for value in T::all() {
+            let index = value.array_index();
+            assert!(index < T::NUM);
+            assert_eq!(T::from_array_index(index), Some(value));
+            assert!(last < Some(index));
+            last = Some(index);
+            count += 1;
+        }
+        assert_eq!(count, expected);
+        assert_eq!(T::all().rev().count(), expected);
+        assert_eq!(T::from_array_index(T::NUM), None);
+    }
+
+    #[test]
+    fn enum_like_works() {
+        check::<Color>(2);
+        check::<Square>(81);
+        check::<PieceKind>(14);
+        #[cfg(feature = "experimental")]
+        check::<crate::Piece>(28);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{Bitboard, Color, CompactMove, Hand, Move, PartialPosition, Piece, Square};
+
+/// A type whose values can be viewed from the other player's side,
+/// i.e., rotated by 180 degrees with the colors of pieces swapped.
+///
+/// This trait makes it possible to write color-symmetric code (e.g. evaluation of the position from white's point of view) only once.
+/// Flipping twice always gives back the original value.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Bitboard, Flip, Move, Piece, Square};
+/// fn is_symmetric<T: Flip + Clone + Eq>(value: &T) -> bool {
+///     value.clone().flip() == *value
+/// }
+/// assert!(is_symmetric(&Square::SQ_5E));
+/// assert!(!is_symmetric(&Bitboard::single(Square::SQ_1A)));
+/// let mv = Move::Drop { piece: Piece::B_P, to: Square::SQ_7F };
+/// assert_eq!(mv.flip(), Move::Drop { piece: Piece::W_P, to: Square::SQ_3D });
+/// ```
+///
+/// Since: 0.1.6
+pub trait Flip {
+    /// Returns `self` viewed from the other player's side.
+    #[must_use]
+    fn flip(self) -> Self;
+}
+
+impl Flip for Color {
+    #[inline(always)]
+    fn flip(self) -> Self {
+        Color::flip(self)
+    }
+}
+
+impl Flip for Square {
+    #[inline(always)]
+    fn flip(self) -> Self {
+        Square::flip(self)
+    }
+}
+
+impl Flip for Bitboard {
+    #[inline(always)]
+    fn flip(self) -> Self {
+        Bitboard::flip(self)
+    }
+}
+
+impl Flip for Piece {
+    #[inline(always)]
+    fn flip(self) -> Self {
+        let (piece_kind, color) = self.to_parts();
+        Piece::new(piece_kind, color.flip())
+    }
+}
+
+impl Flip for Move {
+    fn flip(self) -> Self {
+        match self {
+            Move::Normal { from, to, promote } => Move::Normal {
+                from: from.flip(),
+                to: to.flip(),
+                promote,
+            },
+            Move::Drop { piece, to } => Move::Drop {
+                piece: Flip::flip(piece),
+                to: to.flip(),
+            },
+        }
+    }
+}
+
+impl Flip for CompactMove {
+    fn flip(self) -> Self {
+        let mv: Move = self.into();
+        mv.flip().into()
+    }
+}
+
+/// The hands of black and white, in this order.
+impl Flip for [Hand; 2] {
+    #[inline(always)]
+    fn flip(self) -> Self {
+        [self[1], self[0]]
+    }
+}
+
+/// The ply is kept as it is.
+impl Flip for PartialPosition {
+    fn flip(self) -> Self {
+        let mut result = PartialPosition::empty();
+        for square in Square::all() {
+            result.piece_set(square.flip(), self.piece_at(square).map(Flip::flip));
+        }
+        for color in Color::all() {
+            *result.hand_of_a_player_mut(color.flip()) = self.hand_of_a_player(color);
+        }
+        result.side_to_move_set(self.side_to_move().flip());
+        // `self.ply()` is nonzero, so this always succeeds.
+        let _ = result.ply_set(self.ply());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PieceKind;
+
+    #[test]
+    fn flip_works() {
+        for piece in Piece::all() {
+            assert_eq!(Flip::flip(Flip::flip(piece)), piece);
+            assert_ne!(Flip::flip(piece), piece);
+        }
+        let mv = CompactMove::normal(Square::SQ_2C, Square::SQ_8H, true);
+        assert_eq!(
+            mv.flip(),
+            CompactMove::normal(Square::SQ_8G, Square::SQ_2B, true)
+        );
+
+        let hands = [Hand::new(), Hand::new().added(PieceKind::Pawn).unwrap()];
+        assert_eq!(hands.flip(), [hands[1], hands[0]]);
+
+        let position = PartialPosition::startpos();
+        assert_eq!(position.clone().flip().to_sfen_owned(), {
+            let mut expected = position.clone();
+            expected.side_to_move_set(Color::White);
+            expected.to_sfen_owned()
+        });
+        let sfen = "l6nl/5+P1gk/2np1S3/p1p4Pp/3P2Sp1/1PPb2P1P/P5GS1/R8/LN4bKL w RGgsn5p 75";
+        let position = PartialPosition::from_sfen(sfen).unwrap();
+        let flipped = position.clone().flip();
+        assert_eq!(
+            flipped.to_sfen_owned(),
+            "lkB4nl/8r/1sg5p/p1p2Bpp1/1Ps2p3/Pp4P1P/3s1PN2/KG1+p5/LN6L b GSN5Prg 75",
+        );
+        assert_eq!(flipped.flip(), position);
+    }
+}
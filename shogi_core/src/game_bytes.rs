@@ -0,0 +1,459 @@
+//! A binary encoding of [`Game`]s.
+
+use alloc::vec::Vec;
+
+use crate::{
+    Color, CompactAction, CompactMove, Game, GameResolution, IllegalMoveKind, Move, MoveAnnotation,
+    PackedSfen, PartialPosition, Position, ResolutionDetail, TimeControl,
+};
+
+const MAGIC: [u8; 4] = *b"SHGB";
+// The version of the binary format.
+const VERSION: u8 = 1;
+
+// Tags of the initial position
+const INITIAL_STARTPOS: u8 = 0;
+const INITIAL_PACKED: u8 = 1;
+const INITIAL_SFEN: u8 = 2;
+
+// Tags of optional blocks
+const BLOCK_RESOLUTION_DETAIL: u8 = 1;
+const BLOCK_TIME_CONTROL: u8 = 2;
+const BLOCK_ANNOTATIONS: u8 = 3;
+const BLOCK_MOVE_TIMES: u8 = 4;
+
+/// Binary encoding.
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl Game {
+    /// Encodes `self` in a compact binary format.
+    ///
+    /// The format consists of the following, where integers are little-endian:
+    /// - the magic bytes `SHGB` and the version of the format (currently 1) in a byte;
+    /// - the initial position: a byte `0` for [`PartialPosition::startpos`], a byte `1` followed by a [`PackedSfen`] and the ply (`u16`),
+    ///   or a byte `2` followed by the length (`u8`) and the bytes of the SFEN. Unless it is `0`, the last move follows as a `u16`
+    ///   (`0` if none);
+    /// - the number of moves (`u32`) and the moves, each of which is a [`CompactMove`] (`u16`);
+    /// - the resolution in a byte (`0` if unresolved);
+    /// - optional blocks until the end, each of which is a tag (`u8`), the length of the payload (`u32`) and the payload.
+    ///   They hold the [`ResolutionDetail`] (tag 1), the [`TimeControl`] (tag 2), the [`MoveAnnotation`]s (tag 3) and
+    ///   the time spent on moves (tag 4). Readers skip blocks with unknown tags.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Game, Move, Position, ResolutionDetail, Square};
+    /// let mut position = Position::startpos();
+    /// position.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// let mut game = Game::new(position);
+    /// game.resolve_with_detail(ResolutionDetail::Resignation(Color::White));
+    /// let bytes = game.to_bytes();
+    /// assert_eq!(bytes.len(), 21);
+    /// assert_eq!(Game::from_bytes(&bytes), Some(game));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let position = self.position();
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&MAGIC);
+        bytes.push(VERSION);
+        write_initial(&mut bytes, position.initial_position());
+        bytes.extend_from_slice(&(position.moves().len() as u32).to_le_bytes());
+        for &mv in position.moves() {
+            bytes.extend_from_slice(&move_to_u16(Some(mv)).to_le_bytes());
+        }
+        bytes.push(self.resolution().map_or(0, |resolution| resolution as u8));
+
+        if let Some(detail) = self.resolution_detail() {
+            let (reason, player, kind) = detail_to_bytes(detail);
+            write_block(&mut bytes, BLOCK_RESOLUTION_DETAIL, &[reason, player, kind]);
+        }
+        if let Some(time_control) = self.time_control() {
+            let mut payload = Vec::with_capacity(16);
+            for value in [
+                time_control.main_time,
+                time_control.byoyomi,
+                time_control.increment,
+                time_control.per_move_limit,
+            ] {
+                payload.extend_from_slice(&value.to_le_bytes());
+            }
+            write_block(&mut bytes, BLOCK_TIME_CONTROL, &payload);
+        }
+        let mut payload = Vec::new();
+        for (index, annotation) in self.annotations() {
+            payload.extend_from_slice(&(index as u32).to_le_bytes());
+            match annotation {
+                MoveAnnotation::Brilliant => payload.push(1),
+                MoveAnnotation::Good => payload.push(2),
+                MoveAnnotation::Dubious => payload.push(3),
+                MoveAnnotation::Mistake => payload.push(4),
+                MoveAnnotation::Blunder => payload.push(5),
+                MoveAnnotation::Score(score) => {
+                    payload.push(6);
+                    payload.extend_from_slice(&score.to_le_bytes());
+                }
+            }
+        }
+        if !payload.is_empty() {
+            write_block(&mut bytes, BLOCK_ANNOTATIONS, &payload);
+        }
+        let mut payload = Vec::new();
+        for index in 0..position.moves().len() {
+            if let Some(time) = self.move_time(index) {
+                payload.extend_from_slice(&(index as u32).to_le_bytes());
+                payload.extend_from_slice(&time.to_le_bytes());
+            }
+        }
+        if !payload.is_empty() {
+            write_block(&mut bytes, BLOCK_MOVE_TIMES, &payload);
+        }
+        bytes
+    }
+
+    /// Decodes a [`Game`] written by [`Game::to_bytes`].
+    ///
+    /// Returns [`None`] if `bytes` is not in the format, its version is not supported,
+    /// or a move in it does not make sense.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Game, PartialPosition, Position};
+    /// let position = PartialPosition::from_sfen("8k/9/9/9/9/9/9/9/K7R b G 1").unwrap();
+    /// let game = Game::new(Position::arbitrary_position(position));
+    /// let bytes = game.to_bytes();
+    /// assert_eq!(Game::from_bytes(&bytes), Some(game));
+    /// assert_eq!(Game::from_bytes(&bytes[..bytes.len() - 1]), None);
+    /// assert_eq!(Game::from_bytes(b"SFEN"), None);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+        if reader.bytes(4)? != MAGIC || reader.u8()? != VERSION {
+            return None;
+        }
+        let initial = read_initial(&mut reader)?;
+        let mut position = Position::arbitrary_position(initial);
+        let move_count = reader.u32()?;
+        for _ in 0..move_count {
+            let mv = move_from_u16(reader.u16()?)?;
+            position.make_move(mv)?;
+        }
+        let resolution = match reader.u8()? {
+            0 => None,
+            repr => Some(resolution_from_u8(repr)?),
+        };
+
+        let mut game = Game::new(position);
+        let mut detail = None;
+        while !reader.0.is_empty() {
+            let tag = reader.u8()?;
+            let length = reader.u32()? as usize;
+            let mut payload = Reader(reader.bytes(length)?);
+            match tag {
+                BLOCK_RESOLUTION_DETAIL => {
+                    detail = Some(detail_from_bytes(
+                        payload.u8()?,
+                        payload.u8()?,
+                        payload.u8()?,
+                    )?);
+                }
+                BLOCK_TIME_CONTROL => {
+                    game.time_control_set(Some(TimeControl {
+                        main_time: payload.u32()?,
+                        byoyomi: payload.u32()?,
+                        increment: payload.u32()?,
+                        per_move_limit: payload.u32()?,
+                    }));
+                }
+                BLOCK_ANNOTATIONS => {
+                    while !payload.0.is_empty() {
+                        let index = payload.u32()? as usize;
+                        let annotation = match payload.u8()? {
+                            1 => MoveAnnotation::Brilliant,
+                            2 => MoveAnnotation::Good,
+                            3 => MoveAnnotation::Dubious,
+                            4 => MoveAnnotation::Mistake,
+                            5 => MoveAnnotation::Blunder,
+                            6 => MoveAnnotation::Score(payload.u32()? as i32),
+                            _ => return None,
+                        };
+                        if !game.annotation_set(index, Some(annotation)) {
+                            return None;
+                        }
+                    }
+                }
+                BLOCK_MOVE_TIMES => {
+                    while !payload.0.is_empty() {
+                        let index = payload.u32()? as usize;
+                        if !game.move_time_set(index, Some(payload.u32()?)) {
+                            return None;
+                        }
+                    }
+                }
+                // Blocks added in later versions
+                _ => continue,
+            }
+            if !payload.0.is_empty() {
+                return None;
+            }
+        }
+        match (detail, resolution) {
+            (Some(detail), Some(resolution)) if detail.resolution() == resolution => {
+                game.resolve_with_detail(detail);
+            }
+            (None, Some(resolution)) => game.resolve(resolution),
+            (None, None) => {}
+            _ => return None,
+        }
+        Some(game)
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let bytes = self.bytes(2)?;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.bytes(4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+fn write_block(bytes: &mut Vec<u8>, tag: u8, payload: &[u8]) {
+    bytes.push(tag);
+    bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(payload);
+}
+
+fn write_initial(bytes: &mut Vec<u8>, initial: &PartialPosition) {
+    if *initial == PartialPosition::startpos() {
+        bytes.push(INITIAL_STARTPOS);
+        return;
+    }
+    match PackedSfen::from_position(initial) {
+        Some(packed) => {
+            bytes.push(INITIAL_PACKED);
+            bytes.extend_from_slice(&packed.0);
+            bytes.extend_from_slice(&initial.ply().to_le_bytes());
+        }
+        None => {
+            // SFEN is at most 139 bytes long.
+            let sfen = initial.to_sfen_owned();
+            bytes.push(INITIAL_SFEN);
+            bytes.push(sfen.len() as u8);
+            bytes.extend_from_slice(sfen.as_bytes());
+        }
+    }
+    bytes.extend_from_slice(&move_to_u16(initial.last_move()).to_le_bytes());
+}
+
+fn read_initial(reader: &mut Reader) -> Option<PartialPosition> {
+    let mut initial = match reader.u8()? {
+        INITIAL_STARTPOS => return Some(PartialPosition::startpos()),
+        INITIAL_PACKED => {
+            let mut packed = [0; 32];
+            packed.copy_from_slice(reader.bytes(32)?);
+            let mut initial = PackedSfen(packed).to_position()?;
+            if !initial.ply_set(reader.u16()?) {
+                return None;
+            }
+            initial
+        }
+        INITIAL_SFEN => {
+            let len = reader.u8()? as usize;
+            PartialPosition::from_sfen_bytes(reader.bytes(len)?)?
+        }
+        _ => return None,
+    };
+    let last_move = match reader.u16()? {
+        0 => None,
+        value => Some(move_from_u16(value)?),
+    };
+    initial.last_move_set(last_move.map(Into::into));
+    Some(initial)
+}
+
+fn move_to_u16(mv: Option<Move>) -> u16 {
+    mv.map_or(0, |mv| {
+        let mv: CompactMove = mv.into();
+        CompactAction::from(mv).as_u16()
+    })
+}
+
+fn move_from_u16(value: u16) -> Option<Move> {
+    Some(CompactAction::from_u16(value)?.to_move()?.into())
+}
+
+fn resolution_from_u8(repr: u8) -> Option<GameResolution> {
+    Some(match repr {
+        1 => GameResolution::BlackWins,
+        2 => GameResolution::WhiteWins,
+        3 => GameResolution::Draw,
+        4 => GameResolution::Rematch,
+        5 => GameResolution::Aborted,
+        _ => return None,
+    })
+}
+
+// Returns the reason, the player (0 if none) and the kind of an illegal move (0 if none).
+fn detail_to_bytes(detail: ResolutionDetail) -> (u8, u8, u8) {
+    let reason = match detail {
+        ResolutionDetail::Resignation(_) => 1,
+        ResolutionDetail::Mated(_) => 2,
+        ResolutionDetail::TimeUp(_) => 3,
+        ResolutionDetail::IllegalMove(_, _) => 4,
+        ResolutionDetail::PerpetualCheck(_) => 5,
+        ResolutionDetail::Declaration(_) => 6,
+        ResolutionDetail::Repetition => 7,
+        ResolutionDetail::Impasse => 8,
+        ResolutionDetail::MoveLimit => 9,
+        ResolutionDetail::Aborted => 10,
+    };
+    let player = detail.player().map_or(0, |color| color as u8);
+    let kind = match detail {
+        ResolutionDetail::IllegalMove(_, Some(kind)) => kind as u8,
+        _ => 0,
+    };
+    (reason, player, kind)
+}
+
+fn detail_from_bytes(reason: u8, player: u8, kind: u8) -> Option<ResolutionDetail> {
+    let color = match player {
+        0 => None,
+        1 => Some(Color::Black),
+        2 => Some(Color::White),
+        _ => return None,
+    };
+    let detail = match (reason, color) {
+        (1, Some(color)) => ResolutionDetail::Resignation(color),
+        (2, Some(color)) => ResolutionDetail::Mated(color),
+        (3, Some(color)) => ResolutionDetail::TimeUp(color),
+        (4, Some(color)) => {
+            let kind = match kind {
+                0 => None,
+                kind => Some(IllegalMoveKind::from_u8(kind)?),
+            };
+            return Some(ResolutionDetail::IllegalMove(color, kind));
+        }
+        (5, Some(color)) => ResolutionDetail::PerpetualCheck(color),
+        (6, Some(color)) => ResolutionDetail::Declaration(color),
+        (7, None) => ResolutionDetail::Repetition,
+        (8, None) => ResolutionDetail::Impasse,
+        (9, None) => ResolutionDetail::MoveLimit,
+        (10, None) => ResolutionDetail::Aborted,
+        _ => return None,
+    };
+    if kind != 0 {
+        return None;
+    }
+    Some(detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Square;
+
+    #[test]
+    fn game_bytes_round_trip() {
+        let mut position = Position::startpos();
+        for (from, to) in [
+            (Square::SQ_7G, Square::SQ_7F),
+            (Square::SQ_3C, Square::SQ_3D),
+            (Square::SQ_8H, Square::SQ_2B),
+        ] {
+            position
+                .make_move(Move::Normal {
+                    from,
+                    to,
+                    promote: from == Square::SQ_8H,
+                })
+                .unwrap();
+        }
+        // An initial position with all 40 pieces, which is packed
+        let packed = Position::arbitrary_position(position.inner().clone());
+        // An initial position without some pieces, which is written in SFEN
+        let sfen = Position::arbitrary_position(
+            PartialPosition::from_sfen("8k/9/9/9/9/9/9/9/K7R w 2G 30").unwrap(),
+        );
+        for position in [position, packed, sfen] {
+            let mut game = Game::new(position.clone());
+            assert_eq!(Game::from_bytes(&game.to_bytes()), Some(game.clone()));
+
+            game.time_control_set(Some(TimeControl {
+                main_time: 600_000,
+                byoyomi: 10_000,
+                increment: 1,
+                per_move_limit: 2,
+            }));
+            game.resolve_with_detail(ResolutionDetail::IllegalMove(
+                Color::Black,
+                Some(IllegalMoveKind::TwoPawns),
+            ));
+            if !position.moves().is_empty() {
+                assert!(game.annotation_set(0, Some(MoveAnnotation::Score(-120))));
+                assert!(game.annotation_set(2, Some(MoveAnnotation::Blunder)));
+                assert!(game.move_time_set(0, Some(3_000)));
+                assert!(game.move_time_set(2, Some(5_000)));
+            }
+            let bytes = game.to_bytes();
+            assert_eq!(Game::from_bytes(&bytes), Some(game.clone()));
+            // Truncated bytes are invalid or lack some blocks.
+            for len in 0..bytes.len() {
+                assert_ne!(Game::from_bytes(&bytes[..len]), Some(game.clone()));
+            }
+
+            // Unknown blocks are skipped.
+            let mut extended = bytes.clone();
+            write_block(&mut extended, 200, b"metadata");
+            assert_eq!(Game::from_bytes(&extended), Some(game.clone()));
+
+            game.resolve(GameResolution::Draw);
+            assert_eq!(Game::from_bytes(&game.to_bytes()), Some(game));
+        }
+    }
+
+    #[test]
+    fn from_bytes_rejects_invalid_input() {
+        let game = Game::new(Position::startpos());
+        let bytes = game.to_bytes();
+        assert_eq!(bytes, b"SHGB\x01\x00\x00\x00\x00\x00\x00");
+        let mut other_version = bytes.clone();
+        other_version[4] = 2;
+        assert_eq!(Game::from_bytes(&other_version), None);
+        // 5e5d does not make sense, because 5e is vacant.
+        let mv = CompactAction::from(CompactMove::normal(Square::SQ_5E, Square::SQ_5D, false));
+        let mut invalid_move = b"SHGB\x01\x00\x01\x00\x00\x00".to_vec();
+        invalid_move.extend_from_slice(&mv.as_u16().to_le_bytes());
+        invalid_move.push(0);
+        assert_eq!(Game::from_bytes(&invalid_move), None);
+        // The detail does not match the resolution.
+        let mut mismatch = b"SHGB\x01\x00\x00\x00\x00\x00\x01".to_vec();
+        write_block(&mut mismatch, BLOCK_RESOLUTION_DETAIL, &[1, 1, 0]);
+        assert_eq!(Game::from_bytes(&mismatch), None);
+        mismatch[10] = 2;
+        assert!(Game::from_bytes(&mismatch).is_some());
+        // A detail without a resolution
+        let mut detail_only = bytes;
+        write_block(&mut detail_only, BLOCK_RESOLUTION_DETAIL, &[7, 0, 0]);
+        assert_eq!(Game::from_bytes(&detail_only), None);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::{Color, IllegalMoveKind, OutOfRangeError, PositionStatus};
+
 /// How a game is resolved.
 ///
 /// [`GameResolution`] and <code>[Option]<[GameResolution]></code> are both 1-byte data types.
@@ -33,14 +35,270 @@ impl GameResolution {
     /// # Safety
     /// `repr` must be a valid representation of [`GameResolution`].
     /// This condition is equivalent to `1 <= repr && repr <= 5`.
-    #[export_name = "GameResolution_from_u8_unchecked"]
+    #[cfg_attr(
+        feature = "c-exports",
+        export_name = "GameResolution_from_u8_unchecked"
+    )]
     pub unsafe extern "C" fn from_u8_unchecked(repr: u8) -> Self {
+        if !matches!(repr, 1..=5) {
+            crate::common::unreachable_unchecked();
+        }
         core::mem::transmute(repr)
     }
+
+    /// Converts a [`PositionStatus`] returned by a [`LegalityChecker`](crate::LegalityChecker) to a [`GameResolution`].
+    ///
+    /// A mate results in a win, and [`PositionStatus::Draw`] results in [`GameResolution::Rematch`]
+    /// because a [`PositionStatus::Draw`] is usually caused by repetition (千日手, *sennichite*).
+    /// If the draw is known to be caused by the move limit, [`GameResolution::Draw`] should be used instead.
+    /// [`PositionStatus::InProgress`] and [`PositionStatus::Invalid`] result in [`None`].
+    ///
+    /// See [`PositionStatus::from_resolution`] for the inverse.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{GameResolution, PositionStatus};
+    /// assert_eq!(GameResolution::from_status(PositionStatus::WhiteWins), Some(GameResolution::WhiteWins));
+    /// assert_eq!(GameResolution::from_status(PositionStatus::Draw), Some(GameResolution::Rematch));
+    /// assert_eq!(GameResolution::from_status(PositionStatus::InProgress), None);
+    /// ```
+    pub const fn from_status(status: PositionStatus) -> Option<Self> {
+        match status {
+            PositionStatus::BlackWins => Some(GameResolution::BlackWins),
+            PositionStatus::WhiteWins => Some(GameResolution::WhiteWins),
+            PositionStatus::Draw => Some(GameResolution::Rematch),
+            PositionStatus::InProgress | PositionStatus::Invalid => None,
+        }
+    }
+
+    /// Returns the resolution where `winner` won.
+    #[inline]
+    pub(crate) const fn win_of(winner: Color) -> Self {
+        match winner {
+            Color::Black => GameResolution::BlackWins,
+            Color::White => GameResolution::WhiteWins,
+        }
+    }
 }
 
 impl_ord_for_fieldless_enum!(GameResolution);
 impl_hash_for_fieldless_enum!(GameResolution);
+impl_rkyv_as_self!(GameResolution);
+
+/// Converts a [`u8`] to a [`GameResolution`]. Fails if `value` is not in range `1..=5`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{GameResolution, OutOfRangeError};
+/// assert_eq!(GameResolution::try_from(3u8), Ok(GameResolution::Draw));
+/// assert_eq!(GameResolution::try_from(6u8), Err(OutOfRangeError));
+/// assert_eq!(u8::from(GameResolution::Aborted), 5);
+/// ```
+impl TryFrom<u8> for GameResolution {
+    type Error = OutOfRangeError;
+
+    #[inline]
+    fn try_from(value: u8) -> Result<Self, OutOfRangeError> {
+        if matches!(value, 1..=5) {
+            // Safety: `value` is in range 1..=5
+            Ok(unsafe { GameResolution::from_u8_unchecked(value) })
+        } else {
+            Err(OutOfRangeError)
+        }
+    }
+}
+
+/// Converts a [`GameResolution`] to its representation.
+impl From<GameResolution> for u8 {
+    #[inline(always)]
+    fn from(resolution: GameResolution) -> Self {
+        resolution as u8
+    }
+}
+
+/// Why a game is resolved, in more detail than [`GameResolution`].
+///
+/// Variants with a [`Color`] record the player who caused the termination, e.g., the player who resigned.
+///
+/// Because [`ResolutionDetail`] is cheap to copy, it implements [`Copy`].
+#[repr(C, u8)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum ResolutionDetail {
+    /// The player resigned (投了, *tōryō*).
+    Resignation(Color),
+    /// The player's king was mated (詰み, *tsumi*).
+    Mated(Color),
+    /// The player ran out of time (時間切れ, *jikan-gire*).
+    TimeUp(Color),
+    /// The player made an illegal move (反則, *hansoku*). The kind of the illegal move is recorded if known.
+    IllegalMove(Color, Option<IllegalMoveKind>),
+    /// The player checked the opponent's king continuously and caused a repetition (連続王手の千日手).
+    PerpetualCheck(Color),
+    /// The player declared a win by entering king (入玉宣言, *nyūgyoku sengen*).
+    Declaration(Color),
+    /// The same position appeared four times (千日手, *sennichite*).
+    Repetition,
+    /// Both kings entered the opponent's camp and the game was declared a draw (持将棋, *jishōgi*).
+    Impasse,
+    /// The game reached the move limit.
+    MoveLimit,
+    /// The game was aborted.
+    Aborted,
+}
+
+impl ResolutionDetail {
+    /// Returns the player who caused the termination, if any.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, ResolutionDetail};
+    /// assert_eq!(ResolutionDetail::TimeUp(Color::White).player(), Some(Color::White));
+    /// assert_eq!(ResolutionDetail::Repetition.player(), None);
+    /// ```
+    pub const fn player(self) -> Option<Color> {
+        match self {
+            ResolutionDetail::Resignation(color)
+            | ResolutionDetail::Mated(color)
+            | ResolutionDetail::TimeUp(color)
+            | ResolutionDetail::IllegalMove(color, _)
+            | ResolutionDetail::PerpetualCheck(color)
+            | ResolutionDetail::Declaration(color) => Some(color),
+            ResolutionDetail::Repetition
+            | ResolutionDetail::Impasse
+            | ResolutionDetail::MoveLimit
+            | ResolutionDetail::Aborted => None,
+        }
+    }
+
+    /// Returns the [`GameResolution`] this detail implies.
+    ///
+    /// A declaration wins the game for the declaring player, and the other variants with a player lose the game for them.
+    /// A repetition results in [`GameResolution::Rematch`], and an impasse and the move limit result in [`GameResolution::Draw`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, GameResolution, ResolutionDetail};
+    /// assert_eq!(ResolutionDetail::Resignation(Color::Black).resolution(), GameResolution::WhiteWins);
+    /// assert_eq!(ResolutionDetail::Declaration(Color::Black).resolution(), GameResolution::BlackWins);
+    /// assert_eq!(ResolutionDetail::Repetition.resolution(), GameResolution::Rematch);
+    /// ```
+    pub fn resolution(self) -> GameResolution {
+        match self {
+            ResolutionDetail::Resignation(color)
+            | ResolutionDetail::Mated(color)
+            | ResolutionDetail::TimeUp(color)
+            | ResolutionDetail::IllegalMove(color, _)
+            | ResolutionDetail::PerpetualCheck(color) => GameResolution::win_of(color.flip()),
+            ResolutionDetail::Declaration(color) => GameResolution::win_of(color),
+            ResolutionDetail::Repetition => GameResolution::Rematch,
+            ResolutionDetail::Impasse | ResolutionDetail::MoveLimit => GameResolution::Draw,
+            ResolutionDetail::Aborted => GameResolution::Aborted,
+        }
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::PartialOrd for ResolutionDetail {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::Ord for ResolutionDetail {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        fn key(detail: ResolutionDetail) -> (u8, Option<Color>, Option<IllegalMoveKind>) {
+            let index = match detail {
+                ResolutionDetail::Resignation(_) => 0,
+                ResolutionDetail::Mated(_) => 1,
+                ResolutionDetail::TimeUp(_) => 2,
+                ResolutionDetail::IllegalMove(_, kind) => {
+                    return (3, detail.player(), kind);
+                }
+                ResolutionDetail::PerpetualCheck(_) => 4,
+                ResolutionDetail::Declaration(_) => 5,
+                ResolutionDetail::Repetition => 6,
+                ResolutionDetail::Impasse => 7,
+                ResolutionDetail::MoveLimit => 8,
+                ResolutionDetail::Aborted => 9,
+            };
+            (index, detail.player(), None)
+        }
+        key(*self).cmp(&key(*other))
+    }
+}
+
+#[cfg(feature = "hash")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hash")))]
+impl core::hash::Hash for ResolutionDetail {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        self.player().hash(state);
+        if let ResolutionDetail::IllegalMove(_, kind) = *self {
+            kind.hash(state);
+        }
+    }
+}
+
+impl_rkyv_as_self!(ResolutionDetail);
+
+/// Reasons why a resolution of a game is implausible for its final position. Returned by [`Game::validate_resolution`](crate::Game::validate_resolution).
+///
+/// Since: 0.1.6
+#[repr(u8)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum ResolutionMismatch {
+    /// The final position is invalid, e.g. the game contains illegal moves.
+    ///
+    /// Discriminant = 1.
+    InvalidPosition = 1,
+    /// The game is over in the final position, but it is not resolved.
+    ///
+    /// Discriminant = 2.
+    Unresolved = 2,
+    /// The resolution contradicts the final position, e.g. black is said to win although black is mated.
+    ///
+    /// Discriminant = 3.
+    WrongResult = 3,
+    /// A player is said to be mated, but the player is not mated in the final position.
+    ///
+    /// Discriminant = 4.
+    NotMated = 4,
+    /// A repetition is claimed, but the final position is not a repetition.
+    ///
+    /// Discriminant = 5.
+    NotRepetition = 5,
+}
+
+impl ResolutionMismatch {
+    /// Returns a short description of `self` in English, e.g. `"not mated"`.
+    ///
+    /// The returned strings are stable and equal to what [`Display`](core::fmt::Display) writes.
+    pub const fn description(self) -> &'static str {
+        match self {
+            ResolutionMismatch::InvalidPosition => "invalid position",
+            ResolutionMismatch::Unresolved => "game over but unresolved",
+            ResolutionMismatch::WrongResult => "wrong result",
+            ResolutionMismatch::NotMated => "not mated",
+            ResolutionMismatch::NotRepetition => "not repetition",
+        }
+    }
+}
+
+impl_ord_for_fieldless_enum!(ResolutionMismatch);
+impl_hash_for_fieldless_enum!(ResolutionMismatch);
+
+impl core::fmt::Display for ResolutionMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for ResolutionMismatch {}
 
 /// <code>[Option]<[GameResolution]></code> with defined representation.
 ///
@@ -80,13 +338,103 @@ impl From<OptionGameResolution> for Option<GameResolution> {
     }
 }
 
+impl OptionGameResolution {
+    /// Returns true if and only if `self` holds a resolution, i.e., `self` is not the representation of [`None`].
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn OptionGameResolution_is_some(self) -> bool {
+        self.0 != 0
+    }
+
+    /// Returns true if and only if `self` is the representation of [`None`].
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn OptionGameResolution_is_none(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the [`GameResolution`] held by `self` without checking.
+    ///
+    /// # Safety
+    /// `self` must hold a resolution, i.e., [`OptionGameResolution::OptionGameResolution_is_some`] must return true.
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn OptionGameResolution_unwrap_unchecked(self) -> GameResolution {
+        crate::common::unwrap_unchecked(self.into())
+    }
+}
+
 impl_ord_for_single_field!(OptionGameResolution);
 impl_hash_for_single_field!(OptionGameResolution);
+impl_rkyv_as_self!(OptionGameResolution);
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn status_conversions_work() {
+        let statuses = [
+            PositionStatus::BlackWins,
+            PositionStatus::WhiteWins,
+            PositionStatus::Draw,
+            PositionStatus::InProgress,
+            PositionStatus::Invalid,
+        ];
+        for status in statuses {
+            let resolution = GameResolution::from_status(status);
+            let expected = if resolution.is_some() {
+                status
+            } else {
+                PositionStatus::InProgress
+            };
+            assert_eq!(PositionStatus::from_resolution(resolution), expected);
+        }
+        let resolutions = [
+            GameResolution::BlackWins,
+            GameResolution::WhiteWins,
+            GameResolution::Rematch,
+        ];
+        for resolution in resolutions {
+            let status = PositionStatus::from_resolution(Some(resolution));
+            assert_eq!(GameResolution::from_status(status), Some(resolution));
+        }
+    }
+
+    #[test]
+    fn resolution_detail_works() {
+        for color in Color::all() {
+            let details = [
+                ResolutionDetail::Resignation(color),
+                ResolutionDetail::Mated(color),
+                ResolutionDetail::TimeUp(color),
+                ResolutionDetail::IllegalMove(color, Some(IllegalMoveKind::TwoPawns)),
+                ResolutionDetail::PerpetualCheck(color),
+            ];
+            for detail in details {
+                assert_eq!(detail.player(), Some(color));
+                assert_eq!(detail.resolution(), GameResolution::win_of(color.flip()));
+            }
+            let declaration = ResolutionDetail::Declaration(color);
+            assert_eq!(declaration.resolution(), GameResolution::win_of(color));
+        }
+        assert_eq!(ResolutionDetail::Impasse.resolution(), GameResolution::Draw);
+        assert_eq!(
+            ResolutionDetail::MoveLimit.resolution(),
+            GameResolution::Draw
+        );
+        assert_eq!(
+            ResolutionDetail::Aborted.resolution(),
+            GameResolution::Aborted
+        );
+    }
+
     #[test]
     fn game_resolution_is_one_byte() {
         assert_eq!(core::mem::size_of::<GameResolution>(), 1);
@@ -1,5 +1,6 @@
-use crate::common::{write_ascii_byte, write_u8};
-use crate::{PieceKind, ToUsi};
+use crate::common::{c_bytes, write_ascii_byte, write_u8};
+use crate::sfen_error::{SfenParseError, SfenParseErrorKind};
+use crate::{Piece, PieceKind, ToUsi};
 
 /// A hand of a single player. A hand is a multiset of unpromoted pieces (except a king).
 ///
@@ -19,10 +20,18 @@ impl Hand {
     /// use shogi_core::Hand;
     /// assert_eq!(Hand::new(), Hand::default());
     /// ```
-    #[export_name = "Hand_new"]
+    /// `const`: since 0.1.6
     #[inline(always)]
-    pub extern "C" fn new() -> Self {
-        Self::default()
+    pub const fn new() -> Self {
+        Hand([0; 8])
+    }
+
+    /// C interface of [`Hand::new`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    pub extern "C" fn Hand_new() -> Self {
+        Self::new()
     }
 
     /// Find a new [`Hand`] with `piece_kind` added, if possible.
@@ -51,8 +60,19 @@ impl Hand {
     /// // Somehow, we lost all pawns!
     /// assert_eq!(hand.count(PieceKind::Pawn), Some(0));
     /// ```
+    ///
+    /// `added` can be used in constant contexts:
+    /// ```
+    /// # use shogi_core::{Hand, PieceKind};
+    /// const TWO_PAWNS: Option<Hand> = match Hand::new().added(PieceKind::Pawn) {
+    ///     Some(hand) => hand.added(PieceKind::Pawn),
+    ///     None => None,
+    /// };
+    /// assert_eq!(TWO_PAWNS.unwrap().count(PieceKind::Pawn), Some(2));
+    /// ```
+    /// `const`: since 0.1.6
     #[inline]
-    pub fn added(mut self, piece_kind: PieceKind) -> Option<Hand> {
+    pub const fn added(mut self, piece_kind: PieceKind) -> Option<Hand> {
         let index = (piece_kind as u8 - 1) as usize;
         if index < 7 {
             self.0[index] = self.0[index].wrapping_add(1);
@@ -81,18 +101,31 @@ impl Hand {
     /// let hand = hand.removed(PieceKind::Bishop);
     /// assert_eq!(hand, None);
     /// ```
+    /// `const`: since 0.1.6
     #[inline]
-    pub fn removed(mut self, piece_kind: PieceKind) -> Option<Hand> {
+    pub const fn removed(mut self, piece_kind: PieceKind) -> Option<Hand> {
         let index = (piece_kind as u8 - 1) as usize;
         if index < 7 {
-            self.0[index] = self.0[index].checked_sub(1)?;
+            self.0[index] = match self.0[index].checked_sub(1) {
+                Some(count) => count,
+                None => return None,
+            };
             return Some(self);
         }
         None
     }
     /// Finds the number of pieces `piece_kind` in `self`.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::{Hand, PieceKind};
+    /// const PAWNS: Option<u8> = Hand::new().count(PieceKind::Pawn);
+    /// assert_eq!(PAWNS, Some(0));
+    /// assert_eq!(Hand::new().count(PieceKind::King), None);
+    /// ```
+    /// `const`: since 0.1.6
     #[inline]
-    pub fn count(self, piece_kind: PieceKind) -> Option<u8> {
+    pub const fn count(self, piece_kind: PieceKind) -> Option<u8> {
         let index = (piece_kind as u8 - 1) as usize;
         if index < 7 {
             return Some(self.0[index]);
@@ -100,10 +133,21 @@ impl Hand {
         None
     }
 
+    // Finds a new `Hand` with the number of `piece_kind` replaced with `count`.
+    pub(crate) const fn with_count(mut self, piece_kind: PieceKind, count: u8) -> Option<Hand> {
+        let index = (piece_kind as u8 - 1) as usize;
+        if index < 7 {
+            self.0[index] = count;
+            return Some(self);
+        }
+        None
+    }
+
     /// C interface of [`Hand::added`].
     ///
     /// This function returns true if and only if adding was successful.
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Hand_add(&mut self, piece_kind: PieceKind) -> bool {
         if let Some(new) = self.added(piece_kind) {
             *self = new;
@@ -116,7 +160,8 @@ impl Hand {
     /// C interface of [`Hand::removed`].
     ///
     /// This function returns true if and only if removal was successful.
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Hand_remove(&mut self, piece_kind: PieceKind) -> bool {
         if let Some(new) = self.removed(piece_kind) {
             *self = new;
@@ -129,15 +174,15 @@ impl Hand {
     /// C interface of [`Hand::count`].
     ///
     /// This function returns true if and only if `piece_kind` can be a piece in hand.
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Hand_count(self, piece_kind: PieceKind) -> u8 {
         self.count(piece_kind).unwrap_or(0)
     }
 
     #[inline(always)]
     fn as_u64(self) -> u64 {
-        // Safety: `sizeof::<[u8; 8]>()` = `sizeof::<u64>()` = 8
-        unsafe { core::mem::transmute(self) }
+        u64::from_ne_bytes(self.0)
     }
 
     /// Returns whether a [`PieceKind`] is valid as a piece in hand.
@@ -157,7 +202,8 @@ impl Hand {
         (piece_kind as u8) < 8
     }
     #[doc(hidden)]
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Hand_is_hand_piece(piece_kind: PieceKind) -> bool {
         Hand::is_hand_piece(piece_kind)
     }
@@ -186,6 +232,56 @@ impl Hand {
     /// ```
     /// Since: 0.1.2
     pub const NUM_HAND_PIECES: usize = 7;
+
+    /// The number of pieces of each kind in a game of shogi, in the order of [`Hand::all_hand_pieces`].
+    ///
+    /// Since: 0.1.6
+    pub const MAX_COUNTS: [u8; Hand::NUM_HAND_PIECES] = [18, 4, 4, 4, 4, 2, 2];
+
+    /// Returns the number of pieces of each kind in `self`.
+    ///
+    /// The order is guaranteed to be that of [`Hand::all_hand_pieces`], i.e., pawn, lance, knight, silver, gold, bishop and rook,
+    /// which is the ascending order of the discriminants of [`PieceKind`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Hand, PieceKind};
+    /// let hand = Hand::new().added(PieceKind::Rook).unwrap();
+    /// assert_eq!(hand.to_slice(), [0, 0, 0, 0, 0, 0, 1]);
+    /// assert_eq!(Hand::from_counts(hand.to_slice()), Some(hand));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[inline(always)]
+    pub const fn to_slice(&self) -> [u8; Hand::NUM_HAND_PIECES] {
+        let [p, l, n, s, g, b, r, _] = self.0;
+        [p, l, n, s, g, b, r]
+    }
+
+    /// Creates a [`Hand`] from the number of pieces of each kind, in the same order as [`Hand::to_slice`].
+    ///
+    /// Returns [`None`] if any of the counts exceeds the number of pieces of that kind in a game ([`Hand::MAX_COUNTS`]).
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Hand, PieceKind};
+    /// let hand = Hand::from_counts([18, 0, 0, 0, 0, 2, 0]).unwrap();
+    /// assert_eq!(hand.count(PieceKind::Bishop), Some(2));
+    /// assert_eq!(Hand::from_counts([19, 0, 0, 0, 0, 0, 0]), None);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub const fn from_counts(counts: [u8; Hand::NUM_HAND_PIECES]) -> Option<Self> {
+        let mut index = 0;
+        while index < Hand::NUM_HAND_PIECES {
+            if counts[index] > Hand::MAX_COUNTS[index] {
+                return None;
+            }
+            index += 1;
+        }
+        let [p, l, n, s, g, b, r] = counts;
+        Some(Hand([p, l, n, s, g, b, r, 0]))
+    }
 }
 
 impl PartialEq for Hand {
@@ -197,6 +293,134 @@ impl PartialEq for Hand {
 
 impl_ord_for_single_field!(Hand);
 impl_hash_for_single_field!(Hand);
+impl_rkyv_as_self!(Hand);
+
+/// Formats the number of pieces of each kind, e.g. `Hand { R: 0, B: 1, G: 0, S: 0, N: 0, L: 0, P: 3 }`.
+#[cfg(feature = "defmt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt")))]
+impl defmt::Format for Hand {
+    fn format(&self, f: defmt::Formatter) {
+        let [p, l, n, s, g, b, r, _] = self.0;
+        defmt::write!(
+            f,
+            "Hand {{ R: {=u8}, B: {=u8}, G: {=u8}, S: {=u8}, N: {=u8}, L: {=u8}, P: {=u8} }}",
+            r,
+            b,
+            g,
+            s,
+            n,
+            l,
+            p,
+        );
+    }
+}
+
+impl Hand {
+    /// Parses the hand field of SFEN, e.g. `-` or `RB3p`, into the hands of black and white.
+    /// This is the inverse of [`ToUsi`] for `[Hand; 2]`.
+    ///
+    /// A count may precede each piece. Kings and promoted pieces are not accepted.
+    /// Offsets in the error are relative to `s`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Hand, PieceKind, SfenParseErrorKind};
+    /// let [black, white] = Hand::parse_sfen_hands("RB3p").unwrap();
+    /// assert_eq!(black.count(PieceKind::Rook), Some(1));
+    /// assert_eq!(black.count(PieceKind::Bishop), Some(1));
+    /// assert_eq!(white.count(PieceKind::Pawn), Some(3));
+    /// assert_eq!(Hand::parse_sfen_hands("-"), Ok([Hand::new(); 2]));
+    ///
+    /// let error = Hand::parse_sfen_hands("R+B").unwrap_err();
+    /// assert_eq!(error.kind(), SfenParseErrorKind::InvalidHandPiece);
+    /// assert_eq!(error.offset(), 1);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn parse_sfen_hands(s: &str) -> Result<[Hand; 2], SfenParseError> {
+        Self::parse_sfen_bytes(s.as_bytes())
+    }
+
+    /// C interface of [`Hand::parse_sfen_hands`].
+    /// The hand field is read from `len` bytes starting at `ptr`, which need not be nul-terminated.
+    ///
+    /// If the bytes are a valid hand field, this function writes the hands of black and white to `out` and returns `true`.
+    /// Otherwise, this function returns `false` and `out` is left untouched.
+    ///
+    /// # Safety
+    /// `ptr` must be null or valid for reads of `len` bytes. `out` must be valid for writes of two [`Hand`]s.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn Hand_parse_sfen_hands_c(
+        ptr: *const u8,
+        len: usize,
+        out: *mut Hand,
+    ) -> bool {
+        match Self::parse_sfen_bytes(c_bytes(ptr, len)) {
+            Ok(hands) => {
+                core::ptr::copy_nonoverlapping(hands.as_ptr(), out, 2);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Parses the hand field of SFEN, e.g. `-` or `RB3p`, into the hands of black and white.
+    ///
+    /// A count may precede each piece. Kings and promoted pieces are not accepted.
+    /// Offsets in the error are relative to `bytes`.
+    pub(crate) fn parse_sfen_bytes(bytes: &[u8]) -> Result<[Hand; 2], SfenParseError> {
+        let mut hands = [Hand::new(); 2];
+        if bytes == b"-" {
+            return Ok(hands);
+        }
+        if bytes.is_empty() {
+            return Err(SfenParseError::new(SfenParseErrorKind::MissingField, 0));
+        }
+        // The offset where the current count starts and its value
+        let mut count: Option<(usize, u8)> = None;
+        for (index, &byte) in bytes.iter().enumerate() {
+            if byte.is_ascii_digit() {
+                let digit = byte - b'0';
+                let (start, value) = count.unwrap_or((index, 0));
+                let value = value
+                    .checked_mul(10)
+                    .and_then(|value| value.checked_add(digit))
+                    .ok_or_else(|| {
+                        SfenParseError::new(SfenParseErrorKind::InvalidHandCount, start)
+                    })?;
+                count = Some((start, value));
+                continue;
+            }
+            let piece = match Piece::parse_usi_bytes(&[byte]) {
+                Some(piece) if Hand::is_hand_piece(piece.piece_kind()) => piece,
+                _ => {
+                    return Err(SfenParseError::new(
+                        SfenParseErrorKind::InvalidHandPiece,
+                        index,
+                    ))
+                }
+            };
+            let (piece_kind, color) = piece.to_parts();
+            let (start, count) = count.take().unwrap_or((index, 1));
+            let invalid_count = SfenParseError::new(SfenParseErrorKind::InvalidHandCount, start);
+            if count == 0 {
+                return Err(invalid_count);
+            }
+            let hand = &mut hands[color.array_index()];
+            for _ in 0..count {
+                *hand = hand.added(piece_kind).ok_or(invalid_count)?;
+            }
+        }
+        if let Some((start, _)) = count {
+            return Err(SfenParseError::new(
+                SfenParseErrorKind::InvalidHandCount,
+                start,
+            ));
+        }
+        Ok(hands)
+    }
+}
 
 /// Finds the USI representation of hand: <https://web.archive.org/web/20080131070731/http://www.glaurungchess.com/shogi/usi.html>
 ///
@@ -224,14 +448,14 @@ impl ToUsi for [Hand; 2] {
         for i in 0..2 {
             for j in (0..7).rev() {
                 // Safety: 0 <= j < 8
-                let count = *unsafe { self[i].0.get_unchecked(j) };
+                let count = *unsafe { crate::common::get_unchecked(&self[i].0, j) };
                 if count > 0 {
                     if count >= 2 {
                         write_u8(sink, count)?;
                     }
                     // Safety: `pieces[i][j]` is an ASCII byte.
                     // Furthermore, 0 <= j < 8 holds, which implies pieces[i][j] is always in bounds.
-                    unsafe { write_ascii_byte(sink, *pieces[i].get_unchecked(j)) }?;
+                    unsafe { write_ascii_byte(sink, *crate::common::get_unchecked(pieces[i], j)) }?;
                 }
             }
         }
@@ -1,3 +1,5 @@
+use crate::common::{c_char, strip_nul};
+
 /// Kinds of illegal moves.
 ///
 /// [`IllegalMoveKind`] and <code>[Result]<[()][unit], [IllegalMoveKind]></code> are both 1-byte data types.
@@ -56,15 +58,102 @@ impl IllegalMoveKind {
     /// # Safety
     /// `repr` must be a valid representation of [`IllegalMoveKind`].
     /// This condition is equivalent to `1 <= repr && repr <= 7`.
-    #[export_name = "IllegalMoveKind_from_u8_unchecked"]
+    #[cfg_attr(
+        feature = "c-exports",
+        export_name = "IllegalMoveKind_from_u8_unchecked"
+    )]
     pub unsafe extern "C" fn from_u8_unchecked(repr: u8) -> Self {
+        if !matches!(repr, 1..=7) {
+            crate::common::unreachable_unchecked();
+        }
         core::mem::transmute(repr)
     }
+
+    /// Returns a short description of `self` in English, e.g. `"two pawns on the same file"`.
+    ///
+    /// The returned strings are stable and equal to what [`Display`](core::fmt::Display) writes.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::IllegalMoveKind;
+    /// assert_eq!(IllegalMoveKind::TwoPawns.description(), "two pawns on the same file");
+    /// assert_eq!(IllegalMoveKind::TwoPawns.to_string(), "two pawns on the same file");
+    /// ```
+    pub fn description(self) -> &'static str {
+        strip_nul(DESCRIPTIONS[self as usize - 1])
+    }
+
+    /// Returns a short description of `self` in Japanese, e.g. `"二歩"`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::IllegalMoveKind;
+    /// assert_eq!(IllegalMoveKind::TwoPawns.description_ja(), "二歩");
+    /// assert_eq!(IllegalMoveKind::DropPawnMate.description_ja(), "打ち歩詰め");
+    /// ```
+    pub fn description_ja(self) -> &'static str {
+        strip_nul(DESCRIPTIONS_JA[self as usize - 1])
+    }
+
+    /// C interface of [`IllegalMoveKind::description`].
+    ///
+    /// This function returns a pointer to a static nul-terminated string in UTF-8, which must not be freed.
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn IllegalMoveKind_message(self) -> *const c_char {
+        DESCRIPTIONS[self as usize - 1].as_ptr().cast()
+    }
+
+    /// C interface of [`IllegalMoveKind::description_ja`].
+    ///
+    /// This function returns a pointer to a static nul-terminated string in UTF-8, which must not be freed.
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn IllegalMoveKind_message_ja(self) -> *const c_char {
+        DESCRIPTIONS_JA[self as usize - 1].as_ptr().cast()
+    }
 }
 
+// Descriptions in English, indexed by discriminant - 1.
+// They are nul-terminated so that `IllegalMoveKind_message` can return them as they are.
+const DESCRIPTIONS: [&str; 7] = [
+    "two pawns on the same file\0",
+    "ignored check\0",
+    "drop pawn mate\0",
+    "dropped piece cannot move\0",
+    "moved piece cannot move\0",
+    "game already finished\0",
+    "incorrect move\0",
+];
+
+// Descriptions in Japanese, indexed by discriminant - 1.
+const DESCRIPTIONS_JA: [&str; 7] = [
+    "二歩\0",
+    "王手放置\0",
+    "打ち歩詰め\0",
+    "行き所のない駒打ち\0",
+    "行き所のない駒\0",
+    "終局後の指し手\0",
+    "不正な指し手\0",
+];
+
 impl_ord_for_fieldless_enum!(IllegalMoveKind);
 impl_hash_for_fieldless_enum!(IllegalMoveKind);
 
+impl core::fmt::Display for IllegalMoveKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for IllegalMoveKind {}
+
 /// <code>[Result]<[()][unit], [IllegalMoveKind]></code> with defined representation.
 ///
 /// The representation is:
@@ -133,6 +222,32 @@ impl_hash_for_single_field!(ResultUnitIllegalMoveKind);
 mod tests {
     use super::*;
 
+    // Reads a nul-terminated string returned by a C interface.
+    fn read_c_str(ptr: *const c_char) -> &'static str {
+        let ptr = ptr.cast::<u8>();
+        let mut len = 0;
+        // Safety: `ptr` points to a static nul-terminated string
+        while unsafe { *ptr.add(len) } != 0 {
+            len += 1;
+        }
+        core::str::from_utf8(unsafe { core::slice::from_raw_parts(ptr, len) }).unwrap()
+    }
+
+    #[test]
+    fn message_works() {
+        for repr in 1..=7 {
+            let kind = IllegalMoveKind::from_u8(repr).unwrap();
+            assert_eq!(
+                read_c_str(kind.IllegalMoveKind_message()),
+                kind.description()
+            );
+            assert_eq!(
+                read_c_str(kind.IllegalMoveKind_message_ja()),
+                kind.description_ja(),
+            );
+        }
+    }
+
     #[test]
     fn illegal_move_kind_is_one_byte() {
         assert_eq!(core::mem::size_of::<IllegalMoveKind>(), 1);
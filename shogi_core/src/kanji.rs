@@ -0,0 +1,61 @@
+//! Names of pieces and numbers in Japanese, shared by KI2, KIF and BOD.
+
+use crate::PieceKind;
+
+// Piece names, where longer names come first.
+pub(crate) const PIECE_NAMES: [(&str, PieceKind); 19] = [
+    ("成香", PieceKind::ProLance),
+    ("成桂", PieceKind::ProKnight),
+    ("成銀", PieceKind::ProSilver),
+    ("歩", PieceKind::Pawn),
+    ("香", PieceKind::Lance),
+    ("桂", PieceKind::Knight),
+    ("銀", PieceKind::Silver),
+    ("金", PieceKind::Gold),
+    ("角", PieceKind::Bishop),
+    ("飛", PieceKind::Rook),
+    ("玉", PieceKind::King),
+    ("王", PieceKind::King),
+    ("と", PieceKind::ProPawn),
+    ("杏", PieceKind::ProLance),
+    ("圭", PieceKind::ProKnight),
+    ("全", PieceKind::ProSilver),
+    ("馬", PieceKind::ProBishop),
+    ("龍", PieceKind::ProRook),
+    ("竜", PieceKind::ProRook),
+];
+
+// Full-width digits, used for files.
+pub(crate) const FILES: [char; 9] = ['１', '２', '３', '４', '５', '６', '７', '８', '９'];
+// Kanji numerals from 1 to 9, used for ranks and counts of pieces in hand.
+pub(crate) const RANKS: [char; 9] = ['一', '二', '三', '四', '五', '六', '七', '八', '九'];
+
+// The piece kind of a one-character name, e.g. `歩`. Names like `成香` are not one character.
+pub(crate) fn piece_kind_from_char(c: char) -> Option<PieceKind> {
+    let mut buf = [0; 4];
+    let name: &str = c.encode_utf8(&mut buf);
+    PIECE_NAMES
+        .iter()
+        .find(|&&(candidate, _)| candidate == name)
+        .map(|&(_, piece_kind)| piece_kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn piece_kind_from_char_works() {
+        assert_eq!(piece_kind_from_char('歩'), Some(PieceKind::Pawn));
+        assert_eq!(piece_kind_from_char('王'), Some(PieceKind::King));
+        assert_eq!(piece_kind_from_char('竜'), Some(PieceKind::ProRook));
+        assert_eq!(piece_kind_from_char('成'), None);
+        assert_eq!(piece_kind_from_char('・'), None);
+        // Every piece kind has a one-character name.
+        for piece_kind in PieceKind::all() {
+            assert!(PIECE_NAMES
+                .iter()
+                .any(|&(name, kind)| kind == piece_kind && name.chars().count() == 1));
+        }
+    }
+}
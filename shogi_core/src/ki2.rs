@@ -0,0 +1,434 @@
+//! KI2 notation of moves (e.g. `▲７六歩`, `△同銀左`).
+//!
+//! KI2 omits the source square of a move. A move is written as the destination square (or `同` for the destination of the last move),
+//! the piece, modifiers that tell apart pieces that can move to the destination (`右`, `左`, `直`, `上`, `引`, `寄`),
+//! `打` for a drop, and `成` or `不成` for promotion.
+
+use crate::common::{c_char, strip_nul};
+use crate::kanji::{FILES, PIECE_NAMES, RANKS};
+use crate::{Bitboard, Color, LegalityChecker, Move, PartialPosition, Piece, PieceKind, Square};
+
+/// Errors that can happen in resolving a move in KI2 notation.
+#[repr(u8)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Ki2Error {
+    /// The text is not a move in KI2 notation.
+    ///
+    /// Discriminant = 1.
+    Syntax = 1,
+    /// No legal move matches the text.
+    ///
+    /// Discriminant = 2.
+    NoCandidate = 2,
+    /// Several legal moves match the text.
+    ///
+    /// Discriminant = 3.
+    Ambiguous = 3,
+}
+
+impl Ki2Error {
+    /// Returns a short description of `self` in English, e.g. `"ambiguous move"`.
+    ///
+    /// The returned strings are stable and equal to what [`Display`](core::fmt::Display) writes.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::Ki2Error;
+    /// assert_eq!(Ki2Error::Ambiguous.description(), "ambiguous move");
+    /// assert_eq!(Ki2Error::Ambiguous.to_string(), "ambiguous move");
+    /// ```
+    pub fn description(self) -> &'static str {
+        strip_nul(DESCRIPTIONS[self as usize - 1])
+    }
+
+    /// C interface of [`Ki2Error::description`].
+    ///
+    /// This function returns a pointer to a static nul-terminated string in UTF-8, which must not be freed.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn Ki2Error_message(self) -> *const c_char {
+        DESCRIPTIONS[self as usize - 1].as_ptr().cast()
+    }
+}
+
+// Descriptions, indexed by discriminant - 1.
+// They are nul-terminated so that `Ki2Error_message` can return them as they are.
+const DESCRIPTIONS: [&str; 3] = [
+    "invalid KI2 notation\0",
+    "no matching move\0",
+    "ambiguous move\0",
+];
+
+impl_ord_for_fieldless_enum!(Ki2Error);
+impl_hash_for_fieldless_enum!(Ki2Error);
+
+impl core::fmt::Display for Ki2Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for Ki2Error {}
+
+// Modifiers about the direction of a move.
+#[derive(Eq, PartialEq, Clone, Copy)]
+enum Vertical {
+    // 上
+    Up,
+    // 引
+    Down,
+    // 寄
+    Sideways,
+    // 直
+    Straight,
+}
+
+// Modifiers about the position of the moved piece among candidates.
+#[derive(Eq, PartialEq, Clone, Copy)]
+enum Horizontal {
+    // 右
+    Right,
+    // 左
+    Left,
+}
+
+struct Parsed {
+    color: Option<Color>,
+    // None if the destination is that of the last move (`同`).
+    to: Option<Square>,
+    piece_kind: PieceKind,
+    vertical: Option<Vertical>,
+    horizontal: Option<Horizontal>,
+    drop: bool,
+    promote: Option<bool>,
+}
+
+// Removes `prefix` from `rest` if `rest` starts with it.
+pub(crate) fn eat(rest: &mut &str, prefix: &str) -> bool {
+    match rest.strip_prefix(prefix) {
+        Some(stripped) => {
+            *rest = stripped;
+            true
+        }
+        None => false,
+    }
+}
+
+// Removes a character in `table` from `rest` and returns its 1-based position.
+pub(crate) fn eat_number(rest: &mut &str, table: &[char; 9]) -> Option<u8> {
+    let c = rest.chars().next()?;
+    let index = table.iter().position(|&x| x == c)?;
+    *rest = &rest[c.len_utf8()..];
+    Some(index as u8 + 1)
+}
+
+fn parse(s: &str) -> Option<Parsed> {
+    let mut rest = s.trim();
+    let color = if eat(&mut rest, "▲") || eat(&mut rest, "☗") {
+        Some(Color::Black)
+    } else if eat(&mut rest, "△") || eat(&mut rest, "☖") {
+        Some(Color::White)
+    } else {
+        None
+    };
+    let to = if eat(&mut rest, "同") {
+        rest = rest.trim_start_matches(['　', ' ']);
+        None
+    } else {
+        let file = eat_number(&mut rest, &FILES)?;
+        let rank = eat_number(&mut rest, &RANKS)?;
+        Some(Square::new(file, rank)?)
+    };
+    let &(name, piece_kind) = PIECE_NAMES
+        .iter()
+        .find(|(name, _)| rest.starts_with(name))?;
+    rest = &rest[name.len()..];
+    let mut vertical = None;
+    let mut horizontal = None;
+    loop {
+        let (new_vertical, new_horizontal) = if eat(&mut rest, "右") {
+            (None, Some(Horizontal::Right))
+        } else if eat(&mut rest, "左") {
+            (None, Some(Horizontal::Left))
+        } else if eat(&mut rest, "上") || eat(&mut rest, "行") {
+            (Some(Vertical::Up), None)
+        } else if eat(&mut rest, "引") {
+            (Some(Vertical::Down), None)
+        } else if eat(&mut rest, "寄") {
+            (Some(Vertical::Sideways), None)
+        } else if eat(&mut rest, "直") {
+            (Some(Vertical::Straight), None)
+        } else {
+            break;
+        };
+        if new_vertical.is_some() {
+            if vertical.is_some() {
+                return None;
+            }
+            vertical = new_vertical;
+        }
+        if new_horizontal.is_some() {
+            if horizontal.is_some() {
+                return None;
+            }
+            horizontal = new_horizontal;
+        }
+    }
+    let drop = eat(&mut rest, "打");
+    let promote = if eat(&mut rest, "不成") {
+        Some(false)
+    } else if eat(&mut rest, "成") {
+        Some(true)
+    } else {
+        None
+    };
+    if !rest.is_empty() {
+        return None;
+    }
+    if drop && (vertical.is_some() || horizontal.is_some() || promote.is_some()) {
+        return None;
+    }
+    Some(Parsed {
+        color,
+        to,
+        piece_kind,
+        vertical,
+        horizontal,
+        drop,
+        promote,
+    })
+}
+
+impl PartialPosition {
+    /// Resolves a move in KI2 notation made in `self`, e.g. `▲７六歩` or `△同銀左`.
+    ///
+    /// Candidate moves are found with `checker`, and are narrowed down with the modifiers in `ki2`.
+    /// `打` can be omitted if no piece on the board can move to the destination, as is customary in KI2.
+    /// The mark of the player (`▲`, `△`, `☗` or `☖`) is optional.
+    ///
+    /// Returns [`Ki2Error::NoCandidate`] if the mark of the player does not match the side to move,
+    /// or if `ki2` uses `同` but `self` has no last move.
+    pub fn move_from_ki2<C: LegalityChecker + ?Sized>(
+        &self,
+        ki2: &str,
+        checker: &C,
+    ) -> Result<Move, Ki2Error> {
+        let parsed = parse(ki2).ok_or(Ki2Error::Syntax)?;
+        let side = self.side_to_move();
+        if parsed.color.map_or(false, |color| color != side) {
+            return Err(Ki2Error::NoCandidate);
+        }
+        let to = match parsed.to {
+            Some(to) => to,
+            None => self.last_move().ok_or(Ki2Error::NoCandidate)?.to(),
+        };
+        let piece = Piece::new(parsed.piece_kind, side);
+        let drop = Move::Drop { piece, to };
+        if parsed.drop {
+            return if checker.is_legal_partial_lite(self, drop) {
+                Ok(drop)
+            } else {
+                Err(Ki2Error::NoCandidate)
+            };
+        }
+        let promote = parsed.promote.unwrap_or(false);
+        let mut candidates = Bitboard::empty();
+        for from in checker.normal_to_candidates(self, to, piece) {
+            if checker.is_legal_partial_lite(self, Move::Normal { from, to, promote }) {
+                candidates |= from;
+            }
+        }
+        let unmodified =
+            parsed.vertical.is_none() && parsed.horizontal.is_none() && parsed.promote.is_none();
+        if candidates.is_empty() && unmodified && checker.is_legal_partial_lite(self, drop) {
+            return Ok(drop);
+        }
+        if let Some(vertical) = parsed.vertical {
+            let rank = to.relative_rank(side);
+            let mut filtered = Bitboard::empty();
+            for from in candidates {
+                let from_rank = from.relative_rank(side);
+                let matches = match vertical {
+                    Vertical::Up => from_rank > rank,
+                    Vertical::Down => from_rank < rank,
+                    Vertical::Sideways => from_rank == rank,
+                    Vertical::Straight => from_rank > rank && from.file() == to.file(),
+                };
+                if matches {
+                    filtered |= from;
+                }
+            }
+            candidates = filtered;
+        }
+        if let Some(horizontal) = parsed.horizontal {
+            // The right of black is the side of file 1.
+            let files = candidates.map(|from| from.relative_file(side));
+            let target = match horizontal {
+                Horizontal::Right => files.min(),
+                Horizontal::Left => files.max(),
+            };
+            let mut filtered = Bitboard::empty();
+            for from in candidates {
+                if Some(from.relative_file(side)) == target {
+                    filtered |= from;
+                }
+            }
+            candidates = filtered;
+        }
+        let from = candidates.pop().ok_or(Ki2Error::NoCandidate)?;
+        if !candidates.is_empty() {
+            return Err(Ki2Error::Ambiguous);
+        }
+        Ok(Move::Normal { from, to, promote })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_checker::TestChecker;
+
+    fn normal(from: Square, to: Square, promote: bool) -> Move {
+        Move::Normal { from, to, promote }
+    }
+
+    #[test]
+    fn move_from_ki2_works() {
+        let mut position = PartialPosition::startpos();
+        assert_eq!(
+            position.move_from_ki2("▲７六歩", &TestChecker::PSEUDO_LEGAL),
+            Ok(normal(Square::SQ_7G, Square::SQ_7F, false)),
+        );
+        // Both golds can move to 5h.
+        assert_eq!(
+            position.move_from_ki2("５八金右", &TestChecker::PSEUDO_LEGAL),
+            Ok(normal(Square::SQ_4I, Square::SQ_5H, false)),
+        );
+        assert_eq!(
+            position.move_from_ki2("☗５八金左", &TestChecker::PSEUDO_LEGAL),
+            Ok(normal(Square::SQ_6I, Square::SQ_5H, false)),
+        );
+        assert_eq!(
+            position.move_from_ki2("▲５八金", &TestChecker::PSEUDO_LEGAL),
+            Err(Ki2Error::Ambiguous),
+        );
+        assert_eq!(
+            position.move_from_ki2("▲５八金引", &TestChecker::PSEUDO_LEGAL),
+            Err(Ki2Error::NoCandidate),
+        );
+        assert_eq!(
+            position.move_from_ki2("△７六歩", &TestChecker::PSEUDO_LEGAL),
+            Err(Ki2Error::NoCandidate),
+        );
+        assert_eq!(
+            position.move_from_ki2("▲７六", &TestChecker::PSEUDO_LEGAL),
+            Err(Ki2Error::Syntax),
+        );
+        assert_eq!(
+            position.move_from_ki2("▲５八金右右", &TestChecker::PSEUDO_LEGAL),
+            Err(Ki2Error::Syntax),
+        );
+        assert_eq!(
+            position.move_from_ki2("▲同歩", &TestChecker::PSEUDO_LEGAL),
+            Err(Ki2Error::NoCandidate),
+        );
+
+        for mv in ["▲７六歩", "△３四歩", "▲２二角成"] {
+            let mv = position
+                .move_from_ki2(mv, &TestChecker::PSEUDO_LEGAL)
+                .unwrap();
+            position.make_move(mv).unwrap();
+        }
+        assert_eq!(
+            position.last_move(),
+            Some(normal(Square::SQ_8H, Square::SQ_2B, true)),
+        );
+        // The rook on 8b can also capture the horse.
+        assert_eq!(
+            position.move_from_ki2("△同　銀", &TestChecker::PSEUDO_LEGAL),
+            Ok(normal(Square::SQ_3A, Square::SQ_2B, false)),
+        );
+        assert_eq!(
+            position.move_from_ki2("△同飛", &TestChecker::PSEUDO_LEGAL),
+            Ok(normal(Square::SQ_8B, Square::SQ_2B, false)),
+        );
+        position
+            .make_move(normal(Square::SQ_3A, Square::SQ_2B, false))
+            .unwrap();
+
+        // Black has a bishop in hand, which can be dropped without `打` if no bishop can move there.
+        assert_eq!(
+            position.move_from_ki2("▲５五角", &TestChecker::PSEUDO_LEGAL),
+            Ok(Move::Drop {
+                piece: Piece::B_B,
+                to: Square::SQ_5E,
+            }),
+        );
+        assert_eq!(
+            position.move_from_ki2("▲５五角打", &TestChecker::PSEUDO_LEGAL),
+            Ok(Move::Drop {
+                piece: Piece::B_B,
+                to: Square::SQ_5E,
+            }),
+        );
+        assert_eq!(
+            position.move_from_ki2("▲５五飛打", &TestChecker::PSEUDO_LEGAL),
+            Err(Ki2Error::NoCandidate),
+        );
+        assert_eq!(
+            position.move_from_ki2("▲５五角右打", &TestChecker::PSEUDO_LEGAL),
+            Err(Ki2Error::Syntax),
+        );
+    }
+
+    #[test]
+    fn move_from_ki2_modifiers_work() {
+        let mut position = PartialPosition::empty();
+        position.piece_set(Square::SQ_5E, Some(Piece::B_G));
+        position.piece_set(Square::SQ_4E, Some(Piece::B_G));
+        position.piece_set(Square::SQ_5C, Some(Piece::B_G));
+        position.piece_set(Square::SQ_4C, Some(Piece::B_S));
+        position.piece_set(Square::SQ_6E, Some(Piece::B_S));
+        // Golds on 5e and 4e move up, and the gold on 5c moves down.
+        assert_eq!(
+            position.move_from_ki2("５四金直", &TestChecker::PSEUDO_LEGAL),
+            Ok(normal(Square::SQ_5E, Square::SQ_5D, false)),
+        );
+        assert_eq!(
+            position.move_from_ki2("５四金上", &TestChecker::PSEUDO_LEGAL),
+            Err(Ki2Error::Ambiguous),
+        );
+        assert_eq!(
+            position.move_from_ki2("５四金引", &TestChecker::PSEUDO_LEGAL),
+            Ok(normal(Square::SQ_5C, Square::SQ_5D, false)),
+        );
+        assert_eq!(
+            position.move_from_ki2("５四金右", &TestChecker::PSEUDO_LEGAL),
+            Ok(normal(Square::SQ_4E, Square::SQ_5D, false)),
+        );
+        assert_eq!(
+            position.move_from_ki2("５四金左上", &TestChecker::PSEUDO_LEGAL),
+            Ok(normal(Square::SQ_5E, Square::SQ_5D, false)),
+        );
+        // The silver on 4c leaves the promotion zone, but the silver on 6e does not enter it.
+        assert_eq!(
+            position.move_from_ki2("５四銀引不成", &TestChecker::PSEUDO_LEGAL),
+            Ok(normal(Square::SQ_4C, Square::SQ_5D, false)),
+        );
+        assert_eq!(
+            position.move_from_ki2("５四銀引成", &TestChecker::PSEUDO_LEGAL),
+            Ok(normal(Square::SQ_4C, Square::SQ_5D, true)),
+        );
+        assert_eq!(
+            position.move_from_ki2("５四銀上成", &TestChecker::PSEUDO_LEGAL),
+            Err(Ki2Error::NoCandidate),
+        );
+        position.side_to_move_set(Color::White);
+        assert_eq!(
+            position.move_from_ki2("５四金", &TestChecker::PSEUDO_LEGAL),
+            Err(Ki2Error::NoCandidate),
+        );
+    }
+}
@@ -0,0 +1,881 @@
+//! Streaming readers of game records (棋譜, *kifu*).
+//!
+//! Readers consume a record line by line from a [`BufRead`] and yield [`KifuEvent`]s,
+//! so that large databases can be imported without loading whole files into memory.
+//! [`KifReader`] reads KIF, and `CsaReader` reads CSA (with the `csa` feature).
+//!
+//! For each game in the input, a reader yields [`KifuEvent::Header`]s, then exactly one [`KifuEvent::Start`],
+//! then [`KifuEvent::Move`]s, and finally exactly one [`KifuEvent::End`].
+//! [`KifuEvent::Comment`]s may appear anywhere in a game, in the order they appear in the input.
+//!
+//! Input must be encoded in UTF-8. A byte order mark at the beginning of the input is ignored.
+//! If a line is not valid UTF-8, a reader yields [`KifuReadError::Encoding`] with the guessed encoding of the line,
+//! so that the input can be converted and read again.
+//! Moves are checked only to the extent that [`PartialPosition::make_move`] succeeds; legality is not checked.
+//! After an error, a reader yields nothing.
+
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::string::String;
+
+use crate::kanji::{FILES, PIECE_NAMES, RANKS};
+use crate::ki2::{eat, eat_number};
+use crate::{GameResolution, Move, PartialPosition, Piece, ResolutionDetail, Square, TextEncoding};
+
+/// An event in a game record, yielded by a reader in this module.
+///
+/// Since: 0.1.6
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum KifuEvent {
+    /// An entry of metadata, such as players' names.
+    ///
+    /// Keys are those of the format: e.g. `先手` in KIF, and `N+` or `$EVENT` in CSA.
+    Header {
+        /// The key.
+        key: String,
+        /// The value.
+        value: String,
+    },
+    /// The game starts from the given position.
+    Start(alloc::boxed::Box<PartialPosition>),
+    /// A move was made.
+    Move(Move),
+    /// A comment, without the leading marker (`*` in KIF and `'` in CSA).
+    Comment(String),
+    /// The game ended. If the record tells how the game was resolved, `resolution` is [`Some`],
+    /// and `detail` is also [`Some`] if the reason is known.
+    End {
+        /// How the game was resolved.
+        resolution: Option<GameResolution>,
+        /// Why the game was resolved.
+        detail: Option<ResolutionDetail>,
+    },
+}
+
+impl KifuEvent {
+    fn end(result: Option<Result<ResolutionDetail, GameResolution>>) -> Self {
+        match result {
+            Some(Ok(detail)) => KifuEvent::End {
+                resolution: Some(detail.resolution()),
+                detail: Some(detail),
+            },
+            Some(Err(resolution)) => KifuEvent::End {
+                resolution: Some(resolution),
+                detail: None,
+            },
+            None => KifuEvent::End {
+                resolution: None,
+                detail: None,
+            },
+        }
+    }
+}
+
+/// An error that occurred while reading a game record.
+///
+/// Since: 0.1.6
+#[derive(Debug)]
+pub enum KifuReadError {
+    /// Reading from the input failed.
+    Io(std::io::Error),
+    /// The line with the given 1-based line number is not valid UTF-8.
+    Encoding {
+        /// The line number.
+        line: usize,
+        /// The encoding of the line guessed by [`TextEncoding::detect`], if any.
+        detected: Option<TextEncoding>,
+    },
+    /// The line with the given 1-based line number is invalid.
+    Invalid {
+        /// The line number.
+        line: usize,
+    },
+}
+
+impl core::fmt::Display for KifuReadError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            KifuReadError::Io(error) => write!(f, "I/O error: {}", error),
+            KifuReadError::Encoding {
+                line,
+                detected: Some(detected),
+            } => write!(
+                f,
+                "line {} is not UTF-8 but probably {}",
+                line,
+                detected.label()
+            ),
+            KifuReadError::Encoding { line, .. } => write!(f, "line {} is not UTF-8", line),
+            KifuReadError::Invalid { line } => write!(f, "invalid record at line {}", line),
+        }
+    }
+}
+
+impl std::error::Error for KifuReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KifuReadError::Io(error) => Some(error),
+            KifuReadError::Encoding { .. } | KifuReadError::Invalid { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for KifuReadError {
+    fn from(error: std::io::Error) -> Self {
+        KifuReadError::Io(error)
+    }
+}
+
+// Reads lines one by one, reusing the buffer.
+struct Lines<R> {
+    reader: R,
+    buffer: std::vec::Vec<u8>,
+    number: usize,
+}
+
+impl<R: BufRead> Lines<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: std::vec::Vec::new(),
+            number: 0,
+        }
+    }
+
+    // Reads the next line without the line break. Returns `Ok(None)` at the end of input.
+    fn next_line(&mut self) -> Result<Option<&str>, KifuReadError> {
+        self.buffer.clear();
+        if self.reader.read_until(b'\n', &mut self.buffer)? == 0 {
+            return Ok(None);
+        }
+        self.number += 1;
+        let line = match core::str::from_utf8(&self.buffer) {
+            Ok(line) => line,
+            Err(_) => {
+                return Err(KifuReadError::Encoding {
+                    line: self.number,
+                    detected: TextEncoding::detect(&self.buffer),
+                })
+            }
+        };
+        let mut line = line.trim_end_matches(['\n', '\r']);
+        if self.number == 1 {
+            line = line.trim_start_matches('\u{feff}');
+        }
+        Ok(Some(line))
+    }
+}
+
+// The part of a reader that depends on the format.
+trait Format {
+    // Handles a line. Returns `None` if the line is invalid.
+    fn feed(&mut self, line: &str, events: &mut VecDeque<KifuEvent>) -> Option<()>;
+    // Handles the end of input. Returns `None` if the last game is invalid.
+    fn finish(&mut self, events: &mut VecDeque<KifuEvent>) -> Option<()>;
+}
+
+// Drives a `Format` with lines from a reader.
+struct Driver<R, F> {
+    lines: Lines<R>,
+    format: F,
+    events: VecDeque<KifuEvent>,
+    done: bool,
+}
+
+impl<R: BufRead, F: Format> Driver<R, F> {
+    fn new(reader: R, format: F) -> Self {
+        Self {
+            lines: Lines::new(reader),
+            format,
+            events: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    fn next_event(&mut self) -> Option<Result<KifuEvent, KifuReadError>> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Some(Ok(event));
+            }
+            if self.done {
+                return None;
+            }
+            let result = match self.lines.next_line() {
+                Ok(Some(line)) => self.format.feed(line, &mut self.events),
+                Ok(None) => {
+                    self.done = true;
+                    self.format.finish(&mut self.events)
+                }
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(error));
+                }
+            };
+            if result.is_none() {
+                self.done = true;
+                self.events.clear();
+                return Some(Err(KifuReadError::Invalid {
+                    line: self.lines.number,
+                }));
+            }
+        }
+    }
+}
+
+/// A streaming reader of a game record in KIF format.
+///
+/// Supported are the header entries of the form `key：value`, the handicaps of `手合割`,
+/// a board diagram (BOD, see [`PartialPosition::from_bod`]) in the header,
+/// move lines (e.g. `   1 ７六歩(77)   ( 0:00/00:00:00)`), special moves (e.g. `投了`) and comments starting with `*`.
+/// Variations (`変化：`) are skipped. Lines starting with `#` or `&` and other unrecognized lines are ignored.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Color, GameResolution, Move, PartialPosition, ResolutionDetail, Square};
+/// use shogi_core::kifu::{KifReader, KifuEvent};
+/// let kif = "\
+/// 先手：Alice
+/// 手合割：平手
+/// 手数----指手---------消費時間--
+///    1 ７六歩(77)   ( 0:01/00:00:01)
+/// *Opening the bishop's diagonal
+///    2 投了   ( 0:02/00:00:02)
+/// まで1手で先手の勝ち
+/// ";
+/// let events: Vec<_> = KifReader::new(kif.as_bytes()).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(
+///     events,
+///     [
+///         KifuEvent::Header { key: "先手".to_owned(), value: "Alice".to_owned() },
+///         KifuEvent::Header { key: "手合割".to_owned(), value: "平手".to_owned() },
+///         KifuEvent::Start(PartialPosition::startpos().into()),
+///         KifuEvent::Move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }),
+///         KifuEvent::Comment("Opening the bishop's diagonal".to_owned()),
+///         KifuEvent::End {
+///             resolution: Some(GameResolution::BlackWins),
+///             detail: Some(ResolutionDetail::Resignation(Color::White)),
+///         },
+///     ],
+/// );
+/// ```
+///
+/// Since: 0.1.6
+pub struct KifReader<R> {
+    driver: Driver<R, Kif>,
+}
+
+impl<R: BufRead> KifReader<R> {
+    /// Creates a reader that reads a record in KIF format from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            driver: Driver::new(reader, Kif::default()),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for KifReader<R> {
+    type Item = Result<KifuEvent, KifuReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.driver.next_event()
+    }
+}
+
+#[derive(Default)]
+struct Kif {
+    // The header lines, which may contain a board diagram.
+    header: String,
+    handicap: Option<PartialPosition>,
+    // `Some` after the game started.
+    position: Option<PartialPosition>,
+    last_to: Option<Square>,
+    ended: bool,
+    in_variation: bool,
+}
+
+impl Kif {
+    fn start(&mut self, events: &mut VecDeque<KifuEvent>) -> Option<()> {
+        let position = if self.header.lines().any(|line| line.starts_with('|')) {
+            PartialPosition::from_bod(&self.header)?
+        } else {
+            self.handicap
+                .clone()
+                .unwrap_or_else(PartialPosition::startpos)
+        };
+        events.push_back(KifuEvent::Start(position.clone().into()));
+        self.position = Some(position);
+        Some(())
+    }
+
+    fn feed_move(&mut self, token: &str, events: &mut VecDeque<KifuEvent>) -> Option<()> {
+        let position = self.position.as_mut()?;
+        let side = position.side_to_move();
+        let special = match token {
+            "投了" => Some(ResolutionDetail::Resignation(side)),
+            "中断" => Some(ResolutionDetail::Aborted),
+            "千日手" => Some(ResolutionDetail::Repetition),
+            "持将棋" => Some(ResolutionDetail::Impasse),
+            "詰み" => Some(ResolutionDetail::Mated(side)),
+            "切れ負け" => Some(ResolutionDetail::TimeUp(side)),
+            "反則負け" => Some(ResolutionDetail::IllegalMove(side, None)),
+            "反則勝ち" => Some(ResolutionDetail::IllegalMove(side.flip(), None)),
+            "入玉勝ち" => Some(ResolutionDetail::Declaration(side)),
+            "不詰" => None,
+            _ => {
+                let mv = kif_move(position, self.last_to, token)?;
+                position.make_move(mv)?;
+                self.last_to = Some(mv.to());
+                events.push_back(KifuEvent::Move(mv));
+                return Some(());
+            }
+        };
+        self.ended = true;
+        events.push_back(KifuEvent::end(special.map(Ok)));
+        Some(())
+    }
+}
+
+impl Format for Kif {
+    fn feed(&mut self, line: &str, events: &mut VecDeque<KifuEvent>) -> Option<()> {
+        let line = line.trim_end();
+        if self.in_variation || line.is_empty() || line.starts_with(['#', '&']) {
+            return Some(());
+        }
+        if let Some(comment) = line.strip_prefix('*') {
+            events.push_back(KifuEvent::Comment(comment.into()));
+            return Some(());
+        }
+        if line.starts_with("変化：") {
+            self.in_variation = true;
+            return self.finish(events);
+        }
+        let mut rest = line;
+        let is_move = crate::usi::next_token(&mut rest).map_or(false, |number| {
+            number.bytes().all(|byte| byte.is_ascii_digit())
+        });
+        if self.position.is_none() {
+            if line.starts_with("手数----") {
+                return self.start(events);
+            }
+            if !is_move {
+                self.header.push_str(line);
+                self.header.push('\n');
+                if let Some((key, value)) = line.split_once('：') {
+                    if key == "手合割" {
+                        self.handicap = Some(handicap(value.trim())?);
+                    }
+                    if !key.ends_with("持駒") {
+                        events.push_back(KifuEvent::Header {
+                            key: key.into(),
+                            value: value.into(),
+                        });
+                    }
+                }
+                return Some(());
+            }
+            self.start(events)?;
+        }
+        if !is_move || self.ended {
+            // e.g. `まで64手で後手の勝ち`
+            return Some(());
+        }
+        let token = crate::usi::next_token(&mut rest)?;
+        self.feed_move(token, events)
+    }
+
+    fn finish(&mut self, events: &mut VecDeque<KifuEvent>) -> Option<()> {
+        if self.position.is_none() {
+            if self.header.is_empty() {
+                return Some(());
+            }
+            self.start(events)?;
+        }
+        if !self.ended {
+            self.ended = true;
+            events.push_back(KifuEvent::end(None));
+        }
+        Some(())
+    }
+}
+
+// Parses a move in KIF format, e.g. `７六歩(77)`, `同　角成(88)` or `５五角打`.
+fn kif_move(position: &PartialPosition, last_to: Option<Square>, token: &str) -> Option<Move> {
+    let mut rest = token;
+    let to = if eat(&mut rest, "同") {
+        rest = rest.trim_start_matches('　');
+        last_to?
+    } else {
+        let file = eat_number(&mut rest, &FILES)?;
+        let rank = eat_number(&mut rest, &RANKS)?;
+        Square::new(file, rank)?
+    };
+    let &(name, piece_kind) = PIECE_NAMES
+        .iter()
+        .find(|(name, _)| rest.starts_with(name))?;
+    rest = &rest[name.len()..];
+    let source = match rest.find('(') {
+        Some(index) => {
+            let source = rest[index..].strip_prefix('(')?.strip_suffix(')')?;
+            rest = &rest[..index];
+            Some(source)
+        }
+        None => None,
+    };
+    let source = match source {
+        Some(source) => source,
+        None => {
+            if !(rest.is_empty() || rest == "打") {
+                return None;
+            }
+            let piece = Piece::new(piece_kind, position.side_to_move());
+            return Some(Move::Drop { piece, to });
+        }
+    };
+    let promote = match rest {
+        "" | "不成" => false,
+        "成" => true,
+        _ => return None,
+    };
+    let bytes = source.as_bytes();
+    if bytes.len() != 2 || !bytes.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let from = Square::new(bytes[0] - b'0', bytes[1] - b'0')?;
+    if position.piece_at(from)?.piece_kind() != piece_kind {
+        return None;
+    }
+    Some(Move::Normal { from, to, promote })
+}
+
+// The starting position of a handicap game, where white (上手) removes pieces and moves first.
+fn handicap(name: &str) -> Option<PartialPosition> {
+    let removed: &[Square] = match name {
+        "平手" => return Some(PartialPosition::startpos()),
+        "香落ち" => &[Square::SQ_1A],
+        "右香落ち" => &[Square::SQ_9A],
+        "角落ち" => &[Square::SQ_2B],
+        "飛車落ち" => &[Square::SQ_8B],
+        "飛香落ち" => &[Square::SQ_8B, Square::SQ_1A],
+        "二枚落ち" => &[Square::SQ_8B, Square::SQ_2B],
+        "三枚落ち" => &[Square::SQ_8B, Square::SQ_2B, Square::SQ_1A],
+        "四枚落ち" => &[Square::SQ_8B, Square::SQ_2B, Square::SQ_1A, Square::SQ_9A],
+        "六枚落ち" => &[
+            Square::SQ_8B,
+            Square::SQ_2B,
+            Square::SQ_1A,
+            Square::SQ_9A,
+            Square::SQ_2A,
+            Square::SQ_8A,
+        ],
+        "八枚落ち" => &[
+            Square::SQ_8B,
+            Square::SQ_2B,
+            Square::SQ_1A,
+            Square::SQ_9A,
+            Square::SQ_2A,
+            Square::SQ_8A,
+            Square::SQ_3A,
+            Square::SQ_7A,
+        ],
+        "十枚落ち" => &[
+            Square::SQ_8B,
+            Square::SQ_2B,
+            Square::SQ_1A,
+            Square::SQ_9A,
+            Square::SQ_2A,
+            Square::SQ_8A,
+            Square::SQ_3A,
+            Square::SQ_7A,
+            Square::SQ_4A,
+            Square::SQ_6A,
+        ],
+        _ => return None,
+    };
+    let mut position = PartialPosition::startpos();
+    for &square in removed {
+        position.piece_set(square, None);
+    }
+    position.side_to_move_set(crate::Color::White);
+    Some(position)
+}
+
+/// A streaming reader of game records in CSA format.
+///
+/// Games in the input are separated by lines consisting of `/`.
+/// The header of a game (up to its first move) is parsed with [`csa::parse_csa`],
+/// and each entry of players' names (`N+`, `N-`) and game attributes (e.g. `$EVENT`) is yielded as a [`KifuEvent::Header`].
+/// Special moves are converted in the same way as [`Game::from_csa`](crate::Game::from_csa).
+/// Time spent on moves (`T`) is ignored.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Color, GameResolution, Move, PartialPosition, ResolutionDetail, Square};
+/// use shogi_core::kifu::{CsaReader, KifuEvent};
+/// let csa = "N+Alice\nPI\n+\n+7776FU\nT1\n'good\n%TORYO\n/\nPI\n+\n";
+/// let events: Vec<_> = CsaReader::new(csa.as_bytes()).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(
+///     events,
+///     [
+///         KifuEvent::Header { key: "N+".to_owned(), value: "Alice".to_owned() },
+///         KifuEvent::Start(PartialPosition::startpos().into()),
+///         KifuEvent::Move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }),
+///         KifuEvent::Comment("good".to_owned()),
+///         KifuEvent::End {
+///             resolution: Some(GameResolution::BlackWins),
+///             detail: Some(ResolutionDetail::Resignation(Color::White)),
+///         },
+///         KifuEvent::Start(PartialPosition::startpos().into()),
+///         KifuEvent::End { resolution: None, detail: None },
+///     ],
+/// );
+/// ```
+///
+/// Since: 0.1.6
+#[cfg(feature = "csa")]
+#[cfg_attr(docsrs, doc(cfg(feature = "csa")))]
+pub struct CsaReader<R> {
+    driver: Driver<R, Csa>,
+}
+
+#[cfg(feature = "csa")]
+impl<R: BufRead> CsaReader<R> {
+    /// Creates a reader that reads game records in CSA format from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            driver: Driver::new(reader, Csa::default()),
+        }
+    }
+}
+
+#[cfg(feature = "csa")]
+impl<R: BufRead> Iterator for CsaReader<R> {
+    type Item = Result<KifuEvent, KifuReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.driver.next_event()
+    }
+}
+
+#[cfg(feature = "csa")]
+#[derive(Default)]
+struct Csa {
+    // The header lines of the current game, which are parsed when the first move appears.
+    header: String,
+    // `Some` after the current game started.
+    position: Option<PartialPosition>,
+    ended: bool,
+}
+
+#[cfg(feature = "csa")]
+impl Csa {
+    fn start(&mut self, events: &mut VecDeque<KifuEvent>) -> Option<()> {
+        let record = csa::parse_csa(&self.header).ok()?;
+        let position = PartialPosition::from_csa(&record.start_pos)?;
+        events.push_back(KifuEvent::Start(position.clone().into()));
+        self.position = Some(position);
+        Some(())
+    }
+
+    fn feed_statement(&mut self, statement: &str, events: &mut VecDeque<KifuEvent>) -> Option<()> {
+        if statement.is_empty() {
+            return Some(());
+        }
+        if statement == "/" {
+            self.finish(events)?;
+            *self = Self::default();
+            return Some(());
+        }
+        if let Some(comment) = statement.strip_prefix('\'') {
+            events.push_back(KifuEvent::Comment(comment.into()));
+            return Some(());
+        }
+        if self.ended {
+            return Some(());
+        }
+        let is_move = statement.len() == 7 && statement.starts_with(['+', '-']);
+        if self.position.is_none() {
+            if !is_move && !statement.starts_with('%') {
+                self.header.push_str(statement);
+                self.header.push('\n');
+                let entry = if let Some(name) = statement.strip_prefix("N+") {
+                    Some(("N+", name))
+                } else if let Some(name) = statement.strip_prefix("N-") {
+                    Some(("N-", name))
+                } else if statement.starts_with('$') {
+                    statement.split_once(':')
+                } else {
+                    None
+                };
+                if let Some((key, value)) = entry {
+                    events.push_back(KifuEvent::Header {
+                        key: key.into(),
+                        value: value.into(),
+                    });
+                }
+                return Some(());
+            }
+            self.start(events)?;
+        }
+        let position = self.position.as_mut()?;
+        if statement.starts_with('T') {
+            return Some(());
+        }
+        let action = if is_move {
+            csa_move(statement)?
+        } else {
+            csa_special(statement)?
+        };
+        if let csa::Action::Move(..) = action {
+            let mv = position.move_from_csa(&action)?;
+            position.make_move(mv)?;
+            events.push_back(KifuEvent::Move(mv));
+        } else {
+            let side = position.side_to_move();
+            self.ended = true;
+            events.push_back(KifuEvent::end(crate::csa_interop::special_from_csa(
+                &action, side,
+            )));
+        }
+        Some(())
+    }
+}
+
+#[cfg(feature = "csa")]
+impl Format for Csa {
+    fn feed(&mut self, line: &str, events: &mut VecDeque<KifuEvent>) -> Option<()> {
+        // A comment may contain commas.
+        if line.starts_with('\'') {
+            return self.feed_statement(line, events);
+        }
+        for statement in line.split(',') {
+            self.feed_statement(statement.trim_end(), events)?;
+        }
+        Some(())
+    }
+
+    fn finish(&mut self, events: &mut VecDeque<KifuEvent>) -> Option<()> {
+        if self.position.is_none() {
+            if self.header.is_empty() {
+                return Some(());
+            }
+            self.start(events)?;
+        }
+        if !self.ended {
+            self.ended = true;
+            events.push_back(KifuEvent::end(None));
+        }
+        Some(())
+    }
+}
+
+// Parses a move in CSA format, e.g. `+7776FU`.
+#[cfg(feature = "csa")]
+fn csa_move(statement: &str) -> Option<csa::Action> {
+    const NAMES: [&str; 14] = [
+        "FU", "KY", "KE", "GI", "KI", "KA", "HI", "OU", "TO", "NY", "NK", "NG", "UM", "RY",
+    ];
+    let bytes = statement.as_bytes();
+    let color = if bytes[0] == b'+' {
+        csa::Color::Black
+    } else {
+        csa::Color::White
+    };
+    if !bytes[1..5].iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let from = csa::Square::new(bytes[1] - b'0', bytes[2] - b'0');
+    let to = csa::Square::new(bytes[3] - b'0', bytes[4] - b'0');
+    let index = NAMES.iter().position(|&name| name == &statement[5..])?;
+    let piece_kind = crate::PieceKind::from_u8(index as u8 + 1)?;
+    Some(csa::Action::Move(color, from, to, piece_kind.into()))
+}
+
+// Parses a special move in CSA format, e.g. `%TORYO`.
+#[cfg(feature = "csa")]
+fn csa_special(statement: &str) -> Option<csa::Action> {
+    Some(match statement {
+        "%TORYO" => csa::Action::Toryo,
+        "%CHUDAN" => csa::Action::Chudan,
+        "%SENNICHITE" => csa::Action::Sennichite,
+        "%TIME_UP" => csa::Action::TimeUp,
+        "%ILLEGAL_MOVE" => csa::Action::IllegalMove,
+        "%+ILLEGAL_ACTION" => csa::Action::IllegalAction(csa::Color::Black),
+        "%-ILLEGAL_ACTION" => csa::Action::IllegalAction(csa::Color::White),
+        "%JISHOGI" => csa::Action::Jishogi,
+        "%KACHI" => csa::Action::Kachi,
+        "%HIKIWAKE" => csa::Action::Hikiwake,
+        "%MATTA" => csa::Action::Matta,
+        "%TSUMI" => csa::Action::Tsumi,
+        "%FUZUMI" => csa::Action::Fuzumi,
+        "%ERROR" => csa::Action::Error,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    fn read_kif(s: &str) -> Result<Vec<KifuEvent>, usize> {
+        KifReader::new(s.as_bytes())
+            .collect::<Result<_, _>>()
+            .map_err(|error| match error {
+                KifuReadError::Invalid { line } => line,
+                error => panic!("{}", error),
+            })
+    }
+
+    #[test]
+    fn kif_reader_works() {
+        let kif = "\
+# comment of the file
+手合割：香落ち
+手数----指手---------消費時間--
+   1 ３四歩(33)
+   2 ７六歩(77)
+   3 ８八角成(22)
+   4 同　銀(79)
+   5 ５五角打
+   6 中断
+まで5手で中断
+
+変化：3手
+   3 ４四歩(43)
+";
+        let events = read_kif(kif).unwrap();
+        let mut expected = PartialPosition::startpos();
+        expected.piece_set(Square::SQ_1A, None);
+        expected.side_to_move_set(Color::White);
+        assert_eq!(events[1], KifuEvent::Start(expected.into()));
+        assert_eq!(
+            events[5],
+            KifuEvent::Move(Move::Normal {
+                from: Square::SQ_7I,
+                to: Square::SQ_8H,
+                promote: false,
+            }),
+        );
+        assert_eq!(
+            events[6],
+            KifuEvent::Move(Move::Drop {
+                piece: Piece::W_B,
+                to: Square::SQ_5E,
+            }),
+        );
+        assert_eq!(
+            events[7..],
+            [KifuEvent::End {
+                resolution: Some(GameResolution::Aborted),
+                detail: Some(ResolutionDetail::Aborted),
+            }],
+        );
+
+        // A record without a header and a special move
+        let events = read_kif("1 ７六歩(77)\n").unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[0],
+            KifuEvent::Start(PartialPosition::startpos().into())
+        );
+        assert_eq!(events[2], KifuEvent::end(None));
+        assert_eq!(read_kif(""), Ok(Vec::new()));
+
+        // The piece on 77 is not a silver.
+        assert_eq!(read_kif("手合割：平手\n1 ７六銀(77)\n"), Err(2));
+        assert_eq!(read_kif("手合割：九枚落ち\n"), Err(1));
+    }
+
+    #[test]
+    fn kif_reader_detects_encoding() {
+        // "先手：Alice" in Shift_JIS
+        let kif = b"# KIF\n\x90\xe6\x8e\xe8\x81\x46Alice\n";
+        let mut reader = KifReader::new(&kif[..]);
+        assert!(matches!(
+            reader.next(),
+            Some(Err(KifuReadError::Encoding {
+                line: 2,
+                detected: Some(TextEncoding::ShiftJis),
+            }))
+        ));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn kif_reader_reads_bod() {
+        let kif = "\
+後手の持駒：なし
+  ９ ８ ７ ６ ５ ４ ３ ２ １
++---------------------------+
+| ・ ・ ・ ・ ・ ・ ・ ・v玉|一
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|二
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|三
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|四
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|五
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|六
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|七
+| ・ ・ ・ ・ ・ ・ ・ ・ ・|八
+| ・ ・ ・ ・ 玉 ・ ・ ・ ・|九
++---------------------------+
+先手の持駒：金
+手数----指手---------消費時間--
+   1 １二金打
+   2 詰み
+";
+        let events = read_kif(kif).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[1],
+            KifuEvent::Move(Move::Drop {
+                piece: Piece::B_G,
+                to: Square::SQ_1B,
+            }),
+        );
+        assert_eq!(
+            events[2],
+            KifuEvent::end(Some(Ok(ResolutionDetail::Mated(Color::White)))),
+        );
+    }
+
+    #[cfg(feature = "csa")]
+    #[test]
+    fn csa_reader_works() {
+        let csa = "\
+V2.2
+$EVENT:test
+PI
++
++7776FU,T1,-3334FU
++8822UM
+%-ILLEGAL_ACTION
+'after the game
+/
+/
+";
+        let events: Vec<_> = CsaReader::new(csa.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(events.len(), 7);
+        assert_eq!(
+            events[4],
+            KifuEvent::Move(Move::Normal {
+                from: Square::SQ_8H,
+                to: Square::SQ_2B,
+                promote: true,
+            }),
+        );
+        assert_eq!(
+            events[5],
+            KifuEvent::end(Some(Ok(ResolutionDetail::IllegalMove(Color::White, None)))),
+        );
+        assert_eq!(events[6], KifuEvent::Comment("after the game".into()));
+
+        // The piece on 77 is not a lance.
+        let mut reader = CsaReader::new("PI\n+\n+7776KY\n+2726FU\n".as_bytes());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(KifuReadError::Invalid { line: 3 }))
+        ));
+        assert!(reader.next().is_none());
+    }
+}
@@ -1,6 +1,9 @@
 #[cfg(feature = "alloc")]
 use crate::Position;
-use crate::{Bitboard, IllegalMoveKind, Move, PartialPosition, Piece, Square};
+use crate::{
+    Bitboard, Color, CompactMove, GameResolution, Hand, IllegalMoveKind, Move, PartialPosition,
+    Piece, Square,
+};
 
 /// The status of a position.
 ///
@@ -13,7 +16,7 @@ pub enum PositionStatus {
     BlackWins = 1,
     /// Black's king was mated.
     WhiteWins = 2,
-    /// Draw by repetition happened.
+    /// Draw by repetition happened, or the move limit was reached.
     Draw = 3,
     /// A game is in progress.
     InProgress = 4,
@@ -21,6 +24,31 @@ pub enum PositionStatus {
     Invalid = 5,
 }
 
+impl PositionStatus {
+    /// Finds the [`PositionStatus`] corresponding to how a game is resolved. This is a best-effort inverse of [`GameResolution::from_status`].
+    ///
+    /// Wins map to wins, and [`GameResolution::Draw`] and [`GameResolution::Rematch`] map to [`PositionStatus::Draw`].
+    /// An unresolved or aborted game maps to [`PositionStatus::InProgress`], since the position itself does not end the game.
+    /// Note that resignation and timeout are also mapped to wins although the position may be still in progress.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{GameResolution, PositionStatus};
+    /// assert_eq!(PositionStatus::from_resolution(Some(GameResolution::BlackWins)), PositionStatus::BlackWins);
+    /// assert_eq!(PositionStatus::from_resolution(Some(GameResolution::Draw)), PositionStatus::Draw);
+    /// assert_eq!(PositionStatus::from_resolution(Some(GameResolution::Aborted)), PositionStatus::InProgress);
+    /// assert_eq!(PositionStatus::from_resolution(None), PositionStatus::InProgress);
+    /// ```
+    pub const fn from_resolution(resolution: Option<GameResolution>) -> Self {
+        match resolution {
+            Some(GameResolution::BlackWins) => PositionStatus::BlackWins,
+            Some(GameResolution::WhiteWins) => PositionStatus::WhiteWins,
+            Some(GameResolution::Draw | GameResolution::Rematch) => PositionStatus::Draw,
+            Some(GameResolution::Aborted) | None => PositionStatus::InProgress,
+        }
+    }
+}
+
 impl_ord_for_fieldless_enum!(PositionStatus);
 impl_hash_for_fieldless_enum!(PositionStatus);
 
@@ -40,6 +68,20 @@ pub trait LegalityChecker {
     /// Because [`PartialPosition`] does not have a move sequence in it,
     /// it cannot return [`PositionStatus::Draw`] (which needs repetition check).
     fn status_partial(&self, position: &PartialPosition) -> PositionStatus;
+    /// Returns the status of this position, where a game is drawn after `move_limit` moves.
+    ///
+    /// If the game is still in progress after `move_limit` moves are made, this function returns [`PositionStatus::Draw`].
+    /// A mate on the last move takes precedence.
+    /// Typical values are [`Game::MOVE_LIMIT_FLOODGATE`](crate::Game::MOVE_LIMIT_FLOODGATE) and [`Game::MOVE_LIMIT_WCSC`](crate::Game::MOVE_LIMIT_WCSC).
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn status_with_move_limit(&self, position: &Position, move_limit: u16) -> PositionStatus {
+        let status = self.status(position);
+        if status == PositionStatus::InProgress && position.moves().len() >= move_limit as usize {
+            return PositionStatus::Draw;
+        }
+        status
+    }
 
     // legality checking
 
@@ -128,4 +170,247 @@ pub trait LegalityChecker {
         debug_assert_eq!(result, Some(()));
         Ok(())
     }
+
+    // Mate detection
+
+    /// Finds if the player to move is mated, i.e., has no legal moves.
+    ///
+    /// In shogi, a player with no legal moves loses whether or not their king is in check.
+    /// If `position` is not in progress, this function returns whether the player to move has already lost.
+    /// This function does not allocate.
+    fn is_mated(&self, position: &PartialPosition) -> bool {
+        let side = position.side_to_move();
+        match self.status_partial(position) {
+            PositionStatus::InProgress => {}
+            PositionStatus::BlackWins => return side == Color::White,
+            PositionStatus::WhiteWins => return side == Color::Black,
+            PositionStatus::Draw | PositionStatus::Invalid => return false,
+        }
+        for from in position.player_bitboard(side) {
+            if !self.normal_from_candidates(position, from).is_empty() {
+                return false;
+            }
+        }
+        let hand = position.hand_of_a_player(side);
+        for piece_kind in Hand::all_hand_pieces() {
+            if hand.count(piece_kind) == Some(0) {
+                continue;
+            }
+            let piece = Piece::new(piece_kind, side);
+            if !self.drop_candidates(position, piece).is_empty() {
+                return false;
+            }
+        }
+        true
+    }
+    /// Finds a legal move after which the opponent is mated (see [`LegalityChecker::is_mated`]).
+    ///
+    /// If there are more than one such moves, which one is returned is unspecified.
+    /// If `position` is not in progress, this function returns [`None`].
+    /// This function does not allocate.
+    fn mate_in_one(&self, position: &PartialPosition) -> Option<Move> {
+        if self.status_partial(position) != PositionStatus::InProgress {
+            return None;
+        }
+        let side = position.side_to_move();
+        let mates = |mv: Move| {
+            let mut next = position.clone();
+            next.make_move(mv).is_some() && self.is_mated(&next)
+        };
+        for from in position.player_bitboard(side) {
+            for to in self.normal_from_candidates(position, from) {
+                for promote in [false, true] {
+                    let mv = Move::Normal { from, to, promote };
+                    if self.is_legal_partial_lite(position, mv) && mates(mv) {
+                        return Some(mv);
+                    }
+                }
+            }
+        }
+        let hand = position.hand_of_a_player(side);
+        for piece_kind in Hand::all_hand_pieces() {
+            if hand.count(piece_kind) == Some(0) {
+                continue;
+            }
+            let piece = Piece::new(piece_kind, side);
+            for to in self.drop_candidates(position, piece) {
+                let mv = Move::Drop { piece, to };
+                if mates(mv) {
+                    return Some(mv);
+                }
+            }
+        }
+        None
+    }
+
+    /// Writes all legal moves in `position` to `out`, and returns the number of legal moves.
+    ///
+    /// If `out` is shorter than the number of legal moves, only the first `out.len()` moves are written,
+    /// but the returned value is still the number of all legal moves.
+    /// The order of moves is unspecified.
+    /// This function does not allocate.
+    ///
+    /// Since: 0.1.6
+    fn write_legal_moves_partial(
+        &self,
+        position: &PartialPosition,
+        out: &mut [CompactMove],
+    ) -> usize {
+        if self.status_partial(position) != PositionStatus::InProgress {
+            return 0;
+        }
+        let mut count = 0;
+        let mut push = |mv: CompactMove| {
+            if let Some(slot) = out.get_mut(count) {
+                *slot = mv;
+            }
+            count += 1;
+        };
+        let side = position.side_to_move();
+        for from in position.player_bitboard(side) {
+            for to in self.normal_from_candidates(position, from) {
+                for promote in [false, true] {
+                    let mv = Move::Normal { from, to, promote };
+                    if self.is_legal_partial_lite(position, mv) {
+                        push(CompactMove::normal(from, to, promote));
+                    }
+                }
+            }
+        }
+        let hand = position.hand_of_a_player(side);
+        for piece_kind in Hand::all_hand_pieces() {
+            if hand.count(piece_kind) == Some(0) {
+                continue;
+            }
+            let piece = Piece::new(piece_kind, side);
+            for to in self.drop_candidates(position, piece) {
+                push(CompactMove::drop(piece, to));
+            }
+        }
+        count
+    }
+}
+
+/// C interface of [`LegalityChecker::write_legal_moves_partial`].
+///
+/// This crate does not provide any implementors of [`LegalityChecker`], hence does not export this function by itself.
+/// Crates that provide an implementor can export it for their checker as follows:
+/// ```
+/// # use shogi_core::{Bitboard, CompactMove, IllegalMoveKind, LegalityChecker, Move, PartialPosition, Piece, Position, PositionStatus, Square};
+/// # pub struct MyChecker;
+/// # impl LegalityChecker for MyChecker {
+/// #     fn status(&self, _: &Position) -> PositionStatus { unimplemented!() }
+/// #     fn status_partial(&self, _: &PartialPosition) -> PositionStatus { unimplemented!() }
+/// #     fn is_legal_partial(&self, _: &PartialPosition, _: Move) -> Result<(), IllegalMoveKind> { unimplemented!() }
+/// #     fn is_legal_partial_lite(&self, _: &PartialPosition, _: Move) -> bool { unimplemented!() }
+/// #     fn all_legal_moves_partial(&self, _: &PartialPosition) -> Vec<Move> { unimplemented!() }
+/// #     fn normal_from_candidates(&self, _: &PartialPosition, _: Square) -> Bitboard { unimplemented!() }
+/// #     fn normal_to_candidates(&self, _: &PartialPosition, _: Square, _: Piece) -> Bitboard { unimplemented!() }
+/// #     fn drop_candidates(&self, _: &PartialPosition, _: Piece) -> Bitboard { unimplemented!() }
+/// # }
+/// #[no_mangle]
+/// pub unsafe extern "C" fn MyChecker_legal_moves(
+///     checker: &MyChecker,
+///     position: &PartialPosition,
+///     out: *mut CompactMove,
+///     cap: usize,
+/// ) -> usize {
+///     shogi_core::c_compat::legal_moves_c(checker, position, out, cap)
+/// }
+/// ```
+///
+/// # Safety
+/// `out` must be null or valid for writes of `cap` [`CompactMove`]s. If `out` is null, no moves are written.
+///
+/// Since: 0.1.6
+pub unsafe fn legal_moves_c<C: LegalityChecker + ?Sized>(
+    checker: &C,
+    position: &PartialPosition,
+    out: *mut CompactMove,
+    cap: usize,
+) -> usize {
+    let out: &mut [CompactMove] = if out.is_null() {
+        &mut []
+    } else {
+        core::slice::from_raw_parts_mut(out, cap)
+    };
+    checker.write_legal_moves_partial(position, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_checker::TestChecker;
+    use crate::PieceKind;
+
+    // Only pawns move. Pieces in hand can be dropped on any vacant square.
+    const PAWNS_ONLY: TestChecker = TestChecker {
+        movable: |piece_kind| piece_kind == PieceKind::Pawn,
+        ..TestChecker::PSEUDO_LEGAL
+    };
+
+    #[test]
+    fn is_mated_works() {
+        let mut position = PartialPosition::empty();
+        assert!(PAWNS_ONLY.is_mated(&position));
+        position.piece_set(Square::SQ_5A, Some(Piece::W_K));
+        assert!(PAWNS_ONLY.is_mated(&position));
+        position.piece_set(Square::SQ_5E, Some(Piece::B_P));
+        assert!(!PAWNS_ONLY.is_mated(&position));
+        // The pawn is blocked by a piece of its owner
+        position.piece_set(Square::SQ_5D, Some(Piece::B_S));
+        assert!(PAWNS_ONLY.is_mated(&position));
+        *position.hand_of_a_player_mut(Color::Black) = Hand::new().added(PieceKind::Gold).unwrap();
+        assert!(!PAWNS_ONLY.is_mated(&position));
+    }
+
+    #[test]
+    fn mate_in_one_works() {
+        let mut position = PartialPosition::empty();
+        position.piece_set(Square::SQ_5G, Some(Piece::B_P));
+        position.piece_set(Square::SQ_5C, Some(Piece::W_P));
+        // 5g5f does not stop the white pawn
+        assert_eq!(PAWNS_ONLY.mate_in_one(&position), None);
+        position.piece_set(Square::SQ_5G, None);
+        position.piece_set(Square::SQ_5C, None);
+        position.piece_set(Square::SQ_5E, Some(Piece::B_P));
+        position.piece_set(Square::SQ_5D, Some(Piece::W_P));
+        // 5e5d captures the only white piece
+        assert_eq!(
+            PAWNS_ONLY.mate_in_one(&position),
+            Some(Move::Normal {
+                from: Square::SQ_5E,
+                to: Square::SQ_5D,
+                promote: false,
+            }),
+        );
+        position.piece_set(Square::SQ_5E, None);
+        // A dropped piece does not stop the white pawn, which can capture it
+        *position.hand_of_a_player_mut(Color::Black) = Hand::new().added(PieceKind::Gold).unwrap();
+        assert_eq!(PAWNS_ONLY.mate_in_one(&position), None);
+    }
+
+    #[test]
+    fn write_legal_moves_partial_works() {
+        let mut position = PartialPosition::empty();
+        position.piece_set(Square::SQ_5G, Some(Piece::B_P));
+        position.piece_set(Square::SQ_3D, Some(Piece::B_P));
+        position.piece_set(Square::SQ_2I, Some(Piece::B_N));
+        // 5g5f, 3d3c, 3d3c+, 2i1g and 2i3g
+        let checker = TestChecker::PSEUDO_LEGAL;
+        let mut out = [CompactMove::normal(Square::SQ_1A, Square::SQ_1B, false); 16];
+        assert_eq!(checker.write_legal_moves_partial(&position, &mut out), 5);
+        let moves = &out[..5];
+        assert!(moves.contains(&CompactMove::normal(Square::SQ_5G, Square::SQ_5F, false)));
+        assert!(moves.contains(&CompactMove::normal(Square::SQ_3D, Square::SQ_3C, true)));
+        assert!(moves.contains(&CompactMove::normal(Square::SQ_2I, Square::SQ_1G, false)));
+        assert!(!moves.contains(&CompactMove::normal(Square::SQ_5G, Square::SQ_5F, true)));
+
+        let mut short = [CompactMove::normal(Square::SQ_1A, Square::SQ_1B, false); 4];
+        let count = unsafe { legal_moves_c(&checker, &position, short.as_mut_ptr(), short.len()) };
+        assert_eq!(count, 5);
+        assert_eq!(short[..], out[..4]);
+        let count = unsafe { legal_moves_c(&checker, &position, core::ptr::null_mut(), 16) };
+        assert_eq!(count, 5);
+    }
 }
@@ -9,31 +9,82 @@ extern crate test;
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(all(feature = "std", not(test)))]
+extern crate std;
+
 #[macro_use]
 mod annotated_derive;
 
+mod attack;
 mod bitboard;
+mod board;
+mod bod;
+#[cfg(feature = "alloc")]
+mod book;
+#[cfg(feature = "alloc")]
+mod collection;
 mod color;
 mod common;
+#[cfg(feature = "csa")]
+mod csa_interop;
+mod csa_move;
+mod encoding;
+mod enum_like;
+mod flip;
+#[cfg(feature = "alloc")]
+mod game_bytes;
 mod game_resolution;
 mod hand;
 mod illegal_move_kind;
+mod kanji;
+mod ki2;
 mod legality;
+#[cfg(feature = "material")]
+mod material;
+mod move_annotation;
 mod mv;
+/// cbindgen:ignore
+#[cfg(feature = "opening")]
+mod opening;
+mod out_of_range;
+mod packed_sfen;
 mod piece;
 mod piece_kind;
 mod position;
+/// cbindgen:ignore
+mod position_fixed;
+#[cfg(feature = "alloc")]
+mod position_key_set;
+#[cfg(feature = "rand")]
+mod random;
+mod sfen_error;
+#[cfg(feature = "shogi")]
+mod shogi_interop;
+/// cbindgen:ignore
+#[cfg(feature = "alloc")]
+mod snapshot;
 mod square;
+#[cfg(test)]
+mod test_checker;
+mod time_control;
 mod to_usi;
+mod tsume;
+mod western;
+
+#[doc(inline)]
+pub use crate::to_usi::{ToUsi, UsiParseError};
 
 #[doc(inline)]
-pub use crate::to_usi::ToUsi;
+pub use crate::color::{Color, ColorParseError};
 
 #[doc(inline)]
-pub use crate::color::Color;
+pub use crate::enum_like::{EnumLike, EnumLikeIter};
 
 #[doc(inline)]
-pub use crate::square::Square;
+pub use crate::flip::Flip;
+
+#[doc(inline)]
+pub use crate::square::{PerSquare, RelativeSquare, Square, UsiSquareParseError};
 
 #[doc(inline)]
 pub use crate::piece_kind::PieceKind;
@@ -42,10 +93,10 @@ pub use crate::piece_kind::PieceKind;
 pub use crate::piece::Piece;
 
 #[doc(inline)]
-pub use crate::mv::Move;
+pub use crate::mv::{Move, UsiMoveParseError};
 
 #[doc(inline)]
-pub use crate::mv::CompactMove;
+pub use crate::mv::{CompactAction, CompactMove};
 
 #[doc(inline)]
 pub use crate::hand::Hand;
@@ -54,23 +105,97 @@ pub use crate::hand::Hand;
 pub use crate::bitboard::{Bitboard, ByteSwappedBitboard};
 
 #[doc(inline)]
-pub use crate::game_resolution::GameResolution;
+pub use crate::board::Board;
+
+#[doc(inline)]
+pub use crate::game_resolution::{GameResolution, ResolutionDetail, ResolutionMismatch};
+
+#[doc(inline)]
+pub use crate::position::{PartialGame, PartialPosition, SpliceError};
+
+#[cfg(feature = "long-effect")]
+#[doc(inline)]
+pub use crate::attack::{EffectPosition, MoveDelta};
 
 #[doc(inline)]
-pub use crate::position::{PartialGame, PartialPosition};
+pub use crate::position_fixed::PositionFixed;
+
+#[cfg(feature = "rkyv")]
+#[doc(inline)]
+pub use crate::position::ArchivedPartialGame;
 
 #[cfg(feature = "alloc")]
 #[doc(inline)]
 pub use crate::position::{Game, Position};
 
+#[cfg(all(feature = "alloc", feature = "rkyv"))]
+#[doc(inline)]
+pub use crate::position::{ArchivedGame, ArchivedPosition};
+
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use crate::snapshot::GameSnapshot;
+
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use crate::collection::GameCollection;
+
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use crate::position_key_set::PositionKeySet;
+
+#[cfg(feature = "alloc")]
+#[doc(inline)]
+pub use crate::book::{Book, BookMove};
+
+#[doc(inline)]
+pub use crate::move_annotation::MoveAnnotation;
+
+#[cfg(feature = "opening")]
+#[doc(inline)]
+pub use crate::opening::Opening;
+
+#[doc(inline)]
+pub use crate::time_control::TimeControl;
+
 #[doc(inline)]
 pub use crate::illegal_move_kind::IllegalMoveKind;
 
+#[doc(inline)]
+pub use crate::ki2::Ki2Error;
+
+#[doc(inline)]
+pub use crate::out_of_range::OutOfRangeError;
+
+#[doc(inline)]
+pub use crate::sfen_error::{SfenParseError, SfenParseErrorKind};
+
+#[doc(inline)]
+pub use crate::encoding::TextEncoding;
+
 #[doc(inline)]
 pub use crate::legality::{LegalityChecker, PositionStatus};
 
+#[doc(inline)]
+pub use crate::packed_sfen::{PackedSfen, PackedSfenValue};
+
+#[cfg(feature = "std")]
+#[doc(inline)]
+pub use crate::packed_sfen::PackedSfenValueReader;
+
+/// The version of this crate, e.g. `"0.1.5"`.
+///
+/// Examples:
+/// ```
+/// assert!(!shogi_core::VERSION.is_empty());
+/// ```
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// Types that are exposed to C.
 pub mod c_compat {
+    #[doc(inline)]
+    pub use crate::common::c_char;
+
     #[doc(inline)]
     pub use crate::piece_kind::OptionPieceKind;
 
@@ -83,13 +208,41 @@ pub mod c_compat {
     #[doc(inline)]
     pub use crate::mv::OptionCompactMove;
 
+    #[doc(inline)]
+    pub use crate::mv::{CMove, CMoveKind};
+
     #[doc(inline)]
     pub use crate::game_resolution::OptionGameResolution;
 
     #[doc(inline)]
     pub use crate::illegal_move_kind::ResultUnitIllegalMoveKind;
+
+    #[doc(inline)]
+    pub use crate::legality::legal_moves_c;
 }
 
+pub mod attacks;
+
+pub mod usi;
+
+pub mod zobrist;
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod kifu;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod perft;
+
+#[cfg(feature = "minishogi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "minishogi")))]
+pub mod minishogi;
+
+#[cfg(feature = "wasm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wasm")))]
+pub mod wasm;
+
 /// Constant values.
 ///
 /// Since: 0.1.2
@@ -0,0 +1,245 @@
+//! Conventional material values of pieces.
+
+use crate::{Color, CompactMove, Hand, Move, PartialPosition, Piece, PieceKind};
+
+/// Material values.
+#[cfg_attr(docsrs, doc(cfg(feature = "material")))]
+impl PieceKind {
+    /// Conventional material values of [`PieceKind`]s in centipawns, indexed by [`PieceKind::array_index`].
+    ///
+    /// The values are those commonly used by computer shogi engines (e.g. YaneuraOu).
+    /// The value of a king is large enough to exceed any possible material of the other pieces,
+    /// so that static exchange evaluation never trades a king.
+    ///
+    /// Since: 0.1.6
+    pub const BASE_VALUES: [i32; PieceKind::NUM] = [
+        90, 315, 405, 495, 540, 855, 990, 15000, 540, 540, 540, 540, 945, 1395,
+    ];
+
+    /// Returns the conventional material value of `self` on the board. See [`PieceKind::BASE_VALUES`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PieceKind;
+    /// assert_eq!(PieceKind::Pawn.base_value(), 90);
+    /// assert!(PieceKind::ProRook.base_value() > PieceKind::Rook.base_value());
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[inline]
+    pub const fn base_value(self) -> i32 {
+        Self::BASE_VALUES[self.array_index()]
+    }
+
+    /// Returns the conventional material value of `self` in hand, or [`None`] if `self` cannot be in hand.
+    ///
+    /// A piece in hand is valued the same as on the board.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PieceKind;
+    /// assert_eq!(PieceKind::Gold.hand_value(), Some(540));
+    /// assert_eq!(PieceKind::ProPawn.hand_value(), None);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[inline]
+    pub const fn hand_value(self) -> Option<i32> {
+        match self {
+            PieceKind::King
+            | PieceKind::ProPawn
+            | PieceKind::ProLance
+            | PieceKind::ProKnight
+            | PieceKind::ProSilver
+            | PieceKind::ProBishop
+            | PieceKind::ProRook => None,
+            _ => Some(self.base_value()),
+        }
+    }
+
+    /// Returns the points of `self` in declarations and impasses (入玉宣言 and 持将棋):
+    /// 5 for bishops and rooks (possibly promoted), 0 for kings and 1 for the others.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PieceKind;
+    /// assert_eq!(PieceKind::ProBishop.impasse_points(), 5);
+    /// assert_eq!(PieceKind::Gold.impasse_points(), 1);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[inline]
+    pub const fn impasse_points(self) -> u8 {
+        match self {
+            PieceKind::Bishop | PieceKind::Rook | PieceKind::ProBishop | PieceKind::ProRook => 5,
+            PieceKind::King => 0,
+            _ => 1,
+        }
+    }
+}
+
+/// Material values.
+#[cfg_attr(docsrs, doc(cfg(feature = "material")))]
+impl Piece {
+    /// Returns the conventional material value of `self` from black's point of view,
+    /// i.e., [`PieceKind::base_value`] for a black piece and its negation for a white piece.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::Piece;
+    /// assert_eq!(Piece::B_S.signed_value(), 495);
+    /// assert_eq!(Piece::W_S.signed_value(), -495);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[inline]
+    pub const fn signed_value(self) -> i32 {
+        let (piece_kind, color) = self.to_parts();
+        match color {
+            Color::Black => piece_kind.base_value(),
+            Color::White => -piece_kind.base_value(),
+        }
+    }
+}
+
+/// Material values.
+#[cfg_attr(docsrs, doc(cfg(feature = "material")))]
+impl Hand {
+    /// Returns the sum of the conventional material values of the pieces in `self`. See [`PieceKind::hand_value`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Hand, PieceKind};
+    /// let hand = Hand::new().added(PieceKind::Pawn).unwrap().added(PieceKind::Rook).unwrap();
+    /// assert_eq!(hand.material_value(), 90 + 990);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub const fn material_value(&self) -> i32 {
+        let counts = self.to_slice();
+        let mut result = 0;
+        let mut index = 0;
+        while index < Hand::NUM_HAND_PIECES {
+            // The order of `counts` is that of `PieceKind::BASE_VALUES`.
+            result += counts[index] as i32 * PieceKind::BASE_VALUES[index];
+            index += 1;
+        }
+        result
+    }
+}
+
+// The number of piece kinds whose base values are smaller than that of `piece_kind`. It is at most 13.
+const fn value_rank(piece_kind: PieceKind) -> u16 {
+    let value = piece_kind.base_value();
+    let mut result = 0;
+    let mut index = 0;
+    while index < PieceKind::NUM {
+        if PieceKind::BASE_VALUES[index] < value {
+            result += 1;
+        }
+        index += 1;
+    }
+    result
+}
+
+/// Move ordering.
+#[cfg_attr(docsrs, doc(cfg(feature = "material")))]
+impl CompactMove {
+    /// Returns a key for ordering moves in `position` in the MVV-LVA (most valuable victim, least valuable attacker) manner.
+    /// Moves with larger keys should be searched first.
+    ///
+    /// Keys compare, in this order of priority:
+    /// 1. the value of the captured piece, where a capture always beats a non-capture;
+    /// 2. whether the move promotes;
+    /// 3. the value of the moving piece, where cheaper pieces come first.
+    ///
+    /// Values are compared by [`PieceKind::base_value`]. Legality of `self` is not checked.
+    /// If `self` does not make sense in `position`, the result is unspecified, but this function does not panic.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{CompactMove, PartialPosition, Piece, Square};
+    /// let position = PartialPosition::from_sfen("4k4/9/4r4/9/4B4/5P3/9/9/4K4 b G 1").unwrap();
+    /// let bishop_takes_rook = CompactMove::normal(Square::SQ_5E, Square::SQ_5C, false);
+    /// let bishop_promotes = CompactMove::normal(Square::SQ_5E, Square::SQ_3C, true);
+    /// let pawn_push = CompactMove::normal(Square::SQ_4F, Square::SQ_4E, false);
+    /// let gold_drop = CompactMove::drop(Piece::B_G, Square::SQ_5B);
+    /// let mut moves = [gold_drop, pawn_push, bishop_promotes, bishop_takes_rook];
+    /// moves.sort_by_key(|mv| core::cmp::Reverse(mv.ordering_key(&position)));
+    /// assert_eq!(moves, [bishop_takes_rook, bishop_promotes, pawn_push, gold_drop]);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn ordering_key(self, position: &PartialPosition) -> u16 {
+        let mv: Move = self.into();
+        let (attacker, victim, promote) = match mv {
+            Move::Normal { from, to, promote } => {
+                (position.piece_at(from), position.piece_at(to), promote)
+            }
+            Move::Drop { piece, .. } => (Some(piece), None, false),
+        };
+        let victim = victim.map_or(0, |piece| value_rank(piece.piece_kind()) + 1);
+        let attacker = attacker.map_or(0, |piece| value_rank(piece.piece_kind()));
+        victim << 8 | (promote as u16) << 4 | (15 - attacker)
+    }
+
+    /// C interface of [`CompactMove::ordering_key`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn CompactMove_ordering_key(self, position: &PartialPosition) -> u16 {
+        self.ordering_key(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_rank_works() {
+        for a in PieceKind::all() {
+            for b in PieceKind::all() {
+                assert_eq!(
+                    value_rank(a).cmp(&value_rank(b)),
+                    a.base_value().cmp(&b.base_value()),
+                );
+            }
+        }
+        assert_eq!(value_rank(PieceKind::Pawn), 0);
+        assert_eq!(value_rank(PieceKind::King), 13);
+    }
+
+    #[test]
+    fn material_values_work() {
+        for piece_kind in PieceKind::all() {
+            let piece = Piece::new(piece_kind, Color::Black);
+            assert_eq!(piece.signed_value(), piece_kind.base_value());
+            assert_eq!(
+                Piece::new(piece_kind, Color::White).signed_value(),
+                -piece_kind.base_value(),
+            );
+            if let Some(value) = piece_kind.hand_value() {
+                let hand = Hand::new().added(piece_kind).unwrap();
+                assert_eq!(hand.material_value(), value);
+            }
+        }
+        for (unpromoted, promoted) in PieceKind::promotion_pairs() {
+            assert!(promoted.base_value() >= unpromoted.base_value());
+        }
+        // 40 pieces of the starting position except kings
+        let total: i32 = PieceKind::all()
+            .into_iter()
+            .filter_map(PieceKind::hand_value)
+            .zip(Hand::MAX_COUNTS)
+            .map(|(value, count)| value * count as i32)
+            .sum();
+        assert!(PieceKind::King.base_value() > total);
+        let points: u8 = PieceKind::all()
+            .into_iter()
+            .filter(|piece_kind| piece_kind.hand_value().is_some())
+            .zip(Hand::MAX_COUNTS)
+            .map(|(piece_kind, count)| piece_kind.impasse_points() * count)
+            .sum();
+        assert_eq!(points, 54);
+    }
+}
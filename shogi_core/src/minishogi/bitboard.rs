@@ -0,0 +1,182 @@
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use super::Square55;
+
+/// A subset of all squares of a minishogi board.
+///
+/// Because [`Bitboard55`] is cheap to copy, it implements [`Copy`].
+/// Its [`Default`] value is an empty instance.
+#[repr(transparent)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+// Valid representation: self.0 >> 25 must be equal to 0.
+pub struct Bitboard55(u32);
+
+impl Bitboard55 {
+    const MASK: u32 = (1 << 25) - 1;
+
+    /// Creates an empty [`Bitboard55`].
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::Bitboard55;
+    /// assert_eq!(Bitboard55::empty().count(), 0);
+    /// ```
+    #[inline(always)]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Creates a [`Bitboard55`] with a single element.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::{Bitboard55, Square55};
+    /// let sq11 = Bitboard55::single(Square55::new(1, 1).unwrap());
+    /// assert_eq!(sq11.count(), 1);
+    /// ```
+    #[inline(always)]
+    pub const fn single(square: Square55) -> Self {
+        Self(1 << square.array_index())
+    }
+
+    /// Finds how many elements this [`Bitboard55`] has.
+    #[inline(always)]
+    pub const fn count(self) -> u8 {
+        self.0.count_ones() as u8
+    }
+
+    /// Checks if `self` is an empty set.
+    #[inline(always)]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Finds if `self` as a subset contains a [`Square55`].
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::{Bitboard55, Square55};
+    /// let sq11 = Square55::new(1, 1).unwrap();
+    /// assert!(Bitboard55::single(sq11).contains(sq11));
+    /// assert!(!Bitboard55::single(sq11).contains(sq11.flip()));
+    /// ```
+    #[inline(always)]
+    pub const fn contains(self, square: Square55) -> bool {
+        self.0 & 1 << square.array_index() != 0
+    }
+
+    /// Finds the flipped version of `self`.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::{Bitboard55, Square55};
+    /// let sq11 = Square55::new(1, 1).unwrap();
+    /// assert_eq!(Bitboard55::single(sq11).flip(), Bitboard55::single(sq11.flip()));
+    /// ```
+    #[inline(always)]
+    pub const fn flip(self) -> Self {
+        Self(self.0.reverse_bits() >> 7)
+    }
+
+    /// If `self` is not empty, find a [`Square55`] in `self` and returns it, removing it from `self`.
+    ///
+    /// The returned value is unspecified. It is guaranteed that the returned [`Square55`] is a member of `self`.
+    pub fn pop(&mut self) -> Option<Square55> {
+        if self.0 == 0 {
+            return None;
+        }
+        // Safety: 1 <= trailing_zeros + 1 <= 25 because self.0 >> 25 == 0
+        let square = unsafe { Square55::from_u8_unchecked(self.0.trailing_zeros() as u8 + 1) };
+        self.0 &= self.0 - 1;
+        Some(square)
+    }
+
+    /// Returns the inner representation of `self`.
+    /// The bit `square.array_index()` is set if and only if `square` is a member of `self`.
+    #[inline(always)]
+    pub const fn to_u32(self) -> u32 {
+        self.0
+    }
+
+    /// Creates a [`Bitboard55`] with the given inner representation.
+    ///
+    /// # Safety
+    /// `a >> 25` must be equal to 0.
+    #[inline(always)]
+    pub const unsafe fn from_u32_unchecked(a: u32) -> Self {
+        Self(a)
+    }
+}
+
+impl Iterator for Bitboard55 {
+    type Item = Square55;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.count() as usize;
+        (count, Some(count))
+    }
+}
+
+macro_rules! define_bit_trait {
+    (trait => $trait:ident, assign_trait => $assign_trait:ident, funname => $funname:ident, assign_funname => $assign_funname:ident, op => $op:tt,) => {
+        impl $trait for Bitboard55 {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $funname(self, rhs: Self) -> Self::Output {
+                Self(self.0 $op rhs.0)
+            }
+        }
+        impl $assign_trait for Bitboard55 {
+            #[inline(always)]
+            fn $assign_funname(&mut self, rhs: Self) {
+                *self = *self $op rhs;
+            }
+        }
+        impl $assign_trait<Square55> for Bitboard55 {
+            #[inline(always)]
+            fn $assign_funname(&mut self, rhs: Square55) {
+                *self = *self $op Bitboard55::single(rhs);
+            }
+        }
+    };
+}
+
+define_bit_trait!(
+    trait => BitAnd, assign_trait => BitAndAssign,
+    funname => bitand, assign_funname => bitand_assign,
+    op => &,
+);
+define_bit_trait!(
+    trait => BitOr, assign_trait => BitOrAssign,
+    funname => bitor, assign_funname => bitor_assign,
+    op => |,
+);
+define_bit_trait!(
+    trait => BitXor, assign_trait => BitXorAssign,
+    funname => bitxor, assign_funname => bitxor_assign,
+    op => ^,
+);
+
+impl Not for Bitboard55 {
+    type Output = Self;
+
+    /// Returns the complementary subset of `self`.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::Bitboard55;
+    /// assert_eq!((!Bitboard55::empty()).count(), 25);
+    /// ```
+    #[inline(always)]
+    fn not(self) -> Self::Output {
+        Self(!self.0 & Self::MASK)
+    }
+}
+
+impl_ord_for_single_field!(Bitboard55);
+impl_hash_for_single_field!(Bitboard55);
@@ -0,0 +1,67 @@
+//! Types for minishogi (5五将棋, *gogo shōgi*), a variant of shogi played on a 5x5 board.
+//!
+//! Each player has a king, a gold, a silver, a bishop, a rook and a pawn. Pieces can be promoted in the farthest rank.
+//! [`Color`], [`PieceKind`], [`Piece`](crate::Piece) and [`Hand`](crate::Hand) are shared with standard shogi.
+//! Lances and knights (and their promoted forms) never appear in minishogi; see [`is_valid_piece_kind`].
+//!
+//! Squares, moves and positions are written in the same way as in USI, e.g. `5e4d`, `P*3c` and `rbsgk/4p/5/P4/KGSBR b - 1`.
+
+use crate::{Color, PieceKind};
+
+mod bitboard;
+mod mv;
+mod position;
+mod square;
+
+#[doc(inline)]
+pub use self::bitboard::Bitboard55;
+#[doc(inline)]
+pub use self::mv::Move55;
+#[doc(inline)]
+pub use self::position::PartialPosition55;
+#[doc(inline)]
+pub use self::square::Square55;
+
+/// The number of files and ranks of a minishogi board.
+// Not a part of the C API. The attribute keeps it out of the C header, which is generated with `c-exports` enabled.
+#[cfg_attr(feature = "c-exports", doc = "cbindgen:ignore")]
+pub const BOARD_SIZE: u8 = 5;
+
+/// Piece kinds that appear in minishogi, in the ascending order of their discriminants.
+pub const PIECE_KINDS: [PieceKind; 10] = [
+    PieceKind::Pawn,
+    PieceKind::Silver,
+    PieceKind::Gold,
+    PieceKind::Bishop,
+    PieceKind::Rook,
+    PieceKind::King,
+    PieceKind::ProPawn,
+    PieceKind::ProSilver,
+    PieceKind::ProBishop,
+    PieceKind::ProRook,
+];
+
+/// Checks if `piece_kind` appears in minishogi.
+///
+/// Examples:
+/// ```
+/// use shogi_core::minishogi::is_valid_piece_kind;
+/// use shogi_core::PieceKind;
+/// assert!(is_valid_piece_kind(PieceKind::Silver));
+/// assert!(!is_valid_piece_kind(PieceKind::Lance));
+/// assert!(!is_valid_piece_kind(PieceKind::ProKnight));
+/// ```
+pub const fn is_valid_piece_kind(piece_kind: PieceKind) -> bool {
+    !matches!(
+        piece_kind,
+        PieceKind::Lance | PieceKind::Knight | PieceKind::ProLance | PieceKind::ProKnight,
+    )
+}
+
+/// Checks if a piece of `color` on `rank` is in the promotion zone, i.e., the farthest rank from `color`.
+pub(crate) const fn in_promotion_zone(rank: u8, color: Color) -> bool {
+    match color {
+        Color::Black => rank == 1,
+        Color::White => rank == BOARD_SIZE,
+    }
+}
@@ -0,0 +1,145 @@
+use super::Square55;
+use crate::{Piece, ToUsi};
+
+/// A move in minishogi.
+///
+/// Because [`Move55`] is cheap to copy, it implements [`Copy`].
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Move55 {
+    /// A normal move, where a piece on a square is moved to another square.
+    Normal {
+        /// The source square.
+        from: Square55,
+        /// The destination square.
+        to: Square55,
+        /// Whether this piece is promoted.
+        promote: bool,
+    },
+    /// A drop move, where a piece is placed from a player's hand to a vacant square.
+    Drop {
+        /// The kind of piece to be placed.
+        piece: Piece,
+        /// The destination square.
+        to: Square55,
+    },
+}
+
+impl Move55 {
+    /// Finds the `from` square, if it exists.
+    pub fn from(self) -> Option<Square55> {
+        match self {
+            Move55::Normal { from, .. } => Some(from),
+            Move55::Drop { .. } => None,
+        }
+    }
+
+    /// Finds the `to` square.
+    pub fn to(self) -> Square55 {
+        match self {
+            Move55::Normal { to, .. } | Move55::Drop { to, .. } => to,
+        }
+    }
+
+    /// Does this move promote a piece?
+    pub fn is_promoting(self) -> bool {
+        matches!(self, Move55::Normal { promote: true, .. })
+    }
+
+    /// Is this move a drop move?
+    pub fn is_drop(self) -> bool {
+        matches!(self, Move55::Drop { .. })
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::PartialOrd for Move55 {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::Ord for Move55 {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        match (*self, *other) {
+            (Move55::Normal { .. }, Move55::Drop { .. }) => core::cmp::Ordering::Less,
+            (Move55::Drop { .. }, Move55::Normal { .. }) => core::cmp::Ordering::Greater,
+            (
+                Move55::Normal {
+                    from: from1,
+                    to: to1,
+                    promote: promote1,
+                },
+                Move55::Normal {
+                    from: from2,
+                    to: to2,
+                    promote: promote2,
+                },
+            ) => (from1, to1, promote1).cmp(&(from2, to2, promote2)),
+            (
+                Move55::Drop {
+                    piece: piece1,
+                    to: to1,
+                },
+                Move55::Drop {
+                    piece: piece2,
+                    to: to2,
+                },
+            ) => (piece1, to1).cmp(&(piece2, to2)),
+        }
+    }
+}
+
+#[cfg(feature = "hash")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hash")))]
+impl core::hash::Hash for Move55 {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        match *self {
+            Move55::Normal { from, to, promote } => {
+                from.hash(state);
+                to.hash(state);
+                promote.hash(state);
+            }
+            Move55::Drop { piece, to } => {
+                piece.hash(state);
+                to.hash(state);
+            }
+        }
+    }
+}
+
+/// USI representation of a move, e.g. `5e4d`, `2b1a+` or `P*3c`.
+///
+/// Examples:
+/// ```
+/// use shogi_core::minishogi::{Move55, Square55};
+/// use shogi_core::{Piece, ToUsi};
+/// let mv = Move55::Normal { from: Square55::new(2, 2).unwrap(), to: Square55::new(1, 1).unwrap(), promote: true };
+/// assert_eq!(mv.to_usi_owned(), "2b1a+");
+/// let mv = Move55::Drop { piece: Piece::W_P, to: Square55::new(3, 3).unwrap() };
+/// assert_eq!(mv.to_usi_owned(), "P*3c");
+/// ```
+impl ToUsi for Move55 {
+    fn to_usi<W: core::fmt::Write>(&self, sink: &mut W) -> core::fmt::Result {
+        match *self {
+            Move55::Normal { from, to, promote } => {
+                from.to_usi(sink)?;
+                to.to_usi(sink)?;
+                if promote {
+                    // Safety: b'+' is an ASCII byte
+                    unsafe { crate::common::write_ascii_byte(sink, b'+') }?;
+                }
+            }
+            Move55::Drop { piece, to } => {
+                piece.piece_kind().to_usi(sink)?;
+                // Safety: b'*' is an ASCII byte
+                unsafe { crate::common::write_ascii_byte(sink, b'*') }?;
+                to.to_usi(sink)?;
+            }
+        }
+        Ok(())
+    }
+}
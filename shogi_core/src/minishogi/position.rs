@@ -0,0 +1,357 @@
+use core::fmt::{Result as FmtResult, Write};
+
+use super::{in_promotion_zone, is_valid_piece_kind, Bitboard55, Move55, Square55};
+use crate::common::{write_ascii_byte, write_u16, write_u8};
+use crate::{Color, Hand, Piece, PieceKind, ToUsi};
+
+/// A position of minishogi with its move sequence omitted.
+///
+/// This type is the minishogi counterpart of [`PartialPosition`](crate::PartialPosition).
+/// Like it, this type does not check legality of moves.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct PartialPosition55 {
+    side: Color,
+    ply: u16,
+    hands: [Hand; 2],
+    board: [Option<Piece>; 25],
+    player_bb: [Bitboard55; 2],
+    piece_bb: [Bitboard55; PieceKind::NUM],
+    last_move: Option<Move55>,
+}
+
+impl PartialPosition55 {
+    /// Returns an empty position, where no pieces are on the board or in hand.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::PartialPosition55;
+    /// let position = PartialPosition55::empty();
+    /// assert_eq!(position.to_sfen_owned(), "5/5/5/5/5 b - 1");
+    /// ```
+    pub fn empty() -> Self {
+        Self {
+            side: Color::Black,
+            ply: 1,
+            hands: [Hand::new(); 2],
+            board: [None; 25],
+            player_bb: [Bitboard55::empty(); 2],
+            piece_bb: [Bitboard55::empty(); PieceKind::NUM],
+            last_move: None,
+        }
+    }
+
+    /// Returns the starting position of minishogi.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::PartialPosition55;
+    /// let position = PartialPosition55::startpos();
+    /// assert_eq!(position.to_sfen_owned(), "rbsgk/4p/5/P4/KGSBR b - 1");
+    /// ```
+    pub fn startpos() -> Self {
+        let mut result = Self::empty();
+        let order = [
+            PieceKind::Rook,
+            PieceKind::Bishop,
+            PieceKind::Silver,
+            PieceKind::Gold,
+            PieceKind::King,
+        ];
+        for (file, &piece_kind) in (1..=5).zip(order.iter()) {
+            // Safety: 1 <= file <= 5 and 1 <= rank <= 5
+            let square = unsafe { crate::common::unwrap_unchecked(Square55::new(file, 5)) };
+            result.piece_set(square, Some(Piece::new(piece_kind, Color::Black)));
+            result.piece_set(square.flip(), Some(Piece::new(piece_kind, Color::White)));
+        }
+        // Safety: 1 <= file <= 5 and 1 <= rank <= 5
+        let pawn = unsafe { crate::common::unwrap_unchecked(Square55::new(5, 4)) };
+        result.piece_set(pawn, Some(Piece::B_P));
+        result.piece_set(pawn.flip(), Some(Piece::W_P));
+        result
+    }
+
+    /// Returns the side to move.
+    #[inline(always)]
+    pub fn side_to_move(&self) -> Color {
+        self.side
+    }
+
+    /// Sets the side to move.
+    #[inline(always)]
+    pub fn side_to_move_set(&mut self, side: Color) {
+        self.side = side;
+    }
+
+    /// Returns the [`Hand`] of a player.
+    #[inline(always)]
+    pub fn hand_of_a_player(&self, color: Color) -> Hand {
+        self.hands[color.array_index()]
+    }
+
+    /// Returns a mutable reference to the [`Hand`] of a player.
+    #[inline(always)]
+    pub fn hand_of_a_player_mut(&mut self, color: Color) -> &mut Hand {
+        &mut self.hands[color.array_index()]
+    }
+
+    /// Returns the number of pieces in hand.
+    pub fn hand(&self, piece: Piece) -> Option<u8> {
+        self.hand_of_a_player(piece.color())
+            .count(piece.piece_kind())
+    }
+
+    /// Returns the current ply. The starting position has ply 1.
+    #[inline(always)]
+    pub fn ply(&self) -> u16 {
+        self.ply
+    }
+
+    /// Sets the current ply. Returns `false` and does nothing if `ply` is 0.
+    #[must_use]
+    pub fn ply_set(&mut self, ply: u16) -> bool {
+        if ply == 0 {
+            return false;
+        }
+        self.ply = ply;
+        true
+    }
+
+    /// Finds the piece on `square`, if any.
+    #[inline(always)]
+    pub fn piece_at(&self, square: Square55) -> Option<Piece> {
+        self.board[square.array_index()]
+    }
+
+    /// Places or removes a piece on `square`.
+    ///
+    /// `piece` should be a piece that appears in minishogi (see [`is_valid_piece_kind`]);
+    /// otherwise the position will not make sense as a minishogi position.
+    pub fn piece_set(&mut self, square: Square55, piece: Option<Piece>) {
+        debug_assert!(piece.map_or(true, |piece| is_valid_piece_kind(piece.piece_kind())));
+        let single = Bitboard55::single(square);
+        if let Some(old) = self.board[square.array_index()] {
+            let (piece_kind, color) = old.to_parts();
+            self.player_bb[color.array_index()] ^= single;
+            self.piece_bb[piece_kind.array_index()] ^= single;
+        }
+        if let Some(piece) = piece {
+            let (piece_kind, color) = piece.to_parts();
+            self.player_bb[color.array_index()] |= single;
+            self.piece_bb[piece_kind.array_index()] |= single;
+        }
+        self.board[square.array_index()] = piece;
+    }
+
+    /// Returns the subset of squares where a piece exists.
+    #[inline(always)]
+    pub fn occupied_bitboard(&self) -> Bitboard55 {
+        self.player_bb[0] | self.player_bb[1]
+    }
+
+    /// Returns the subset of squares where no pieces exist.
+    #[inline(always)]
+    pub fn vacant_bitboard(&self) -> Bitboard55 {
+        !self.occupied_bitboard()
+    }
+
+    /// Returns the subset of squares where `color`'s pieces exist.
+    #[inline(always)]
+    pub fn player_bitboard(&self, color: Color) -> Bitboard55 {
+        self.player_bb[color.array_index()]
+    }
+
+    /// Returns the subset of squares where `piece` exists.
+    #[inline(always)]
+    pub fn piece_bitboard(&self, piece: Piece) -> Bitboard55 {
+        let (piece_kind, color) = piece.to_parts();
+        self.piece_bb[piece_kind.array_index()] & self.player_bb[color.array_index()]
+    }
+
+    /// Returns the square where `color`'s king is, if any.
+    pub fn king_position(&self, color: Color) -> Option<Square55> {
+        self.piece_bitboard(Piece::new(PieceKind::King, color))
+            .pop()
+    }
+
+    /// Returns the last move, if it exists.
+    #[inline(always)]
+    pub fn last_move(&self) -> Option<Move55> {
+        self.last_move
+    }
+
+    /// Makes a move. Note that this function will never check legality.
+    ///
+    /// Returns Some(()) if the given move makes sense, i.e.,
+    /// moves a piece to another square or drops a piece on a vacant square.
+    /// In addition, a piece can be promoted only if the move starts or ends in the promotion zone, the farthest rank.
+    ///
+    /// If it returns None, it is guaranteed that self is not modified.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::{Move55, PartialPosition55, Square55};
+    /// let mut position = PartialPosition55::startpos();
+    /// let mv = Move55::Normal {
+    ///     from: Square55::new(5, 4).unwrap(),
+    ///     to: Square55::new(5, 3).unwrap(),
+    ///     promote: false,
+    /// };
+    /// assert_eq!(position.make_move(mv), Some(()));
+    /// assert_eq!(position.to_sfen_owned(), "rbsgk/4p/P4/5/KGSBR w - 2");
+    /// ```
+    pub fn make_move(&mut self, mv: Move55) -> Option<()> {
+        let color = self.side;
+        match mv {
+            Move55::Normal { from, to, promote } => {
+                let piece = self.piece_at(from)?;
+                if piece.color() != color {
+                    return None;
+                }
+                let target_piece = if promote {
+                    if !in_promotion_zone(from.rank(), color)
+                        && !in_promotion_zone(to.rank(), color)
+                    {
+                        return None;
+                    }
+                    piece.promote()?
+                } else {
+                    piece
+                };
+                if let Some(enemy) = self.piece_at(to) {
+                    if piece.color() == enemy.color() {
+                        return None;
+                    }
+                    let hand = self.hand_of_a_player_mut(color);
+                    *hand = hand.added(enemy.piece_kind().unpromoted_or_self())?;
+                }
+                self.piece_set(from, None);
+                self.piece_set(to, Some(target_piece));
+            }
+            Move55::Drop { piece, to } => {
+                if piece.color() != color {
+                    return None;
+                }
+                if !is_valid_piece_kind(piece.piece_kind()) || piece.unpromote().is_some() {
+                    return None;
+                }
+                if self.piece_at(to).is_some() {
+                    return None;
+                }
+                let hand = self.hand_of_a_player_mut(color);
+                *hand = hand.removed(piece.piece_kind())?;
+                self.piece_set(to, Some(piece));
+            }
+        }
+        self.last_move = Some(mv);
+        self.side = self.side.flip();
+        self.ply = self.ply.wrapping_add(1);
+        Some(())
+    }
+
+    /// Write the current position in SFEN notation.
+    pub fn to_sfen<W: Write>(&self, sink: &mut W) -> FmtResult {
+        for rank in 1..=5 {
+            let mut vacant = 0;
+            for file in (1..=5).rev() {
+                // Safety: 1 <= file <= 5 and 1 <= rank <= 5
+                let square = unsafe { crate::common::unwrap_unchecked(Square55::new(file, rank)) };
+                if let Some(occupying) = self.piece_at(square) {
+                    if vacant > 0 {
+                        write_u8(sink, vacant)?;
+                        vacant = 0;
+                    }
+                    occupying.to_usi(sink)?;
+                } else {
+                    vacant += 1;
+                }
+            }
+            if vacant > 0 {
+                write_u8(sink, vacant)?;
+            }
+            if rank < 5 {
+                // Safety: '/' is in ASCII
+                unsafe { write_ascii_byte(sink, b'/') }?;
+            }
+        }
+        // Safety: ' ' is in ASCII
+        unsafe { write_ascii_byte(sink, b' ') }?;
+        self.side.to_usi(sink)?;
+        // Safety: ' ' is in ASCII
+        unsafe { write_ascii_byte(sink, b' ') }?;
+        self.hands.to_usi(sink)?;
+        // Safety: ' ' is in ASCII
+        unsafe { write_ascii_byte(sink, b' ') }?;
+        write_u16(sink, self.ply)?;
+        Ok(())
+    }
+
+    /// Returns the SFEN representation of the current position.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn to_sfen_owned(&self) -> alloc::string::String {
+        let mut s = alloc::string::String::new();
+        let _ = self.to_sfen(&mut s); // Cannot fail
+        s
+    }
+}
+
+impl_ord_with_fields!(PartialPosition55; side, ply, hands, board, last_move);
+impl_hash_with_fields!(PartialPosition55; side, ply, hands, board, last_move);
+
+impl Default for PartialPosition55 {
+    fn default() -> Self {
+        Self::startpos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sq(file: u8, rank: u8) -> Square55 {
+        Square55::new(file, rank).unwrap()
+    }
+
+    fn normal(from: (u8, u8), to: (u8, u8), promote: bool) -> Move55 {
+        Move55::Normal {
+            from: sq(from.0, from.1),
+            to: sq(to.0, to.1),
+            promote,
+        }
+    }
+
+    #[test]
+    fn make_move_works() {
+        let mut position = PartialPosition55::startpos();
+        assert_eq!(position.king_position(Color::Black), Some(sq(5, 5)));
+        assert_eq!(position.king_position(Color::White), Some(sq(1, 1)));
+        assert_eq!(position.occupied_bitboard().count(), 12);
+
+        assert_eq!(position.make_move(normal((5, 4), (5, 3), false)), Some(()));
+        assert_eq!(position.make_move(normal((1, 2), (1, 3), false)), Some(()));
+        // 5b is not in the promotion zone.
+        assert_eq!(position.make_move(normal((5, 3), (5, 2), true)), None);
+        assert_eq!(position.make_move(normal((5, 3), (5, 2), false)), Some(()));
+        assert_eq!(position.make_move(normal((4, 1), (5, 2), true)), None);
+        assert_eq!(position.make_move(normal((4, 1), (5, 2), false)), Some(()));
+        assert_eq!(position.hand(Piece::W_P), Some(1));
+        assert_eq!(position.make_move(normal((2, 5), (5, 2), false)), Some(()));
+        assert_eq!(position.hand(Piece::B_B), Some(1));
+        // Black has no pawns in hand, and it is white's turn.
+        let drop = Move55::Drop {
+            piece: Piece::B_P,
+            to: sq(5, 3),
+        };
+        assert_eq!(position.make_move(drop), None);
+        let drop = Move55::Drop {
+            piece: Piece::W_P,
+            to: sq(5, 3),
+        };
+        assert_eq!(position.make_move(drop), Some(()));
+        // Moves into the promotion zone can promote.
+        assert_eq!(position.make_move(normal((5, 2), (4, 1), true)), Some(()));
+        assert_eq!(position.piece_at(sq(4, 1)), Some(Piece::B_PB));
+        assert_eq!(position.last_move(), Some(normal((5, 2), (4, 1), true)));
+        assert_eq!(position.to_sfen_owned(), "r+Bsgk/5/p3p/5/KGS1R w B 8");
+    }
+}
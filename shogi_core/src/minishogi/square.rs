@@ -0,0 +1,208 @@
+use core::num::NonZeroU8;
+
+use crate::{common, Color, ToUsi};
+
+/// A square of a minishogi board.
+///
+/// [`Square55`] and <code>[Option]<[Square55]></code> are both 1-byte data types.
+/// Because they are cheap to copy, they implement [`Copy`].
+#[repr(transparent)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Square55(NonZeroU8);
+
+impl Square55 {
+    /// Creates a new [`Square55`] with given `file` and `rank`.
+    ///
+    /// `file` and `rank` must be between 1 and 5 (both inclusive).
+    /// If this condition is not met, this function returns None.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::Square55;
+    /// assert_eq!(Square55::new(3, 4).unwrap().index(), 14);
+    /// assert_eq!(Square55::new(6, 4), None);
+    /// ```
+    #[inline(always)]
+    pub const fn new(file: u8, rank: u8) -> Option<Self> {
+        if file.wrapping_sub(1) >= 5 || rank.wrapping_sub(1) >= 5 {
+            return None;
+        }
+        // Safety: file >= 1 && rank >= 1 implies file * 5 + rank - 5 >= 1
+        Some(Square55(unsafe {
+            crate::common::nonzero_u8_unchecked(file * 5 + rank - 5)
+        }))
+    }
+
+    /// Finds the file in range `1..=5`.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::Square55;
+    /// assert_eq!(Square55::new(3, 4).unwrap().file(), 3);
+    /// ```
+    #[inline(always)]
+    pub const fn file(self) -> u8 {
+        (self.0.get() + 4) / 5
+    }
+
+    /// Finds the rank in range `1..=5`.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::Square55;
+    /// assert_eq!(Square55::new(3, 4).unwrap().rank(), 4);
+    /// ```
+    #[inline(always)]
+    pub const fn rank(self) -> u8 {
+        self.0.get() + 5 - 5 * self.file()
+    }
+
+    /// Finds the index of `self` in range `1..=25`.
+    /// It is guaranteed that the result is equal to the internal representation, `5 * file + rank - 5`.
+    #[inline(always)]
+    pub const fn index(self) -> u8 {
+        self.0.get()
+    }
+
+    /// Finds the rank from the perspective of `color`.
+    pub const fn relative_rank(self, color: Color) -> u8 {
+        let rank = self.rank();
+        match color {
+            Color::Black => rank,
+            Color::White => 6 - rank,
+        }
+    }
+
+    /// Finds the file from the perspective of `color`.
+    pub const fn relative_file(self, color: Color) -> u8 {
+        let file = self.file();
+        match color {
+            Color::Black => file,
+            Color::White => 6 - file,
+        }
+    }
+
+    /// Finds the reflected square of `self`.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::Square55;
+    /// assert_eq!(Square55::new(1, 1).unwrap().flip(), Square55::new(5, 5).unwrap());
+    /// assert_eq!(Square55::new(2, 4).unwrap().flip(), Square55::new(4, 2).unwrap());
+    /// ```
+    #[inline(always)]
+    pub const fn flip(self) -> Self {
+        // Safety: self.0.get() is in range 1..=25.
+        unsafe { Self::from_u8_unchecked(26 - self.0.get()) }
+    }
+
+    /// Converts a [`u8`] to a [`Square55`]. If `value` is not in range `1..=25`, this function returns [`None`].
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::Square55;
+    /// assert_eq!(Square55::from_u8(13), Square55::new(3, 3));
+    /// assert_eq!(Square55::from_u8(0), None);
+    /// assert_eq!(Square55::from_u8(26), None);
+    /// ```
+    #[inline]
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        if matches!(value, 1..=25) {
+            // Safety: `value` is in `1..=25`.
+            Some(unsafe { Self::from_u8_unchecked(value) })
+        } else {
+            None
+        }
+    }
+
+    /// Converts [`u8`] to [`Square55`] without checking.
+    ///
+    /// # Safety
+    /// `value` must be in range 1..=25
+    #[inline(always)]
+    pub const unsafe fn from_u8_unchecked(value: u8) -> Self {
+        if !matches!(value, 1..=25) {
+            crate::common::unreachable_unchecked();
+        }
+        Self(crate::common::nonzero_u8_unchecked(value))
+    }
+
+    /// Shifts `self` by the given arguments. If the result would be out of the board, this function returns [`None`].
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::Square55;
+    /// let sq33 = Square55::new(3, 3).unwrap();
+    /// assert_eq!(sq33.shift(-1, 2), Square55::new(2, 5));
+    /// assert_eq!(sq33.shift(0, -3), None);
+    /// ```
+    pub fn shift(self, file_delta: i8, rank_delta: i8) -> Option<Self> {
+        let file = (self.file() as i8).wrapping_add(file_delta);
+        let rank = (self.rank() as i8).wrapping_add(rank_delta);
+        if !matches!(file, 1..=5) || !matches!(rank, 1..=5) {
+            return None;
+        }
+        Self::new(file as u8, rank as u8)
+    }
+
+    /// Returns the index of `self` for array accesses. This function returns an integer in range `0..Square55::NUM`.
+    #[inline(always)]
+    pub const fn array_index(self) -> usize {
+        (self.0.get() - 1) as usize
+    }
+
+    /// How many elements should an array indexed by [`Square55`] have?
+    pub const NUM: usize = 25;
+
+    /// Returns an iterator that iterates over all possible [`Square55`]s
+    /// in the ascending order of their indices.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::minishogi::Square55;
+    /// assert_eq!(Square55::all().count(), 25);
+    /// ```
+    pub fn all() -> impl core::iter::Iterator<Item = Self> {
+        (1..=25).map(|index| unsafe { Self::from_u8_unchecked(index) })
+    }
+}
+
+impl_ord_for_single_field!(Square55);
+impl_hash_for_single_field!(Square55);
+
+/// USI representation of a square, e.g. `5e`.
+///
+/// Examples:
+/// ```
+/// use shogi_core::minishogi::Square55;
+/// use shogi_core::ToUsi;
+/// assert_eq!(Square55::new(5, 5).unwrap().to_usi_owned(), "5e");
+/// ```
+impl ToUsi for Square55 {
+    fn to_usi<W: core::fmt::Write>(&self, sink: &mut W) -> core::fmt::Result {
+        // Safety: '1'..='5' is always an ASCII byte
+        unsafe { common::write_ascii_byte(sink, b'0' + self.file()) }?;
+        // Safety: 'a'..='e' is always an ASCII byte
+        unsafe { common::write_ascii_byte(sink, b'a' + self.rank() - 1) }?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_rank_works() {
+        for file in 1..=5 {
+            for rank in 1..=5 {
+                let square = Square55::new(file, rank).unwrap();
+                assert_eq!((square.file(), square.rank()), (file, rank));
+                assert_eq!(Square55::from_u8(square.index()), Some(square));
+                assert_eq!(square.flip().flip(), square);
+                assert_eq!(square.flip().relative_rank(Color::White), rank);
+            }
+        }
+        assert_eq!(Square55::all().last().unwrap().index(), 25);
+    }
+}
@@ -0,0 +1,163 @@
+use core::fmt::{Result as FmtResult, Write};
+
+use crate::common::write_i32;
+
+/// An evaluation of a move, as given in annotated game records and analyses by GUIs.
+///
+/// Annotations of moves in a game are stored in [`Game`](crate::Game); see [`Game::annotation`](crate::Game::annotation).
+///
+/// Because [`MoveAnnotation`] is cheap to copy, it implements [`Copy`].
+///
+/// Since: 0.1.6
+#[repr(C, u8)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum MoveAnnotation {
+    /// A brilliant move (`!!`, 妙手).
+    Brilliant,
+    /// A good move (`!`, 好手).
+    Good,
+    /// A dubious move (`?!`, 疑問手).
+    Dubious,
+    /// A mistake (`?`, 悪手).
+    Mistake,
+    /// A blunder (`??`, 大悪手).
+    Blunder,
+    /// The evaluation of the position after the move by an engine, in centipawns.
+    /// Positive values are good for black, as in analyses by Kifu for Windows and ShogiGUI.
+    Score(i32),
+}
+
+impl MoveAnnotation {
+    /// Reads an annotation from a comment of a game record, without the leading marker (`*` in KIF).
+    ///
+    /// The following comments are recognized:
+    /// - the symbols `!!`, `!`, `?!`, `?` and `??`, possibly surrounded by whitespace;
+    /// - analyses that contain `評価値 <score>`, e.g. `*解析 0 ○ 候補1 時間 00:01.0 深さ 20/35 評価値 120 読み筋 ▲２六歩`.
+    ///
+    /// Returns [`None`] if `comment` is not an annotation.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::MoveAnnotation;
+    /// assert_eq!(MoveAnnotation::from_comment("?!"), Some(MoveAnnotation::Dubious));
+    /// assert_eq!(
+    ///     MoveAnnotation::from_comment("*解析 0 ○ 候補1 時間 00:01.0 深さ 20/35 評価値 -120 読み筋 ▲２六歩"),
+    ///     Some(MoveAnnotation::Score(-120)),
+    /// );
+    /// assert_eq!(MoveAnnotation::from_comment("Opening the bishop's diagonal"), None);
+    /// ```
+    pub fn from_comment(comment: &str) -> Option<Self> {
+        let comment = comment.trim();
+        let annotation = match comment {
+            "!!" => MoveAnnotation::Brilliant,
+            "!" => MoveAnnotation::Good,
+            "?!" => MoveAnnotation::Dubious,
+            "?" => MoveAnnotation::Mistake,
+            "??" => MoveAnnotation::Blunder,
+            _ => {
+                let (_, rest) = comment.split_once("評価値")?;
+                let score = rest.split_whitespace().next()?;
+                MoveAnnotation::Score(score.parse().ok()?)
+            }
+        };
+        Some(annotation)
+    }
+
+    /// Writes `self` as a comment that [`MoveAnnotation::from_comment`] reads back,
+    /// e.g. `!!` or `評価値 120`. The leading marker is not written.
+    ///
+    /// This function returns Err(core::fmt::Error) if and only if it fails to write to `sink`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::MoveAnnotation;
+    /// let mut s = String::new();
+    /// MoveAnnotation::Score(-45).to_comment(&mut s).unwrap();
+    /// assert_eq!(s, "評価値 -45");
+    /// ```
+    pub fn to_comment<W: Write>(self, sink: &mut W) -> FmtResult {
+        match self {
+            MoveAnnotation::Brilliant => sink.write_str("!!"),
+            MoveAnnotation::Good => sink.write_str("!"),
+            MoveAnnotation::Dubious => sink.write_str("?!"),
+            MoveAnnotation::Mistake => sink.write_str("?"),
+            MoveAnnotation::Blunder => sink.write_str("??"),
+            MoveAnnotation::Score(score) => {
+                sink.write_str("評価値 ")?;
+                write_i32(sink, score)
+            }
+        }
+    }
+
+    // The variants in the order of declaration, followed by the score.
+    #[cfg(any(feature = "ord", feature = "hash"))]
+    fn key(self) -> (u8, i32) {
+        match self {
+            MoveAnnotation::Brilliant => (0, 0),
+            MoveAnnotation::Good => (1, 0),
+            MoveAnnotation::Dubious => (2, 0),
+            MoveAnnotation::Mistake => (3, 0),
+            MoveAnnotation::Blunder => (4, 0),
+            MoveAnnotation::Score(score) => (5, score),
+        }
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl PartialOrd for MoveAnnotation {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl Ord for MoveAnnotation {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+#[cfg(feature = "hash")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hash")))]
+impl core::hash::Hash for MoveAnnotation {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl_rkyv_as_self!(MoveAnnotation);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn comment_round_trip_works() {
+        let annotations = [
+            MoveAnnotation::Brilliant,
+            MoveAnnotation::Good,
+            MoveAnnotation::Dubious,
+            MoveAnnotation::Mistake,
+            MoveAnnotation::Blunder,
+            MoveAnnotation::Score(0),
+            MoveAnnotation::Score(i32::MIN),
+            MoveAnnotation::Score(31111),
+        ];
+        for annotation in annotations {
+            let mut comment = alloc::string::String::new();
+            annotation.to_comment(&mut comment).unwrap();
+            assert_eq!(MoveAnnotation::from_comment(&comment), Some(annotation));
+        }
+        assert_eq!(
+            MoveAnnotation::from_comment(" !! "),
+            Some(MoveAnnotation::Brilliant)
+        );
+        assert_eq!(MoveAnnotation::from_comment("!!!"), None);
+        assert_eq!(MoveAnnotation::from_comment("評価値"), None);
+        assert_eq!(MoveAnnotation::from_comment("評価値 +-3"), None);
+    }
+}
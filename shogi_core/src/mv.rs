@@ -1,11 +1,14 @@
 use core::num::NonZeroU16;
 
-use crate::{c_compat::OptionSquare, Piece, Square, ToUsi};
+use crate::c_compat::{OptionPiece, OptionSquare};
+use crate::common::{c_bytes, write_nul_terminated};
+use crate::{Color, Piece, PieceKind, Square, ToUsi};
 
 /// A move.
 ///
 /// Because [`Move`] is cheap to copy, it implements [`Copy`].
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Move {
     /// A normal move, where a piece on a square is moved to another square.
     /// You can choose to promote a piece if certain conditions are met.
@@ -28,6 +31,35 @@ pub enum Move {
 }
 
 impl Move {
+    /// Creates a normal move. Same as `Move::Normal { from, to, promote }`, but can be passed as a function.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Move, Square};
+    /// const OPENING: [Move; 2] = [
+    ///     Move::normal(Square::SQ_7G, Square::SQ_7F, false),
+    ///     Move::normal(Square::SQ_3C, Square::SQ_3D, false),
+    /// ];
+    /// assert_eq!(OPENING[0], Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false });
+    /// ```
+    #[inline(always)]
+    pub const fn normal(from: Square, to: Square, promote: bool) -> Self {
+        Move::Normal { from, to, promote }
+    }
+
+    /// Creates a drop move. Same as `Move::Drop { piece, to }`, but can be passed as a function.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Move, Piece, Square};
+    /// const DROP: Move = Move::drop(Piece::B_G, Square::SQ_5B);
+    /// assert_eq!(DROP, Move::Drop { piece: Piece::B_G, to: Square::SQ_5B });
+    /// ```
+    #[inline(always)]
+    pub const fn drop(piece: Piece, to: Square) -> Self {
+        Move::Drop { piece, to }
+    }
+
     /// Finds the `from` square, if it exists.
     ///
     /// Examples:
@@ -68,6 +100,141 @@ impl Move {
     pub fn is_drop(self) -> bool {
         matches!(self, Move::Drop { .. })
     }
+
+    /// C interface for creating a normal move.
+    ///
+    /// `from` and `to` are the representations of squares (see [`Square::index`]).
+    /// If either of them is not a valid square, this function returns a [`CMove`] whose fields are all zero,
+    /// which [`CMove::to_move`] rejects.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn Move_normal_c(from: u8, to: u8, promote: bool) -> CMove {
+        match (Square::from_u8(from), Square::from_u8(to)) {
+            (Some(from), Some(to)) => Move::Normal { from, to, promote }.into(),
+            _ => CMove::zeroed(),
+        }
+    }
+
+    /// C interface for creating a drop move.
+    ///
+    /// `piece` and `to` are the representations of a piece and a square (see [`Piece::as_u8`] and [`Square::index`]).
+    /// If either of them is not valid, this function returns a [`CMove`] whose fields are all zero,
+    /// which [`CMove::to_move`] rejects.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn Move_drop_c(piece: u8, to: u8) -> CMove {
+        match (Piece::try_from(piece), Square::from_u8(to)) {
+            (Ok(piece), Some(to)) => Move::Drop { piece, to }.into(),
+            _ => CMove::zeroed(),
+        }
+    }
+
+    /// Parses a move in USI format, e.g. `7g7f`, `8h2b+`, `S*5b`.
+    ///
+    /// Because USI does not tell the color of a dropped piece, `side` must be the player who makes the move.
+    pub(crate) fn parse_usi_bytes(bytes: &[u8], side: Color) -> Option<Self> {
+        Self::try_parse_usi_bytes(bytes, side).ok()
+    }
+
+    /// Parses a move in USI format, e.g. `7g7f`, `8h2b+`, `S*5b`.
+    /// `side` is the player who makes the move, which determines the color of a dropped piece.
+    ///
+    /// If `s` is not a valid move, the returned error tells which part of `s` is wrong.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Move, Piece, Square, UsiMoveParseError};
+    /// assert_eq!(
+    ///     Move::from_usi("8h2b+", Color::Black),
+    ///     Ok(Move::Normal { from: Square::SQ_8H, to: Square::SQ_2B, promote: true }),
+    /// );
+    /// assert_eq!(
+    ///     Move::from_usi("S*5b", Color::White),
+    ///     Ok(Move::Drop { piece: Piece::W_S, to: Square::SQ_5B }),
+    /// );
+    /// assert_eq!(Move::from_usi("7g7j", Color::Black), Err(UsiMoveParseError::InvalidSquare));
+    /// assert_eq!(Move::from_usi("K*5b", Color::Black), Err(UsiMoveParseError::InvalidPiece));
+    /// assert_eq!(Move::from_usi("7g7f=", Color::Black), Err(UsiMoveParseError::InvalidPromotion));
+    /// assert_eq!(Move::from_usi("7g", Color::Black), Err(UsiMoveParseError::Syntax));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn from_usi(s: &str, side: Color) -> Result<Self, UsiMoveParseError> {
+        Self::try_parse_usi_bytes(s.as_bytes(), side)
+    }
+
+    fn try_parse_usi_bytes(bytes: &[u8], side: Color) -> Result<Self, UsiMoveParseError> {
+        let square = |file, rank| {
+            Square::parse_usi_bytes(&[file, rank]).ok_or(UsiMoveParseError::InvalidSquare)
+        };
+        match *bytes {
+            [piece_kind, b'*', file, rank] => {
+                let piece_kind = match PieceKind::parse_usi_byte(piece_kind) {
+                    Some(piece_kind) if piece_kind != PieceKind::King => piece_kind,
+                    _ => return Err(UsiMoveParseError::InvalidPiece),
+                };
+                Ok(Move::Drop {
+                    piece: Piece::new(piece_kind, side),
+                    to: square(file, rank)?,
+                })
+            }
+            [from_file, from_rank, to_file, to_rank, ref rest @ ..] => {
+                let from = square(from_file, from_rank)?;
+                let to = square(to_file, to_rank)?;
+                let promote = match *rest {
+                    [] => false,
+                    [b'+'] => true,
+                    _ => return Err(UsiMoveParseError::InvalidPromotion),
+                };
+                Ok(Move::Normal { from, to, promote })
+            }
+            _ => Err(UsiMoveParseError::Syntax),
+        }
+    }
+
+    /// C interface of [`ToUsi::to_usi`].
+    /// Writes the USI representation of `mv` to `ptr` followed by a nul byte.
+    ///
+    /// Returns the number of bytes written excluding the nul byte.
+    /// If `capacity` is not large enough or `mv` is not a valid move (see [`CMove::to_move`]), this function returns 0.
+    /// A capacity of 6 is always sufficient.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for writes of `capacity` bytes.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn Move_to_usi_c(mv: CMove, ptr: *mut u8, capacity: usize) -> usize {
+        match mv.to_move() {
+            Some(mv) => write_nul_terminated(ptr, capacity, |sink| mv.to_usi(sink)),
+            None => 0,
+        }
+    }
+
+    /// Parses a move in USI format (e.g. `7g7f`, `8h2b+`, `S*5b`) from `len` bytes starting at `ptr`.
+    /// The bytes need not be nul-terminated.
+    /// `side` is the player who makes the move, which determines the color of a dropped piece.
+    ///
+    /// If the bytes are a valid move, this function writes it to `out` and returns `true`.
+    /// Otherwise, this function returns `false` and `out` is left untouched.
+    ///
+    /// # Safety
+    /// `ptr` must be null or valid for reads of `len` bytes. `out` must be valid for writes.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn Move_parse_c(
+        ptr: *const u8,
+        len: usize,
+        side: Color,
+        out: *mut CMove,
+    ) -> bool {
+        match Self::parse_usi_bytes(c_bytes(ptr, len), side) {
+            Some(mv) => {
+                *out = mv.into();
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 #[cfg(feature = "ord")]
@@ -166,6 +333,236 @@ impl ToUsi for Move {
     }
 }
 
+/// Parses a normal move in USI format, e.g. `7g7f` or `8h2b+`.
+///
+/// Drops (e.g. `S*5b`) are rejected with [`UsiMoveParseError::Drop`], because USI does not tell the color of a dropped piece.
+/// Use [`Move::from_usi`] if the player who makes the move is known.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Move, Square, UsiMoveParseError};
+/// assert_eq!(
+///     "8h2b+".parse::<Move>(),
+///     Ok(Move::Normal { from: Square::SQ_8H, to: Square::SQ_2B, promote: true }),
+/// );
+/// assert_eq!("S*5b".parse::<Move>(), Err(UsiMoveParseError::Drop));
+/// assert_eq!("7g7j".parse::<Move>(), Err(UsiMoveParseError::InvalidSquare));
+/// ```
+///
+/// Since: 0.1.6
+impl core::str::FromStr for Move {
+    type Err = UsiMoveParseError;
+
+    fn from_str(s: &str) -> Result<Self, UsiMoveParseError> {
+        // The color is only used for drops, which are rejected below.
+        match Self::from_usi(s, Color::Black)? {
+            Move::Drop { .. } => Err(UsiMoveParseError::Drop),
+            mv => Ok(mv),
+        }
+    }
+}
+
+/// An error returned by [`Move::from_usi`].
+///
+/// Since: 0.1.6
+#[repr(u8)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum UsiMoveParseError {
+    /// The string is neither `<square><square>[+]` nor `<piece>*<square>`.
+    ///
+    /// Discriminant = 1.
+    Syntax = 1,
+    /// A square is not in `1a`..=`9i`.
+    ///
+    /// Discriminant = 2.
+    InvalidSquare = 2,
+    /// The dropped piece is not one of `PLNSGBR`.
+    ///
+    /// Discriminant = 3.
+    InvalidPiece = 3,
+    /// Something other than `+` follows the squares of a normal move.
+    ///
+    /// Discriminant = 4.
+    InvalidPromotion = 4,
+    /// The string is a valid drop, but the color of the dropped piece is unknown.
+    /// Returned only by the [`FromStr`](core::str::FromStr) implementation of [`Move`].
+    ///
+    /// Discriminant = 5.
+    Drop = 5,
+}
+
+impl UsiMoveParseError {
+    /// Returns a short description of `self` in English, e.g. `"invalid square"`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::UsiMoveParseError;
+    /// assert_eq!(UsiMoveParseError::InvalidSquare.description(), "invalid square");
+    /// ```
+    pub const fn description(self) -> &'static str {
+        match self {
+            UsiMoveParseError::Syntax => "invalid USI move",
+            UsiMoveParseError::InvalidSquare => "invalid square",
+            UsiMoveParseError::InvalidPiece => "invalid piece to drop",
+            UsiMoveParseError::InvalidPromotion => "invalid promotion",
+            UsiMoveParseError::Drop => "drop without the side to move",
+        }
+    }
+}
+
+impl_ord_for_fieldless_enum!(UsiMoveParseError);
+impl_hash_for_fieldless_enum!(UsiMoveParseError);
+
+impl core::fmt::Display for UsiMoveParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for UsiMoveParseError {}
+
+/// Kinds of [`CMove`].
+#[repr(u8)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum CMoveKind {
+    /// A normal move. Corresponds to [`Move::Normal`].
+    ///
+    /// Discriminant = 1.
+    Normal = 1,
+    /// A drop move. Corresponds to [`Move::Drop`].
+    ///
+    /// Discriminant = 2.
+    Drop = 2,
+}
+
+impl CMoveKind {
+    /// Converts a [`u8`] to [`CMoveKind`] if possible.
+    ///
+    /// If `repr` is a valid representation of [`CMoveKind`], this function returns `Some(kind)`.
+    /// This condition is equivalent to `1 <= repr && repr <= 2`.
+    pub const fn from_u8(repr: u8) -> Option<Self> {
+        match repr {
+            1 => Some(Self::Normal),
+            2 => Some(Self::Drop),
+            _ => None,
+        }
+    }
+}
+
+impl_ord_for_fieldless_enum!(CMoveKind);
+impl_hash_for_fieldless_enum!(CMoveKind);
+
+/// C-compatible version of [`Move`] with named fields.
+///
+/// Unlike [`CompactMove`], C callers can create and inspect values of this type without bit manipulation.
+/// All fields are plain integers on the C side, so C callers can set any value; invalid values are rejected by [`CMove::to_move`].
+/// Which fields are meaningful depends on `kind`:
+/// - [`CMoveKind::Normal`]: `from`, `to` and `promote` are meaningful. `piece` is `0` (`None`) if created by this crate.
+/// - [`CMoveKind::Drop`]: `piece` and `to` are meaningful. `from` is `0` (`None`) and `promote` is `false` if created by this crate.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Move, Piece, Square};
+/// # use shogi_core::c_compat::{CMove, CMoveKind};
+/// let mv = Move::Drop { piece: Piece::B_S, to: Square::SQ_5B };
+/// let cmove = CMove::from(mv);
+/// assert_eq!(cmove.kind, CMoveKind::Drop as u8);
+/// assert_eq!(cmove.to_move(), Some(mv));
+/// ```
+#[repr(C)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct CMove {
+    /// The kind of this move, which is the discriminant of a [`CMoveKind`].
+    pub kind: u8,
+    /// The source square. Meaningful only if `kind` is [`CMoveKind::Normal`].
+    pub from: OptionSquare,
+    /// The destination square. Never `0` (`None`) if created by this crate.
+    pub to: OptionSquare,
+    /// Whether this piece is promoted. Meaningful only if `kind` is [`CMoveKind::Normal`].
+    pub promote: bool,
+    /// The piece to be placed. Meaningful only if `kind` is [`CMoveKind::Drop`].
+    pub piece: OptionPiece,
+}
+
+impl CMove {
+    // A value that does not represent a move, returned by C interfaces that fail to create one.
+    fn zeroed() -> Self {
+        Self {
+            kind: 0,
+            from: None.into(),
+            to: None.into(),
+            promote: false,
+            piece: None.into(),
+        }
+    }
+
+    /// Converts `self` to a [`Move`].
+    ///
+    /// If `self.kind` is not a valid [`CMoveKind`], the fields required by `self.kind` are not set or hold invalid values,
+    /// or `promote` is set for a drop move, this function returns [`None`].
+    pub fn to_move(self) -> Option<Move> {
+        let to = self.to.to_checked_option()?;
+        match CMoveKind::from_u8(self.kind)? {
+            CMoveKind::Normal => Some(Move::Normal {
+                from: self.from.to_checked_option()?,
+                to,
+                promote: self.promote,
+            }),
+            CMoveKind::Drop => {
+                if self.promote {
+                    return None;
+                }
+                Some(Move::Drop {
+                    piece: self.piece.to_checked_option()?,
+                    to,
+                })
+            }
+        }
+    }
+
+    /// Converts a [`CMove`] to a [`CompactMove`].
+    ///
+    /// If `self` does not represent a valid move (see [`CMove::to_move`]), this function returns `0` (`None`).
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn CMove_to_compact_move(self) -> OptionCompactMove {
+        self.to_move().map(<CompactMove as From<Move>>::from).into()
+    }
+
+    /// Converts a [`CompactMove`] to a [`CMove`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn CMove_from_compact_move(mv: CompactMove) -> CMove {
+        <Move as From<CompactMove>>::from(mv).into()
+    }
+}
+
+impl From<Move> for CMove {
+    fn from(mv: Move) -> Self {
+        match mv {
+            Move::Normal { from, to, promote } => Self {
+                kind: CMoveKind::Normal as u8,
+                from: Some(from).into(),
+                to: Some(to).into(),
+                promote,
+                piece: None.into(),
+            },
+            Move::Drop { piece, to } => Self {
+                kind: CMoveKind::Drop as u8,
+                from: None.into(),
+                to: Some(to).into(),
+                promote: false,
+                piece: Some(piece).into(),
+            },
+        }
+    }
+}
+
+impl_ord_with_fields!(CMove; kind, from, to, promote, piece);
+impl_hash_with_fields!(CMove; kind, from, to, promote, piece);
+
 /// A move packed in two bytes. C-compatible version of [`Move`].
 ///
 /// Representation is as follows:
@@ -186,7 +583,7 @@ impl From<Move> for CompactMove {
             Move::Drop { piece, to } => (piece.as_u8() as u16) << 8 | 128 | to.index() as u16,
         };
         // Safety: value != 0 is implied from to.index() != 0
-        Self(unsafe { NonZeroU16::new_unchecked(value) })
+        Self(unsafe { crate::common::nonzero_u16_unchecked(value) })
     }
 }
 
@@ -209,6 +606,37 @@ impl From<CompactMove> for Move {
     }
 }
 
+/// A [`Move`] is archived as a [`CompactMove`], which takes only 2 bytes and can be used in place.
+#[cfg(feature = "rkyv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rkyv")))]
+impl rkyv::Archive for Move {
+    type Archived = CompactMove;
+    type Resolver = ();
+
+    #[inline(always)]
+    unsafe fn resolve(&self, _pos: usize, _resolver: (), out: *mut CompactMove) {
+        out.write((*self).into());
+    }
+}
+
+#[cfg(feature = "rkyv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rkyv")))]
+impl<S: rkyv::Fallible + ?Sized> rkyv::Serialize<S> for Move {
+    #[inline(always)]
+    fn serialize(&self, _serializer: &mut S) -> Result<(), S::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rkyv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rkyv")))]
+impl<D: rkyv::Fallible + ?Sized> rkyv::Deserialize<Move, D> for CompactMove {
+    #[inline(always)]
+    fn deserialize(&self, _deserializer: &mut D) -> Result<Move, D::Error> {
+        Ok((*self).into())
+    }
+}
+
 impl CompactMove {
     /// Creates a normal move.
     ///
@@ -219,12 +647,24 @@ impl CompactMove {
     /// let to = Square::SQ_3D;
     /// let promote = false;
     /// assert_eq!(<CompactMove as From<Move>>::from(Move::Normal { from, to, promote }), CompactMove::normal(from, to, promote));
+    /// const FIRST_MOVE: CompactMove = CompactMove::normal(Square::SQ_7G, Square::SQ_7F, false);
+    /// assert_eq!(FIRST_MOVE.to(), Square::SQ_7F);
     /// ```
-    #[export_name = "CompactMove_normal"]
-    pub extern "C" fn normal(from: Square, to: Square, promote: bool) -> Self {
-        let value = (promote as u16) << 15 | (from.index() as u16) << 8 | to.index() as u16;
-        // Safety: value != 0 is implied from to.index() != 0
-        Self(unsafe { NonZeroU16::new_unchecked(value) })
+    /// `const`: since 0.1.6
+    #[inline]
+    pub const fn normal(from: Square, to: Square, promote: bool) -> Self {
+        let from = from.array_index() as u16 + 1;
+        let to = to.array_index() as u16 + 1;
+        let value = (promote as u16) << 15 | from << 8 | to;
+        // Safety: value != 0 is implied from to != 0
+        Self(unsafe { crate::common::nonzero_u16_unchecked(value) })
+    }
+
+    /// C interface to [`CompactMove::normal`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn CompactMove_normal(from: Square, to: Square, promote: bool) -> Self {
+        Self::normal(from, to, promote)
     }
 
     /// Creates a drop move.
@@ -236,11 +676,20 @@ impl CompactMove {
     /// let to = Square::SQ_3D;
     /// assert_eq!(<CompactMove as From<Move>>::from(Move::Drop { piece, to }), CompactMove::drop(piece, to));
     /// ```
-    #[export_name = "CompactMove_drop"]
-    pub extern "C" fn drop(piece: Piece, to: Square) -> Self {
-        let value = (piece.as_u8() as u16) << 8 | 128 | to.index() as u16;
-        // Safety: value != 0 is implied from to.index() != 0
-        Self(unsafe { NonZeroU16::new_unchecked(value) })
+    /// `const`: since 0.1.6
+    #[inline]
+    pub const fn drop(piece: Piece, to: Square) -> Self {
+        let to = to.array_index() as u16 + 1;
+        let value = (piece.as_u8() as u16) << 8 | 128 | to;
+        // Safety: value != 0 is implied from to != 0
+        Self(unsafe { crate::common::nonzero_u16_unchecked(value) })
+    }
+
+    /// C interface to [`CompactMove::drop`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn CompactMove_drop(piece: Piece, to: Square) -> Self {
+        Self::drop(piece, to)
     }
 
     /// Finds the `from` square, if it exists.
@@ -257,13 +706,14 @@ impl CompactMove {
     }
 
     /// C interface of [`CompactMove::from`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn CompactMove_from(self) -> OptionSquare {
         self.from().into()
     }
 
     /// Finds the `to` square.
-    #[export_name = "CompactMove_to"]
+    #[cfg_attr(feature = "c-exports", export_name = "CompactMove_to")]
     pub extern "C" fn to(self) -> Square {
         let to = (self.0.get() & 127) as u8;
         // Safety: for all valid `CompactMove`, the least 7 bits represent a valid square.
@@ -271,28 +721,221 @@ impl CompactMove {
     }
 
     /// Finds whether `self` promotes a piece.
-    #[export_name = "CompactMove_is_promoting"]
+    #[cfg_attr(feature = "c-exports", export_name = "CompactMove_is_promoting")]
     pub extern "C" fn is_promoting(self) -> bool {
         (self.0.get() & 32768) != 0
     }
 
     /// Finds whether `self` is a drop move.
-    #[export_name = "CompactMove_is_drop"]
-    #[inline]
+    #[cfg_attr(feature = "c-exports", export_name = "CompactMove_is_drop")]
+    #[cfg_attr(not(feature = "c-exports"), inline)]
     pub extern "C" fn is_drop(self) -> bool {
         (self.0.get() & 128) != 0
     }
+
+    /// Parses a move in USI format (e.g. `7g7f`, `8h2b+`, `S*5b`) without going through [`Move`].
+    /// `side` is the player who makes the move, which determines the color of a dropped piece.
+    ///
+    /// Returns [`None`] if `s` is not a valid move in USI format.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, CompactMove, Piece, Square};
+    /// assert_eq!(
+    ///     CompactMove::from_usi("8h2b+", Color::Black),
+    ///     Some(CompactMove::normal(Square::SQ_8H, Square::SQ_2B, true)),
+    /// );
+    /// assert_eq!(
+    ///     CompactMove::from_usi("S*5b", Color::White),
+    ///     Some(CompactMove::drop(Piece::W_S, Square::SQ_5B)),
+    /// );
+    /// assert_eq!(CompactMove::from_usi("K*5b", Color::Black), None);
+    /// assert_eq!(CompactMove::from_usi("7g7f=", Color::Black), None);
+    /// ```
+    pub fn from_usi(s: &str, side: Color) -> Option<Self> {
+        match *s.as_bytes() {
+            [piece_kind, b'*', file, rank] => {
+                let piece_kind = PieceKind::parse_usi_byte(piece_kind)?;
+                if piece_kind == PieceKind::King {
+                    return None;
+                }
+                let to = Square::parse_usi_bytes(&[file, rank])?;
+                Some(Self::drop(Piece::new(piece_kind, side), to))
+            }
+            [from_file, from_rank, to_file, to_rank, ref rest @ ..] => {
+                let promote = match *rest {
+                    [] => false,
+                    [b'+'] => true,
+                    _ => return None,
+                };
+                let from = Square::parse_usi_bytes(&[from_file, from_rank])?;
+                let to = Square::parse_usi_bytes(&[to_file, to_rank])?;
+                Some(Self::normal(from, to, promote))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl_ord_for_single_field!(CompactMove);
 impl_hash_for_single_field!(CompactMove);
+impl_rkyv_as_self!(CompactMove);
 
 /// USI representation of a move (compact representation).
 ///
+/// The representation is written without converting `self` to a [`Move`].
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{CompactMove, Piece, Square, ToUsi};
+/// assert_eq!(CompactMove::normal(Square::SQ_8H, Square::SQ_2B, true).to_usi_owned(), "8h2b+");
+/// assert_eq!(CompactMove::drop(Piece::W_S, Square::SQ_5B).to_usi_owned(), "S*5b");
+/// ```
 /// Since: 0.1.4
 impl ToUsi for CompactMove {
     fn to_usi<W: core::fmt::Write>(&self, sink: &mut W) -> core::fmt::Result {
-        <Move as From<CompactMove>>::from(*self).to_usi(sink)
+        let inner = self.0.get();
+        if self.is_drop() {
+            // Safety: for all valid `CompactMove` which is a drop move, the part masked by 0x0f00 represents a valid piece kind.
+            let piece_kind = unsafe { PieceKind::from_u8_unchecked(((inner >> 8) & 15) as u8) };
+            piece_kind.to_usi(sink)?;
+            // Safety: b'*' is an ASCII byte
+            unsafe { crate::common::write_ascii_byte(sink, b'*') }?;
+            self.to().to_usi(sink)?;
+        } else {
+            let from = ((inner >> 8) & 127) as u8;
+            // Safety: for all valid `CompactMove` which is normal, the part masked by 0x7f00 represents a valid square.
+            unsafe { Square::from_u8_unchecked(from) }.to_usi(sink)?;
+            self.to().to_usi(sink)?;
+            if self.is_promoting() {
+                // Safety: b'+' is an ASCII byte
+                unsafe { crate::common::write_ascii_byte(sink, b'+') }?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A [`CompactMove`] or a special action that is not a move, packed in two bytes.
+///
+/// Moves are represented in the same way as [`CompactMove`]. The following values, which never represent a move, are reserved for special actions:
+/// - 1: resignation ([`CompactAction::RESIGN`], `resign` in USI)
+/// - 2: declaration of win ([`CompactAction::WIN`], `win` in USI)
+/// - 3: null move, i.e. a pass ([`CompactAction::NULL`], `pass` in USI)
+///
+/// These values are stable, so that they can be stored in e.g. opening books.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Color, CompactAction, CompactMove, Square, ToUsi};
+/// let mv = CompactMove::normal(Square::SQ_7G, Square::SQ_7F, false);
+/// assert_eq!(CompactAction::from(mv).to_move(), Some(mv));
+/// assert_eq!(CompactAction::RESIGN.to_move(), None);
+/// assert_eq!(CompactAction::from_usi("win", Color::Black), Some(CompactAction::WIN));
+/// assert_eq!(CompactAction::NULL.to_usi_owned(), "pass");
+/// assert_eq!(CompactAction::from_u16(CompactAction::WIN.as_u16()), Some(CompactAction::WIN));
+/// assert_eq!(CompactAction::from_u16(4), None);
+/// ```
+///
+/// Since: 0.1.6
+#[repr(transparent)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct CompactAction(NonZeroU16);
+
+impl CompactAction {
+    /// Resignation.
+    // Safety: 1 != 0
+    pub const RESIGN: Self = Self(unsafe { crate::common::nonzero_u16_unchecked(1) });
+
+    /// Declaration of win (入玉宣言).
+    // Safety: 2 != 0
+    pub const WIN: Self = Self(unsafe { crate::common::nonzero_u16_unchecked(2) });
+
+    /// A null move (pass). See [`PartialPosition::make_null_move`](crate::PartialPosition::make_null_move).
+    // Safety: 3 != 0
+    pub const NULL: Self = Self(unsafe { crate::common::nonzero_u16_unchecked(3) });
+
+    /// Creates a [`CompactAction`] that represents a move.
+    #[inline(always)]
+    pub const fn from_move(mv: CompactMove) -> Self {
+        Self(mv.0)
+    }
+
+    /// Returns the move `self` represents, if any.
+    #[inline]
+    pub fn to_move(self) -> Option<CompactMove> {
+        if self.0.get() <= 3 {
+            None
+        } else {
+            Some(CompactMove(self.0))
+        }
+    }
+
+    /// Returns the representation of `self`.
+    #[inline(always)]
+    pub const fn as_u16(self) -> u16 {
+        self.0.get()
+    }
+
+    /// Converts a [`u16`] to a [`CompactAction`].
+    /// If `value` represents neither a valid move nor a special action, this function returns [`None`].
+    pub fn from_u16(value: u16) -> Option<Self> {
+        let value = NonZeroU16::new(value)?;
+        if value.get() <= 3 {
+            return Some(Self(value));
+        }
+        let to = Square::from_u8((value.get() & 127) as u8)?;
+        let mv = if value.get() & 128 != 0 {
+            let piece = (value.get() >> 8) as u8;
+            if !matches!(piece, 1..=7 | 17..=23) {
+                return None;
+            }
+            // Safety: `piece` represents a piece in hand of either player.
+            CompactMove::drop(unsafe { Piece::from_u8_unchecked(piece) }, to)
+        } else {
+            let from = Square::from_u8(((value.get() >> 8) & 127) as u8)?;
+            CompactMove::normal(from, to, value.get() & 32768 != 0)
+        };
+        if mv.0 != value {
+            return None;
+        }
+        Some(Self(value))
+    }
+
+    /// Parses a move or a special action in USI format.
+    /// `side` is the player who makes the move, which determines the color of a dropped piece.
+    ///
+    /// In addition to moves, `resign`, `win` and `pass` are accepted.
+    /// `0000`, which some programs use for a null move, is also accepted as [`CompactAction::NULL`].
+    pub fn from_usi(s: &str, side: Color) -> Option<Self> {
+        match s {
+            "resign" => Some(Self::RESIGN),
+            "win" => Some(Self::WIN),
+            "pass" | "0000" => Some(Self::NULL),
+            _ => CompactMove::from_usi(s, side).map(Self::from_move),
+        }
+    }
+}
+
+impl From<CompactMove> for CompactAction {
+    #[inline(always)]
+    fn from(mv: CompactMove) -> Self {
+        Self::from_move(mv)
+    }
+}
+
+impl_ord_for_single_field!(CompactAction);
+impl_hash_for_single_field!(CompactAction);
+impl_rkyv_as_self!(CompactAction);
+
+impl ToUsi for CompactAction {
+    fn to_usi<W: core::fmt::Write>(&self, sink: &mut W) -> core::fmt::Result {
+        match self.to_move() {
+            Some(mv) => mv.to_usi(sink),
+            None if *self == Self::RESIGN => sink.write_str("resign"),
+            None if *self == Self::WIN => sink.write_str("win"),
+            None => sink.write_str("pass"),
+        }
     }
 }
 
@@ -304,9 +947,10 @@ impl ToUsi for CompactMove {
 #[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
 pub struct OptionCompactMove(u16);
 
-impl From<Option<CompactMove>> for OptionCompactMove {
+impl OptionCompactMove {
+    // A `const` version of `From<Option<CompactMove>>`.
     #[inline(always)]
-    fn from(arg: Option<CompactMove>) -> Self {
+    pub(crate) const fn from_option(arg: Option<CompactMove>) -> Self {
         Self(match arg {
             Some(result) => result.0.get(),
             None => 0,
@@ -314,6 +958,13 @@ impl From<Option<CompactMove>> for OptionCompactMove {
     }
 }
 
+impl From<Option<CompactMove>> for OptionCompactMove {
+    #[inline(always)]
+    fn from(arg: Option<CompactMove>) -> Self {
+        Self::from_option(arg)
+    }
+}
+
 impl From<OptionCompactMove> for Option<CompactMove> {
     #[inline(always)]
     fn from(arg: OptionCompactMove) -> Self {
@@ -321,13 +972,114 @@ impl From<OptionCompactMove> for Option<CompactMove> {
     }
 }
 
+impl OptionCompactMove {
+    /// Returns true if and only if `self` holds a move, i.e., `self` is not the representation of [`None`].
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn OptionCompactMove_is_some(self) -> bool {
+        self.0 != 0
+    }
+
+    /// Returns true if and only if `self` is the representation of [`None`].
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn OptionCompactMove_is_none(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the [`CompactMove`] held by `self` without checking.
+    ///
+    /// # Safety
+    /// `self` must hold a move, i.e., [`OptionCompactMove::OptionCompactMove_is_some`] must return true.
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn OptionCompactMove_unwrap_unchecked(self) -> CompactMove {
+        crate::common::unwrap_unchecked(self.into())
+    }
+}
+
 impl_ord_for_single_field!(OptionCompactMove);
 impl_hash_for_single_field!(OptionCompactMove);
+impl_rkyv_as_self!(OptionCompactMove);
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn compact_move_usi_works() {
+        let mut moves = vec![];
+        for from in Square::all() {
+            for to in Square::all() {
+                for promote in [false, true] {
+                    moves.push(Move::Normal { from, to, promote });
+                }
+            }
+        }
+        for piece in Piece::all() {
+            if piece.piece_kind().unpromote().is_none() && piece.piece_kind() != PieceKind::King {
+                for to in Square::all() {
+                    moves.push(Move::Drop { piece, to });
+                }
+            }
+        }
+        for mv in moves {
+            let compact: CompactMove = mv.into();
+            let usi = compact.to_usi_owned();
+            assert_eq!(usi, mv.to_usi_owned());
+            let side = match mv {
+                Move::Drop { piece, .. } => piece.color(),
+                Move::Normal { .. } => Color::Black,
+            };
+            assert_eq!(CompactMove::from_usi(&usi, side), Some(compact));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn compact_action_works() {
+        let mut count = 0;
+        for value in 0..=u16::MAX {
+            let action = match CompactAction::from_u16(value) {
+                Some(action) => action,
+                None => continue,
+            };
+            count += 1;
+            assert_eq!(action.as_u16(), value);
+            if let Some(mv) = action.to_move() {
+                let full: Move = mv.into();
+                assert_eq!(<CompactMove as From<Move>>::from(full), mv);
+                let side = match full {
+                    Move::Drop { piece, .. } => piece.color(),
+                    Move::Normal { .. } => Color::Black,
+                };
+                assert_eq!(
+                    CompactAction::from_usi(&action.to_usi_owned(), side),
+                    Some(action),
+                );
+            }
+        }
+        // 3 special actions, 81 * 81 * 2 normal moves (including those with from == to) and 14 * 81 drops
+        assert_eq!(count, 3 + 81 * 81 * 2 + 14 * 81);
+        for action in [
+            CompactAction::RESIGN,
+            CompactAction::WIN,
+            CompactAction::NULL,
+        ] {
+            assert_eq!(
+                CompactAction::from_usi(&action.to_usi_owned(), Color::Black),
+                Some(action),
+            );
+        }
+    }
+
     #[test]
     fn from_into_works() {
         // normal moves
@@ -355,6 +1107,185 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cmove_works() {
+        for from in Square::all() {
+            for to in Square::all() {
+                for promote in [false, true] {
+                    let mv = Move::Normal { from, to, promote };
+                    let cmove = Move::Move_normal_c(from.index(), to.index(), promote);
+                    assert_eq!(cmove, CMove::from(mv));
+                    assert_eq!(cmove.to_move(), Some(mv));
+                    assert_eq!(
+                        CMove::CMove_from_compact_move(mv.into()).to_move(),
+                        Some(mv),
+                    );
+                }
+            }
+        }
+        for piece in Piece::all() {
+            for to in Square::all() {
+                let mv = Move::Drop { piece, to };
+                let cmove = Move::Move_drop_c(piece.as_u8(), to.index());
+                assert_eq!(cmove, CMove::from(mv));
+                assert_eq!(cmove.to_move(), Some(mv));
+                assert_eq!(
+                    Option::<CompactMove>::from(cmove.CMove_to_compact_move()),
+                    Some(mv.into()),
+                );
+            }
+        }
+        // Missing fields
+        let mut cmove = Move::Move_normal_c(Square::SQ_7G.index(), Square::SQ_7F.index(), false);
+        cmove.from = None.into();
+        assert_eq!(cmove.to_move(), None);
+        assert_eq!(cmove.CMove_to_compact_move(), None.into());
+        let mut cmove = Move::Move_drop_c(Piece::B_P.as_u8(), Square::SQ_5E.index());
+        cmove.promote = true;
+        assert_eq!(cmove.to_move(), None);
+        cmove.promote = false;
+        cmove.piece = None.into();
+        assert_eq!(cmove.to_move(), None);
+        // Out-of-range representations
+        for (from, to) in [(0, 1), (1, 0), (82, 1), (1, 255)] {
+            let cmove = Move::Move_normal_c(from, to, false);
+            assert_eq!(cmove, CMove::zeroed());
+            assert_eq!(cmove.to_move(), None);
+        }
+        for (piece, to) in [(0, 1), (15, 1), (Piece::B_P.as_u8(), 82)] {
+            let cmove = Move::Move_drop_c(piece, to);
+            assert_eq!(cmove, CMove::zeroed());
+            assert_eq!(cmove.to_move(), None);
+        }
+        // Values that C callers can create but are not valid
+        let cmove = Move::Move_normal_c(Square::SQ_7G.index(), Square::SQ_7F.index(), false);
+        assert_eq!(CMove { kind: 0, ..cmove }.to_move(), None);
+        assert_eq!(CMove { kind: 3, ..cmove }.to_move(), None);
+        assert_eq!(
+            CMove {
+                to: None.into(),
+                ..cmove
+            }
+            .to_move(),
+            None
+        );
+        for repr in [0, 82, 255] {
+            let square = unsafe { core::mem::transmute::<u8, OptionSquare>(repr) };
+            assert_eq!(
+                CMove {
+                    to: square,
+                    ..cmove
+                }
+                .to_move(),
+                None
+            );
+            assert_eq!(
+                CMove {
+                    from: square,
+                    ..cmove
+                }
+                .to_move(),
+                None
+            );
+        }
+        let cmove = Move::Move_drop_c(Piece::B_P.as_u8(), Square::SQ_5E.index());
+        for repr in [0, 15, 16, 31, 255] {
+            let piece = unsafe { core::mem::transmute::<u8, OptionPiece>(repr) };
+            assert_eq!(CMove { piece, ..cmove }.to_move(), None);
+        }
+    }
+
+    #[test]
+    fn from_usi_reports_errors() {
+        for (s, error) in [
+            ("", UsiMoveParseError::Syntax),
+            ("7g7", UsiMoveParseError::Syntax),
+            ("P*5", UsiMoveParseError::Syntax),
+            ("0g7f", UsiMoveParseError::InvalidSquare),
+            ("7g7F", UsiMoveParseError::InvalidSquare),
+            ("P*5j", UsiMoveParseError::InvalidSquare),
+            ("+P*5", UsiMoveParseError::InvalidSquare),
+            ("p*5e", UsiMoveParseError::InvalidPiece),
+            ("X*5e", UsiMoveParseError::InvalidPiece),
+            ("K*5e", UsiMoveParseError::InvalidPiece),
+            ("7g7f+ ", UsiMoveParseError::InvalidPromotion),
+            ("7g7f-", UsiMoveParseError::InvalidPromotion),
+        ] {
+            assert_eq!(Move::from_usi(s, Color::Black), Err(error), "{}", s);
+            assert_eq!(s.parse::<Move>(), Err(error), "{}", s);
+        }
+        assert!(Move::from_usi("P*5e", Color::White).is_ok());
+        assert_eq!("P*5e".parse::<Move>(), Err(UsiMoveParseError::Drop));
+    }
+
+    #[test]
+    fn parse_usi_bytes_works() {
+        for from in Square::all() {
+            for to in Square::all() {
+                for promote in [true, false] {
+                    let mv = Move::Normal { from, to, promote };
+                    let usi = mv.to_usi_owned();
+                    assert_eq!(
+                        Move::parse_usi_bytes(usi.as_bytes(), Color::Black),
+                        Some(mv)
+                    );
+                }
+            }
+        }
+        for color in Color::all() {
+            for piece_kind in PieceKind::all() {
+                if piece_kind.unpromote().is_some() || piece_kind == PieceKind::King {
+                    continue;
+                }
+                for to in Square::all() {
+                    let mv = Move::Drop {
+                        piece: Piece::new(piece_kind, color),
+                        to,
+                    };
+                    let usi = mv.to_usi_owned();
+                    assert_eq!(Move::parse_usi_bytes(usi.as_bytes(), color), Some(mv));
+                    let mut buf = [0u8; 6];
+                    let written =
+                        unsafe { Move::Move_to_usi_c(mv.into(), buf.as_mut_ptr(), buf.len()) };
+                    assert_eq!(&buf[..written], usi.as_bytes());
+                    assert_eq!(buf[written], 0);
+                }
+            }
+        }
+        for invalid in [
+            &b""[..],
+            b"7g7",
+            b"7g7f=",
+            b"7g7f++",
+            b"K*5e",
+            b"p*5e",
+            b"+P*5e",
+            b"P*5j",
+            b"7g0f",
+        ] {
+            assert_eq!(Move::parse_usi_bytes(invalid, Color::Black), None);
+        }
+
+        let mut cmove = Move::Move_normal_c(Square::SQ_7G.index(), Square::SQ_7F.index(), false);
+        let usi = b"8h2b+";
+        assert!(unsafe { Move::Move_parse_c(usi.as_ptr(), usi.len(), Color::Black, &mut cmove) });
+        assert_eq!(
+            cmove.to_move(),
+            Some(Move::Normal {
+                from: Square::SQ_8H,
+                to: Square::SQ_2B,
+                promote: true,
+            }),
+        );
+        assert!(!unsafe { Move::Move_parse_c(usi.as_ptr(), 3, Color::Black, &mut cmove) });
+        let mut buf = [0u8; 6];
+        cmove.from = None.into();
+        assert_eq!(
+            unsafe { Move::Move_to_usi_c(cmove, buf.as_mut_ptr(), buf.len()) },
+            0
+        );
+    }
+
     #[test]
     fn normal_works() {
         for from in Square::all() {
@@ -0,0 +1,228 @@
+//! Classification of openings by positions.
+
+use crate::{Game, PartialPosition};
+
+/// Standard openings (戦型) recognized by [`Game::detect_opening`].
+///
+/// Since: 0.1.6
+#[cfg_attr(docsrs, doc(cfg(feature = "opening")))]
+#[repr(u8)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Opening {
+    /// Yagura (矢倉).
+    ///
+    /// Discriminant = 1.
+    Yagura = 1,
+    /// Bishop exchange (角換わり).
+    ///
+    /// Discriminant = 2.
+    Kakugawari = 2,
+    /// Double wing attack (相掛かり).
+    ///
+    /// Discriminant = 3.
+    Aigakari = 3,
+    /// Side pawn capture (横歩取り).
+    ///
+    /// Discriminant = 4.
+    Yokofudori = 4,
+    /// Fourth file rook (四間飛車).
+    ///
+    /// Discriminant = 5.
+    Shikenbisha = 5,
+    /// Third file rook (三間飛車).
+    ///
+    /// Discriminant = 6.
+    Sangenbisha = 6,
+    /// Central rook (中飛車).
+    ///
+    /// Discriminant = 7.
+    Nakabisha = 7,
+}
+
+impl_ord_for_fieldless_enum!(Opening);
+impl_hash_for_fieldless_enum!(Opening);
+
+// Characteristic positions of openings, keyed by `PartialPosition::canonical_sfen(true)`.
+// The comment above each entry is the sequence of moves from the starting position that reaches it.
+const OPENINGS: [(&str, Opening); 8] = [
+    // 7g7f 8c8d 7i6h 3c3d 6g6f
+    (
+        "lnsgkgsnl/1r5b1/p1pppp1pp/1p4p2/9/2PP5/PP2PPPPP/1B1S3R1/LN1GKGSNL w - 1",
+        Opening::Yagura,
+    ),
+    // 7g7f 8c8d 2g2f 4a3b 6i7h 8d8e 8h7g 3c3d 7i8h 2b7g+ 8h7g
+    (
+        "lnsgk1snl/1r4g2/p1pppp1pp/6p2/1p7/2P4P1/PPSPPPP1P/2G4R1/LN2KGSNL w Bb 1",
+        Opening::Kakugawari,
+    ),
+    // 2g2f 8c8d 2f2e 8d8e
+    (
+        "lnsgkgsnl/1r5b1/p1ppppppp/9/1p5P1/9/PPPPPPP1P/1B5R1/LNSGKGSNL b - 1",
+        Opening::Aigakari,
+    ),
+    // 7g7f 3c3d 2g2f 8c8d 2f2e 8d8e 6i7h 4a3b 2e2d 2c2d 2h2d 8e8f 8g8f 8b8f 2d3d
+    (
+        "lnsgk1snl/6gb1/p1pppp2p/6R2/9/1rP6/P2PPPP1P/1BG6/LNS1KGSNL w 3P2p 1",
+        Opening::Yokofudori,
+    ),
+    // 7g7f 8c8d 6g6f 3c3d 2h6h
+    (
+        "lnsgkgsnl/1r5b1/p1pppp1pp/1p4p2/9/2PP5/PP2PPPPP/1B1R5/LNSGKGSNL w - 1",
+        Opening::Shikenbisha,
+    ),
+    // 7g7f 3c3d 2g2f 4c4d 2f2e 2b3c 3i4h 8b4b
+    (
+        "lnsgkgsnl/5r3/ppppp1bpp/5pp2/7P1/2P6/PP1PPPP1P/1B3S1R1/LNSGKG1NL b - 1",
+        Opening::Shikenbisha,
+    ),
+    // 7g7f 8c8d 6g6f 3c3d 2h7h
+    (
+        "lnsgkgsnl/1r5b1/p1pppp1pp/1p4p2/9/2PP5/PP2PPPPP/1BR6/LNSGKGSNL w - 1",
+        Opening::Sangenbisha,
+    ),
+    // 5g5f 8c8d 2h5h
+    (
+        "lnsgkgsnl/1r5b1/p1ppppppp/1p7/9/4P4/PPPP1PPPP/1B2R4/LNSGKGSNL w - 1",
+        Opening::Nakabisha,
+    ),
+];
+
+impl Opening {
+    /// How many moves from the beginning of a game [`Game::detect_opening`] looks at.
+    pub const MAX_PLY: usize = 40;
+
+    /// Returns the opening whose characteristic position is `position`, if any.
+    ///
+    /// The ply of `position` is ignored.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Opening, PartialPosition};
+    /// let sfen = "lnsgkgsnl/1r5b1/p1ppppppp/1p7/9/4P4/PPPP1PPPP/1B2R4/LNSGKGSNL w - 4";
+    /// let position = PartialPosition::from_sfen(sfen).unwrap();
+    /// assert_eq!(Opening::from_position(&position), Some(Opening::Nakabisha));
+    /// assert_eq!(Opening::from_position(&PartialPosition::startpos()), None);
+    /// ```
+    pub fn from_position(position: &PartialPosition) -> Option<Self> {
+        let key = position.canonical_sfen(true);
+        OPENINGS
+            .iter()
+            .find(|&&(sfen, _)| sfen == key)
+            .map(|&(_, opening)| opening)
+    }
+
+    /// Returns the name of `self` in romanized Japanese, e.g. `"Shikenbisha"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Opening::Yagura => "Yagura",
+            Opening::Kakugawari => "Kakugawari",
+            Opening::Aigakari => "Aigakari",
+            Opening::Yokofudori => "Yokofudori",
+            Opening::Shikenbisha => "Shikenbisha",
+            Opening::Sangenbisha => "Sangenbisha",
+            Opening::Nakabisha => "Nakabisha",
+        }
+    }
+
+    /// Returns the name of `self` in Japanese, e.g. `"四間飛車"`.
+    pub const fn name_ja(self) -> &'static str {
+        match self {
+            Opening::Yagura => "矢倉",
+            Opening::Kakugawari => "角換わり",
+            Opening::Aigakari => "相掛かり",
+            Opening::Yokofudori => "横歩取り",
+            Opening::Shikenbisha => "四間飛車",
+            Opening::Sangenbisha => "三間飛車",
+            Opening::Nakabisha => "中飛車",
+        }
+    }
+}
+
+/// Opening classification.
+#[cfg_attr(docsrs, doc(cfg(feature = "opening")))]
+impl Game {
+    /// Detects the opening of `self` from the positions in its first [`Opening::MAX_PLY`] moves.
+    ///
+    /// Each position, including the initial one, is looked up with [`Opening::from_position`],
+    /// so openings are detected regardless of move orders.
+    /// If more than one position matches, the last one is used, because later positions are more specific.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Game, Move, Opening, Position, Square};
+    /// let mut position = Position::startpos();
+    /// let moves = [
+    ///     (Square::SQ_7G, Square::SQ_7F),
+    ///     (Square::SQ_8C, Square::SQ_8D),
+    ///     (Square::SQ_6G, Square::SQ_6F),
+    ///     (Square::SQ_3C, Square::SQ_3D),
+    ///     (Square::SQ_2H, Square::SQ_6H),
+    ///     (Square::SQ_7A, Square::SQ_6B),
+    /// ];
+    /// for (from, to) in moves {
+    ///     position.make_move(Move::Normal { from, to, promote: false }).unwrap();
+    /// }
+    /// let game = Game::new(position);
+    /// assert_eq!(game.detect_opening(), Some(Opening::Shikenbisha));
+    /// assert_eq!(Game::new(Position::startpos()).detect_opening(), None);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn detect_opening(&self) -> Option<Opening> {
+        let position = self.position();
+        let mut current = position.initial_position().clone();
+        let mut result = Opening::from_position(&current);
+        for &mv in position.moves().iter().take(Opening::MAX_PLY) {
+            // The moves were already made in `position`, so replaying them always succeeds.
+            current.make_move(mv)?;
+            if let Some(opening) = Opening::from_position(&current) {
+                result = Some(opening);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompactMove, Position};
+
+    #[test]
+    fn openings_are_reachable() {
+        let lines = [
+            "7g7f 8c8d 7i6h 3c3d 6g6f",
+            "7g7f 8c8d 2g2f 4a3b 6i7h 8d8e 8h7g 3c3d 7i8h 2b7g+ 8h7g",
+            "2g2f 8c8d 2f2e 8d8e",
+            "7g7f 3c3d 2g2f 8c8d 2f2e 8d8e 6i7h 4a3b 2e2d 2c2d 2h2d 8e8f 8g8f 8b8f 2d3d",
+            "7g7f 8c8d 6g6f 3c3d 2h6h",
+            "7g7f 3c3d 2g2f 4c4d 2f2e 2b3c 3i4h 8b4b",
+            "7g7f 8c8d 6g6f 3c3d 2h7h",
+            "5g5f 8c8d 2h5h",
+        ];
+        for (line, &(sfen, opening)) in lines.iter().zip(OPENINGS.iter()) {
+            let mut position = Position::startpos();
+            for mv in line.split(' ') {
+                let mv = CompactMove::from_usi(mv, position.side_to_move()).unwrap();
+                position.make_move(mv.into()).unwrap();
+            }
+            assert_eq!(position.inner().canonical_sfen(true), sfen);
+            assert_eq!(Game::new(position).detect_opening(), Some(opening));
+        }
+    }
+
+    #[test]
+    fn detect_opening_prefers_later_positions() {
+        // Aigakari, then side pawn capture
+        let mut position = Position::startpos();
+        let line = "2g2f 8c8d 2f2e 8d8e 7g7f 3c3d 6i7h 4a3b 2e2d 2c2d 2h2d 8e8f 8g8f 8b8f 2d3d";
+        for mv in line.split(' ') {
+            let mv = CompactMove::from_usi(mv, position.side_to_move()).unwrap();
+            position.make_move(mv.into()).unwrap();
+        }
+        assert_eq!(
+            Game::new(position).detect_opening(),
+            Some(Opening::Yokofudori),
+        );
+    }
+}
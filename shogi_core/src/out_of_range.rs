@@ -0,0 +1,24 @@
+/// An error returned when an integer does not represent a value of a type, e.g. by <code>[Square]::[try_from]\(0u8)</code>.
+///
+/// This is the error type of the <code>[TryFrom]<[u8]></code> implementations of [`Color`](crate::Color), [`PieceKind`](crate::PieceKind), [`Piece`](crate::Piece), [`Square`](crate::Square) and [`GameResolution`](crate::GameResolution),
+/// and of <code>[TryFrom]<[usize]></code> of [`Square`](crate::Square).
+///
+/// [Square]: crate::Square
+/// [try_from]: TryFrom::try_from
+///
+/// Since: 0.1.6
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct OutOfRangeError;
+
+impl_ord_for_fieldless_struct!(OutOfRangeError);
+impl_hash_for_fieldless_struct!(OutOfRangeError);
+
+impl core::fmt::Display for OutOfRangeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("integer out of range")
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for OutOfRangeError {}
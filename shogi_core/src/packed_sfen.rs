@@ -0,0 +1,506 @@
+//! Compact binary formats of positions used in training data of evaluation functions.
+
+use crate::{Color, Hand, Move, PartialPosition, Piece, PieceKind, Square};
+
+// Piece kinds in the order of YaneuraOu, which the Huffman codes and drops in moves are indexed by.
+const KINDS: [PieceKind; 7] = [
+    PieceKind::Pawn,
+    PieceKind::Lance,
+    PieceKind::Knight,
+    PieceKind::Silver,
+    PieceKind::Bishop,
+    PieceKind::Rook,
+    PieceKind::Gold,
+];
+
+// Huffman codes (written from the least significant bit) and their lengths of pieces on the board,
+// indexed by `KINDS`. A vacant square is written as a single 0.
+// A piece in hand is written without the least significant bit, which is always 1.
+const CODES: [(u8, u8); 7] = [
+    (0x01, 2),
+    (0x03, 4),
+    (0x0b, 4),
+    (0x07, 4),
+    (0x1f, 6),
+    (0x3f, 6),
+    (0x0f, 5),
+];
+
+// The index of the unpromoted version of `piece_kind` in `KINDS`, or `None` for kings.
+fn kind_index(piece_kind: PieceKind) -> Option<usize> {
+    let unpromoted = piece_kind.unpromoted_or_self();
+    KINDS.iter().position(|&kind| kind == unpromoted)
+}
+
+struct BitWriter {
+    data: [u8; 32],
+    cursor: usize,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u8, bits: u8) -> Option<()> {
+        for i in 0..bits {
+            let byte = self.data.get_mut(self.cursor / 8)?;
+            *byte |= ((value >> i) & 1) << (self.cursor % 8);
+            self.cursor += 1;
+        }
+        Some(())
+    }
+}
+
+struct BitReader<'a> {
+    data: &'a [u8; 32],
+    cursor: usize,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, bits: u8) -> Option<u8> {
+        let mut value = 0;
+        for i in 0..bits {
+            let byte = *self.data.get(self.cursor / 8)?;
+            value |= ((byte >> (self.cursor % 8)) & 1) << i;
+            self.cursor += 1;
+        }
+        Some(value)
+    }
+
+    // Reads a Huffman code. `hand` tells if the code is that of a piece in hand.
+    fn read_code(&mut self, hand: bool) -> Option<usize> {
+        let mut value = 0;
+        let mut bits = 0;
+        loop {
+            value |= self.read(1)? << bits;
+            bits += 1;
+            let found = CODES.iter().position(|&(code, length)| {
+                if hand {
+                    (code >> 1, length - 1) == (value, bits)
+                } else {
+                    (code, length) == (value, bits)
+                }
+            });
+            if let Some(index) = found {
+                return Some(index);
+            }
+            if bits >= 6 {
+                return None;
+            }
+        }
+    }
+
+    // Reads the promotion flag (except for golds) and the color of a piece.
+    fn read_piece(&mut self, index: usize) -> Option<Piece> {
+        let mut piece_kind = KINDS[index];
+        if piece_kind != PieceKind::Gold && self.read(1)? == 1 {
+            piece_kind = piece_kind.promote()?;
+        }
+        let color = if self.read(1)? == 0 {
+            Color::Black
+        } else {
+            Color::White
+        };
+        Some(Piece::new(piece_kind, color))
+    }
+}
+
+/// A position packed in 256 bits, in the format of YaneuraOu's `PackedSfen`.
+///
+/// The side to move, the squares of the kings, the pieces on the other squares and the pieces in hand are
+/// written in this order with Huffman codes. The ply is not recorded.
+/// Because the codes are assigned so that all 40 pieces take exactly 256 bits,
+/// only positions with all 40 pieces (on the board or in hand) can be packed.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{PackedSfen, PartialPosition};
+/// let position = PartialPosition::startpos();
+/// let packed = PackedSfen::from_position(&position).unwrap();
+/// assert_eq!(packed.to_position(), Some(position));
+/// ```
+///
+/// Since: 0.1.6
+#[repr(C)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct PackedSfen(pub [u8; 32]);
+
+impl PackedSfen {
+    /// Packs a position.
+    ///
+    /// Returns [`None`] if `position` does not have exactly 40 pieces or a player does not have exactly one king.
+    pub fn from_position(position: &PartialPosition) -> Option<Self> {
+        let mut writer = BitWriter {
+            data: [0; 32],
+            cursor: 0,
+        };
+        writer.write(position.side_to_move() as u8 - 1, 1)?;
+        for color in Color::all() {
+            writer.write(position.king_position(color)?.index0(), 7)?;
+        }
+        for square in Square::all() {
+            let piece = match position.piece_at(square) {
+                Some(piece) => piece,
+                None => {
+                    writer.write(0, 1)?;
+                    continue;
+                }
+            };
+            let (piece_kind, color) = piece.to_parts();
+            if piece_kind == PieceKind::King {
+                if position.king_position(color) != Some(square) {
+                    return None;
+                }
+                continue;
+            }
+            let index = kind_index(piece_kind)?;
+            let (code, length) = CODES[index];
+            writer.write(code, length)?;
+            if KINDS[index] != PieceKind::Gold {
+                writer.write(piece_kind.unpromote().is_some() as u8, 1)?;
+            }
+            writer.write(color as u8 - 1, 1)?;
+        }
+        for color in Color::all() {
+            let hand = position.hand_of_a_player(color);
+            for (index, &piece_kind) in KINDS.iter().enumerate() {
+                let (code, length) = CODES[index];
+                for _ in 0..hand.count(piece_kind)? {
+                    writer.write(code >> 1, length - 1)?;
+                    if piece_kind != PieceKind::Gold {
+                        writer.write(0, 1)?;
+                    }
+                    writer.write(color as u8 - 1, 1)?;
+                }
+            }
+        }
+        if writer.cursor != 256 {
+            return None;
+        }
+        Some(Self(writer.data))
+    }
+
+    /// Unpacks a position. The ply of the returned position is 1.
+    ///
+    /// Returns [`None`] if `self` is not a valid packed position.
+    pub fn to_position(&self) -> Option<PartialPosition> {
+        let mut reader = BitReader {
+            data: &self.0,
+            cursor: 0,
+        };
+        let mut position = PartialPosition::empty();
+        if reader.read(1)? == 1 {
+            position.side_to_move_set(Color::White);
+        }
+        for color in Color::all() {
+            let square = Square::from_index0(reader.read(7)?)?;
+            if position.piece_at(square).is_some() {
+                return None;
+            }
+            position.piece_set(square, Some(Piece::new(PieceKind::King, color)));
+        }
+        for square in Square::all() {
+            if position.piece_at(square).is_some() {
+                continue;
+            }
+            if reader.read(1)? == 0 {
+                continue;
+            }
+            // The first bit was 1, so we read it again.
+            reader.cursor -= 1;
+            let index = reader.read_code(false)?;
+            position.piece_set(square, Some(reader.read_piece(index)?));
+        }
+        while reader.cursor < 256 {
+            let index = reader.read_code(true)?;
+            let piece = reader.read_piece(index)?;
+            if piece.piece_kind() != KINDS[index] {
+                return None;
+            }
+            let hand: &mut Hand = position.hand_of_a_player_mut(piece.color());
+            *hand = hand.added(piece.piece_kind())?;
+        }
+        Some(position)
+    }
+
+    /// C interface of [`PackedSfen::from_position`]. Returns `false` if `position` cannot be packed.
+    ///
+    /// # Safety
+    /// `out` must be valid for writes of a [`PackedSfen`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn PackedSfen_from_position(
+        position: &PartialPosition,
+        out: *mut PackedSfen,
+    ) -> bool {
+        match Self::from_position(position) {
+            Some(packed) => {
+                out.write(packed);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl_ord_for_single_field!(PackedSfen);
+impl_hash_for_single_field!(PackedSfen);
+
+/// A training record in the format of YaneuraOu's `PackedSfenValue`, which takes 40 bytes.
+///
+/// Files of training data are sequences of records without headers.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Move, PackedSfen, PackedSfenValue, PartialPosition, Square};
+/// let position = PartialPosition::startpos();
+/// let mut record = PackedSfenValue {
+///     sfen: PackedSfen::from_position(&position).unwrap(),
+///     score: 42,
+///     move16: 0,
+///     game_ply: 1,
+///     game_result: 1,
+/// };
+/// let mv = Move::Normal { from: Square::SQ_2G, to: Square::SQ_2F, promote: false };
+/// record.set_best_move(Some(mv));
+/// let bytes = record.to_bytes();
+/// assert_eq!(PackedSfenValue::from_bytes(&bytes), record);
+/// assert_eq!(record.best_move(), Some(mv));
+/// let file = [bytes, bytes].concat();
+/// assert_eq!(PackedSfenValue::iter_bytes(&file).count(), 2);
+/// ```
+///
+/// Since: 0.1.6
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct PackedSfenValue {
+    /// The position.
+    pub sfen: PackedSfen,
+    /// The evaluation of the position from the viewpoint of the side to move.
+    pub score: i16,
+    /// The best move in the position in YaneuraOu's 16-bit format, or 0 if none.
+    /// See [`PackedSfenValue::best_move`].
+    pub move16: u16,
+    /// The ply of the position in the game.
+    pub game_ply: u16,
+    /// The result of the game from the viewpoint of the side to move: 1 for a win, 0 for a draw and -1 for a loss.
+    pub game_result: i8,
+}
+
+impl PackedSfenValue {
+    /// The size of a record in bytes.
+    pub const SIZE: usize = 40;
+
+    /// Reads a record. Integers are read in little endian.
+    pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Self {
+        let mut sfen = [0; 32];
+        sfen.copy_from_slice(&bytes[..32]);
+        Self {
+            sfen: PackedSfen(sfen),
+            score: i16::from_le_bytes([bytes[32], bytes[33]]),
+            move16: u16::from_le_bytes([bytes[34], bytes[35]]),
+            game_ply: u16::from_le_bytes([bytes[36], bytes[37]]),
+            game_result: bytes[38] as i8,
+        }
+    }
+
+    /// Reads a record from the beginning of `bytes`.
+    ///
+    /// Returns [`None`] if `bytes` is shorter than [`PackedSfenValue::SIZE`].
+    pub fn read(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.get(..Self::SIZE)?;
+        let mut buffer = [0; Self::SIZE];
+        buffer.copy_from_slice(bytes);
+        Some(Self::from_bytes(&buffer))
+    }
+
+    /// Writes `self`. Integers are written in little endian, and the last byte (padding) is 0.
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut result = [0; Self::SIZE];
+        result[..32].copy_from_slice(&self.sfen.0);
+        result[32..34].copy_from_slice(&self.score.to_le_bytes());
+        result[34..36].copy_from_slice(&self.move16.to_le_bytes());
+        result[36..38].copy_from_slice(&self.game_ply.to_le_bytes());
+        result[38] = self.game_result as u8;
+        result
+    }
+
+    /// Writes `self` to the beginning of `bytes`.
+    ///
+    /// Returns [`None`] if `bytes` is shorter than [`PackedSfenValue::SIZE`].
+    pub fn write(&self, bytes: &mut [u8]) -> Option<()> {
+        bytes
+            .get_mut(..Self::SIZE)?
+            .copy_from_slice(&self.to_bytes());
+        Some(())
+    }
+
+    /// Returns an iterator over the records in `bytes`, e.g. the contents of a file of training data.
+    /// Trailing bytes that do not make up a whole record are ignored.
+    pub fn iter_bytes(bytes: &[u8]) -> impl Iterator<Item = Self> + '_ {
+        bytes.chunks_exact(Self::SIZE).filter_map(Self::read)
+    }
+
+    /// Finds the best move, if any.
+    ///
+    /// In a move, bits 0-6 are the destination square and bits 7-13 the source square (see [`Square::index0`]).
+    /// For a drop, bits 7-13 are the piece kind (1: pawn, 2: lance, 3: knight, 4: silver, 5: bishop, 6: rook, 7: gold),
+    /// and bit 14 is set. Bit 15 is set for a promotion.
+    ///
+    /// Returns [`None`] if [`PackedSfenValue::move16`] is not a valid move, e.g. 0.
+    pub fn best_move(&self) -> Option<Move> {
+        let side = if self.sfen.0[0] & 1 == 0 {
+            Color::Black
+        } else {
+            Color::White
+        };
+        let to = Square::from_index0((self.move16 & 0x7f) as u8)?;
+        let from = ((self.move16 >> 7) & 0x7f) as u8;
+        let promote = self.move16 & 1 << 15 != 0;
+        if self.move16 & 1 << 14 != 0 {
+            if promote {
+                return None;
+            }
+            let piece_kind = *KINDS.get((from as usize).checked_sub(1)?)?;
+            return Some(Move::Drop {
+                piece: Piece::new(piece_kind, side),
+                to,
+            });
+        }
+        let from = Square::from_index0(from)?;
+        if from == to {
+            return None;
+        }
+        Some(Move::Normal { from, to, promote })
+    }
+
+    /// Sets the best move. If `mv` is [`None`], [`PackedSfenValue::move16`] is set to 0.
+    pub fn set_best_move(&mut self, mv: Option<Move>) {
+        self.move16 = match mv {
+            Some(Move::Normal { from, to, promote }) => {
+                to.index0() as u16 | (from.index0() as u16) << 7 | (promote as u16) << 15
+            }
+            Some(Move::Drop { piece, to }) => {
+                // A piece in hand is never a king.
+                let index = kind_index(piece.piece_kind()).unwrap_or(0);
+                to.index0() as u16 | (index as u16 + 1) << 7 | 1 << 14
+            }
+            None => 0,
+        };
+    }
+}
+
+impl_ord_with_fields!(PackedSfenValue; sfen, score, move16, game_ply, game_result);
+impl_hash_with_fields!(PackedSfenValue; sfen, score, move16, game_ply, game_result);
+
+/// An iterator over the records read from a file of training data. Created by [`PackedSfenValueReader::new`].
+///
+/// Since: 0.1.6
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub struct PackedSfenValueReader<R> {
+    reader: R,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> PackedSfenValueReader<R> {
+    /// Creates an iterator over the records read from `reader`.
+    /// If `reader` ends in the middle of a record, the iterator yields an error of kind [`std::io::ErrorKind::UnexpectedEof`].
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Iterator for PackedSfenValueReader<R> {
+    type Item = std::io::Result<PackedSfenValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buffer = [0; PackedSfenValue::SIZE];
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match self.reader.read(&mut buffer[filled..]) {
+                Ok(0) if filled == 0 => return None,
+                Ok(0) => return Some(Err(std::io::ErrorKind::UnexpectedEof.into())),
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        Some(Ok(PackedSfenValue::from_bytes(&buffer)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packed_sfen_works() {
+        let sfens = [
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+            "l6nl/5+P1gk/2np1S3/p1p4Pp/3P2Sp1/1PPb2P1P/P5GS1/R8/LN4bKL w RGgsn5p 1",
+            "4k3l/9/9/9/9/9/9/9/4K4 b RBrb4g4s4n3l18p 1",
+        ];
+        for sfen in sfens {
+            let position = PartialPosition::from_sfen(sfen).unwrap();
+            let packed = PackedSfen::from_position(&position).unwrap();
+            assert_eq!(packed.to_position(), Some(position));
+        }
+        // Missing pieces
+        for sfen in [
+            "4k4/9/9/9/9/9/9/9/4K4 b - 1",
+            "8l/9/9/9/9/9/9/9/9 b RBrb4g4s4n3l18p 1",
+        ] {
+            let position = PartialPosition::from_sfen(sfen).unwrap();
+            assert_eq!(PackedSfen::from_position(&position), None);
+        }
+
+        let packed = PackedSfen::from_position(&PartialPosition::startpos()).unwrap();
+        // The side to move is black, and the black king is on 5i (index 44).
+        assert_eq!(packed.0[0], 44 << 1);
+    }
+
+    #[test]
+    fn best_move_works() {
+        let mut record = PackedSfenValue {
+            sfen: PackedSfen([0; 32]),
+            score: -100,
+            move16: 0,
+            game_ply: 10,
+            game_result: -1,
+        };
+        assert_eq!(record.best_move(), None);
+        let moves = [
+            Move::Normal {
+                from: Square::SQ_8H,
+                to: Square::SQ_2B,
+                promote: true,
+            },
+            Move::Drop {
+                piece: Piece::B_G,
+                to: Square::SQ_5E,
+            },
+        ];
+        for mv in moves {
+            record.set_best_move(Some(mv));
+            assert_eq!(record.best_move(), Some(mv));
+        }
+        // Gold is 7, and 5e is 40.
+        assert_eq!(record.move16, 1 << 14 | 7 << 7 | 40);
+        let bytes = record.to_bytes();
+        assert_eq!(bytes[32..], [0x9c, 0xff, 0xa8, 0x43, 10, 0, 0xff, 0]);
+        assert_eq!(PackedSfenValue::read(&bytes[..39]), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn reader_works() {
+        let record = PackedSfenValue::from_bytes(&[1; 40]);
+        let mut data = [record.to_bytes(), record.to_bytes()].concat();
+        data.push(0);
+        let mut reader = PackedSfenValueReader::new(&data[..]);
+        assert_eq!(reader.next().unwrap().unwrap(), record);
+        assert_eq!(reader.next().unwrap().unwrap(), record);
+        assert_eq!(
+            reader.next().unwrap().unwrap_err().kind(),
+            std::io::ErrorKind::UnexpectedEof
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Move path enumeration (perft) for validating implementors of [`LegalityChecker`].
+//!
+//! [`perft`] counts the leaf nodes of the game tree of a given depth, and [`divide`] breaks them down by the first move.
+//! Comparing the results with known reference numbers is the standard way to find bugs in move generators.
+
+use alloc::vec::Vec;
+
+use crate::{LegalityChecker, Move, PartialPosition};
+
+/// Statistics of the leaf nodes of a game tree.
+///
+/// Except for `nodes`, the fields count the last moves leading to leaf nodes, as is customary for perft.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct PerftStats {
+    /// The number of leaf nodes.
+    pub nodes: u64,
+    /// The number of leaf nodes reached by a capture.
+    pub captures: u64,
+    /// The number of leaf nodes reached by a drop.
+    pub drops: u64,
+    /// The number of leaf nodes reached by a promotion.
+    pub promotions: u64,
+    /// The number of leaf nodes where the side to move is in check.
+    pub checks: u64,
+}
+
+impl PerftStats {
+    fn add(&mut self, other: &Self) {
+        self.nodes += other.nodes;
+        self.captures += other.captures;
+        self.drops += other.drops;
+        self.promotions += other.promotions;
+        self.checks += other.checks;
+    }
+}
+
+impl_ord_with_fields!(PerftStats; nodes, captures, drops, promotions, checks);
+impl_hash_with_fields!(PerftStats; nodes, captures, drops, promotions, checks);
+
+/// Counts the leaf nodes reachable from `position` in exactly `depth` legal moves.
+///
+/// Legal moves are generated by [`LegalityChecker::all_legal_moves_partial`].
+/// If `depth` is 0, `position` itself is the only leaf node, and no moves are counted.
+pub fn perft<C: LegalityChecker + ?Sized>(
+    position: &PartialPosition,
+    checker: &C,
+    depth: u32,
+) -> PerftStats {
+    let mut result = PerftStats::default();
+    if depth == 0 {
+        result.nodes = 1;
+        return result;
+    }
+    for mv in checker.all_legal_moves_partial(position) {
+        result.add(&perft_move(position, checker, depth, mv));
+    }
+    result
+}
+
+/// Counts the leaf nodes reachable from `position` in exactly `depth` legal moves, for each legal move in `position`.
+///
+/// The sum of the returned statistics equals `perft(position, checker, depth)`.
+/// If `depth` is 0, the result is empty.
+pub fn divide<C: LegalityChecker + ?Sized>(
+    position: &PartialPosition,
+    checker: &C,
+    depth: u32,
+) -> Vec<(Move, PerftStats)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+    checker
+        .all_legal_moves_partial(position)
+        .into_iter()
+        .map(|mv| (mv, perft_move(position, checker, depth, mv)))
+        .collect()
+}
+
+// Counts the leaf nodes under `mv`, where `depth` includes `mv` itself.
+fn perft_move<C: LegalityChecker + ?Sized>(
+    position: &PartialPosition,
+    checker: &C,
+    depth: u32,
+    mv: Move,
+) -> PerftStats {
+    let mut next = position.clone();
+    if next.make_move(mv).is_none() {
+        return PerftStats::default();
+    }
+    if depth > 1 {
+        return perft(&next, checker, depth - 1);
+    }
+    let (capture, drop, promotion) = match mv {
+        Move::Normal { to, promote, .. } => (position.piece_at(to).is_some(), false, promote),
+        Move::Drop { .. } => (false, true, false),
+    };
+    PerftStats {
+        nodes: 1,
+        captures: capture as u64,
+        drops: drop as u64,
+        promotions: promotion as u64,
+        checks: next.is_in_check() as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_checker::TestChecker;
+    use crate::{Piece, PieceKind, Square};
+
+    // Only kings move, one step in any direction to a square not occupied by the player's own pieces.
+    const KINGS_ONLY: TestChecker = TestChecker {
+        movable: |piece_kind| piece_kind == PieceKind::King,
+        drops: false,
+        ..TestChecker::PSEUDO_LEGAL
+    };
+
+    #[test]
+    fn perft_works() {
+        let mut position = PartialPosition::empty();
+        position.piece_set(Square::SQ_5I, Some(Piece::B_K));
+        position.piece_set(Square::SQ_5A, Some(Piece::W_K));
+        assert_eq!(perft(&position, &KINGS_ONLY, 0).nodes, 1);
+        assert_eq!(perft(&position, &KINGS_ONLY, 1).nodes, 5);
+        assert_eq!(perft(&position, &KINGS_ONLY, 2).nodes, 25);
+
+        // The rook on 5i is blocked by the king on 5h.
+        // Every king move except to 5g gives a discovered check, and the king can capture the pawn on 4g.
+        let mut position = PartialPosition::empty();
+        position.piece_set(Square::SQ_5H, Some(Piece::B_K));
+        position.piece_set(Square::SQ_5I, Some(Piece::B_R));
+        position.piece_set(Square::SQ_4G, Some(Piece::W_P));
+        position.piece_set(Square::SQ_5A, Some(Piece::W_K));
+        assert_eq!(
+            perft(&position, &KINGS_ONLY, 1),
+            PerftStats {
+                nodes: 7,
+                captures: 1,
+                drops: 0,
+                promotions: 0,
+                checks: 6,
+            },
+        );
+    }
+
+    #[test]
+    fn divide_works() {
+        let position = PartialPosition::startpos();
+        let result = divide(&position, &KINGS_ONLY, 2);
+        // Both kings can go to three squares.
+        assert_eq!(result.len(), 3);
+        let mut total = PerftStats::default();
+        for (mv, stats) in &result {
+            assert!(matches!(
+                mv,
+                Move::Normal {
+                    from: Square::SQ_5I,
+                    ..
+                }
+            ));
+            assert_eq!(stats.nodes, 3);
+            total.add(stats);
+        }
+        assert_eq!(total, perft(&position, &KINGS_ONLY, 2));
+        assert!(divide(&position, &KINGS_ONLY, 0).is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use core::num::NonZeroU8;
 
-use crate::common::write_ascii_byte;
-use crate::{Color, PieceKind, ToUsi};
+use crate::common::{c_bytes, write_ascii_byte, write_nul_terminated};
+use crate::{Color, OutOfRangeError, PieceKind, ToUsi, UsiParseError};
 
 /// A piece + who owns it.
 ///
@@ -41,17 +41,20 @@ impl Piece {
                 Color::White => 16,
             };
         // Safety: disc > 0 always holds
-        Piece(unsafe { NonZeroU8::new_unchecked(value) })
+        Piece(unsafe { crate::common::nonzero_u8_unchecked(value) })
     }
     /// C interface to [`Piece::new`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Piece_new(piece_kind: PieceKind, color: Color) -> Self {
         Self::new(piece_kind, color)
     }
     /// An inverse of [`Piece::new`]. Finds a [`PieceKind`] and a [`Color`] from a [`Piece`].
+    ///
+    /// `const`: since 0.1.6
     #[must_use]
     #[inline(always)]
-    pub fn to_parts(self) -> (PieceKind, Color) {
+    pub const fn to_parts(self) -> (PieceKind, Color) {
         let data = self.0.get();
         let disc = data & 15;
         (
@@ -66,23 +69,25 @@ impl Piece {
     }
     /// Finds the [`PieceKind`] of this piece.
     #[must_use]
-    #[export_name = "Piece_piece_kind"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "Piece_piece_kind")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn piece_kind(self) -> PieceKind {
         self.to_parts().0
     }
     /// Finds the [`Color`] of this piece.
     #[must_use]
-    #[export_name = "Piece_color"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "Piece_color")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn color(self) -> Color {
         self.to_parts().1
     }
 
     /// Returns the internal representation.
     #[must_use]
+    ///
+    /// `const`: since 0.1.6
     #[inline(always)]
-    pub fn as_u8(self) -> u8 {
+    pub const fn as_u8(self) -> u8 {
         self.0.get()
     }
 
@@ -94,8 +99,9 @@ impl Piece {
     }
 
     /// C interface of [`Piece::promote`].
-    #[no_mangle]
-    #[inline(always)]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn Piece_promote(self) -> OptionPiece {
         OptionPiece::from(self.promote())
     }
@@ -108,16 +114,47 @@ impl Piece {
     }
 
     /// C interface of [`Piece::unpromote`].
-    #[no_mangle]
-    #[inline(always)]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn Piece_unpromote(self) -> OptionPiece {
         OptionPiece::from(self.unpromote())
     }
 
+    /// Returns the promoted version of `self`, or `self` if it cannot promote. Same as [`PieceKind::promoted_or_self`] with color.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::Piece;
+    /// assert_eq!(Piece::W_P.promoted_or_self(), Piece::W_PP);
+    /// assert_eq!(Piece::B_K.promoted_or_self(), Piece::B_K);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[must_use]
+    pub fn promoted_or_self(self) -> Piece {
+        self.promote().unwrap_or(self)
+    }
+
+    /// Returns the un-promoted version of `self`, or `self` if it is not a promoted piece. Same as [`PieceKind::unpromoted_or_self`] with color.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::Piece;
+    /// assert_eq!(Piece::B_PR.unpromoted_or_self(), Piece::B_R);
+    /// assert_eq!(Piece::W_G.unpromoted_or_self(), Piece::W_G);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[must_use]
+    pub fn unpromoted_or_self(self) -> Piece {
+        self.unpromote().unwrap_or(self)
+    }
+
     /// `value` must be in range 1..=14 or 17..=30.
     #[inline(always)]
     pub(crate) unsafe fn from_u8_unchecked(value: u8) -> Self {
-        Self(NonZeroU8::new_unchecked(value))
+        Self(crate::common::nonzero_u8_unchecked(value))
     }
 
     /// Returns the index of `self` for array accesses. This function returns an integer in range `0..Piece::MAX`.
@@ -129,7 +166,7 @@ impl Piece {
     pub const fn array_index(self) -> usize {
         let result = self.0.get() as usize - 1;
         if result >= Self::NUM {
-            unsafe { core::hint::unreachable_unchecked() };
+            unsafe { crate::common::unreachable_unchecked() };
         }
         result
     }
@@ -161,7 +198,8 @@ impl Piece {
         for &piece_kind in &piece_kinds {
             for &color in &colors {
                 // Safety: 0 <= index < 28
-                *unsafe { result.get_unchecked_mut(index) } = Piece::new(piece_kind, color);
+                *unsafe { crate::common::get_unchecked_mut(&mut result, index) } =
+                    Piece::new(piece_kind, color);
                 index += 1;
             }
         }
@@ -171,10 +209,43 @@ impl Piece {
 
 impl_ord_for_single_field!(Piece);
 impl_hash_for_single_field!(Piece);
+impl_rkyv_as_self!(Piece);
 
-impl From<Option<Piece>> for OptionPiece {
+/// Converts a [`u8`] to a [`Piece`]. Fails if `value` is not in range `1..=14` or `17..=30`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{OutOfRangeError, Piece};
+/// assert_eq!(Piece::try_from(1u8), Ok(Piece::B_P));
+/// assert_eq!(Piece::try_from(17u8), Ok(Piece::W_P));
+/// assert_eq!(Piece::try_from(16u8), Err(OutOfRangeError));
+/// ```
+impl TryFrom<u8> for Piece {
+    type Error = OutOfRangeError;
+
+    #[inline]
+    fn try_from(value: u8) -> Result<Self, OutOfRangeError> {
+        if matches!(value, 1..=14 | 17..=30) {
+            // Safety: `value` is in range 1..=14 or 17..=30
+            Ok(unsafe { Piece::from_u8_unchecked(value) })
+        } else {
+            Err(OutOfRangeError)
+        }
+    }
+}
+
+/// Converts a [`Piece`] to its representation, as [`Piece::as_u8`] does.
+impl From<Piece> for u8 {
     #[inline(always)]
-    fn from(arg: Option<Piece>) -> Self {
+    fn from(piece: Piece) -> Self {
+        piece.as_u8()
+    }
+}
+
+impl OptionPiece {
+    // A `const` version of `From<Option<Piece>>`.
+    #[inline(always)]
+    pub(crate) const fn from_option(arg: Option<Piece>) -> Self {
         Self(match arg {
             Some(result) => result.0.get(),
             None => 0,
@@ -182,15 +253,181 @@ impl From<Option<Piece>> for OptionPiece {
     }
 }
 
+impl From<Option<Piece>> for OptionPiece {
+    #[inline(always)]
+    fn from(arg: Option<Piece>) -> Self {
+        Self::from_option(arg)
+    }
+}
+
+impl OptionPiece {
+    // A `const` version of `From<OptionPiece>` for <code>[Option]<[Piece]></code>.
+    #[inline(always)]
+    pub(crate) const fn to_option(self) -> Option<Piece> {
+        match NonZeroU8::new(self.0) {
+            Some(value) => Some(Piece(value)),
+            None => None,
+        }
+    }
+}
+
 impl From<OptionPiece> for Option<Piece> {
     #[inline(always)]
     fn from(arg: OptionPiece) -> Self {
-        Some(Piece(NonZeroU8::new(arg.0)?))
+        arg.to_option()
+    }
+}
+
+impl OptionPiece {
+    // Converts `self` to <code>[Option]<[Piece]></code>, also returning `None` if `self` holds an invalid value.
+    // C callers can create such values because the representation is a plain `uint8_t`.
+    #[inline]
+    pub(crate) fn to_checked_option(self) -> Option<Piece> {
+        if matches!(self.0, 1..=14 | 17..=30) {
+            self.to_option()
+        } else {
+            None
+        }
+    }
+}
+
+impl OptionPiece {
+    /// Returns true if and only if `self` holds a piece, i.e., `self` is not the representation of [`None`].
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn OptionPiece_is_some(self) -> bool {
+        self.0 != 0
+    }
+
+    /// Returns true if and only if `self` is the representation of [`None`].
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn OptionPiece_is_none(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the [`Piece`] held by `self` without checking.
+    ///
+    /// # Safety
+    /// `self` must hold a piece, i.e., [`OptionPiece::OptionPiece_is_some`] must return true.
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn OptionPiece_unwrap_unchecked(self) -> Piece {
+        crate::common::unwrap_unchecked(self.into())
     }
 }
 
 impl_ord_for_single_field!(OptionPiece);
 impl_hash_for_single_field!(OptionPiece);
+impl_rkyv_as_self!(OptionPiece);
+
+impl Piece {
+    /// Parses an unpromoted piece in SFEN format, e.g. `P` for a black pawn or `r` for a white rook.
+    ///
+    /// Promoted pieces are written in two characters (e.g. `+r`); use [`Piece::from_sfen_chars`] for them.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::Piece;
+    /// assert_eq!(Piece::from_sfen_char('P'), Some(Piece::B_P));
+    /// assert_eq!(Piece::from_sfen_char('r'), Some(Piece::W_R));
+    /// assert_eq!(Piece::from_sfen_char('x'), None);
+    /// assert_eq!(Piece::from_sfen_char('+'), None);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub const fn from_sfen_char(c: char) -> Option<Self> {
+        if !c.is_ascii() {
+            return None;
+        }
+        let byte = c as u8;
+        let color = if byte.is_ascii_lowercase() {
+            Color::White
+        } else {
+            Color::Black
+        };
+        match PieceKind::parse_usi_byte(byte.to_ascii_uppercase()) {
+            Some(piece_kind) => Some(Piece::new(piece_kind, color)),
+            None => None,
+        }
+    }
+
+    /// Parses a promoted piece in SFEN format, given as two characters, e.g. `['+', 'r']` for a white dragon.
+    ///
+    /// Returns [`None`] if the first character is not `+` or the second one is not a piece that can promote.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::Piece;
+    /// assert_eq!(Piece::from_sfen_chars(['+', 'P']), Some(Piece::B_PP));
+    /// assert_eq!(Piece::from_sfen_chars(['+', 'r']), Some(Piece::W_PR));
+    /// assert_eq!(Piece::from_sfen_chars(['+', 'G']), None);
+    /// assert_eq!(Piece::from_sfen_chars(['-', 'P']), None);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub const fn from_sfen_chars(chars: [char; 2]) -> Option<Self> {
+        if chars[0] != '+' || !chars[1].is_ascii() {
+            return None;
+        }
+        let byte = chars[1] as u8;
+        let color = if byte.is_ascii_lowercase() {
+            Color::White
+        } else {
+            Color::Black
+        };
+        let piece_kind = match PieceKind::parse_usi_byte(byte.to_ascii_uppercase()) {
+            Some(piece_kind) => piece_kind,
+            None => return None,
+        };
+        match piece_kind.promote() {
+            Some(promoted) => Some(Piece::new(promoted, color)),
+            None => None,
+        }
+    }
+
+    /// Parses a piece in USI format, e.g. `P`, `+r`.
+    pub(crate) fn parse_usi_bytes(bytes: &[u8]) -> Option<Self> {
+        match *bytes {
+            [byte] => Self::from_sfen_char(byte as char),
+            [b'+', byte] => Self::from_sfen_chars(['+', byte as char]),
+            _ => None,
+        }
+    }
+
+    /// C interface of [`ToUsi::to_usi`].
+    /// Writes the USI representation of `self` to `ptr` followed by a nul byte.
+    ///
+    /// Returns the number of bytes written excluding the nul byte.
+    /// If `capacity` is not large enough, this function returns 0. A capacity of 3 is always sufficient.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for writes of `capacity` bytes.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn Piece_to_usi_c(self, ptr: *mut u8, capacity: usize) -> usize {
+        write_nul_terminated(ptr, capacity, |sink| self.to_usi(sink))
+    }
+
+    /// Parses a piece in USI format (e.g. `P`, `+r`) from `len` bytes starting at `ptr`.
+    /// The bytes need not be nul-terminated.
+    ///
+    /// If the bytes are not a valid piece, this function returns `0` (`None`).
+    ///
+    /// # Safety
+    /// `ptr` must be null or valid for reads of `len` bytes.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn Piece_parse_c(ptr: *const u8, len: usize) -> OptionPiece {
+        Self::parse_usi_bytes(c_bytes(ptr, len)).into()
+    }
+}
 
 impl ToUsi for Piece {
     fn to_usi<W: core::fmt::Write>(&self, sink: &mut W) -> core::fmt::Result {
@@ -207,7 +444,10 @@ impl ToUsi for Piece {
                 debug_assert!(index < 12);
                 // Safety: table has only ASCII bytes, index < 12
                 sink.write_str(unsafe {
-                    core::str::from_utf8_unchecked(table.get_unchecked(2 * index..2 * index + 2))
+                    crate::common::str_from_utf8_unchecked(crate::common::get_unchecked(
+                        table,
+                        2 * index..2 * index + 2,
+                    ))
                 })
             }
             (piece_kind, color) => {
@@ -218,7 +458,9 @@ impl ToUsi for Piece {
                     Color::White => 8,
                 };
                 // Safety: 1 <= offset + piece_kind <= 16
-                let c = *unsafe { symbols.get_unchecked(offset + piece_kind as usize - 1) };
+                let c = *unsafe {
+                    crate::common::get_unchecked(symbols, offset + piece_kind as usize - 1)
+                };
                 // Safety: the written byte is in ASCII for every branch
                 unsafe { write_ascii_byte(sink, c) }
             }
@@ -226,10 +468,71 @@ impl ToUsi for Piece {
     }
 }
 
+/// Displays a piece in USI format, e.g. `P` or `+r`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::Piece;
+/// assert_eq!(format!("{}", Piece::W_PR), "+r");
+/// ```
+///
+/// Since: 0.1.6
+impl core::fmt::Display for Piece {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.to_usi(f)
+    }
+}
+
+/// Parses a piece in USI format, e.g. `P` or `+r`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::Piece;
+/// assert_eq!("+r".parse::<Piece>(), Ok(Piece::W_PR));
+/// assert!("x".parse::<Piece>().is_err());
+/// ```
+///
+/// Since: 0.1.6
+impl core::str::FromStr for Piece {
+    type Err = UsiParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_usi_bytes(s.as_bytes()).ok_or(UsiParseError)
+    }
+}
+
+/// Formats a piece in USI format, e.g. `P` or `+r`.
+#[cfg(feature = "defmt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt")))]
+impl defmt::Format for Piece {
+    fn format(&self, f: defmt::Formatter) {
+        let (piece_kind, color) = self.to_parts();
+        let (prefix, base) = match piece_kind.unpromote() {
+            Some(base) => ("+", base),
+            None => ("", piece_kind),
+        };
+        let c = b"PLNSGBRK"[base as usize - 1];
+        let c = match color {
+            Color::Black => c,
+            Color::White => c.to_ascii_lowercase(),
+        };
+        defmt::write!(f, "{=str}{=char}", prefix, c as char);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn u8_conversions_work() {
+        for piece in Piece::all() {
+            assert_eq!(Piece::try_from(u8::from(piece)), Ok(piece));
+        }
+        let valid = (0..=255u8).filter(|&x| Piece::try_from(x).is_ok()).count();
+        assert_eq!(valid, 28);
+    }
+
     #[test]
     fn to_parts_works() {
         let piece_kinds = PieceKind::all();
@@ -244,6 +547,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_usi_bytes_works() {
+        for piece in Piece::all() {
+            let usi = piece.to_usi_owned();
+            assert_eq!(Piece::parse_usi_bytes(usi.as_bytes()), Some(piece));
+            let mut buf = [0u8; 3];
+            let written = unsafe { piece.Piece_to_usi_c(buf.as_mut_ptr(), buf.len()) };
+            assert_eq!(&buf[..written], usi.as_bytes());
+            assert_eq!(buf[written], 0);
+        }
+        for invalid in [&b""[..], b"+", b"+k", b"+G", b"x", b"pp", b"P+"] {
+            assert_eq!(Piece::parse_usi_bytes(invalid), None);
+        }
+    }
+
+    #[test]
+    fn from_sfen_char_works() {
+        for piece in Piece::all() {
+            let usi = piece.to_usi_owned();
+            let chars: alloc::vec::Vec<char> = usi.chars().collect();
+            let parsed = match *chars {
+                [c] => Piece::from_sfen_char(c),
+                [first, second] => Piece::from_sfen_chars([first, second]),
+                _ => unreachable!(),
+            };
+            assert_eq!(parsed, Some(piece));
+        }
+        assert_eq!(Piece::from_sfen_char('\u{ff30}'), None);
+        assert_eq!(Piece::from_sfen_chars(['+', '\u{ff30}']), None);
+        assert_eq!(Piece::from_sfen_chars(['+', '+']), None);
+        const ROOK: Option<Piece> = Piece::from_sfen_char('R');
+        assert_eq!(ROOK, Some(Piece::B_R));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn display_and_from_str_work() {
+        use alloc::string::ToString;
+        for piece in Piece::all() {
+            let s = piece.to_string();
+            assert_eq!(s, piece.to_usi_owned());
+            assert_eq!(s.parse::<Piece>(), Ok(piece));
+            let piece_kind = piece.piece_kind();
+            assert_eq!(piece_kind.to_string().parse::<PieceKind>(), Ok(piece_kind));
+        }
+        assert_eq!("+k".parse::<Piece>(), Err(UsiParseError));
+        assert_eq!("".parse::<PieceKind>(), Err(UsiParseError));
+    }
+
     // reference implementation
     fn to_usi_reference<W: core::fmt::Write>(this: &Piece, sink: &mut W) -> core::fmt::Result {
         let (piece_kind, color) = this.to_parts();
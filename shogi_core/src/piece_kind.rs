@@ -1,4 +1,4 @@
-use crate::ToUsi;
+use crate::{OutOfRangeError, ToUsi, UsiParseError};
 
 /// Kinds of pieces.
 ///
@@ -6,6 +6,7 @@ use crate::ToUsi;
 /// Because they are cheap to copy, they implement [`Copy`].
 #[repr(u8)]
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum PieceKind {
     /// A pawn. Unlike in chess, it always moves one square forward,
     /// even if the destination square is occuipied by an enemy piece.
@@ -111,9 +112,11 @@ impl PieceKind {
     /// Returns the promoted version of `self`.
     ///
     /// If `self` cannot promote, this function returns [`None`].
+    ///
+    /// `const`: since 0.1.6
     #[must_use]
     #[inline]
-    pub fn promote(self) -> Option<Self> {
+    pub const fn promote(self) -> Option<Self> {
         match self {
             PieceKind::Pawn => Some(PieceKind::ProPawn),
             PieceKind::Lance => Some(PieceKind::ProLance),
@@ -155,6 +158,41 @@ impl PieceKind {
         }
     }
 
+    /// Returns the promoted version of `self`, or `self` if it cannot promote.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PieceKind;
+    /// assert_eq!(PieceKind::Silver.promoted_or_self(), PieceKind::ProSilver);
+    /// assert_eq!(PieceKind::Gold.promoted_or_self(), PieceKind::Gold);
+    /// assert_eq!(PieceKind::ProRook.promoted_or_self(), PieceKind::ProRook);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[must_use]
+    #[inline]
+    pub fn promoted_or_self(self) -> Self {
+        self.promote().unwrap_or(self)
+    }
+
+    /// Returns the un-promoted version of `self`, or `self` if it is not a promoted piece.
+    ///
+    /// This is the kind of piece a player obtains when capturing `self`, unless `self` is a king.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PieceKind;
+    /// assert_eq!(PieceKind::ProBishop.unpromoted_or_self(), PieceKind::Bishop);
+    /// assert_eq!(PieceKind::Knight.unpromoted_or_self(), PieceKind::Knight);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[must_use]
+    #[inline]
+    pub fn unpromoted_or_self(self) -> Self {
+        self.unpromote().unwrap_or(self)
+    }
+
     /// Converts a [`u8`] to [`PieceKind`] if possible.
     ///
     /// If `repr` is a valid representation of [`PieceKind`], this function returns `Some(piece_kind)`.
@@ -171,7 +209,8 @@ impl PieceKind {
     }
 
     /// C interface of [`PieceKind::from_u8`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn PieceKind_from_u8(repr: u8) -> OptionPieceKind {
         Self::from_u8(repr).into()
     }
@@ -185,6 +224,9 @@ impl PieceKind {
     /// `const`: since 0.1.2
     #[inline(always)]
     pub const unsafe fn from_u8_unchecked(repr: u8) -> Self {
+        if !matches!(repr, 1..=14) {
+            crate::common::unreachable_unchecked();
+        }
         core::mem::transmute(repr)
     }
 
@@ -193,22 +235,26 @@ impl PieceKind {
     /// # Safety
     /// `repr` must be a valid representation of [`PieceKind`].
     /// This condition is equivalent to `1 <= repr && repr <= 14`.
-    #[no_mangle]
-    #[inline(always)]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub unsafe extern "C" fn PieceKind_from_u8_unchecked(repr: u8) -> Self {
+        if !matches!(repr, 1..=14) {
+            crate::common::unreachable_unchecked();
+        }
         core::mem::transmute(repr)
     }
 
     /// C interface of [`PieceKind::promote`].
     #[allow(non_snake_case)]
-    #[no_mangle]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn PieceKind_promote(self) -> OptionPieceKind {
         self.promote().into()
     }
 
     /// C interface of [`PieceKind::unpromote`].
     #[allow(non_snake_case)]
-    #[no_mangle]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn PieceKind_unpromote(self) -> OptionPieceKind {
         self.unpromote().into()
     }
@@ -221,7 +267,7 @@ impl PieceKind {
         let result = self as usize - 1;
         // Safety: 0 <= self as usize - 1 <= 13 always holds
         if result >= 14 {
-            unsafe { core::hint::unreachable_unchecked() };
+            unsafe { crate::common::unreachable_unchecked() };
         }
         result
     }
@@ -274,10 +320,97 @@ impl PieceKind {
             PieceKind::ProRook,
         ]
     }
+
+    /// All [`PieceKind`]s that can promote, in the ascending order of their discriminants.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PieceKind;
+    /// let promotable = PieceKind::all().into_iter().filter(|piece_kind| piece_kind.promote().is_some());
+    /// assert!(promotable.eq(PieceKind::PROMOTABLE));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub const PROMOTABLE: [Self; 6] = [
+        PieceKind::Pawn,
+        PieceKind::Lance,
+        PieceKind::Knight,
+        PieceKind::Silver,
+        PieceKind::Bishop,
+        PieceKind::Rook,
+    ];
+
+    /// All promoted [`PieceKind`]s, in the ascending order of their discriminants.
+    /// The `i`-th element is the promoted version of `PieceKind::PROMOTABLE[i]`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PieceKind;
+    /// let promoted = PieceKind::all().into_iter().filter(|piece_kind| piece_kind.unpromote().is_some());
+    /// assert!(promoted.eq(PieceKind::PROMOTED));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub const PROMOTED: [Self; 6] = [
+        PieceKind::ProPawn,
+        PieceKind::ProLance,
+        PieceKind::ProKnight,
+        PieceKind::ProSilver,
+        PieceKind::ProBishop,
+        PieceKind::ProRook,
+    ];
+
+    /// Returns all pairs of a [`PieceKind`] and its promoted version,
+    /// in the ascending order of the discriminants of the former.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PieceKind;
+    /// // Promotion bonuses for an evaluation function
+    /// let mut bonus = [0; PieceKind::NUM];
+    /// for (unpromoted, promoted) in PieceKind::promotion_pairs() {
+    ///     bonus[promoted.array_index()] = 100 - unpromoted as i32 * 10;
+    /// }
+    /// assert_eq!(bonus[PieceKind::ProPawn.array_index()], 90);
+    /// assert_eq!(PieceKind::promotion_pairs().count(), 6);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn promotion_pairs() -> impl Iterator<Item = (Self, Self)> {
+        Self::PROMOTABLE.into_iter().zip(Self::PROMOTED)
+    }
 }
 
 impl_ord_for_fieldless_enum!(PieceKind);
 impl_hash_for_fieldless_enum!(PieceKind);
+impl_rkyv_as_self!(PieceKind);
+
+/// Converts a [`u8`] to a [`PieceKind`] in the same way as [`PieceKind::from_u8`].
+/// Fails if `value` is not in range `1..=14`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{OutOfRangeError, PieceKind};
+/// assert_eq!(PieceKind::try_from(8u8), Ok(PieceKind::King));
+/// assert_eq!(PieceKind::try_from(15u8), Err(OutOfRangeError));
+/// assert_eq!(u8::from(PieceKind::Pawn), 1);
+/// ```
+impl TryFrom<u8> for PieceKind {
+    type Error = OutOfRangeError;
+
+    #[inline]
+    fn try_from(value: u8) -> Result<Self, OutOfRangeError> {
+        PieceKind::from_u8(value).ok_or(OutOfRangeError)
+    }
+}
+
+/// Converts a [`PieceKind`] to its representation.
+impl From<PieceKind> for u8 {
+    #[inline(always)]
+    fn from(piece_kind: PieceKind) -> Self {
+        piece_kind as u8
+    }
+}
 
 /// USI representation of a piece kind.
 ///
@@ -291,19 +424,100 @@ impl ToUsi for PieceKind {
             debug_assert!(index < 12);
             // Safety: table has only ASCII bytes, index < 6
             sink.write_str(unsafe {
-                core::str::from_utf8_unchecked(table.get_unchecked(2 * index..2 * index + 2))
+                crate::common::str_from_utf8_unchecked(crate::common::get_unchecked(
+                    table,
+                    2 * index..2 * index + 2,
+                ))
             })
         } else {
             debug_assert!(piece_kind as u8 <= PieceKind::King as u8);
             let symbols = b"PLNSGBRK";
             // Safety: 1 <= piece_kind <= 8
-            let c = *unsafe { symbols.get_unchecked(piece_kind as usize - 1) };
+            let c = *unsafe { crate::common::get_unchecked(symbols, piece_kind as usize - 1) };
             // Safety: the written byte is in ASCII for every branch
             unsafe { crate::common::write_ascii_byte(sink, c) }
         }
     }
 }
 
+/// Displays a piece kind in USI format, e.g. `P` or `+R`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::PieceKind;
+/// assert_eq!(format!("{}", PieceKind::ProRook), "+R");
+/// ```
+///
+/// Since: 0.1.6
+impl core::fmt::Display for PieceKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.to_usi(f)
+    }
+}
+
+/// Parses a piece kind in USI format, e.g. `P` or `+R`. Only uppercase letters are accepted.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::PieceKind;
+/// assert_eq!("+R".parse::<PieceKind>(), Ok(PieceKind::ProRook));
+/// assert!("+K".parse::<PieceKind>().is_err());
+/// assert!("p".parse::<PieceKind>().is_err());
+/// ```
+///
+/// Since: 0.1.6
+impl core::str::FromStr for PieceKind {
+    type Err = UsiParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match *s.as_bytes() {
+            [byte] => Self::parse_usi_byte(byte),
+            [b'+', byte] => Self::parse_usi_byte(byte).and_then(Self::promote),
+            _ => None,
+        }
+        .ok_or(UsiParseError)
+    }
+}
+
+impl PieceKind {
+    /// Parses an unpromoted piece kind in USI format, e.g. `P` or `K`.
+    /// Only uppercase letters are accepted, because lowercase letters denote white's pieces in USI.
+    /// Promoted piece kinds are written in two characters (e.g. `+P`) and can be parsed with [`FromStr`](core::str::FromStr).
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PieceKind;
+    /// assert_eq!(PieceKind::from_usi_char('R'), Some(PieceKind::Rook));
+    /// assert_eq!(PieceKind::from_usi_char('K'), Some(PieceKind::King));
+    /// assert_eq!(PieceKind::from_usi_char('r'), None);
+    /// assert_eq!(PieceKind::from_usi_char('+'), None);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub const fn from_usi_char(c: char) -> Option<Self> {
+        if !c.is_ascii() {
+            return None;
+        }
+        Self::parse_usi_byte(c as u8)
+    }
+
+    /// Parses an unpromoted piece kind in USI format, e.g. `P` or `K`.
+    /// Only uppercase letters are accepted.
+    pub(crate) const fn parse_usi_byte(byte: u8) -> Option<Self> {
+        Some(match byte {
+            b'P' => PieceKind::Pawn,
+            b'L' => PieceKind::Lance,
+            b'N' => PieceKind::Knight,
+            b'S' => PieceKind::Silver,
+            b'G' => PieceKind::Gold,
+            b'B' => PieceKind::Bishop,
+            b'R' => PieceKind::Rook,
+            b'K' => PieceKind::King,
+            _ => return None,
+        })
+    }
+}
+
 /// <code>[Option]<[PieceKind]></code> with defined representation.
 ///
 /// The correspondence is:
@@ -341,6 +555,38 @@ impl From<OptionPieceKind> for Option<PieceKind> {
     }
 }
 
+impl OptionPieceKind {
+    /// Returns true if and only if `self` holds a piece kind, i.e., `self` is not the representation of [`None`].
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn OptionPieceKind_is_some(self) -> bool {
+        self.0 != 0
+    }
+
+    /// Returns true if and only if `self` is the representation of [`None`].
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn OptionPieceKind_is_none(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the [`PieceKind`] held by `self` without checking.
+    ///
+    /// # Safety
+    /// `self` must hold a piece kind, i.e., [`OptionPieceKind::OptionPieceKind_is_some`] must return true.
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn OptionPieceKind_unwrap_unchecked(self) -> PieceKind {
+        crate::common::unwrap_unchecked(self.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -360,6 +606,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn promotion_pairs_works() {
+        for (unpromoted, promoted) in PieceKind::promotion_pairs() {
+            assert_eq!(unpromoted.promote(), Some(promoted));
+            assert_eq!(promoted.unpromote(), Some(unpromoted));
+            assert_eq!(unpromoted.promoted_or_self(), promoted);
+            assert_eq!(promoted.promoted_or_self(), promoted);
+            assert_eq!(promoted.unpromoted_or_self(), unpromoted);
+            assert_eq!(unpromoted.unpromoted_or_self(), unpromoted);
+        }
+        for piece_kind in [PieceKind::Gold, PieceKind::King] {
+            assert_eq!(piece_kind.promoted_or_self(), piece_kind);
+            assert_eq!(piece_kind.unpromoted_or_self(), piece_kind);
+        }
+    }
+
     // reference implementation
     fn to_usi_reference<W: core::fmt::Write>(this: &PieceKind, sink: &mut W) -> core::fmt::Result {
         match *this {
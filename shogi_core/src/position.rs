@@ -2,39 +2,99 @@ use core::fmt::{Result as FmtResult, Write};
 use core::mem::MaybeUninit;
 
 use crate::c_compat::{OptionCompactMove, OptionGameResolution, OptionPiece, OptionSquare};
-use crate::common::{write_ascii_byte, write_u16, write_u8};
+#[cfg(feature = "alloc")]
+use crate::common::abort_on_panic;
+use crate::common::c_bytes;
+use crate::common::{write_ascii_byte, write_nul_terminated, write_u16};
+use crate::sfen_error::{SfenParseError, SfenParseErrorKind};
+use crate::zobrist;
 use crate::{
     Bitboard, Color, CompactMove, GameResolution, Hand, Move, Piece, PieceKind, Square, ToUsi,
 };
+#[cfg(feature = "alloc")]
+use crate::{
+    LegalityChecker, MoveAnnotation, PositionStatus, ResolutionDetail, ResolutionMismatch,
+    TimeControl,
+};
 
 /// A record of a game. A position and how a game is resolved.
 #[cfg(feature = "alloc")]
 #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
 #[derive(Eq, PartialEq, Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct Game {
     inner: Position,
     resolution: OptionGameResolution,
+    resolution_detail: Option<ResolutionDetail>,
+    time_control: Option<TimeControl>,
+    // `annotations[i]` is the annotation of `inner.moves()[i]`. Trailing `None`s are removed.
+    annotations: alloc::vec::Vec<Option<MoveAnnotation>>,
+    // `move_times[i]` is the time in milliseconds spent on `inner.moves()[i]`. Trailing `None`s are removed.
+    move_times: alloc::vec::Vec<Option<u32>>,
 }
 
 #[cfg(feature = "alloc")]
 impl Game {
+    /// Creates an unresolved [`Game`] with the given position.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Game, Position};
+    /// let game = Game::new(Position::startpos());
+    /// assert_eq!(game.position(), &Position::startpos());
+    /// assert_eq!(game.resolution(), None);
+    /// ```
+    pub fn new(position: Position) -> Self {
+        Self {
+            inner: position,
+            resolution: None.into(),
+            resolution_detail: None,
+            time_control: None,
+            annotations: alloc::vec::Vec::new(),
+            move_times: alloc::vec::Vec::new(),
+        }
+    }
     /// Returns the inner position.
-    #[export_name = "Game_position"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "Game_position")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn position(&self) -> &Position {
         &self.inner
     }
     /// Sets the resolution of this game.
-    #[export_name = "Game_resolve"]
-    #[inline(always)]
+    ///
+    /// The [`ResolutionDetail`] of this game is cleared.
+    #[cfg_attr(feature = "c-exports", export_name = "Game_resolve")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn resolve(&mut self, resolution: GameResolution) {
         self.resolution = Some(resolution).into();
+        self.resolution_detail = None;
+    }
+    /// Sets the resolution of this game with its detail.
+    /// The resolution is set to [`ResolutionDetail::resolution`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Game, GameResolution, Position, ResolutionDetail};
+    /// let mut game = Game::new(Position::startpos());
+    /// game.resolve_with_detail(ResolutionDetail::TimeUp(Color::Black));
+    /// assert_eq!(game.resolution(), Some(GameResolution::WhiteWins));
+    /// assert_eq!(game.resolution_detail(), Some(ResolutionDetail::TimeUp(Color::Black)));
+    /// game.resolve(GameResolution::WhiteWins);
+    /// assert_eq!(game.resolution_detail(), None);
+    /// ```
+    pub fn resolve_with_detail(&mut self, detail: ResolutionDetail) {
+        self.resolution = Some(detail.resolution()).into();
+        self.resolution_detail = Some(detail);
     }
     /// Unsets the resolution of this game.
-    #[export_name = "Game_unresolve"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "Game_unresolve")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn unresolve(&mut self) {
         self.resolution = None.into();
+        self.resolution_detail = None;
     }
     /// Returns the resolution of this game.
     #[inline(always)]
@@ -42,19 +102,313 @@ impl Game {
         self.resolution.into()
     }
     /// C interface to [`Game::resolution`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Game_resolution(&self) -> OptionGameResolution {
         self.resolution
     }
+    /// Returns why this game is resolved, if known.
+    #[inline(always)]
+    pub fn resolution_detail(&self) -> Option<ResolutionDetail> {
+        self.resolution_detail
+    }
+    /// Returns the time control of this game, if known.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Game, Position, TimeControl};
+    /// let mut game = Game::new(Position::startpos());
+    /// assert_eq!(game.time_control(), None);
+    /// game.time_control_set(Some(TimeControl::with_byoyomi(600_000, 10_000)));
+    /// assert_eq!(game.time_control().unwrap().byoyomi, 10_000);
+    /// ```
+    #[inline(always)]
+    pub fn time_control(&self) -> Option<TimeControl> {
+        self.time_control
+    }
+    /// Sets the time control of this game.
+    #[inline(always)]
+    pub fn time_control_set(&mut self, time_control: Option<TimeControl>) {
+        self.time_control = time_control;
+    }
+
+    /// Returns the annotation of the `index`-th move in [`Position::moves`], if any.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Game, Move, MoveAnnotation, Position, Square};
+    /// let mut position = Position::startpos();
+    /// position.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// let mut game = Game::new(position);
+    /// assert!(game.annotation_set(0, Some(MoveAnnotation::Score(50))));
+    /// assert_eq!(game.annotation(0), Some(MoveAnnotation::Score(50)));
+    /// // There is no second move.
+    /// assert!(!game.annotation_set(1, Some(MoveAnnotation::Blunder)));
+    /// assert_eq!(game.annotation(1), None);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn annotation(&self, index: usize) -> Option<MoveAnnotation> {
+        self.annotations.get(index).copied().flatten()
+    }
+
+    /// Sets or clears the annotation of the `index`-th move in [`Position::moves`].
+    ///
+    /// Returns `false` and does nothing if there is no such move.
+    ///
+    /// Since: 0.1.6
+    #[must_use]
+    pub fn annotation_set(&mut self, index: usize, annotation: Option<MoveAnnotation>) -> bool {
+        if index >= self.inner.moves().len() {
+            return false;
+        }
+        set_trimmed(&mut self.annotations, index, annotation);
+        true
+    }
+
+    /// Returns an iterator over the indices of annotated moves in [`Position::moves`] and their annotations, in ascending order of the indices.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Game, Move, MoveAnnotation, Position, Square};
+    /// let mut position = Position::startpos();
+    /// position.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// position.make_move(Move::Normal { from: Square::SQ_3C, to: Square::SQ_3D, promote: false }).unwrap();
+    /// let mut game = Game::new(position);
+    /// assert!(game.annotation_set(1, Some(MoveAnnotation::Good)));
+    /// assert_eq!(game.annotations().collect::<Vec<_>>(), [(1, MoveAnnotation::Good)]);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn annotations(&self) -> impl Iterator<Item = (usize, MoveAnnotation)> + '_ {
+        self.annotations
+            .iter()
+            .enumerate()
+            .filter_map(|(index, annotation)| annotation.map(|annotation| (index, annotation)))
+    }
+
+    /// Returns the time in milliseconds spent on the `index`-th move in [`Position::moves`], if known.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Game, Move, Position, Square};
+    /// let mut position = Position::startpos();
+    /// position.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// let mut game = Game::new(position);
+    /// assert_eq!(game.move_time(0), None);
+    /// assert!(game.move_time_set(0, Some(3_000)));
+    /// assert_eq!(game.move_time(0), Some(3_000));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn move_time(&self, index: usize) -> Option<u32> {
+        self.move_times.get(index).copied().flatten()
+    }
+
+    /// Sets or clears the time in milliseconds spent on the `index`-th move in [`Position::moves`].
+    ///
+    /// Returns `false` and does nothing if there is no such move.
+    ///
+    /// Since: 0.1.6
+    #[must_use]
+    pub fn move_time_set(&mut self, index: usize, time: Option<u32>) -> bool {
+        if index >= self.inner.moves().len() {
+            return false;
+        }
+        set_trimmed(&mut self.move_times, index, time);
+        true
+    }
+
+    /// Computes the remaining main time of `color` in milliseconds after the first `at_ply` moves in [`Position::moves`] are made,
+    /// according to [`Game::time_control`] and [`Game::move_time`].
+    ///
+    /// For each move, the time spent is subtracted from the remaining time of the player who made it.
+    /// If it exceeds the remaining time, byoyomi is used and the remaining time becomes 0.
+    /// Then the increment is added. The clocks stop when a player's flag falls (see [`Game::flag_fall_ply`]),
+    /// and the player whose flag fell has no time left.
+    ///
+    /// Returns [`None`] if the time control is unknown, if `at_ply` exceeds the number of moves,
+    /// or if the time of a move needed for the computation is unknown.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Game, Move, Position, Square, TimeControl};
+    /// let mut position = Position::startpos();
+    /// position.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// position.make_move(Move::Normal { from: Square::SQ_3C, to: Square::SQ_3D, promote: false }).unwrap();
+    /// let mut game = Game::new(position);
+    /// game.time_control_set(Some(TimeControl::fischer(60_000, 5_000)));
+    /// assert!(game.move_time_set(0, Some(10_000)));
+    /// assert!(game.move_time_set(1, Some(2_000)));
+    /// assert_eq!(game.remaining_time(Color::Black, 0), Some(60_000));
+    /// assert_eq!(game.remaining_time(Color::Black, 2), Some(55_000));
+    /// assert_eq!(game.remaining_time(Color::White, 2), Some(63_000));
+    /// assert_eq!(game.remaining_time(Color::White, 3), None);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn remaining_time(&self, color: Color, at_ply: usize) -> Option<u32> {
+        if at_ply > self.inner.moves().len() {
+            return None;
+        }
+        let (remaining, replayed, flag_fall) = self.replay_clocks(at_ply)?;
+        if replayed < at_ply && flag_fall.is_none() {
+            return None;
+        }
+        Some(remaining[color.array_index()])
+    }
+
+    /// Finds the first move in [`Position::moves`] on which the player who made it ran out of time,
+    /// i.e., spent more time than the remaining time and byoyomi, or more than the per-move limit.
+    /// The index of the move is returned, so that the flag fell after that many moves were made.
+    ///
+    /// Only the moves before the first move whose time is unknown are examined.
+    /// Returns [`None`] if the time control is unknown or no flag fell.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Game, Move, Position, Square, TimeControl};
+    /// let mut position = Position::startpos();
+    /// position.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// position.make_move(Move::Normal { from: Square::SQ_3C, to: Square::SQ_3D, promote: false }).unwrap();
+    /// let mut game = Game::new(position);
+    /// game.time_control_set(Some(TimeControl::with_byoyomi(0, 10_000)));
+    /// assert!(game.move_time_set(0, Some(10_000)));
+    /// assert!(game.move_time_set(1, Some(10_001)));
+    /// assert_eq!(game.flag_fall_ply(), Some(1));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn flag_fall_ply(&self) -> Option<usize> {
+        self.replay_clocks(self.inner.moves().len())?.2
+    }
+
+    // Runs the clocks over the first `moves` moves. Returns the remaining main time of each player,
+    // the number of moves replayed and the index of the move on which a flag fell, if any.
+    // Replaying stops at a move whose time is unknown or on which a flag fell.
+    fn replay_clocks(&self, moves: usize) -> Option<([u32; 2], usize, Option<usize>)> {
+        let time_control = self.time_control?;
+        let mut remaining = [time_control.main_time; 2];
+        let mut side = self.inner.initial_position().side_to_move();
+        for index in 0..moves {
+            let spent = match self.move_time(index) {
+                Some(spent) => spent,
+                None => return Some((remaining, index, None)),
+            };
+            let clock = &mut remaining[side.array_index()];
+            if (time_control.per_move_limit != 0 && spent > time_control.per_move_limit)
+                || spent > clock.saturating_add(time_control.byoyomi)
+            {
+                *clock = 0;
+                return Some((remaining, index, Some(index)));
+            }
+            *clock = clock
+                .saturating_sub(spent)
+                .saturating_add(time_control.increment);
+            side = side.flip();
+        }
+        Some((remaining, moves, None))
+    }
+
+    /// The number of moves after which a game is drawn in floodgate.
+    pub const MOVE_LIMIT_FLOODGATE: u16 = 256;
+    /// The number of moves after which a game is drawn in the World Computer Shogi Championship (WCSC).
+    pub const MOVE_LIMIT_WCSC: u16 = 320;
+    /// A longer move limit adopted by some tournaments.
+    pub const MOVE_LIMIT_LONG: u16 = 512;
+
+    /// Checks if the number of moves made in this game has reached `limit`.
+    ///
+    /// Only moves in [`Position::moves`] are counted, regardless of the ply of the initial position.
+    /// Tournaments usually declare a game drawn if it is still in progress at this point;
+    /// see also [`LegalityChecker::status_with_move_limit`](crate::LegalityChecker::status_with_move_limit).
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Game, Move, Position, Square};
+    /// let mut position = Position::startpos();
+    /// position.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// let game = Game::new(position);
+    /// assert!(game.exceeds_move_limit(1));
+    /// assert!(!game.exceeds_move_limit(2));
+    /// assert!(!game.exceeds_move_limit(Game::MOVE_LIMIT_FLOODGATE));
+    /// ```
+    pub fn exceeds_move_limit(&self, limit: u16) -> bool {
+        self.inner.moves().len() >= limit as usize
+    }
+
+    /// Checks if the resolution of this game is plausible for its final position, as judged by `checker`.
+    ///
+    /// - If the final position is mated, the game must be won by the other player.
+    /// - If the final position is a repetition, the game must be a draw or a rematch,
+    ///   or a win with [`ResolutionDetail::PerpetualCheck`] or [`ResolutionDetail::IllegalMove`].
+    /// - Otherwise, the game may be unresolved or resolved in any way (e.g. by resignation),
+    ///   except for [`ResolutionDetail::Mated`], [`ResolutionDetail::Repetition`] and [`ResolutionDetail::PerpetualCheck`].
+    ///
+    /// This is useful for finding corrupted or mislabeled records on import.
+    ///
+    /// Since: 0.1.6
+    pub fn validate_resolution<C: LegalityChecker + ?Sized>(
+        &self,
+        checker: &C,
+    ) -> Result<(), ResolutionMismatch> {
+        let resolution = self.resolution();
+        let detail = self.resolution_detail;
+        match checker.status(&self.inner) {
+            PositionStatus::Invalid => Err(ResolutionMismatch::InvalidPosition),
+            status @ (PositionStatus::BlackWins | PositionStatus::WhiteWins) => match resolution {
+                None => Err(ResolutionMismatch::Unresolved),
+                Some(resolution) if PositionStatus::from_resolution(Some(resolution)) == status => {
+                    Ok(())
+                }
+                Some(_) => Err(ResolutionMismatch::WrongResult),
+            },
+            PositionStatus::Draw => match (resolution, detail) {
+                (None, _) => Err(ResolutionMismatch::Unresolved),
+                (Some(GameResolution::Draw | GameResolution::Rematch), _) => Ok(()),
+                (
+                    Some(GameResolution::BlackWins | GameResolution::WhiteWins),
+                    Some(ResolutionDetail::PerpetualCheck(_) | ResolutionDetail::IllegalMove(..)),
+                ) => Ok(()),
+                _ => Err(ResolutionMismatch::WrongResult),
+            },
+            PositionStatus::InProgress => match detail {
+                Some(ResolutionDetail::Mated(_)) => Err(ResolutionMismatch::NotMated),
+                Some(ResolutionDetail::Repetition | ResolutionDetail::PerpetualCheck(_)) => {
+                    Err(ResolutionMismatch::NotRepetition)
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+// Sets `vec[index]` to `value`, keeping `vec` free of trailing `None`s.
+#[cfg(feature = "alloc")]
+fn set_trimmed<T: Copy>(vec: &mut alloc::vec::Vec<Option<T>>, index: usize, value: Option<T>) {
+    if index >= vec.len() {
+        if value.is_none() {
+            return;
+        }
+        vec.resize(index + 1, None);
+    }
+    vec[index] = value;
+    while let Some(None) = vec.last() {
+        vec.pop();
+    }
 }
 
 #[cfg(feature = "alloc")]
-impl_ord_with_fields!(Game; inner, resolution);
+impl_ord_with_fields!(Game; inner, resolution, resolution_detail, time_control, annotations, move_times);
 #[cfg(feature = "alloc")]
-impl_hash_with_fields!(Game; inner, resolution);
+impl_hash_with_fields!(Game; inner, resolution, resolution_detail, time_control, annotations, move_times);
 
 /// A record of a game. A position and how a game is resolved.
 #[derive(Eq, PartialEq, Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct PartialGame {
     inner: PartialPosition,
     resolution: OptionGameResolution,
@@ -62,20 +416,20 @@ pub struct PartialGame {
 
 impl PartialGame {
     /// Returns the inner position.
-    #[export_name = "PartialGame_position"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "PartialGame_position")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn position(&self) -> &PartialPosition {
         &self.inner
     }
     /// Sets the resolution of this game.
-    #[export_name = "PartialGame_resolve"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "PartialGame_resolve")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn resolve(&mut self, resolution: GameResolution) {
         self.resolution = Some(resolution).into();
     }
     /// Unsets the resolution of this game.
-    #[export_name = "PartialGame_unresolve"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "PartialGame_unresolve")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn unresolve(&mut self) {
         self.resolution = None.into();
     }
@@ -85,7 +439,8 @@ impl PartialGame {
         self.resolution.into()
     }
     /// C interface to [`PartialGame::resolution`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn PartialGame_resolution(&self) -> OptionGameResolution {
         self.resolution
     }
@@ -94,28 +449,53 @@ impl PartialGame {
 impl_ord_with_fields!(PartialGame; inner, resolution);
 impl_hash_with_fields!(PartialGame; inner, resolution);
 
+/// Accessors of an archived [`PartialGame`], which can be read in place without deserialization.
+#[cfg(feature = "rkyv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rkyv")))]
+impl ArchivedPartialGame {
+    /// Returns the position of this game.
+    #[inline(always)]
+    pub fn position(&self) -> &PartialPosition {
+        &self.inner
+    }
+
+    /// Returns how this game is resolved, if it is.
+    #[inline(always)]
+    pub fn resolution(&self) -> Option<GameResolution> {
+        self.resolution.into()
+    }
+}
+
 /// A position. It provides sufficient data for legality checking.
+///
+/// With the `shared-moves` feature, clones of a [`Position`] share their moves made so far,
+/// so cloning a [`Position`] takes constant time regardless of the number of moves.
+/// The moves are copied when a move is made in a clone whose moves are shared with another.
 #[cfg(feature = "alloc")]
 #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
 #[derive(Eq, PartialEq, Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub struct Position {
     initial: PartialPosition,
     inner: PartialPosition,
-    moves: alloc::vec::Vec<Move>,
+    moves: MoveHistory,
 }
 
 #[cfg(feature = "alloc")]
 impl Position {
     /// Returns the inner [`PartialPosition`].
-    #[export_name = "Position_inner"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "Position_inner")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn inner(&self) -> &PartialPosition {
         &self.inner
     }
 
     /// Returns the initial position of [`Position`], i.e., the position before any moves given to it.
-    #[export_name = "Position_initial_position"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "Position_initial_position")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn initial_position(&self) -> &PartialPosition {
         &self.initial
     }
@@ -126,17 +506,31 @@ impl Position {
     }
 
     /// C interface of [`Position::startpos`].
-    #[no_mangle]
+    ///
+    /// If memory allocation fails, this function returns a null pointer.
+    /// The returned pointer must be released with [`Position::Position_destruct`].
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Position_startpos() -> *mut Self {
-        alloc::boxed::Box::leak(alloc::boxed::Box::new(Self::startpos()))
+        let layout = core::alloc::Layout::new::<Self>();
+        // Safety: `Self` is not zero-sized
+        let ptr = unsafe { alloc::alloc::alloc(layout) } as *mut Self;
+        if ptr.is_null() {
+            return ptr;
+        }
+        // Safety: `ptr` is a freshly allocated region for `Self`.
+        unsafe { ptr.write(abort_on_panic(Self::startpos)) };
+        ptr
     }
 
     /// Destructs a [`Position`].
     ///
     /// # Safety
     /// `ptr` must be the one created by a function in this type.
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub unsafe extern "C" fn Position_destruct(ptr: *mut Self) {
+        // Safety: `ptr` was allocated with the layout of `Self`, which is what `Box` uses.
         drop(alloc::boxed::Box::from_raw(ptr));
     }
 
@@ -145,7 +539,7 @@ impl Position {
         Self {
             initial: p.clone(),
             inner: p,
-            moves: alloc::vec::Vec::new(),
+            moves: MoveHistory::default(),
         }
     }
 
@@ -159,13 +553,13 @@ impl Position {
     /// pos.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
     /// assert_eq!(pos.side_to_move(), Color::White);
     /// ```
-    #[export_name = "Position_side_to_move"]
+    #[cfg_attr(feature = "c-exports", export_name = "Position_side_to_move")]
     pub extern "C" fn side_to_move(&self) -> Color {
         self.inner.side_to_move()
     }
 
     /// Returns the [`Hand`] of a player.
-    #[export_name = "Position_hand_of_a_player"]
+    #[cfg_attr(feature = "c-exports", export_name = "Position_hand_of_a_player")]
     pub extern "C" fn hand_of_a_player(&self, color: Color) -> Hand {
         self.inner.hand_of_a_player(color)
     }
@@ -190,7 +584,7 @@ impl Position {
     }
 
     /// Finds how many moves were made.
-    #[export_name = "Position_ply"]
+    #[cfg_attr(feature = "c-exports", export_name = "Position_ply")]
     pub extern "C" fn ply(&self) -> u16 {
         self.inner.ply()
     }
@@ -211,11 +605,22 @@ impl Position {
     }
 
     /// C interface to [`Position::piece_at`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Position_piece_at(&self, square: Square) -> OptionPiece {
         self.inner.PartialPosition_piece_at(square)
     }
 
+    /// C interface to [`PartialPosition::PartialPosition_board`].
+    ///
+    /// # Safety
+    /// `out` must be valid for writes of 81 elements.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn Position_board(&self, out: *mut OptionPiece) {
+        self.inner.PartialPosition_board(out)
+    }
+
     /// Place a piece on a square.
     ///
     /// This function makes no guarantee about the consistency of the position.
@@ -231,20 +636,20 @@ impl Position {
     /// Finds the subset of squares with a piece.
     ///
     /// Since: 0.1.4
-    #[export_name = "Position_occupied_bitboard"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "Position_occupied_bitboard")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn occupied_bitboard(&self) -> Bitboard {
         self.inner.occupied_bitboard()
     }
 
     /// Finds the subset of squares with no pieces.
-    #[export_name = "Position_vacant_bitboard"]
+    #[cfg_attr(feature = "c-exports", export_name = "Position_vacant_bitboard")]
     pub extern "C" fn vacant_bitboard(&self) -> Bitboard {
         self.inner.vacant_bitboard()
     }
 
     /// Finds the subset of squares where a piece of the specified player is placed.
-    #[export_name = "Position_player_bitboard"]
+    #[cfg_attr(feature = "c-exports", export_name = "Position_player_bitboard")]
     pub extern "C" fn player_bitboard(&self, color: Color) -> Bitboard {
         self.inner.player_bitboard(color)
     }
@@ -260,7 +665,7 @@ impl Position {
     /// let white_rook = pos.piece_bitboard(Piece::W_R);
     /// assert_eq!(white_rook, Bitboard::single(Square::SQ_8B));
     /// ```
-    #[export_name = "Position_piece_bitboard"]
+    #[cfg_attr(feature = "c-exports", export_name = "Position_piece_bitboard")]
     pub extern "C" fn piece_bitboard(&self, piece: Piece) -> Bitboard {
         self.inner.piece_bitboard(piece)
     }
@@ -300,7 +705,8 @@ impl Position {
     }
 
     /// C interface to [`Position::last_compact_move`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Position_last_compact_move(&self) -> OptionCompactMove {
         self.inner.PartialPosition_last_compact_move()
     }
@@ -325,12 +731,32 @@ impl Position {
     /// moves a piece to another square or drops a piece on a vacant square.
     ///
     /// If it returns false, it is guaranteed that self is not modified.
-    #[export_name = "Position_make_compact_move"]
     pub extern "C" fn make_compact_move(&mut self, mv: CompactMove) -> bool {
         let mv = mv.into();
         self.make_move(mv).is_some()
     }
 
+    /// C interface to [`Position::make_compact_move`].
+    /// Note that this function will never check legality.
+    ///
+    /// Returns true if the given move makes sense, i.e.,
+    /// moves a piece to another square or drops a piece on a vacant square.
+    ///
+    /// If it returns false, it is guaranteed that self is not modified.
+    /// This function also returns false if memory allocation for the move history fails.
+    /// With the `shared-moves` feature, the shared block that holds the move history is allocated infallibly,
+    /// so this function aborts the process if that allocation fails.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn Position_make_compact_move(&mut self, mv: CompactMove) -> bool {
+        abort_on_panic(|| {
+            if self.moves.try_reserve(1).is_err() {
+                return false;
+            }
+            self.make_compact_move(mv)
+        })
+    }
+
     // TODO: fn revert_move(&mut self) -> Option<Move>
 
     /// Returns the SFEN representation of the current position.
@@ -349,15 +775,166 @@ impl Position {
         self.inner.to_sfen_owned()
     }
 
+    /// Returns an iterator over the SFEN representations of the positions in the history,
+    /// i.e., the initial position and the position after every move made so far.
+    ///
+    /// The iterator yields `self.moves().len() + 1` strings lazily, replaying the moves from [`Position::initial_position`].
+    /// The last one is equal to `self.to_sfen_owned()`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Move, Position, Square};
+    /// let mut pos = Position::startpos();
+    /// pos.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// pos.make_move(Move::Normal { from: Square::SQ_3C, to: Square::SQ_3D, promote: false }).unwrap();
+    /// let history: Vec<String> = pos.sfen_history().collect();
+    /// assert_eq!(
+    ///     history,
+    ///     [
+    ///         "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+    ///         "lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL w - 2",
+    ///         "lnsgkgsnl/1r5b1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL b - 3",
+    ///     ],
+    /// );
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn sfen_history(&self) -> impl Iterator<Item = alloc::string::String> + '_ {
+        let initial = self.initial.clone();
+        let first = core::iter::once(initial.to_sfen_owned());
+        let rest = self.moves.iter().scan(initial, |position, &mv| {
+            // The moves were already made in `self`, so replaying them always succeeds.
+            position.make_move(mv)?;
+            Some(position.to_sfen_owned())
+        });
+        first.chain(rest)
+    }
+
+    /// Finds the player who loses by perpetual check (連続王手の千日手), if any.
+    ///
+    /// If the current position has appeared four times and one player checked the opponent's king
+    /// with every move since the first of the four appearances, that player loses.
+    /// Positions are compared by [`PartialPosition::zobrist_key`].
+    /// Returns [`None`] if the current position is not a repetition, if no player checked continuously,
+    /// or (which is unusual) if both players did.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Move, PartialPosition, Position, Square};
+    /// let initial = PartialPosition::from_sfen("4k4/9/9/9/9/9/9/9/4K3R b - 1").unwrap();
+    /// let mut pos = Position::arbitrary_position(initial);
+    /// pos.make_move(Move::Normal { from: Square::SQ_1I, to: Square::SQ_1A, promote: false }).unwrap();
+    /// // Black's rook keeps checking white's king.
+    /// let cycle = [
+    ///     (Square::SQ_5A, Square::SQ_5B),
+    ///     (Square::SQ_1A, Square::SQ_1B),
+    ///     (Square::SQ_5B, Square::SQ_5A),
+    ///     (Square::SQ_1B, Square::SQ_1A),
+    /// ];
+    /// for _ in 0..3 {
+    ///     assert_eq!(pos.perpetual_check_loser(), None);
+    ///     for (from, to) in cycle {
+    ///         pos.make_move(Move::Normal { from, to, promote: false }).unwrap();
+    ///     }
+    /// }
+    /// assert_eq!(pos.perpetual_check_loser(), Some(Color::Black));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn perpetual_check_loser(&self) -> Option<Color> {
+        let last = self.inner.zobrist_key();
+        let count = self.key_set().count(last);
+        if count < 4 {
+            return None;
+        }
+        // The moves made since the first of the last four appearances, i.e. the `count - 3`-th one, are examined.
+        let mut position = self.initial.clone();
+        let mut seen = (position.zobrist_key() == last) as u32;
+        let mut continuous = [true; Color::NUM];
+        for &mv in self.moves.iter() {
+            let mover = position.side_to_move();
+            // The moves were already made in `self`, so replaying them always succeeds.
+            position.make_move(mv)?;
+            if seen + 3 >= count && !position.is_in_check() {
+                continuous[mover.array_index()] = false;
+            }
+            if position.zobrist_key() == last {
+                seen += 1;
+            }
+        }
+        match continuous {
+            [true, false] => Some(Color::Black),
+            [false, true] => Some(Color::White),
+            _ => None,
+        }
+    }
+
+    /// Appends the moves of `continuation` to `self`, e.g. to join the record of an adjourned game
+    /// or a line of engine analysis onto a game.
+    ///
+    /// The initial position of `continuation` must be the current position of `self`.
+    /// Only the board, the hands and the side to move are compared;
+    /// the ply and the last move are ignored because records resumed from SFEN usually lose them.
+    /// Legality of the moves is not checked.
+    ///
+    /// If it returns an error, it is guaranteed that `self` is not modified.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Move, PartialPosition, Position, SpliceError, Square};
+    /// let mut game = Position::startpos();
+    /// game.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// let resumed = PartialPosition::from_sfen("lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL w - 1").unwrap();
+    /// let mut continuation = Position::arbitrary_position(resumed);
+    /// continuation.make_move(Move::Normal { from: Square::SQ_3C, to: Square::SQ_3D, promote: false }).unwrap();
+    ///
+    /// assert_eq!(game.append(&Position::startpos()), Err(SpliceError::PositionMismatch));
+    /// assert_eq!(game.append(&continuation), Ok(()));
+    /// assert_eq!(game.moves().len(), 2);
+    /// assert_eq!(game.ply(), 3);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn append(&mut self, continuation: &Position) -> Result<(), SpliceError> {
+        let start = &continuation.initial;
+        if start.side != self.inner.side
+            || start.hands != self.inner.hands
+            || start.board != self.inner.board
+        {
+            return Err(SpliceError::PositionMismatch);
+        }
+        let mut inner = self.inner.clone();
+        for (index, &mv) in continuation.moves.iter().enumerate() {
+            // `continuation` may have been modified with `piece_set` after some moves.
+            if inner.make_move(mv).is_none() {
+                return Err(SpliceError::Move { index });
+            }
+        }
+        self.inner = inner;
+        for &mv in continuation.moves.iter() {
+            self.moves.push(mv);
+        }
+        Ok(())
+    }
+
     /// C interface of `to_sfen`.
     ///
     /// # Safety
     /// This function writes to `ptr` at most 139 (= 129 + 1 + 1 + 1 + 0 + 1 + 5 + 1) bytes.
     /// Caller should ensure that `ptr` has enough space for that.
-    #[export_name = "Position_to_sfen_c"]
+    #[cfg_attr(feature = "c-exports", export_name = "Position_to_sfen_c")]
     pub unsafe extern "C" fn to_sfen_c(&self, ptr: *mut u8) {
         self.inner.to_sfen_c(ptr)
     }
+
+    /// C interface of `to_sfen` with bounds checking. See [`PartialPosition::to_sfen_bounded`] for details.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for writes of `capacity` bytes.
+    #[cfg_attr(feature = "c-exports", export_name = "Position_to_sfen_bounded")]
+    pub unsafe extern "C" fn to_sfen_bounded(&self, ptr: *mut u8, capacity: usize) -> usize {
+        self.inner.to_sfen_bounded(ptr, capacity)
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -365,153 +942,474 @@ impl_ord_with_fields!(Position; initial, inner, moves);
 #[cfg(feature = "alloc")]
 impl_hash_with_fields!(Position; initial, inner, moves);
 
-/// A position with its move sequence omitted.
-///
-/// This data is insufficient for complete legality checking (such as repetition checking),
-/// but in most cases it suffices. If you need a complete legality checking, use `Position`.
+/// An error returned by [`Position::append`].
 ///
-/// TODO: describe exactly when a position is considered valid
-#[derive(Eq, PartialEq, Clone, Debug)]
-#[repr(C)]
-pub struct PartialPosition {
-    side: Color,
-    ply: u16,
-    hands: [Hand; 2],
-    board: [OptionPiece; 81],
-    player_bb: [Bitboard; 2],
-    piece_bb: [Bitboard; 14],
-    last_move: OptionCompactMove,
-    king_square: [OptionSquare; 2],
+/// Since: 0.1.6
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum SpliceError {
+    /// The initial position of the continuation is not the current position.
+    PositionMismatch,
+    /// The move with the given 0-based index in the continuation does not make sense.
+    Move {
+        /// The index of the move.
+        index: usize,
+    },
 }
 
-impl PartialPosition {
-    /// Returns an empty position.
-    pub fn empty() -> Self {
-        Self {
-            side: Color::Black,
-            ply: 1,
-            hands: [Default::default(); 2],
-            board: [None.into(); 81],
-            player_bb: [Bitboard::empty(); 2],
-            piece_bb: [Bitboard::empty(); PieceKind::NUM],
-            last_move: None.into(),
-            king_square: [None.into(); Color::NUM],
-        }
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::PartialOrd for SpliceError {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
     }
+}
 
-    const STARTPOS_BLACK_BB: Bitboard = {
-        let mut result = Bitboard::empty();
-        let mut i = 0;
-        while i < 9 {
-            // Safety: i+1 is in range 1..=9.
-            let file = unsafe { Bitboard::from_file_unchecked(i as u8 + 1, 1 << 8 | 1 << 6) };
-            result = result.or(file);
-            i += 1;
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::Ord for SpliceError {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        fn key(error: SpliceError) -> Option<usize> {
+            match error {
+                SpliceError::PositionMismatch => None,
+                SpliceError::Move { index } => Some(index),
+            }
         }
-        result = result.or(Bitboard::single(Square::SQ_2H));
-        result = result.or(Bitboard::single(Square::SQ_8H));
-        result
-    };
-
-    const STARTPOS_WHITE_BB: Bitboard = {
-        let mut result = Bitboard::empty();
-        let mut i = 0;
-        while i < 9 {
-            // Safety: i+1 is in range 1..=9.
-            let file = unsafe { Bitboard::from_file_unchecked(i as u8 + 1, 1 << 2 | 1) };
-            result = result.or(file);
-            i += 1;
-        }
-        result = result.or(Bitboard::single(Square::SQ_2B));
-        result = result.or(Bitboard::single(Square::SQ_8B));
-        result
-    };
+        // `PositionMismatch` is smaller than any `Move`.
+        key(*self).cmp(&key(*other))
+    }
+}
 
-    /// Returns the starting position of shogi.
-    pub fn startpos() -> Self {
-        // TODO stop panicking
-        let mut board: [OptionPiece; 81] = [None.into(); 81];
-        // Pawns
-        for i in 0..9 {
-            board[6 + i * 9] = Some(Piece::B_P).into();
-            board[2 + i * 9] = Some(Piece::W_P).into();
-        }
-        // Bishop, Rook
-        board[70] = Some(Piece::B_B).into();
-        board[10] = Some(Piece::W_B).into();
-        board[16] = Some(Piece::B_R).into();
-        board[64] = Some(Piece::W_R).into();
-        // Other minor pieces
-        let order = [
-            PieceKind::Lance,
-            PieceKind::Knight,
-            PieceKind::Silver,
-            PieceKind::Gold,
-            PieceKind::King,
-            PieceKind::Gold,
-            PieceKind::Silver,
-            PieceKind::Knight,
-            PieceKind::Lance,
-        ];
-        for i in 0..9 {
-            board[8 + 9 * i] = Some(Piece::new(order[i], Color::Black)).into();
-            board[9 * i] = Some(Piece::new(order[i], Color::White)).into();
+#[cfg(feature = "hash")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hash")))]
+impl core::hash::Hash for SpliceError {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        if let SpliceError::Move { index } = *self {
+            index.hash(state);
         }
-        let mut piece_bb = [Bitboard::empty(); PieceKind::NUM];
-        for square in Square::all() {
-            if let Some(piece) =
-                <Option<Piece>>::from(*unsafe { board.get_unchecked(square.array_index()) })
-            {
-                let piece_kind = piece.piece_kind();
-                piece_bb[piece_kind.array_index()] |= square;
-            }
+    }
+}
+
+impl core::fmt::Display for SpliceError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            SpliceError::PositionMismatch => f.write_str("positions do not match"),
+            SpliceError::Move { index } => write!(f, "invalid move at index {}", index),
         }
-        Self {
-            side: Color::Black,
-            ply: 1,
-            hands: [Default::default(); 2],
-            board,
-            player_bb: [Self::STARTPOS_BLACK_BB, Self::STARTPOS_WHITE_BB],
-            piece_bb,
-            last_move: None.into(),
-            king_square: [Some(Square::SQ_5I).into(), Some(Square::SQ_5A).into()],
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for SpliceError {}
+
+// The moves made in a `Position`.
+#[cfg(all(feature = "alloc", not(feature = "shared-moves")))]
+type MoveHistory = alloc::vec::Vec<Move>;
+#[cfg(feature = "shared-moves")]
+type MoveHistory = SharedMoves;
+
+// A move sequence shared between clones of a `Position`, which is copied only when a clone with shared moves is modified.
+// An empty sequence is represented by `None`, so that creating a `Position` does not allocate.
+// Its `Debug`, ordering, hash and archived form are those of `Vec<Move>`.
+#[cfg(feature = "shared-moves")]
+#[derive(Clone, Default)]
+struct SharedMoves(Option<alloc::sync::Arc<alloc::vec::Vec<Move>>>);
+
+#[cfg(feature = "shared-moves")]
+impl SharedMoves {
+    fn push(&mut self, mv: Move) {
+        match &mut self.0 {
+            Some(moves) => alloc::sync::Arc::make_mut(moves).push(mv),
+            None => self.0 = Some(alloc::sync::Arc::new(alloc::vec![mv])),
         }
     }
 
-    /// C interface of `startpos`.
-    #[no_mangle]
-    pub extern "C" fn PartialPosition_startpos(buf: &mut MaybeUninit<Self>) {
-        buf.write(Self::startpos());
+    // Reserves capacity for the moves fallibly.
+    // If the moves are not owned by `self` yet, a new `Arc` is allocated for them, which aborts on failure,
+    // because `Arc` cannot be allocated fallibly on stable Rust.
+    fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), alloc::collections::TryReserveError> {
+        if let Some(moves) = self.0.as_mut().and_then(alloc::sync::Arc::get_mut) {
+            return moves.try_reserve(additional);
+        }
+        // The moves are shared or not allocated yet, so we make a copy with enough capacity here.
+        let mut moves = alloc::vec::Vec::new();
+        moves.try_reserve(self.len() + additional)?;
+        moves.extend_from_slice(self);
+        self.0 = Some(alloc::sync::Arc::new(moves));
+        Ok(())
     }
+}
+
+#[cfg(feature = "shared-moves")]
+impl core::ops::Deref for SharedMoves {
+    type Target = [Move];
 
-    /// Finds which player is to move.
-    ///
-    /// Examples:
-    /// ```
-    /// # use shogi_core::{Color, Move, PartialPosition, Square};
-    /// let mut pos = PartialPosition::startpos();
-    /// assert_eq!(pos.side_to_move(), Color::Black);
-    /// pos.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
-    /// assert_eq!(pos.side_to_move(), Color::White);
-    /// ```
-    #[export_name = "PartialPosition_side_to_move"]
     #[inline(always)]
-    pub extern "C" fn side_to_move(&self) -> Color {
-        self.side
+    fn deref(&self) -> &[Move] {
+        match &self.0 {
+            Some(moves) => moves,
+            None => &[],
+        }
     }
+}
 
-    /// Sets which player is to move.
-    #[inline(always)]
-    pub fn side_to_move_set(&mut self, side: Color) {
-        self.side = side;
+#[cfg(feature = "shared-moves")]
+impl PartialEq for SharedMoves {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
     }
+}
 
-    /// Returns the [`Hand`] of a player.
-    #[export_name = "PartialPosition_hand_of_a_player"]
+#[cfg(feature = "shared-moves")]
+impl Eq for SharedMoves {}
+
+#[cfg(feature = "shared-moves")]
+impl core::fmt::Debug for SharedMoves {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> FmtResult {
+        (**self).fmt(f)
+    }
+}
+
+#[cfg(all(feature = "shared-moves", feature = "ord"))]
+impl core::cmp::PartialOrd for SharedMoves {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(all(feature = "shared-moves", feature = "ord"))]
+impl core::cmp::Ord for SharedMoves {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+#[cfg(all(feature = "shared-moves", feature = "hash"))]
+impl core::hash::Hash for SharedMoves {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+#[cfg(all(feature = "shared-moves", feature = "rkyv"))]
+impl rkyv::Archive for SharedMoves {
+    type Archived = rkyv::vec::ArchivedVec<CompactMove>;
+    type Resolver = rkyv::vec::VecResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        rkyv::vec::ArchivedVec::resolve_from_slice(self, pos, resolver, out);
+    }
+}
+
+#[cfg(all(feature = "shared-moves", feature = "rkyv"))]
+impl<S: rkyv::ser::ScratchSpace + rkyv::ser::Serializer + ?Sized> rkyv::Serialize<S>
+    for SharedMoves
+{
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        rkyv::vec::ArchivedVec::serialize_from_slice(self, serializer)
+    }
+}
+
+#[cfg(all(feature = "shared-moves", feature = "rkyv"))]
+impl<D: rkyv::Fallible + ?Sized> rkyv::Deserialize<SharedMoves, D>
+    for rkyv::vec::ArchivedVec<CompactMove>
+{
+    fn deserialize(&self, _deserializer: &mut D) -> Result<SharedMoves, D::Error> {
+        if self.is_empty() {
+            return Ok(SharedMoves::default());
+        }
+        let moves = self.iter().map(|&mv| mv.into()).collect();
+        Ok(SharedMoves(Some(alloc::sync::Arc::new(moves))))
+    }
+}
+
+/// Accessors of an archived [`Game`], which can be read in place without deserialization.
+#[cfg(all(feature = "alloc", feature = "rkyv"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "alloc", feature = "rkyv"))))]
+impl ArchivedGame {
+    /// Returns the archived position of this game.
+    #[inline(always)]
+    pub fn position(&self) -> &ArchivedPosition {
+        &self.inner
+    }
+
+    /// Returns how this game is resolved, if it is.
+    #[inline(always)]
+    pub fn resolution(&self) -> Option<GameResolution> {
+        self.resolution.into()
+    }
+
+    /// Returns why this game is resolved, if known.
+    #[inline(always)]
+    pub fn resolution_detail(&self) -> Option<ResolutionDetail> {
+        self.resolution_detail.as_ref().copied()
+    }
+
+    /// Returns the time control of this game, if known.
+    #[inline(always)]
+    pub fn time_control(&self) -> Option<TimeControl> {
+        self.time_control.as_ref().copied()
+    }
+
+    /// Returns the annotation of the `index`-th move, if any.
+    ///
+    /// Since: 0.1.6
+    #[inline(always)]
+    pub fn annotation(&self, index: usize) -> Option<MoveAnnotation> {
+        self.annotations
+            .get(index)
+            .and_then(|annotation| annotation.as_ref().copied())
+    }
+
+    /// Returns the time in milliseconds spent on the `index`-th move, if known.
+    ///
+    /// Since: 0.1.6
+    #[inline(always)]
+    pub fn move_time(&self, index: usize) -> Option<u32> {
+        self.move_times
+            .get(index)
+            .and_then(|time| time.as_ref().copied())
+    }
+}
+
+/// Accessors of an archived [`Position`], which can be read in place without deserialization.
+#[cfg(all(feature = "alloc", feature = "rkyv"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "alloc", feature = "rkyv"))))]
+impl ArchivedPosition {
+    /// Returns the current position.
+    #[inline(always)]
+    pub fn inner(&self) -> &PartialPosition {
+        &self.inner
+    }
+
+    /// Returns the initial position.
+    #[inline(always)]
+    pub fn initial_position(&self) -> &PartialPosition {
+        &self.initial
+    }
+
+    /// Returns the moves made so far, in their compact form.
+    #[inline(always)]
+    pub fn moves(&self) -> &[CompactMove] {
+        &self.moves
+    }
+}
+
+// Updates the bitboards, the material, the Zobrist key and the king square of `$position`
+// for `$piece` being added to or removed from `$square`. The board itself is not updated.
+// `$key` is the Zobrist key of `$piece` on `$square`.
+// This is a macro so that `const fn`s, which cannot take `&mut self`, and other functions can share it.
+macro_rules! update_piece_counts {
+    ($position:ident, remove $piece:ident, $square:ident, $key:expr) => {{
+        let single = Bitboard::single($square);
+        let (piece_kind, color) = $piece.to_parts();
+        let (kind_index, color_index) = (piece_kind.array_index(), color.array_index());
+        $position.player_bb[color_index] = single.andnot($position.player_bb[color_index]);
+        $position.piece_bb[kind_index] = single.andnot($position.piece_bb[kind_index]);
+        $position.material[color_index][kind_index] =
+            $position.material[color_index][kind_index].wrapping_sub(1);
+        $position.board_key ^= $key;
+    }};
+    ($position:ident, add $piece:ident, $square:ident, $key:expr) => {{
+        let single = Bitboard::single($square);
+        let (piece_kind, color) = $piece.to_parts();
+        let (kind_index, color_index) = (piece_kind.array_index(), color.array_index());
+        $position.player_bb[color_index] = $position.player_bb[color_index].or(single);
+        $position.piece_bb[kind_index] = $position.piece_bb[kind_index].or(single);
+        $position.material[color_index][kind_index] =
+            $position.material[color_index][kind_index].wrapping_add(1);
+        $position.board_key ^= $key;
+        if let PieceKind::King = piece_kind {
+            $position.king_square[color_index] = OptionSquare::from_option(Some($square));
+        }
+    }};
+}
+
+// The starting position of shogi, which is built at compile time.
+const STARTPOS: PartialPosition = {
+    const ORDER: [PieceKind; 9] = [
+        PieceKind::Lance,
+        PieceKind::Knight,
+        PieceKind::Silver,
+        PieceKind::Gold,
+        PieceKind::King,
+        PieceKind::Gold,
+        PieceKind::Silver,
+        PieceKind::Knight,
+        PieceKind::Lance,
+    ];
+    let mut result = PartialPosition::empty();
+    let mut i = 0;
+    while i < 9 {
+        let file = 9 - i as u8;
+        // Safety: 1 <= file <= 9
+        let (black_pawn, white_pawn, black_back, white_back) = unsafe {
+            (
+                Square::from_u8_unchecked(file * 9 - 2),
+                Square::from_u8_unchecked(file * 9 - 6),
+                Square::from_u8_unchecked(file * 9),
+                Square::from_u8_unchecked(file * 9 - 8),
+            )
+        };
+        result = result
+            .with_piece(black_pawn, Some(Piece::B_P))
+            .with_piece(white_pawn, Some(Piece::W_P))
+            .with_piece(black_back, Some(Piece::new(ORDER[i], Color::Black)))
+            .with_piece(white_back, Some(Piece::new(ORDER[i], Color::White)));
+        i += 1;
+    }
+    result
+        .with_piece(Square::SQ_8H, Some(Piece::B_B))
+        .with_piece(Square::SQ_2B, Some(Piece::W_B))
+        .with_piece(Square::SQ_2H, Some(Piece::B_R))
+        .with_piece(Square::SQ_8B, Some(Piece::W_R))
+};
+
+/// A position with its move sequence omitted.
+///
+/// This data is insufficient for complete legality checking (such as repetition checking),
+/// but in most cases it suffices. If you need a complete legality checking, use `Position`.
+///
+/// TODO: describe exactly when a position is considered valid
+#[derive(Eq, PartialEq, Clone, Debug)]
+#[repr(C)]
+pub struct PartialPosition {
+    side: Color,
+    ply: u16,
+    hands: [Hand; 2],
+    board: [OptionPiece; 81],
+    player_bb: [Bitboard; 2],
+    piece_bb: [Bitboard; 14],
+    last_move: OptionCompactMove,
+    king_square: [OptionSquare; 2],
+    // How many pieces of each kind are on the board. Pieces in hand are not counted.
+    material: [[u8; 14]; 2],
+    // The Zobrist key of the pieces on the board.
+    board_key: u64,
+}
+
+impl PartialPosition {
+    /// Returns an empty position.
+    ///
+    /// `const`: since 0.1.6
+    pub const fn empty() -> Self {
+        Self {
+            side: Color::Black,
+            ply: 1,
+            hands: [Hand::new(); 2],
+            board: [OptionPiece::from_option(None); 81],
+            player_bb: [Bitboard::empty(); 2],
+            piece_bb: [Bitboard::empty(); PieceKind::NUM],
+            last_move: OptionCompactMove::from_option(None),
+            king_square: [OptionSquare::from_option(None); Color::NUM],
+            material: [[0; PieceKind::NUM]; Color::NUM],
+            board_key: 0,
+        }
+    }
+
+    /// Returns the starting position of shogi.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{PartialPosition, Piece, Square};
+    /// const STARTPOS: PartialPosition = PartialPosition::startpos();
+    /// assert_eq!(STARTPOS.piece_at(Square::SQ_5I), Some(Piece::B_K));
+    /// ```
+    /// `const`: since 0.1.6
+    pub const fn startpos() -> Self {
+        STARTPOS
+    }
+
+    /// Returns a new position with a piece placed on a square, or a square emptied if `piece` is [`None`].
+    ///
+    /// This is a `const` version of [`PartialPosition::piece_set`], which makes it possible to embed fixed positions as constants.
+    /// Like [`PartialPosition::piece_set`], this function makes no guarantee about the consistency of the position.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Hand, PartialPosition, Piece, PieceKind, Square};
+    /// const TSUME: PartialPosition = PartialPosition::empty()
+    ///     .with_piece(Square::SQ_1A, Some(Piece::W_K))
+    ///     .with_piece(Square::SQ_1C, Some(Piece::B_P))
+    ///     .with_hand_of_a_player(Color::Black, match Hand::new().added(PieceKind::Gold) {
+    ///         Some(hand) => hand,
+    ///         None => panic!(),
+    ///     });
+    /// assert_eq!(TSUME.to_sfen_owned(), "8k/9/8P/9/9/9/9/9/9 b G 1");
+    /// ```
+    pub const fn with_piece(mut self, square: Square, piece: Option<Piece>) -> Self {
+        if let Some(old) = self.board[square.array_index()].to_option() {
+            update_piece_counts!(self, remove old, square, zobrist::piece_key_const(old, square));
+        }
+        self.board[square.array_index()] = OptionPiece::from_option(piece);
+        if let Some(piece) = piece {
+            update_piece_counts!(self, add piece, square, zobrist::piece_key_const(piece, square));
+        }
+        self
+    }
+
+    /// Returns a new position with the player to move replaced. A `const` version of [`PartialPosition::side_to_move_set`].
+    #[inline(always)]
+    pub const fn with_side_to_move(mut self, side: Color) -> Self {
+        self.side = side;
+        self
+    }
+
+    /// Returns a new position with the hand of `color` replaced by `hand`.
     #[inline(always)]
+    pub const fn with_hand_of_a_player(mut self, color: Color, hand: Hand) -> Self {
+        self.hands[color.array_index()] = hand;
+        self
+    }
+
+    /// C interface of `startpos`.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn PartialPosition_startpos(buf: &mut MaybeUninit<Self>) {
+        buf.write(Self::startpos());
+    }
+
+    /// Finds which player is to move.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Move, PartialPosition, Square};
+    /// let mut pos = PartialPosition::startpos();
+    /// assert_eq!(pos.side_to_move(), Color::Black);
+    /// pos.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// assert_eq!(pos.side_to_move(), Color::White);
+    /// ```
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_side_to_move")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    pub extern "C" fn side_to_move(&self) -> Color {
+        self.side
+    }
+
+    /// Sets which player is to move.
+    #[cfg_attr(
+        feature = "c-exports",
+        export_name = "PartialPosition_side_to_move_set"
+    )]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    pub extern "C" fn side_to_move_set(&mut self, side: Color) {
+        self.side = side;
+    }
+
+    /// Returns the [`Hand`] of a player.
+    #[cfg_attr(
+        feature = "c-exports",
+        export_name = "PartialPosition_hand_of_a_player"
+    )]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn hand_of_a_player(&self, color: Color) -> Hand {
         // Safety: color as usize is either 1 or 2
-        *unsafe { self.hands.get_unchecked((color as u8 - 1) as usize) }
+        *unsafe { crate::common::get_unchecked(&self.hands, (color as u8 - 1) as usize) }
     }
 
     /// Gives the reference to the hand of the specified player.
@@ -521,7 +1419,7 @@ impl PartialPosition {
     #[inline(always)]
     pub fn hand_of_a_player_mut(&mut self, color: Color) -> &mut Hand {
         // Safety: color as usize is either 1 or 2
-        unsafe { self.hands.get_unchecked_mut((color as u8 - 1) as usize) }
+        unsafe { crate::common::get_unchecked_mut(&mut self.hands, (color as u8 - 1) as usize) }
     }
 
     /// Returns how many pieces of `piece` are in hand.
@@ -532,8 +1430,33 @@ impl PartialPosition {
         hand.count(piece.piece_kind())
     }
 
+    /// Sets how many pieces of `piece_kind` `color` has in hand. Returns whether this operation was successful.
+    /// This operation succeeds iff `piece_kind` is a valid piece in hand.
+    ///
+    /// This function is provided so that C callers can set up arbitrary positions.
+    /// It makes no guarantee about the consistency of the position.
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn PartialPosition_hand_set_c(
+        &mut self,
+        color: Color,
+        piece_kind: PieceKind,
+        count: u8,
+    ) -> bool {
+        let hand = self.hand_of_a_player_mut(color);
+        match hand.with_count(piece_kind, count) {
+            Some(new) => {
+                *hand = new;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Finds how many moves were made.
-    #[export_name = "PartialPosition_ply"]
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_ply")]
     #[must_use]
     pub extern "C" fn ply(&self) -> u16 {
         self.ply
@@ -541,8 +1464,9 @@ impl PartialPosition {
 
     /// Sets how many moves are made. Returns whether this operation was successful.
     /// This operation succeeds iff `ply != 0`.
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_ply_set")]
     #[must_use]
-    pub fn ply_set(&mut self, ply: u16) -> bool {
+    pub extern "C" fn ply_set(&mut self, ply: u16) -> bool {
         if ply == 0 {
             return false;
         }
@@ -567,12 +1491,36 @@ impl PartialPosition {
     }
 
     /// C interface to [`PartialPosition::piece_at`].
-    #[no_mangle]
-    #[inline(always)]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn PartialPosition_piece_at(&self, square: Square) -> OptionPiece {
         let index = square.index() - 1;
         // Safety: square.index() is in range 1..=81
-        *unsafe { self.board.get_unchecked(index as usize) }
+        *unsafe { crate::common::get_unchecked(&self.board, index as usize) }
+    }
+
+    /// Writes the pieces on all squares to `out`. `out[square.array_index()]` is the piece on `square`.
+    ///
+    /// This function is provided so that C callers can find the whole board in one call.
+    ///
+    /// # Safety
+    /// `out` must be valid for writes of 81 elements.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{PartialPosition, Piece, Square};
+    /// # use shogi_core::c_compat::OptionPiece;
+    /// let pos = PartialPosition::startpos();
+    /// let mut board = [OptionPiece::from(None); 81];
+    /// unsafe { PartialPosition::PartialPosition_board(&pos, board.as_mut_ptr()) };
+    /// assert_eq!(Option::<Piece>::from(board[Square::SQ_2H.array_index()]), Some(Piece::B_R));
+    /// assert_eq!(Option::<Piece>::from(board[Square::SQ_5E.array_index()]), None);
+    /// ```
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn PartialPosition_board(&self, out: *mut OptionPiece) {
+        core::ptr::copy_nonoverlapping(self.board.as_ptr(), out, 81);
     }
 
     /// Place a piece on a square.
@@ -580,55 +1528,81 @@ impl PartialPosition {
     /// This function makes no guarantee about the consistency of the position.
     /// Users should have a good reason when using it. Exported for parsers.
     pub fn piece_set(&mut self, square: Square, piece: Option<Piece>) {
-        let index = square.index() - 1;
-        let old = self.piece_at(square);
-        // Safety: square.index() is in range 1..=81
-        *unsafe { self.board.get_unchecked_mut(index as usize) } = OptionPiece::from(piece);
-        let single = Bitboard::single_inlined(square);
-        self.player_bb[0] = single.andnot(self.player_bb[0]);
-        self.player_bb[1] = single.andnot(self.player_bb[1]);
-        if let Some((_, color)) = piece.map(Piece::to_parts) {
-            match color {
-                Color::Black => self.player_bb[0] |= single,
-                Color::White => self.player_bb[1] |= single,
-            }
-        }
-        if let Some(piece) = old {
-            let piece_kind = piece.piece_kind();
-            self.piece_bb[piece_kind.array_index()] =
-                single.andnot(self.piece_bb[piece_kind.array_index()]);
+        if let Some(old) = self.piece_at(square) {
+            update_piece_counts!(self, remove old, square, zobrist::piece_key(old, square));
         }
+        // Safety: square.array_index() is in range 0..81
+        *unsafe { crate::common::get_unchecked_mut(&mut self.board, square.array_index()) } =
+            OptionPiece::from(piece);
         if let Some(piece) = piece {
-            let piece_kind = piece.piece_kind();
-            self.piece_bb[piece_kind.array_index()] |= single;
-            if let Piece::B_K = piece {
-                self.king_square[0] = OptionSquare::from(Some(square));
-            }
-            if let Piece::W_K = piece {
-                self.king_square[1] = OptionSquare::from(Some(square));
-            }
+            update_piece_counts!(self, add piece, square, zobrist::piece_key(piece, square));
         }
     }
 
+    /// C interface to [`PartialPosition::piece_set`].
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn PartialPosition_piece_set_c(&mut self, square: Square, piece: OptionPiece) {
+        self.piece_set(square, piece.into());
+    }
+
+    /// Returns the Zobrist key of this position.
+    ///
+    /// The key depends only on the pieces on the board, the pieces in hand and the player to move;
+    /// the number of moves made and the last move are not taken into account.
+    /// The key of the pieces on the board is cached and updated by [`PartialPosition::piece_set`],
+    /// so this function is cheap.
+    /// Keys are stable across releases; see [`zobrist`](crate::zobrist) for how they are computed.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Move, PartialPosition, Square};
+    /// let mut pos = PartialPosition::startpos();
+    /// let key = pos.zobrist_key();
+    /// for (from, to) in [
+    ///     (Square::SQ_2H, Square::SQ_3H),
+    ///     (Square::SQ_8B, Square::SQ_7B),
+    ///     (Square::SQ_3H, Square::SQ_2H),
+    ///     (Square::SQ_7B, Square::SQ_8B),
+    /// ] {
+    ///     pos.make_move(Move::Normal { from, to, promote: false }).unwrap();
+    /// }
+    /// assert_eq!(pos.zobrist_key(), key);
+    /// assert_ne!(pos, PartialPosition::startpos()); // the ply differs
+    /// ```
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_zobrist_key")]
+    pub extern "C" fn zobrist_key(&self) -> u64 {
+        let side_key = match self.side {
+            Color::Black => 0,
+            Color::White => zobrist::SIDE_KEY,
+        };
+        self.board_key ^ zobrist::hands_key(self.hands) ^ side_key
+    }
+
     /// Finds the subset of squares with a piece.
     ///
     /// Since: 0.1.4
-    #[export_name = "PartialPosition_occupied_bitboard"]
-    #[inline(always)]
+    #[cfg_attr(
+        feature = "c-exports",
+        export_name = "PartialPosition_occupied_bitboard"
+    )]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn occupied_bitboard(&self) -> Bitboard {
         self.player_bb[0] | self.player_bb[1]
     }
 
     /// Finds the subset of squares with no pieces.
-    #[export_name = "PartialPosition_vacant_bitboard"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_vacant_bitboard")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn vacant_bitboard(&self) -> Bitboard {
         !self.occupied_bitboard()
     }
 
     /// Finds the subset of squares where a piece of the specified player is placed.
-    #[export_name = "PartialPosition_player_bitboard"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_player_bitboard")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn player_bitboard(&self, color: Color) -> Bitboard {
         self.player_bb[color.array_index()]
     }
@@ -644,8 +1618,8 @@ impl PartialPosition {
     /// let white_rook = pos.piece_bitboard(Piece::W_R);
     /// assert_eq!(white_rook, Bitboard::single(Square::SQ_8B));
     /// ```
-    #[export_name = "PartialPosition_piece_bitboard"]
-    #[inline(always)]
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_piece_bitboard")]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn piece_bitboard(&self, piece: Piece) -> Bitboard {
         let (piece_kind, color) = piece.to_parts();
         self.piece_bb[piece_kind.array_index()] & self.player_bb[color.array_index()]
@@ -660,8 +1634,11 @@ impl PartialPosition {
     /// let rooks = pos.piece_kind_bitboard(PieceKind::Rook);
     /// assert_eq!(rooks, Bitboard::single(Square::SQ_2H) | Bitboard::single(Square::SQ_8B));
     /// ```
-    #[export_name = "PartialPosition_piece_kind_bitboard"]
-    #[inline(always)]
+    #[cfg_attr(
+        feature = "c-exports",
+        export_name = "PartialPosition_piece_kind_bitboard"
+    )]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
     pub extern "C" fn piece_kind_bitboard(&self, piece_kind: PieceKind) -> Bitboard {
         self.piece_bb[piece_kind.array_index()]
     }
@@ -689,16 +1666,78 @@ impl PartialPosition {
     }
 
     /// C interface to [`PartialPosition::last_compact_move`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn PartialPosition_last_compact_move(&self) -> OptionCompactMove {
         self.last_move
     }
 
+    /// Finds how many pieces of each kind `color` owns, indexed by [`PieceKind::array_index`].
+    ///
+    /// Pieces on the board are counted as they are, and pieces in hand are counted as unpromoted pieces.
+    /// The counts of pieces on the board are maintained incrementally, so this function does not scan the board.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Move, PartialPosition, PieceKind, Square};
+    /// let mut pos = PartialPosition::startpos();
+    /// assert_eq!(pos.material(Color::Black)[PieceKind::Pawn.array_index()], 9);
+    /// assert_eq!(pos.material(Color::Black)[PieceKind::ProBishop.array_index()], 0);
+    /// pos.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// pos.make_move(Move::Normal { from: Square::SQ_3C, to: Square::SQ_3D, promote: false }).unwrap();
+    /// pos.make_move(Move::Normal { from: Square::SQ_8H, to: Square::SQ_2B, promote: true }).unwrap();
+    /// let material = pos.material(Color::Black);
+    /// assert_eq!(material[PieceKind::Bishop.array_index()], 1); // in hand
+    /// assert_eq!(material[PieceKind::ProBishop.array_index()], 1); // on the board
+    /// assert_eq!(pos.material(Color::White)[PieceKind::Bishop.array_index()], 0);
+    /// ```
+    pub fn material(&self, color: Color) -> [u8; PieceKind::NUM] {
+        let mut result = self.material[color.array_index()];
+        let hand = self.hand_of_a_player(color);
+        for piece_kind in Hand::all_hand_pieces() {
+            // Safety: `piece_kind` is a valid piece in hand
+            let count = unsafe { crate::common::unwrap_unchecked(hand.count(piece_kind)) };
+            result[piece_kind.array_index()] = result[piece_kind.array_index()].wrapping_add(count);
+        }
+        result
+    }
+
     #[inline(always)]
     pub fn king_position(&self, color: Color) -> Option<Square> {
         self.king_square[color.array_index()].into()
     }
 
+    /// Finds the squares within `radius` files and ranks of `color`'s king, clipped to the board.
+    /// The square of the king is included, so a radius of 1 gives the king and its neighbors.
+    ///
+    /// Returns an empty [`Bitboard`] if `color` has no king.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Bitboard, Color, PartialPosition, Square};
+    /// let position = PartialPosition::startpos();
+    /// let zone = position.king_zone(Color::Black, 1);
+    /// assert_eq!(zone, Bitboard::rect(4..=6, 8..=9));
+    /// assert_eq!(position.king_zone(Color::White, 2).count(), 15);
+    /// assert_eq!(position.king_zone(Color::White, 0), Bitboard::single(Square::SQ_5A));
+    /// assert_eq!(position.king_zone(Color::White, u8::MAX).count(), 81);
+    /// assert!(PartialPosition::empty().king_zone(Color::Black, 1).is_empty());
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_king_zone")]
+    pub extern "C" fn king_zone(&self, color: Color, radius: u8) -> Bitboard {
+        let king = match self.king_position(color) {
+            Some(king) => king,
+            None => return Bitboard::empty(),
+        };
+        let (file, rank) = (king.file(), king.rank());
+        Bitboard::rect(
+            file.saturating_sub(radius)..=file.saturating_add(radius),
+            rank.saturating_sub(radius)..=rank.saturating_add(radius),
+        )
+    }
+
     /// Makes a move. Note that this function will never check legality.
     ///
     /// Returns Some(()) if the given move makes sense, i.e.,
@@ -719,13 +1758,7 @@ impl PartialPosition {
                         return None;
                     }
                     let hand = self.hand_of_a_player_mut(piece.color());
-                    let obtaining = enemy.piece_kind();
-                    let unpromoted = if let Some(piece_kind) = obtaining.unpromote() {
-                        piece_kind
-                    } else {
-                        obtaining
-                    };
-                    *hand = hand.added(unpromoted)?;
+                    *hand = hand.added(enemy.piece_kind().unpromoted_or_self())?;
                 }
                 self.piece_set(from, None);
                 self.piece_set(to, Some(target_piece));
@@ -758,38 +1791,87 @@ impl PartialPosition {
     /// moves a piece to another square or drops a piece on a vacant square.
     ///
     /// If it returns false, it is guaranteed that self is not modified.
-    #[export_name = "PartialPosition_make_compact_move"]
+    #[cfg_attr(
+        feature = "c-exports",
+        export_name = "PartialPosition_make_compact_move"
+    )]
     pub extern "C" fn make_compact_move(&mut self, mv: CompactMove) -> bool {
         let mv = mv.into();
         self.make_move(mv).is_some()
     }
 
+    /// Makes a null move (pass), which is used in null move pruning. Note that null moves are illegal in shogi.
+    ///
+    /// The side to move is flipped and the ply is incremented, so that the SFEN of the resulting position makes sense.
+    /// The last move is cleared, because a null move is not a [`Move`].
+    /// To undo a null move, pass the last move before it to [`PartialPosition::unmake_null_move`].
+    ///
+    /// Returns `false` if the ply would overflow. If it returns `false`, it is guaranteed that `self` is not modified.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Move, PartialPosition, Square};
+    /// let mut pos = PartialPosition::startpos();
+    /// pos.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// let last_move = pos.last_compact_move();
+    /// assert!(pos.make_null_move());
+    /// assert_eq!(pos.to_sfen_owned(), "lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL b - 3");
+    /// assert_eq!(pos.last_move(), None);
+    /// assert!(pos.unmake_null_move(last_move));
+    /// assert_eq!(pos.to_sfen_owned(), "lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL w - 2");
+    /// assert_eq!(pos.last_compact_move(), last_move);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_make_null_move")]
+    pub extern "C" fn make_null_move(&mut self) -> bool {
+        let ply = match self.ply.checked_add(1) {
+            Some(ply) => ply,
+            None => return false,
+        };
+        self.last_move = None.into();
+        self.side = self.side.flip();
+        self.ply = ply;
+        true
+    }
+
+    /// Undoes a null move made by [`PartialPosition::make_null_move`].
+    /// `last_move` should be the last move before the null move, which is restored.
+    ///
+    /// Returns `false` if the ply would become 0. If it returns `false`, it is guaranteed that `self` is not modified.
+    ///
+    /// Since: 0.1.6
+    pub fn unmake_null_move(&mut self, last_move: Option<CompactMove>) -> bool {
+        self.PartialPosition_unmake_null_move(last_move.into())
+    }
+
+    /// C interface of [`PartialPosition::unmake_null_move`].
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn PartialPosition_unmake_null_move(
+        &mut self,
+        last_move: OptionCompactMove,
+    ) -> bool {
+        if self.ply <= 1 {
+            return false;
+        }
+        self.last_move = last_move;
+        self.side = self.side.flip();
+        self.ply -= 1;
+        true
+    }
+
+    // Sets the last move without making it.
+    #[cfg(any(feature = "alloc", feature = "long-effect"))]
+    pub(crate) fn last_move_set(&mut self, last_move: Option<CompactMove>) {
+        self.last_move = last_move.into();
+    }
+
     /// Write the current position in SFEN notation.
     pub fn to_sfen<W: Write>(&self, sink: &mut W) -> FmtResult {
-        for i in 0..9 {
-            let mut vacant = 0;
-            for j in 0..9 {
-                // Safety: the index is in range 0..81.
-                let current = *unsafe { self.board.get_unchecked(9 * (8 - j) + i) };
-                let current: Option<Piece> = current.into();
-                if let Some(occupying) = current {
-                    if vacant > 0 {
-                        write_u8(sink, vacant)?;
-                        vacant = 0;
-                    }
-                    occupying.to_usi(sink)?;
-                } else {
-                    vacant += 1;
-                }
-            }
-            if vacant > 0 {
-                write_u8(sink, vacant)?;
-            }
-            if i < 8 {
-                // Safety: '/' is in ASCII
-                unsafe { write_ascii_byte(sink, b'/') }?;
-            }
-        }
+        self.board().to_sfen(sink)?;
         // Safety: ' ' is in ASCII
         unsafe { write_ascii_byte(sink, b' ') }?;
         self.side.to_usi(sink)?;
@@ -822,23 +1904,435 @@ impl PartialPosition {
         s
     }
 
-    /// C interface of `to_sfen`.
+    /// Returns the canonical SFEN representation of the current position.
     ///
-    /// # Safety
-    /// This function writes to `ptr` at most 139 (= 129 + 1 + 1 + 1 + 0 + 1 + 5 + 1) bytes.
-    /// Caller should ensure that `ptr` has enough space for that.
-    #[export_name = "PartialPosition_to_sfen_c"]
-    pub unsafe extern "C" fn to_sfen_c(&self, ptr: *mut u8) {
-        struct Bridge(*mut u8);
-        impl Write for Bridge {
-            #[inline(always)]
-            fn write_str(&mut self, s: &str) -> FmtResult {
-                fn inner(this: &mut Bridge, s: &str) {
-                    let slice = s.as_bytes();
-                    unsafe {
-                        for (i, &byte) in slice.iter().enumerate() {
-                            core::ptr::write(this.0.add(i), byte);
-                        }
+    /// [`PartialPosition::to_sfen`] always writes the same string for the same position:
+    /// pieces in hand are written in the order `RBGSNLPrbgsnlp`, and consecutive vacant squares are written as a single digit.
+    /// In addition, if `reset_ply` is `true`, the ply is written as 1, so that the same position reached at different plies has the same key.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PartialPosition;
+    /// let pos = PartialPosition::from_sfen("8k/9/9/9/9/9/9/9/K7+R w 2Pb 30").unwrap();
+    /// assert_eq!(pos.canonical_sfen(false), "8k/9/9/9/9/9/9/9/K7+R w 2Pb 30");
+    /// assert_eq!(pos.canonical_sfen(true), "8k/9/9/9/9/9/9/9/K7+R w 2Pb 1");
+    ///```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn canonical_sfen(&self, reset_ply: bool) -> alloc::string::String {
+        if reset_ply && self.ply != 1 {
+            let mut position = self.clone();
+            position.ply = 1;
+            return position.to_sfen_owned();
+        }
+        self.to_sfen_owned()
+    }
+
+    /// Normalizes a position in SFEN notation. See [`PartialPosition::canonical_sfen`] for the normal form.
+    ///
+    /// Returns [`None`] if `sfen` is not a valid SFEN. See [`PartialPosition::from_sfen`] for what is accepted.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PartialPosition;
+    /// assert_eq!(
+    ///     PartialPosition::normalize_sfen("8k/9/9/9/9/9/9/9/K1111111+R  w b2P  30", false),
+    ///     Some("8k/9/9/9/9/9/9/9/K7+R w 2Pb 30".to_string()),
+    /// );
+    /// assert_eq!(
+    ///     PartialPosition::normalize_sfen("8k/9/9/9/9/9/9/9/K7+R w 2Pb", true),
+    ///     Some("8k/9/9/9/9/9/9/9/K7+R w 2Pb 1".to_string()),
+    /// );
+    /// assert_eq!(PartialPosition::normalize_sfen("8k/9/9/9/9/9/9/9/K7+R x - 1", false), None);
+    ///```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn normalize_sfen(sfen: &str, reset_ply: bool) -> Option<alloc::string::String> {
+        Some(Self::from_sfen(sfen)?.canonical_sfen(reset_ply))
+    }
+
+    /// Parses a position in SFEN notation, e.g. `lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1`.
+    ///
+    /// The ply may be omitted, in which case it is assumed to be 1.
+    /// This function does not check the legality of the position: for example, positions with two pawns on the same file are accepted.
+    /// If `sfen` is not a valid SFEN, this function returns [`None`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, PartialPosition, Piece, Square};
+    /// let sfen = "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1";
+    /// assert_eq!(PartialPosition::from_sfen(sfen), Some(PartialPosition::startpos()));
+    /// let pos = PartialPosition::from_sfen("8k/9/9/9/9/9/9/9/K7+R w 2Pb 30").unwrap();
+    /// assert_eq!(pos.side_to_move(), Color::White);
+    /// assert_eq!(pos.piece_at(Square::SQ_1I), Some(Piece::B_PR));
+    /// assert_eq!(pos.hand(Piece::B_P), Some(2));
+    /// assert_eq!(pos.hand(Piece::W_B), Some(1));
+    /// assert_eq!(pos.ply(), 30);
+    /// assert_eq!(PartialPosition::from_sfen("9/9/9/9/9/9/9/9/8 b - 1"), None);
+    /// ```
+    pub fn from_sfen(sfen: &str) -> Option<Self> {
+        Self::from_sfen_bytes(sfen.as_bytes())
+    }
+
+    /// Parses a position in SFEN notation from bytes. See [`PartialPosition::from_sfen`] for what is accepted.
+    ///
+    /// This function neither allocates nor requires `sfen` to be valid UTF-8,
+    /// so that positions can be read directly from a buffer, e.g. one received over a serial port.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PartialPosition;
+    /// let buf = b"lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1\r\n";
+    /// assert_eq!(PartialPosition::from_sfen_bytes(buf), Some(PartialPosition::startpos()));
+    /// assert_eq!(PartialPosition::from_sfen_bytes(b"8k/9/9/9/9/9/9/9/K8 b - \xff"), None);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn from_sfen_bytes(sfen: &[u8]) -> Option<Self> {
+        Self::try_from_sfen_bytes(sfen).ok()
+    }
+
+    /// Parses a position in SFEN notation, reporting what is wrong and where if it fails.
+    ///
+    /// This function accepts the same inputs as [`PartialPosition::from_sfen`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{PartialPosition, SfenParseErrorKind};
+    /// let error = PartialPosition::try_from_sfen("9/9/9/9/9/9/9/9/9 b 0P 1").unwrap_err();
+    /// assert_eq!(error.kind(), SfenParseErrorKind::InvalidHandCount);
+    /// assert_eq!(error.offset(), 20);
+    /// let pos = PartialPosition::try_from_sfen("9/9/9/9/9/9/9/9/9 b -").unwrap();
+    /// assert_eq!(pos.ply(), 1);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn try_from_sfen(sfen: &str) -> Result<Self, SfenParseError> {
+        Self::try_from_sfen_bytes(sfen.as_bytes())
+    }
+
+    /// Parses a position in SFEN notation from bytes, reporting what is wrong and where if it fails.
+    /// See [`PartialPosition::try_from_sfen`].
+    ///
+    /// Since: 0.1.6
+    pub fn try_from_sfen_bytes(sfen: &[u8]) -> Result<Self, SfenParseError> {
+        let mut index = 0;
+        let mut fields = core::iter::from_fn(|| {
+            while sfen.get(index).map_or(false, u8::is_ascii_whitespace) {
+                index += 1;
+            }
+            if index == sfen.len() {
+                return None;
+            }
+            let start = index;
+            while sfen
+                .get(index)
+                .map_or(false, |byte| !byte.is_ascii_whitespace())
+            {
+                index += 1;
+            }
+            Some((start, &sfen[start..index]))
+        });
+        let missing = SfenParseError::new(SfenParseErrorKind::MissingField, sfen.len());
+        let board = fields.next().ok_or(missing)?;
+        let side = fields.next().ok_or(missing)?;
+        let hands = fields.next().ok_or(missing)?;
+        let ply = fields.next();
+        if let Some((start, _)) = fields.next() {
+            return Err(SfenParseError::new(
+                SfenParseErrorKind::TrailingField,
+                start,
+            ));
+        }
+        Self::parse_sfen_fields(board, side, hands, ply)
+    }
+
+    /// Replaces `self` with the position in SFEN notation given as bytes. See [`PartialPosition::from_sfen_bytes`].
+    ///
+    /// Returns `true` if `sfen` is a valid SFEN. Otherwise, this function returns `false` and `self` is not modified.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, PartialPosition};
+    /// let mut pos = PartialPosition::startpos();
+    /// assert!(pos.sfen_set(b"8k/9/9/9/9/9/9/9/K8 w 2G 10"));
+    /// assert_eq!(pos.side_to_move(), Color::White);
+    /// assert!(!pos.sfen_set(b"8k/9/9/9/9/9/9/9/K8 x - 1"));
+    /// assert_eq!(pos.ply(), 10);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn sfen_set(&mut self, sfen: &[u8]) -> bool {
+        match Self::from_sfen_bytes(sfen) {
+            Some(position) => {
+                *self = position;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// C interface of [`PartialPosition::sfen_set`]. The SFEN is read from `len` bytes starting at `ptr`,
+    /// which need not be nul-terminated.
+    ///
+    /// # Safety
+    /// `ptr` must be null or valid for reads of `len` bytes.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn PartialPosition_sfen_set_c(
+        &mut self,
+        ptr: *const u8,
+        len: usize,
+    ) -> bool {
+        self.sfen_set(c_bytes(ptr, len))
+    }
+
+    // Parses a position from the fields of SFEN.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn from_sfen_fields(
+        board: &[u8],
+        side: &[u8],
+        hands: &[u8],
+        ply: Option<&[u8]>,
+    ) -> Option<Self> {
+        Self::parse_sfen_fields((0, board), (0, side), (0, hands), ply.map(|ply| (0, ply))).ok()
+    }
+
+    // Parses a position from the fields of SFEN, each of which comes with its offset in the input.
+    fn parse_sfen_fields(
+        (board_start, board): (usize, &[u8]),
+        (side_start, side): (usize, &[u8]),
+        (hands_start, hands): (usize, &[u8]),
+        ply: Option<(usize, &[u8])>,
+    ) -> Result<Self, SfenParseError> {
+        let mut result = Self::empty();
+        let board =
+            Self::parse_sfen_board_bytes(board).map_err(|error| error.shifted(board_start))?;
+        for (square, &piece) in Square::all().zip(board.iter()) {
+            if piece.is_some() {
+                result.piece_set(square, piece);
+            }
+        }
+        result.side = match side {
+            b"b" => Color::Black,
+            b"w" => Color::White,
+            _ => {
+                return Err(SfenParseError::new(
+                    SfenParseErrorKind::InvalidSide,
+                    side_start,
+                ))
+            }
+        };
+        result.hands = Hand::parse_sfen_bytes(hands).map_err(|error| error.shifted(hands_start))?;
+        if let Some((ply_start, ply)) = ply {
+            let mut value: u16 = 0;
+            for (index, &byte) in ply.iter().enumerate() {
+                let invalid =
+                    SfenParseError::new(SfenParseErrorKind::InvalidPly, ply_start + index);
+                if !byte.is_ascii_digit() {
+                    return Err(invalid);
+                }
+                value = value
+                    .checked_mul(10)
+                    .and_then(|value| value.checked_add((byte - b'0') as u16))
+                    .ok_or(invalid)?;
+            }
+            if !result.ply_set(value) {
+                return Err(SfenParseError::new(
+                    SfenParseErrorKind::InvalidPly,
+                    ply_start,
+                ));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Parses the board field of SFEN, e.g. `lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL`.
+    ///
+    /// The result is indexed by [`Square::array_index`]. Only the syntax is checked: for example, boards without kings are accepted.
+    /// If `board` is not a valid board field, this function returns [`None`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{PartialPosition, Piece, Square};
+    /// let board = PartialPosition::parse_sfen_board("8k/9/9/9/9/9/9/9/K7+R").unwrap();
+    /// assert_eq!(board[Square::SQ_1A.array_index()], Some(Piece::W_K));
+    /// assert_eq!(board[Square::SQ_1I.array_index()], Some(Piece::B_PR));
+    /// assert_eq!(board.iter().filter(|piece| piece.is_some()).count(), 3);
+    /// assert_eq!(PartialPosition::parse_sfen_board("9/9/9/9/9/9/9/9/8"), None);
+    /// assert_eq!(PartialPosition::parse_sfen_board("9/9/9/9/9/9/9/9/9 b - 1"), None);
+    /// ```
+    pub fn parse_sfen_board(board: &str) -> Option<[Option<Piece>; 81]> {
+        Self::parse_sfen_board_bytes(board.as_bytes()).ok()
+    }
+
+    /// Checks if two SFEN strings describe the same position, ignoring the ply.
+    ///
+    /// Fields are compared by their meaning rather than by their text:
+    /// for example, `1` followed by `1` in a rank equals `2`, and `2Pb` equals `bPP` in the hands.
+    /// Only the board and the hands are parsed, so this is cheaper than constructing a [`PartialPosition`] from each string,
+    /// which helps when removing duplicates from a large set of positions.
+    /// Returns `false` if either string is not a valid SFEN, except that the ply is not checked.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PartialPosition;
+    /// assert!(PartialPosition::sfen_eq(
+    ///     "8k/9/9/9/9/9/9/9/K7+R w 2Pb 30",
+    ///     "8k/9/9/9/9/9/9/9/K1111111+R w bPP 1",
+    /// ));
+    /// assert!(PartialPosition::sfen_eq("8k/9/9/9/9/9/9/9/K7+R w - 1", "8k/9/9/9/9/9/9/9/K7+R w -"));
+    /// assert!(!PartialPosition::sfen_eq("8k/9/9/9/9/9/9/9/K7+R w - 1", "8k/9/9/9/9/9/9/9/K7+R b - 1"));
+    /// assert!(!PartialPosition::sfen_eq("8k/9/9/9/9/9/9/9/K7+R w P 1", "8k/9/9/9/9/9/9/9/K7+R w p 1"));
+    /// assert!(!PartialPosition::sfen_eq("8k/9/9/9/9/9/9/9/K7+R x - 1", "8k/9/9/9/9/9/9/9/K7+R x - 1"));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn sfen_eq(a: &str, b: &str) -> bool {
+        // Splits a SFEN into the board, the side to move and the hands.
+        fn fields(sfen: &str) -> Option<(&str, &str, &str)> {
+            let mut fields = sfen.split_ascii_whitespace();
+            let result = (fields.next()?, fields.next()?, fields.next()?);
+            // The ply, which is ignored
+            fields.next();
+            if fields.next().is_some() {
+                return None;
+            }
+            Some(result)
+        }
+        let ((board_a, side_a, hands_a), (board_b, side_b, hands_b)) = match (fields(a), fields(b))
+        {
+            (Some(a), Some(b)) => (a, b),
+            _ => return false,
+        };
+        if !matches!(side_a, "b" | "w") || side_a != side_b {
+            return false;
+        }
+        let hands_eq = match (
+            Hand::parse_sfen_bytes(hands_a.as_bytes()),
+            Hand::parse_sfen_bytes(hands_b.as_bytes()),
+        ) {
+            (Ok(hands_a), Ok(hands_b)) => hands_a == hands_b,
+            _ => false,
+        };
+        hands_eq && Self::sfen_board_field_eq(board_a, board_b)
+    }
+
+    /// Checks if two SFEN strings have the same board, ignoring the side to move, the hands and the ply.
+    ///
+    /// Only the first field of each string is read, so both full SFEN strings and board fields are accepted.
+    /// Boards are compared by their meaning as in [`PartialPosition::sfen_eq`].
+    /// Returns `false` if either board is not valid.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PartialPosition;
+    /// assert!(PartialPosition::sfen_board_eq("8k/9/9/9/9/9/9/9/K7+R w 2Pb 30", "8k/9/9/9/9/9/9/9/K1111111+R b - 1"));
+    /// assert!(PartialPosition::sfen_board_eq("8k/9/9/9/9/9/9/9/K7+R", "8k/9/9/9/9/9/9/9/K7+R b - 1"));
+    /// assert!(!PartialPosition::sfen_board_eq("8k/9/9/9/9/9/9/9/K7+R", "8k/9/9/9/9/9/9/9/K7R"));
+    /// assert!(!PartialPosition::sfen_board_eq("8k/9/9/9/9/9/9/9/K8R", "8k/9/9/9/9/9/9/9/K8R"));
+    /// assert!(!PartialPosition::sfen_board_eq("", ""));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn sfen_board_eq(a: &str, b: &str) -> bool {
+        match (
+            a.split_ascii_whitespace().next(),
+            b.split_ascii_whitespace().next(),
+        ) {
+            (Some(a), Some(b)) => Self::sfen_board_field_eq(a, b),
+            _ => false,
+        }
+    }
+
+    // Checks if two board fields of SFEN are valid and equal.
+    fn sfen_board_field_eq(a: &str, b: &str) -> bool {
+        // Identical fields are common in practice and need parsing only once.
+        if a == b {
+            return Self::parse_sfen_board_bytes(a.as_bytes()).is_ok();
+        }
+        match (
+            Self::parse_sfen_board_bytes(a.as_bytes()),
+            Self::parse_sfen_board_bytes(b.as_bytes()),
+        ) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    // Parses the board field of SFEN. Offsets in the error are relative to `bytes`.
+    fn parse_sfen_board_bytes(bytes: &[u8]) -> Result<[Option<Piece>; 81], SfenParseError> {
+        let error = |kind, offset| Err(SfenParseError::new(kind, offset));
+        let mut result = [None; 81];
+        let mut rank = 1;
+        // The file where the next piece is placed. 0 means the current rank is full.
+        let mut file: u8 = 9;
+        let mut index = 0;
+        while let Some(&byte) = bytes.get(index) {
+            match byte {
+                b'/' => {
+                    if file != 0 {
+                        return error(SfenParseErrorKind::TooFewFiles, index);
+                    }
+                    if rank == 9 {
+                        return error(SfenParseErrorKind::TooManyRanks, index);
+                    }
+                    rank += 1;
+                    file = 9;
+                    index += 1;
+                }
+                b'1'..=b'9' => {
+                    file = match file.checked_sub(byte - b'0') {
+                        Some(file) => file,
+                        None => return error(SfenParseErrorKind::TooManyFiles, index),
+                    };
+                    index += 1;
+                }
+                _ => {
+                    let len = if byte == b'+' { 2 } else { 1 };
+                    let piece = bytes
+                        .get(index..index + len)
+                        .and_then(Piece::parse_usi_bytes);
+                    let piece = match piece {
+                        Some(piece) => piece,
+                        None => return error(SfenParseErrorKind::InvalidPiece, index),
+                    };
+                    let square = match Square::new(file, rank) {
+                        Some(square) => square,
+                        None => return error(SfenParseErrorKind::TooManyFiles, index),
+                    };
+                    result[square.array_index()] = Some(piece);
+                    file -= 1;
+                    index += len;
+                }
+            }
+        }
+        if file != 0 {
+            return error(SfenParseErrorKind::TooFewFiles, index);
+        }
+        if rank != 9 {
+            return error(SfenParseErrorKind::TooFewRanks, index);
+        }
+        Ok(result)
+    }
+
+    /// C interface of `to_sfen`.
+    ///
+    /// # Safety
+    /// This function writes to `ptr` at most 139 (= 129 + 1 + 1 + 1 + 0 + 1 + 5 + 1) bytes.
+    /// Caller should ensure that `ptr` has enough space for that.
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_to_sfen_c")]
+    pub unsafe extern "C" fn to_sfen_c(&self, ptr: *mut u8) {
+        struct Bridge(*mut u8);
+        impl Write for Bridge {
+            #[inline(always)]
+            fn write_str(&mut self, s: &str) -> FmtResult {
+                fn inner(this: &mut Bridge, s: &str) {
+                    let slice = s.as_bytes();
+                    unsafe {
+                        for (i, &byte) in slice.iter().enumerate() {
+                            core::ptr::write(this.0.add(i), byte);
+                        }
                         this.0 = this.0.add(slice.len());
                     }
                 }
@@ -851,17 +2345,73 @@ impl PartialPosition {
         // Safety: nul is in ASCII
         let _ = write_ascii_byte(&mut sink, b'\0');
     }
+
+    /// C interface of `to_sfen` with bounds checking.
+    ///
+    /// This function writes the SFEN representation of `self` followed by a nul byte to `ptr`,
+    /// and returns the number of bytes written excluding the nul byte.
+    /// If `capacity` is not large enough to hold them, this function returns 0. In that case, the contents of `ptr` are unspecified.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for writes of `capacity` bytes.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::PartialPosition;
+    /// let pos = PartialPosition::startpos();
+    /// let mut buf = [0u8; 100];
+    /// let written = unsafe { pos.to_sfen_bounded(buf.as_mut_ptr(), buf.len()) };
+    /// assert_eq!(&buf[..written], b"lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1");
+    /// assert_eq!(buf[written], 0);
+    /// assert_eq!(unsafe { pos.to_sfen_bounded(buf.as_mut_ptr(), written) }, 0);
+    /// ```
+    #[cfg_attr(feature = "c-exports", export_name = "PartialPosition_to_sfen_bounded")]
+    pub unsafe extern "C" fn to_sfen_bounded(&self, ptr: *mut u8, capacity: usize) -> usize {
+        write_nul_terminated(ptr, capacity, |sink| self.to_sfen(sink))
+    }
 }
 
 impl_ord_with_fields!(PartialPosition; side, ply, hands, board, last_move);
+#[cfg(not(feature = "zobrist-hash"))]
 impl_hash_with_fields!(PartialPosition; side, ply, hands, board, last_move);
 
+/// With the `zobrist-hash` feature, only [`PartialPosition::zobrist_key`] is hashed,
+/// which is much cheaper than hashing all fields.
+#[cfg(feature = "zobrist-hash")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zobrist-hash")))]
+impl core::hash::Hash for PartialPosition {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.zobrist_key().hash(state);
+    }
+}
+impl_rkyv_as_self!(PartialPosition);
+
 impl Default for PartialPosition {
     fn default() -> Self {
         Self::startpos()
     }
 }
 
+/// Parses a position in SFEN, as [`PartialPosition::try_from_sfen`] does.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{PartialPosition, SfenParseErrorKind};
+/// let sfen = "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1";
+/// assert_eq!(sfen.parse::<PartialPosition>(), Ok(PartialPosition::startpos()));
+/// let error = "9/9/9/9/9/9/9/9/8 b - 1".parse::<PartialPosition>().unwrap_err();
+/// assert_eq!(error.kind(), SfenParseErrorKind::TooFewFiles);
+/// ```
+///
+/// Since: 0.1.6
+impl core::str::FromStr for PartialPosition {
+    type Err = SfenParseError;
+
+    fn from_str(s: &str) -> Result<Self, SfenParseError> {
+        Self::try_from_sfen(s)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -901,11 +2451,489 @@ mod tests {
         .unwrap(); // 3a2b
         assert_eq!(s.hand(Piece::B_B), Some(1));
         assert_eq!(s.hand(Piece::W_B), Some(1));
+        assert_eq!(s.material(Color::Black)[PieceKind::Bishop.array_index()], 1);
+        assert_eq!(s.material(Color::White)[PieceKind::Bishop.array_index()], 1);
+        assert_eq!(s.material(Color::White)[PieceKind::Silver.array_index()], 2);
         assert_eq!(
             s.to_sfen_owned(),
             "lnsgkg1nl/1r5s1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/7R1/LNSGKGSNL b Bb 5",
         );
     }
+    #[test]
+    fn from_sfen_works() {
+        let sfens = [
+            "lnsgkg1nl/1r5s1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/7R1/LNSGKGSNL b Bb 5",
+            "ln1g3+Rl/2sk1s+P2/2ppppb1p/p1b3p2/8P/P4P3/2PPP1P2/1+r2GS3/LN+p1KGSNL w GN2P 60",
+            "9/9/9/9/9/9/9/9/9 b 2R2B4G4S4N4L18P 1",
+        ];
+        for sfen in sfens {
+            let pos = PartialPosition::from_sfen(sfen).unwrap();
+            assert_eq!(pos.to_sfen_owned(), sfen);
+            for color in Color::all() {
+                let material = pos.material(color);
+                for piece_kind in PieceKind::all() {
+                    let on_board = pos.piece_bitboard(Piece::new(piece_kind, color)).count();
+                    let in_hand = pos.hand_of_a_player(color).count(piece_kind).unwrap_or(0);
+                    assert_eq!(material[piece_kind.array_index()], on_board + in_hand);
+                }
+            }
+        }
+        let invalid = [
+            "",
+            "9/9/9/9/9/9/9/9/9 x - 1",
+            "9/9/9/9/9/9/9/9/9 b - 0",
+            "9/9/9/9/9/9/9/9/9 b - +1",
+            "9/9/9/9/9/9/9/9/9 b - 1 1",
+            "9/9/9/9/9/9/9/9/9/9 b - 1",
+            "9/9/9/9/9/9/9/9/55 b - 1",
+            "9/9/9/9/9/9/9/9/8+ b - 1",
+            "9/9/9/9/9/9/9/9/8+K b - 1",
+            "9/9/9/9/9/9/9/9/9 b K 1",
+            "9/9/9/9/9/9/9/9/9 b +P 1",
+            "9/9/9/9/9/9/9/9/9 b 0P 1",
+            "9/9/9/9/9/9/9/9/9 b P2 1",
+            "9/9/9/9/9/9/9/9/9 b - 65536",
+        ];
+        for sfen in invalid {
+            assert_eq!(PartialPosition::from_sfen(sfen), None, "{}", sfen);
+            let mut pos = PartialPosition::startpos();
+            assert!(!pos.sfen_set(sfen.as_bytes()), "{}", sfen);
+            assert_eq!(pos, PartialPosition::startpos());
+        }
+    }
+
+    #[test]
+    fn try_from_sfen_works() {
+        use crate::SfenParseErrorKind::*;
+        let cases = [
+            ("", MissingField, 0),
+            ("9/9/9/9/9/9/9/9/9 b", MissingField, 19),
+            ("9/9/9/9/9/9/9/9/9 b - 1  1", TrailingField, 25),
+            ("9/9/9/9/9/9/9/9/4x4 b - 1", InvalidPiece, 17),
+            ("9/9/9/9/9/9/9/9/4+4 b - 1", InvalidPiece, 17),
+            ("9/9/9/9/9/9/9/9/+G8 b - 1", InvalidPiece, 16),
+            ("9/9/9/9/9/9/9/9/08 b - 1", InvalidPiece, 16),
+            ("9/9/9/9/9/9/9/9/55 b - 1", TooManyFiles, 17),
+            ("9/9/9/9/9/9/9/9/9K b - 1", TooManyFiles, 17),
+            ("9/9/9/8/9/9/9/9/9 b - 1", TooFewFiles, 7),
+            ("9/9/9/9/9/9/9/9/8 b - 1", TooFewFiles, 17),
+            ("9/9/9/9/9/9/9/9/9/9 b - 1", TooManyRanks, 17),
+            ("9/9/9/9/9/9/9/9 b - 1", TooFewRanks, 15),
+            ("9/9/9/9/9/9/9/9/9 x - 1", InvalidSide, 18),
+            ("9/9/9/9/9/9/9/9/9 b 2K 1", InvalidHandPiece, 21),
+            ("9/9/9/9/9/9/9/9/9 b P+P 1", InvalidHandPiece, 21),
+            ("9/9/9/9/9/9/9/9/9 b R256P 1", InvalidHandCount, 21),
+            ("9/9/9/9/9/9/9/9/9 b 300p 1", InvalidHandCount, 20),
+            ("9/9/9/9/9/9/9/9/9 b P2 1", InvalidHandCount, 21),
+            ("9/9/9/9/9/9/9/9/9 b 0P 1", InvalidHandCount, 20),
+            ("9/9/9/9/9/9/9/9/9 b - 0", InvalidPly, 22),
+            ("9/9/9/9/9/9/9/9/9 b - 1x", InvalidPly, 23),
+            ("9/9/9/9/9/9/9/9/9 b - 65536", InvalidPly, 26),
+        ];
+        for (sfen, kind, offset) in cases {
+            let error = PartialPosition::try_from_sfen(sfen).unwrap_err();
+            assert_eq!((error.kind(), error.offset()), (kind, offset), "{}", sfen);
+            assert_eq!(PartialPosition::from_sfen(sfen), None);
+        }
+        assert_eq!(
+            PartialPosition::try_from_sfen("9/9/9/9/9/9/9/9/9 b 18P2L 65535").map(|pos| pos.ply()),
+            Ok(65535),
+        );
+    }
+
+    #[test]
+    fn from_sfen_bytes_works() {
+        let sfen =
+            b"\tln1g3+Rl/2sk1s+P2/2ppppb1p/p1b3p2/8P/P4P3/2PPP1P2/1+r2GS3/LN+p1KGSNL  w GN2P 60\n";
+        let pos = PartialPosition::from_sfen_bytes(sfen).unwrap();
+        assert_eq!(
+            pos.to_sfen_owned(),
+            "ln1g3+Rl/2sk1s+P2/2ppppb1p/p1b3p2/8P/P4P3/2PPP1P2/1+r2GS3/LN+p1KGSNL w GN2P 60",
+        );
+        assert_eq!(
+            PartialPosition::from_sfen_bytes(b"9/9/9/9/9/9/9/9/9 b 2R2B4G4S4N4L18P"),
+            PartialPosition::from_sfen("9/9/9/9/9/9/9/9/9 b 2R2B4G4S4N4L18P 1"),
+        );
+        assert_eq!(
+            PartialPosition::from_sfen_bytes(b"9/9/9/9/9/9/9/9/9 b - 1\xff"),
+            None,
+        );
+
+        let mut pos = PartialPosition::startpos();
+        unsafe {
+            assert!(pos.PartialPosition_sfen_set_c(sfen.as_ptr(), sfen.len()));
+            assert_eq!(pos.ply(), 60);
+            assert!(!pos.PartialPosition_sfen_set_c(core::ptr::null(), 0));
+            assert_eq!(pos.ply(), 60);
+        }
+    }
+
+    #[test]
+    fn zobrist_key_works() {
+        let mut pos = PartialPosition::startpos();
+        let moves = ["7g7f", "3c3d", "8h2b+", "3a2b", "B*4e", "B*6e", "4e3d"];
+        let mut keys = vec![pos.zobrist_key()];
+        for mv in moves {
+            let mv = Move::parse_usi_bytes(mv.as_bytes(), pos.side_to_move()).unwrap();
+            pos.make_move(mv).unwrap();
+            let from_scratch = PartialPosition::from_sfen(&pos.to_sfen_owned()).unwrap();
+            assert_eq!(pos.zobrist_key(), from_scratch.zobrist_key());
+            keys.push(pos.zobrist_key());
+        }
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), moves.len() + 1);
+
+        let mut flipped = pos.clone();
+        flipped.side_to_move_set(pos.side_to_move().flip());
+        assert_ne!(flipped.zobrist_key(), pos.zobrist_key());
+        let mut hand = pos.clone();
+        *hand.hand_of_a_player_mut(Color::Black) = Hand::new();
+        assert_ne!(hand.zobrist_key(), pos.zobrist_key());
+    }
+
+    #[test]
+    fn null_move_works() {
+        let mut pos = PartialPosition::startpos();
+        let key = pos.zobrist_key();
+        assert!(!pos.unmake_null_move(None));
+        assert!(pos.make_null_move());
+        assert_ne!(pos.zobrist_key(), key);
+        assert!(pos.unmake_null_move(None));
+        assert_eq!(pos, PartialPosition::startpos());
+
+        assert!(pos.ply_set(u16::MAX));
+        let before = pos.clone();
+        assert!(!pos.make_null_move());
+        assert_eq!(pos, before);
+    }
+
+    #[test]
+    fn material_works() {
+        let mut pos = PartialPosition::startpos();
+        for color in Color::all() {
+            let material = pos.material(color);
+            for piece_kind in PieceKind::all() {
+                let piece = Piece::new(piece_kind, color);
+                assert_eq!(
+                    material[piece_kind.array_index()],
+                    pos.piece_bitboard(piece).count(),
+                );
+            }
+            assert_eq!(material.iter().map(|&x| x as u32).sum::<u32>(), 20);
+        }
+        pos.piece_set(Square::SQ_5I, None);
+        pos.piece_set(Square::SQ_5E, Some(Piece::W_PR));
+        assert_eq!(pos.material(Color::Black)[PieceKind::King.array_index()], 0);
+        assert_eq!(
+            pos.material(Color::White)[PieceKind::ProRook.array_index()],
+            1
+        );
+        pos.piece_set(Square::SQ_5E, Some(Piece::B_PP));
+        assert_eq!(
+            pos.material(Color::White)[PieceKind::ProRook.array_index()],
+            0
+        );
+        assert_eq!(
+            pos.material(Color::Black)[PieceKind::ProPawn.array_index()],
+            1
+        );
+        assert_eq!(
+            PartialPosition::empty().material(Color::Black),
+            [0; PieceKind::NUM]
+        );
+    }
+
+    #[test]
+    fn with_piece_works() {
+        let startpos = PartialPosition::startpos();
+        let from_sfen = PartialPosition::from_sfen(
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+        )
+        .unwrap();
+        assert_eq!(startpos, from_sfen);
+        assert_eq!(startpos.zobrist_key(), from_sfen.zobrist_key());
+
+        let changes = [
+            (Square::SQ_5I, None),
+            (Square::SQ_5E, Some(Piece::W_PR)),
+            (Square::SQ_5E, Some(Piece::B_PP)),
+            (Square::SQ_1A, Some(Piece::B_K)),
+        ];
+        let mut expected = startpos.clone();
+        let mut actual = startpos;
+        for (square, piece) in changes {
+            expected.piece_set(square, piece);
+            actual = actual.with_piece(square, piece);
+            assert_eq!(actual, expected);
+        }
+        expected.side_to_move_set(Color::White);
+        *expected.hand_of_a_player_mut(Color::White) = Hand::new().added(PieceKind::Pawn).unwrap();
+        let actual = actual
+            .with_side_to_move(Color::White)
+            .with_hand_of_a_player(Color::White, Hand::new().added(PieceKind::Pawn).unwrap());
+        assert_eq!(actual, expected);
+        assert_eq!(actual.zobrist_key(), expected.zobrist_key());
+    }
+
+    #[test]
+    fn board_works() {
+        let mut pos = PartialPosition::startpos();
+        pos.make_move(Move::Normal {
+            from: Square::SQ_7G,
+            to: Square::SQ_7F,
+            promote: false,
+        })
+        .unwrap();
+        let mut board = [OptionPiece::from(None); 81];
+        unsafe { pos.PartialPosition_board(board.as_mut_ptr()) };
+        for square in Square::all() {
+            assert_eq!(
+                Option::<Piece>::from(board[square.array_index()]),
+                pos.piece_at(square),
+            );
+        }
+    }
+
+    #[test]
+    fn position_startpos_c_works() {
+        let ptr = Position::Position_startpos();
+        assert!(!ptr.is_null());
+        let pos = unsafe { &mut *ptr };
+        assert_eq!(pos.inner(), &PartialPosition::startpos());
+        assert!(pos.Position_make_compact_move(CompactMove::normal(
+            Square::SQ_7G,
+            Square::SQ_7F,
+            false,
+        )));
+        assert_eq!(pos.ply(), 2);
+        unsafe { Position::Position_destruct(ptr) };
+    }
+
+    #[test]
+    fn to_sfen_bounded_works() {
+        let pos = PartialPosition::startpos();
+        let expected = pos.to_sfen_owned();
+        let mut buf = [0xffu8; 100];
+        for capacity in 0..=expected.len() {
+            assert_eq!(
+                unsafe { pos.to_sfen_bounded(buf.as_mut_ptr(), capacity) },
+                0
+            );
+        }
+        // Bytes past `capacity` are never touched
+        assert!(buf[expected.len() + 1..].iter().all(|&byte| byte == 0xff));
+        let written = unsafe { pos.to_sfen_bounded(buf.as_mut_ptr(), expected.len() + 1) };
+        assert_eq!(written, expected.len());
+        assert_eq!(&buf[..written], expected.as_bytes());
+        assert_eq!(buf[written], 0);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn validate_resolution_works() {
+        use crate::test_checker::TestChecker;
+
+        let fixed_status = |status| TestChecker {
+            status,
+            ..TestChecker::PSEUDO_LEGAL
+        };
+        let mated = fixed_status(|_| PositionStatus::BlackWins);
+        let repetition = fixed_status(|_| PositionStatus::Draw);
+        let in_progress = fixed_status(|_| PositionStatus::InProgress);
+        let invalid = fixed_status(|_| PositionStatus::Invalid);
+
+        let mut game = Game::new(Position::startpos());
+        assert_eq!(game.validate_resolution(&in_progress), Ok(()));
+        assert_eq!(
+            game.validate_resolution(&mated),
+            Err(ResolutionMismatch::Unresolved),
+        );
+        assert_eq!(
+            game.validate_resolution(&invalid),
+            Err(ResolutionMismatch::InvalidPosition),
+        );
+
+        game.resolve_with_detail(ResolutionDetail::Mated(Color::White));
+        assert_eq!(game.validate_resolution(&mated), Ok(()));
+        assert_eq!(
+            game.validate_resolution(&in_progress),
+            Err(ResolutionMismatch::NotMated),
+        );
+        assert_eq!(
+            game.validate_resolution(&repetition),
+            Err(ResolutionMismatch::WrongResult),
+        );
+
+        game.resolve_with_detail(ResolutionDetail::Resignation(Color::Black));
+        assert_eq!(game.validate_resolution(&in_progress), Ok(()));
+        assert_eq!(
+            game.validate_resolution(&mated),
+            Err(ResolutionMismatch::WrongResult),
+        );
+
+        game.resolve_with_detail(ResolutionDetail::Repetition);
+        assert_eq!(game.validate_resolution(&repetition), Ok(()));
+        assert_eq!(
+            game.validate_resolution(&in_progress),
+            Err(ResolutionMismatch::NotRepetition),
+        );
+        game.resolve_with_detail(ResolutionDetail::PerpetualCheck(Color::White));
+        assert_eq!(game.validate_resolution(&repetition), Ok(()));
+        game.resolve(GameResolution::BlackWins);
+        assert_eq!(
+            game.validate_resolution(&repetition),
+            Err(ResolutionMismatch::WrongResult),
+        );
+    }
+
+    #[test]
+    fn perpetual_check_loser_works() {
+        // A repetition without checks
+        let mut position = Position::startpos();
+        for _ in 0..3 {
+            for (from, to) in [
+                (Square::SQ_2H, Square::SQ_3H),
+                (Square::SQ_8B, Square::SQ_7B),
+                (Square::SQ_3H, Square::SQ_2H),
+                (Square::SQ_7B, Square::SQ_8B),
+            ] {
+                let mv = Move::Normal {
+                    from,
+                    to,
+                    promote: false,
+                };
+                position.make_move(mv).unwrap();
+            }
+        }
+        assert_eq!(position.perpetual_check_loser(), None);
+
+        // White's rook keeps checking black's king.
+        let initial = PartialPosition::from_sfen("4k3r/9/9/9/9/9/9/9/4K4 w - 1").unwrap();
+        let mut position = Position::arbitrary_position(initial.clone());
+        let mv = Move::Normal {
+            from: Square::SQ_1A,
+            to: Square::SQ_1I,
+            promote: false,
+        };
+        position.make_move(mv).unwrap();
+        for _ in 0..3 {
+            for (from, to) in [
+                (Square::SQ_5I, Square::SQ_5H),
+                (Square::SQ_1I, Square::SQ_1H),
+                (Square::SQ_5H, Square::SQ_5I),
+                (Square::SQ_1H, Square::SQ_1I),
+            ] {
+                let mv = Move::Normal {
+                    from,
+                    to,
+                    promote: false,
+                };
+                position.make_move(mv).unwrap();
+            }
+        }
+        assert_eq!(position.perpetual_check_loser(), Some(Color::White));
+
+        // The position appears five times, but only the last four appearances count.
+        let mut position = Position::arbitrary_position(initial);
+        position.make_move(mv).unwrap();
+        let quiet = [
+            (Square::SQ_5I, Square::SQ_4H),
+            (Square::SQ_1I, Square::SQ_1G),
+            (Square::SQ_4H, Square::SQ_5I),
+            (Square::SQ_1G, Square::SQ_1I),
+        ];
+        let checks = [
+            (Square::SQ_5I, Square::SQ_5H),
+            (Square::SQ_1I, Square::SQ_1H),
+            (Square::SQ_5H, Square::SQ_5I),
+            (Square::SQ_1H, Square::SQ_1I),
+        ];
+        for (index, cycle) in [quiet, checks, checks, checks].into_iter().enumerate() {
+            assert_eq!(position.perpetual_check_loser(), None, "{}", index);
+            for (from, to) in cycle {
+                let mv = Move::Normal {
+                    from,
+                    to,
+                    promote: false,
+                };
+                position.make_move(mv).unwrap();
+            }
+        }
+        assert_eq!(position.perpetual_check_loser(), Some(Color::White));
+    }
+
+    #[test]
+    fn append_works() {
+        let mut game = Position::startpos();
+        let mut continuation = Position::startpos();
+        let mv = Move::Normal {
+            from: Square::SQ_7G,
+            to: Square::SQ_7F,
+            promote: false,
+        };
+        continuation.make_move(mv).unwrap();
+        // A piece that is not in the initial position of `continuation` moves.
+        continuation
+            .inner
+            .piece_set(Square::SQ_5E, Some(Piece::W_P));
+        let mv = Move::Normal {
+            from: Square::SQ_5E,
+            to: Square::SQ_5F,
+            promote: false,
+        };
+        continuation.make_move(mv).unwrap();
+        assert_eq!(
+            game.append(&continuation),
+            Err(SpliceError::Move { index: 1 }),
+        );
+        assert_eq!(game, Position::startpos());
+
+        // The ply and the last move need not match.
+        let initial = PartialPosition::from_sfen(
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 10",
+        )
+        .unwrap();
+        let continuation = Position::arbitrary_position(initial);
+        game.append(&continuation).unwrap();
+        assert_eq!(game, Position::startpos());
+        game.append(&Position::startpos()).unwrap();
+        assert_eq!(game.ply(), 1);
+    }
+
+    #[test]
+    fn clone_works() {
+        let mut position = Position::startpos();
+        // An empty move sequence is not allocated.
+        #[cfg(feature = "shared-moves")]
+        assert!(position.moves.0.is_none());
+        let mv = Move::Normal {
+            from: Square::SQ_7G,
+            to: Square::SQ_7F,
+            promote: false,
+        };
+        position.make_move(mv).unwrap();
+        let mut cloned = position.clone();
+        #[cfg(feature = "shared-moves")]
+        assert!(alloc::sync::Arc::ptr_eq(
+            position.moves.0.as_ref().unwrap(),
+            cloned.moves.0.as_ref().unwrap(),
+        ));
+        assert_eq!(cloned, position);
+
+        // Modifying a clone does not affect the original.
+        let mv2 = Move::Normal {
+            from: Square::SQ_3C,
+            to: Square::SQ_3D,
+            promote: false,
+        };
+        cloned.make_move(mv2).unwrap();
+        assert_eq!(position.moves(), [mv]);
+        assert_eq!(cloned.moves(), [mv, mv2]);
+        assert!(position.clone().make_compact_move(mv2.into()));
+        assert_eq!(position.moves(), [mv]);
+    }
+
     #[test]
     fn to_sfen_works() {
         let pos = PartialPosition::startpos();
@@ -915,4 +2943,35 @@ mod tests {
             "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
         );
     }
+
+    #[cfg(feature = "rkyv")]
+    #[test]
+    fn rkyv_works() {
+        use rkyv::Deserialize;
+
+        let mut position = Position::startpos();
+        let mv = Move::Normal {
+            from: Square::SQ_7G,
+            to: Square::SQ_7F,
+            promote: false,
+        };
+        position.make_move(mv).unwrap();
+        let mut game = Game::new(position);
+        game.resolve(GameResolution::Aborted);
+
+        let bytes = rkyv::to_bytes::<_, 1024>(&game).unwrap();
+        // Safety: `bytes` was made by `rkyv::to_bytes` from a `Game`.
+        let archived = unsafe { rkyv::archived_root::<Game>(&bytes) };
+        assert_eq!(archived.resolution(), Some(GameResolution::Aborted));
+        let compact: CompactMove = mv.into();
+        assert_eq!(archived.position().moves(), [compact]);
+        assert_eq!(archived.position().inner(), game.position().inner());
+        assert_eq!(
+            archived.position().initial_position(),
+            &PartialPosition::startpos(),
+        );
+
+        let deserialized: Game = archived.deserialize(&mut rkyv::Infallible).unwrap();
+        assert_eq!(deserialized, game);
+    }
 }
@@ -0,0 +1,278 @@
+use crate::{Bitboard, Color, Hand, Move, PartialPosition, Piece, Square};
+
+/// A position whose moves are stored in an inline array of capacity `N`, instead of on the heap.
+///
+/// [`PositionFixed`] is a counterpart of [`Position`](crate::Position) available without `alloc`,
+/// e.g. on embedded boards or in WebAssembly modules without an allocator.
+/// It keeps the [`PartialPosition::zobrist_key`]s of all positions in its history,
+/// so that repetitions can be detected without replaying moves.
+///
+/// A [`PositionFixed`] stores a [`Move`] and a 64-bit key per move in addition to two [`PartialPosition`]s,
+/// so it should be boxed or kept in a `static` if `N` is large.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Move, PositionFixed, Square};
+/// let mut pos = PositionFixed::<4>::startpos();
+/// let moves = [
+///     Move::Normal { from: Square::SQ_2H, to: Square::SQ_3H, promote: false },
+///     Move::Normal { from: Square::SQ_8B, to: Square::SQ_7B, promote: false },
+///     Move::Normal { from: Square::SQ_3H, to: Square::SQ_2H, promote: false },
+///     Move::Normal { from: Square::SQ_7B, to: Square::SQ_8B, promote: false },
+/// ];
+/// for mv in moves {
+///     pos.make_move(mv).unwrap();
+/// }
+/// assert_eq!(pos.repetition_count(), 2);
+/// assert!(pos.is_full());
+/// assert_eq!(pos.make_move(moves[0]), None);
+/// ```
+///
+/// Since: 0.1.6
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct PositionFixed<const N: usize> {
+    initial: PartialPosition,
+    inner: PartialPosition,
+    len: usize,
+    // Only `moves[..len]` and `keys[..len]` are meaningful. The rest are filled with `FILLER` and 0.
+    moves: [Move; N],
+    // `keys[i]` is the Zobrist key of the position after `moves[i]`.
+    keys: [u64; N],
+}
+
+const FILLER: Move = Move::Drop {
+    piece: Piece::B_P,
+    to: Square::SQ_1A,
+};
+
+impl<const N: usize> PositionFixed<N> {
+    /// The maximum number of moves a [`PositionFixed`] can hold.
+    pub const CAPACITY: usize = N;
+
+    /// Creates a [`PositionFixed`] with the starting position of shogi.
+    pub fn startpos() -> Self {
+        Self::arbitrary_position(PartialPosition::startpos())
+    }
+
+    /// Creates a [`PositionFixed`] with its initial position `p`.
+    pub fn arbitrary_position(p: PartialPosition) -> Self {
+        Self {
+            initial: p.clone(),
+            inner: p,
+            len: 0,
+            moves: [FILLER; N],
+            keys: [0; N],
+        }
+    }
+
+    /// Returns the inner [`PartialPosition`].
+    #[inline(always)]
+    pub fn inner(&self) -> &PartialPosition {
+        &self.inner
+    }
+
+    /// Returns the initial position, i.e., the position before any moves given to it.
+    #[inline(always)]
+    pub fn initial_position(&self) -> &PartialPosition {
+        &self.initial
+    }
+
+    /// Returns the side to move.
+    #[inline(always)]
+    pub fn side_to_move(&self) -> Color {
+        self.inner.side_to_move()
+    }
+
+    /// Returns the [`Hand`] of a player.
+    #[inline(always)]
+    pub fn hand_of_a_player(&self, color: Color) -> Hand {
+        self.inner.hand_of_a_player(color)
+    }
+
+    /// Returns the current ply.
+    #[inline(always)]
+    pub fn ply(&self) -> u16 {
+        self.inner.ply()
+    }
+
+    /// Returns the piece on the given square.
+    #[inline(always)]
+    pub fn piece_at(&self, square: Square) -> Option<Piece> {
+        self.inner.piece_at(square)
+    }
+
+    /// Finds the subset of squares with a piece.
+    #[inline(always)]
+    pub fn occupied_bitboard(&self) -> Bitboard {
+        self.inner.occupied_bitboard()
+    }
+
+    /// Finds the subset of squares where a piece of the specified player is placed.
+    #[inline(always)]
+    pub fn player_bitboard(&self, color: Color) -> Bitboard {
+        self.inner.player_bitboard(color)
+    }
+
+    /// Finds the subset of squares where a piece is placed.
+    #[inline(always)]
+    pub fn piece_bitboard(&self, piece: Piece) -> Bitboard {
+        self.inner.piece_bitboard(piece)
+    }
+
+    /// Returns the last move, if it exists.
+    pub fn last_move(&self) -> Option<Move> {
+        self.inner.last_move()
+    }
+
+    /// Returns all moves made so far.
+    pub fn moves(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+
+    /// Returns true if and only if no more moves can be made, i.e., `self.moves().len() == N`.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Makes a move. Note that this function will never check legality.
+    ///
+    /// Returns Some(()) if the given move makes sense, i.e.,
+    /// moves a piece to another square or drops a piece on a vacant square.
+    /// This function also returns None if `self` is full (see [`PositionFixed::is_full`]).
+    ///
+    /// If it returns None, it is guaranteed that self is not modified.
+    #[must_use]
+    pub fn make_move(&mut self, mv: Move) -> Option<()> {
+        if self.is_full() {
+            return None;
+        }
+        self.inner.make_move(mv)?;
+        self.moves[self.len] = mv;
+        self.keys[self.len] = self.inner.zobrist_key();
+        self.len += 1;
+        Some(())
+    }
+
+    /// Returns how many times the current position has appeared so far, including the current one.
+    ///
+    /// Positions are compared by [`PartialPosition::zobrist_key`].
+    /// The result is 1 if the current position is not a repetition,
+    /// and 4 or more if the game is a repetition (千日手).
+    pub fn repetition_count(&self) -> usize {
+        let current = self.inner.zobrist_key();
+        let in_moves = self.keys[..self.len]
+            .iter()
+            .filter(|&&key| key == current)
+            .count();
+        let in_initial = (self.initial.zobrist_key() == current) as usize;
+        in_moves + in_initial
+    }
+
+    /// Converts `self` to a [`Position`](crate::Position) with the same initial position and moves.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn to_position(&self) -> crate::Position {
+        let mut position = crate::Position::arbitrary_position(self.initial.clone());
+        for &mv in self.moves() {
+            // The moves were already made in `self`, so replaying them always succeeds.
+            let result = position.make_move(mv);
+            debug_assert_eq!(result, Some(()));
+        }
+        position
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl<const N: usize> PartialOrd for PositionFixed<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl<const N: usize> Ord for PositionFixed<N> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (&self.initial, &self.inner, self.moves()).cmp(&(
+            &other.initial,
+            &other.inner,
+            other.moves(),
+        ))
+    }
+}
+
+#[cfg(feature = "hash")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hash")))]
+impl<const N: usize> core::hash::Hash for PositionFixed<N> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.initial.hash(state);
+        self.inner.hash(state);
+        self.moves().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normal(from: Square, to: Square) -> Move {
+        Move::Normal {
+            from,
+            to,
+            promote: false,
+        }
+    }
+
+    #[test]
+    fn position_fixed_works() {
+        let initial = PartialPosition::from_sfen("4k4/9/9/9/9/9/9/9/4K3R b - 1").unwrap();
+        let mut pos = PositionFixed::<13>::arbitrary_position(initial.clone());
+        assert_eq!(pos.repetition_count(), 1);
+        let cycle = [
+            (Square::SQ_1I, Square::SQ_1H),
+            (Square::SQ_5A, Square::SQ_5B),
+            (Square::SQ_1H, Square::SQ_1I),
+            (Square::SQ_5B, Square::SQ_5A),
+        ];
+        for count in 2..=4 {
+            for (from, to) in cycle {
+                pos.make_move(normal(from, to)).unwrap();
+            }
+            assert_eq!(pos.repetition_count(), count);
+        }
+        assert_eq!(pos.moves().len(), 12);
+        assert_eq!(pos.ply(), 13);
+        assert!(!pos.is_full());
+
+        // A move that does not make sense does not modify `pos`.
+        let before = pos.clone();
+        assert_eq!(pos.make_move(normal(Square::SQ_5E, Square::SQ_5D)), None);
+        assert_eq!(pos, before);
+
+        let last = Move::Normal {
+            from: Square::SQ_1I,
+            to: Square::SQ_1A,
+            promote: true,
+        };
+        pos.make_move(last).unwrap();
+        assert!(pos.is_full());
+        assert_eq!(pos.repetition_count(), 1);
+        assert_eq!(pos.piece_at(Square::SQ_1A), Some(Piece::B_PR));
+        let full = pos.clone();
+        assert_eq!(pos.make_move(normal(Square::SQ_1A, Square::SQ_1B)), None);
+        assert_eq!(pos, full);
+
+        #[cfg(feature = "alloc")]
+        {
+            let position = pos.to_position();
+            assert_eq!(position.moves(), pos.moves());
+            assert_eq!(position.inner(), pos.inner());
+            assert_eq!(position.initial_position(), &initial);
+        }
+
+        let empty = PositionFixed::<0>::startpos();
+        assert!(empty.is_full());
+        assert_eq!(empty.moves(), []);
+    }
+}
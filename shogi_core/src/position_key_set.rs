@@ -0,0 +1,279 @@
+//! A multiset of Zobrist keys of positions.
+
+use alloc::vec::Vec;
+
+use crate::Position;
+
+/// A multiset of Zobrist keys (see [`PartialPosition::zobrist_key`](crate::PartialPosition::zobrist_key)),
+/// which tells how many times each position has appeared.
+///
+/// Keys are stored in an open-addressing hash table, so [`PositionKeySet::insert`], [`PositionKeySet::remove`]
+/// and [`PositionKeySet::count`] take constant time on average regardless of the length of a game.
+/// It does not depend on `std`, so it can be used for repetition (千日手, *sennichite*) checking in `no_std` environments.
+///
+/// [`Position`] does not keep a set up to date by itself, because [`Position::piece_set`] and [`Position::hand_of_a_player_mut`]
+/// can change the current position without making a move. [`Position::perpetual_check_loser`] builds one with [`Position::key_set`] on each call.
+/// To check repetitions in constant time during a game, create a set with [`Position::key_set`] once
+/// and insert the key of each new position afterwards.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{PartialPosition, PositionKeySet};
+/// let mut set = PositionKeySet::new();
+/// let key = PartialPosition::startpos().zobrist_key();
+/// assert_eq!(set.insert(key), 1);
+/// assert_eq!(set.insert(key), 2);
+/// assert_eq!(set.count(key), 2);
+/// assert!(set.remove(key));
+/// assert_eq!(set.count(key), 1);
+/// assert_eq!(set.len(), 1);
+/// ```
+///
+/// Since: 0.1.6
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Clone, Debug, Default)]
+pub struct PositionKeySet {
+    // `None` for a vacant slot. Slots of removed keys stay occupied with a count of 0,
+    // so that probe sequences of other keys are not broken.
+    slots: Vec<Option<(u64, u32)>>,
+    // The number of slots that are not `None`.
+    occupied: usize,
+    // The number of keys with a positive count.
+    len: usize,
+}
+
+const MIN_CAPACITY: usize = 16;
+
+impl PositionKeySet {
+    /// Creates an empty [`PositionKeySet`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty [`PositionKeySet`] that can hold at least `capacity` distinct keys without reallocation.
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity == 0 {
+            return Self::new();
+        }
+        Self {
+            slots: alloc::vec![None; slot_count(capacity)],
+            occupied: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of distinct keys in `self`.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if `self` has no keys.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns how many times `key` is in `self`.
+    pub fn count(&self, key: u64) -> u32 {
+        match self.find(key) {
+            Some(index) => self.slots[index].map_or(0, |(_, count)| count),
+            None => 0,
+        }
+    }
+
+    /// Checks if `key` is in `self`.
+    #[inline]
+    pub fn contains(&self, key: u64) -> bool {
+        self.count(key) > 0
+    }
+
+    /// Adds `key` to `self` and returns how many times it is in `self` now.
+    pub fn insert(&mut self, key: u64) -> u32 {
+        if (self.occupied + 1) * 4 > self.slots.len() * 3 {
+            self.rehash(slot_count(self.len + 1));
+        }
+        let index = self.probe(key);
+        if self.slots[index].is_none() {
+            self.occupied += 1;
+        }
+        let (_, count) = self.slots[index].get_or_insert((key, 0));
+        if *count == 0 {
+            self.len += 1;
+        }
+        *count = count.saturating_add(1);
+        *count
+    }
+
+    /// Removes one occurrence of `key` from `self`, e.g. when a move is undone.
+    ///
+    /// Returns `false` if `key` is not in `self`.
+    pub fn remove(&mut self, key: u64) -> bool {
+        let index = match self.find(key) {
+            Some(index) => index,
+            None => return false,
+        };
+        match &mut self.slots[index] {
+            Some((_, count)) if *count > 0 => {
+                *count -= 1;
+                if *count == 0 {
+                    self.len -= 1;
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes all keys from `self`, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+        self.occupied = 0;
+        self.len = 0;
+    }
+
+    /// Returns an iterator over the keys in `self` and their counts, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, u32)> + '_ {
+        self.slots
+            .iter()
+            .filter_map(|&slot| slot)
+            .filter(|&(_, count)| count > 0)
+    }
+
+    // Finds the slot of `key`, if it exists.
+    fn find(&self, key: u64) -> Option<usize> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        let index = self.probe(key);
+        self.slots[index].map(|_| index)
+    }
+
+    // Finds the slot of `key` or the vacant slot where it should be inserted.
+    // `self.slots` must have a vacant slot.
+    fn probe(&self, key: u64) -> usize {
+        let mask = self.slots.len() - 1;
+        // Zobrist keys are random, so their low bits are good enough as a hash.
+        let mut index = key as usize & mask;
+        while let Some((candidate, _)) = self.slots[index] {
+            if candidate == key {
+                break;
+            }
+            index = (index + 1) & mask;
+        }
+        index
+    }
+
+    // Moves the keys with a positive count to a new table with `slot_count` slots.
+    fn rehash(&mut self, slot_count: usize) {
+        let old = core::mem::replace(&mut self.slots, alloc::vec![None; slot_count]);
+        self.occupied = 0;
+        for (key, count) in old.into_iter().flatten() {
+            if count > 0 {
+                let index = self.probe(key);
+                self.slots[index] = Some((key, count));
+                self.occupied += 1;
+            }
+        }
+    }
+}
+
+// The number of slots for `capacity` keys, which is a power of two and keeps the load factor at most 1/2.
+fn slot_count(capacity: usize) -> usize {
+    (capacity * 2).next_power_of_two().max(MIN_CAPACITY)
+}
+
+impl Extend<u64> for PositionKeySet {
+    fn extend<I: IntoIterator<Item = u64>>(&mut self, iter: I) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+impl FromIterator<u64> for PositionKeySet {
+    fn from_iter<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        let mut result = Self::new();
+        result.extend(iter);
+        result
+    }
+}
+
+impl Position {
+    /// Returns the Zobrist keys of the initial position and the positions after every move made so far.
+    ///
+    /// This takes time proportional to the number of moves. To check repetitions during a long game,
+    /// create it once and insert the key of each new position afterwards.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Move, Position, Square};
+    /// let mut position = Position::startpos();
+    /// for (from, to) in [(Square::SQ_2H, Square::SQ_3H), (Square::SQ_8B, Square::SQ_7B), (Square::SQ_3H, Square::SQ_2H)] {
+    ///     position.make_move(Move::Normal { from, to, promote: false }).unwrap();
+    /// }
+    /// let mut keys = position.key_set();
+    /// assert_eq!(keys.len(), 4);
+    /// position.make_move(Move::Normal { from: Square::SQ_7B, to: Square::SQ_8B, promote: false }).unwrap();
+    /// assert_eq!(keys.insert(position.inner().zobrist_key()), 2);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn key_set(&self) -> PositionKeySet {
+        let mut result = PositionKeySet::with_capacity(self.moves().len() + 1);
+        let mut position = self.initial_position().clone();
+        result.insert(position.zobrist_key());
+        for &mv in self.moves() {
+            // The moves were already made in `self`, so replaying them always succeeds.
+            if position.make_move(mv).is_none() {
+                break;
+            }
+            result.insert(position.zobrist_key());
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeMap;
+
+    #[test]
+    fn position_key_set_works() {
+        let mut set = PositionKeySet::new();
+        let mut model = BTreeMap::new();
+        // Keys sharing low bits collide in the table.
+        let keys: Vec<u64> = (0..40).map(|i| (i % 13) << 40 | (i % 3)).collect();
+        for (step, &key) in keys.iter().cycle().take(400).enumerate() {
+            if step % 3 == 2 {
+                let expected = model.get(&key).map_or(false, |&count| count > 0);
+                assert_eq!(set.remove(key), expected);
+                if expected {
+                    *model.get_mut(&key).unwrap() -= 1;
+                }
+            } else {
+                let count = model.entry(key).or_insert(0u32);
+                *count += 1;
+                assert_eq!(set.insert(key), *count);
+            }
+            for (&key, &count) in &model {
+                assert_eq!(set.count(key), count);
+            }
+            let len = model.values().filter(|&&count| count > 0).count();
+            assert_eq!(set.len(), len);
+            assert_eq!(set.iter().count(), len);
+        }
+        assert!(!set.contains(1 << 63));
+        assert!(!set.remove(1 << 63));
+
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(set.count(keys[0]), 0);
+        let set: PositionKeySet = keys.iter().copied().collect();
+        assert_eq!(
+            set.iter().map(|(_, count)| count as usize).sum::<usize>(),
+            40
+        );
+    }
+}
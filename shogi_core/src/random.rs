@@ -0,0 +1,316 @@
+use rand_core::RngCore;
+
+use crate::{Bitboard, Color, PartialPosition, Piece, PieceKind, Square};
+#[cfg(feature = "alloc")]
+use crate::{Game, GameResolution, LegalityChecker, Position, PositionStatus};
+
+// Chooses an integer in 0..n uniformly at random.
+fn below<R: RngCore + ?Sized>(rng: &mut R, n: u32) -> u32 {
+    debug_assert!(n > 0);
+    // 2^32 mod n. Values below it are rejected, so that the number of accepted values is a multiple of `n`.
+    let threshold = n.wrapping_neg() % n;
+    loop {
+        let value = rng.next_u32();
+        if value >= threshold {
+            return value % n;
+        }
+    }
+}
+
+fn choose_square<R: RngCore + ?Sized>(rng: &mut R, candidates: Bitboard) -> Option<Square> {
+    let count = candidates.count();
+    if count == 0 {
+        return None;
+    }
+    candidates
+        .into_iter()
+        .nth(below(rng, count as u32) as usize)
+}
+
+fn file_bitboard(file: u8) -> Bitboard {
+    debug_assert!((1..=9).contains(&file));
+    // Safety: 1 <= file <= 9 and 0x1ff < 512
+    unsafe { Bitboard::from_file_unchecked(file, 0x1ff) }
+}
+
+// Checks if a piece of `attacker` attacks `target`.
+fn is_attacked_by(position: &PartialPosition, target: Square, attacker: Color) -> bool {
+    position
+        .player_bitboard(attacker)
+        .into_iter()
+        .any(|from| position.attacks_from(from).contains(target))
+}
+
+impl PartialPosition {
+    /// Generates a random position.
+    ///
+    /// All 40 pieces are used. Pieces other than kings are placed on random squares or in random players' hands,
+    /// and some of them are promoted. The returned position is valid in that:
+    /// - no unpromoted piece is placed where it can never move,
+    /// - no player has two unpromoted pawns on the same file,
+    /// - the player who is not to move is not in check.
+    ///
+    /// The distribution of returned positions is unspecified.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, PartialPosition, PieceKind};
+    /// # struct Counter(u64);
+    /// # impl rand_core::RngCore for Counter {
+    /// #     fn next_u32(&mut self) -> u32 { self.next_u64() as u32 }
+    /// #     fn next_u64(&mut self) -> u64 { self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407); self.0 >> 16 }
+    /// #     fn fill_bytes(&mut self, dest: &mut [u8]) { rand_core::impls::fill_bytes_via_next(self, dest) }
+    /// #     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> { self.fill_bytes(dest); Ok(()) }
+    /// # }
+    /// let mut rng = Counter(1);
+    /// let position = PartialPosition::random(&mut rng);
+    /// assert!(position.king_position(Color::Black).is_some());
+    /// assert!(position.king_position(Color::White).is_some());
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "rand")))]
+    pub fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        loop {
+            if let Some(position) = Self::random_attempt(rng) {
+                return position;
+            }
+        }
+    }
+
+    fn random_attempt<R: RngCore + ?Sized>(rng: &mut R) -> Option<Self> {
+        const PIECES: [(PieceKind, u8); 7] = [
+            (PieceKind::Pawn, 18),
+            (PieceKind::Lance, 4),
+            (PieceKind::Knight, 4),
+            (PieceKind::Silver, 4),
+            (PieceKind::Gold, 4),
+            (PieceKind::Bishop, 2),
+            (PieceKind::Rook, 2),
+        ];
+        let mut position = PartialPosition::empty();
+        for color in Color::all() {
+            let square = choose_square(rng, position.vacant_bitboard())?;
+            position.piece_set(square, Some(Piece::new(PieceKind::King, color)));
+        }
+        for (piece_kind, count) in PIECES {
+            for _ in 0..count {
+                let color = if below(rng, 2) == 0 {
+                    Color::Black
+                } else {
+                    Color::White
+                };
+                if below(rng, 4) == 0 {
+                    let hand = position.hand_of_a_player_mut(color);
+                    *hand = hand.added(piece_kind)?;
+                    continue;
+                }
+                let square = choose_square(rng, position.vacant_bitboard())?;
+                let relative_rank = square.relative_rank(color);
+                let is_dead = match piece_kind {
+                    PieceKind::Pawn | PieceKind::Lance => relative_rank == 1,
+                    PieceKind::Knight => relative_rank <= 2,
+                    _ => false,
+                };
+                let is_double_pawn = piece_kind == PieceKind::Pawn
+                    && (position.piece_bitboard(Piece::new(PieceKind::Pawn, color))
+                        & file_bitboard(square.file()))
+                    .count()
+                        > 0;
+                let promote = is_dead || is_double_pawn || below(rng, 4) == 0;
+                let piece_kind = if promote {
+                    piece_kind.promoted_or_self()
+                } else {
+                    piece_kind
+                };
+                position.piece_set(square, Some(Piece::new(piece_kind, color)));
+            }
+        }
+        let side = if below(rng, 2) == 0 {
+            Color::Black
+        } else {
+            Color::White
+        };
+        position.side_to_move_set(side);
+        let opponent_king = position.king_position(side.flip())?;
+        if is_attacked_by(&position, opponent_king, side) {
+            return None;
+        }
+        Some(position)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Game {
+    /// Plays a game from the starting position, choosing a legal move uniformly at random in each ply.
+    ///
+    /// The playout stops when the game is no longer in progress according to `checker`, or after `max_plies` moves.
+    /// If the game ended with a win or repetition, the returned game is resolved accordingly;
+    /// otherwise it is left unresolved.
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "rand", feature = "alloc"))))]
+    pub fn random_playout<R: RngCore + ?Sized, C: LegalityChecker + ?Sized>(
+        rng: &mut R,
+        checker: &C,
+        max_plies: u16,
+    ) -> Self {
+        let mut position = Position::startpos();
+        let mut status = checker.status(&position);
+        for _ in 0..max_plies {
+            if status != PositionStatus::InProgress {
+                break;
+            }
+            let moves = checker.all_legal_moves(&position);
+            if moves.is_empty() {
+                break;
+            }
+            let mv = moves[below(rng, moves.len() as u32) as usize];
+            if checker.make_move(&mut position, mv).is_err() {
+                break;
+            }
+            status = checker.status(&position);
+        }
+        let mut game = Game::new(position);
+        if let Some(resolution) = GameResolution::from_status(status) {
+            game.resolve(resolution);
+        }
+        game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_checker::TestChecker;
+
+    // A linear congruential generator, which is good enough for tests.
+    struct Lcg(u64);
+
+    impl RngCore for Lcg {
+        fn next_u32(&mut self) -> u32 {
+            (self.next_u64() >> 32) as u32
+        }
+        fn next_u64(&mut self) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            self.0
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dest)
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    // Returns the given values in order.
+    struct Sequence(alloc::vec::IntoIter<u32>);
+
+    impl RngCore for Sequence {
+        fn next_u32(&mut self) -> u32 {
+            self.0.next().unwrap()
+        }
+        fn next_u64(&mut self) -> u64 {
+            rand_core::impls::next_u64_via_u32(self)
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dest)
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn below_rejects_biased_values() {
+        // 2^32 = 3 * 1431655765 + 1, so 0 is rejected for n = 3.
+        let mut rng = Sequence(alloc::vec![0, 0, 5].into_iter());
+        assert_eq!(below(&mut rng, 3), 2);
+        // Every value is accepted for a power of two.
+        let mut rng = Sequence(alloc::vec![0, u32::MAX].into_iter());
+        assert_eq!(below(&mut rng, 4), 0);
+        assert_eq!(below(&mut rng, 4), 3);
+        let mut rng = Sequence(alloc::vec![u32::MAX].into_iter());
+        assert_eq!(below(&mut rng, 1), 0);
+    }
+
+    #[test]
+    fn random_works() {
+        let mut rng = Lcg(42);
+        for _ in 0..100 {
+            let position = PartialPosition::random(&mut rng);
+            let mut total = 0;
+            for color in Color::all() {
+                for piece_kind in PieceKind::all() {
+                    total += position.material(color)[piece_kind.array_index()] as u32;
+                }
+                let pawn = Piece::new(PieceKind::Pawn, color);
+                for file in 1..=9 {
+                    let pawns = position.piece_bitboard(pawn) & file_bitboard(file);
+                    assert!(pawns.count() <= 1);
+                }
+                for square in position.player_bitboard(color) {
+                    let piece_kind = position.piece_at(square).unwrap().piece_kind();
+                    let relative_rank = square.relative_rank(color);
+                    match piece_kind {
+                        PieceKind::Pawn | PieceKind::Lance => assert!(relative_rank >= 2),
+                        PieceKind::Knight => assert!(relative_rank >= 3),
+                        _ => {}
+                    }
+                }
+            }
+            assert_eq!(total, 40);
+            let side = position.side_to_move();
+            let king = position.king_position(side.flip()).unwrap();
+            assert!(!is_attacked_by(&position, king, side));
+        }
+    }
+
+    #[test]
+    fn is_attacked_by_works() {
+        let position = PartialPosition::startpos();
+        assert!(is_attacked_by(&position, Square::SQ_7F, Color::Black));
+        assert!(!is_attacked_by(&position, Square::SQ_7E, Color::Black));
+        // the rook on 8h attacks 9h and 7h
+        assert!(is_attacked_by(&position, Square::SQ_9H, Color::Black));
+        // the bishop on 2b is blocked by pawns
+        assert!(!is_attacked_by(&position, Square::SQ_7G, Color::White));
+        assert!(is_attacked_by(&position, Square::SQ_3C, Color::White));
+    }
+
+    // Only pawns move. The game is drawn after 4 plies.
+    const PAWN_PUSHER: TestChecker = TestChecker {
+        movable: |piece_kind| piece_kind == PieceKind::Pawn,
+        drops: false,
+        status: |position| {
+            if position.ply() > 4 {
+                PositionStatus::Draw
+            } else {
+                PositionStatus::InProgress
+            }
+        },
+    };
+
+    #[test]
+    fn random_playout_works() {
+        let mut rng = Lcg(1);
+        let game = Game::random_playout(&mut rng, &PAWN_PUSHER, 100);
+        assert_eq!(game.position().moves().len(), 4);
+        assert_eq!(game.resolution(), Some(GameResolution::Rematch));
+
+        let game = Game::random_playout(&mut rng, &PAWN_PUSHER, 3);
+        assert_eq!(game.position().moves().len(), 3);
+        assert_eq!(game.resolution(), None);
+        assert!(game.exceeds_move_limit(3));
+        let position = game.position();
+        assert_eq!(
+            PAWN_PUSHER.status_with_move_limit(position, 4),
+            PositionStatus::InProgress,
+        );
+        assert_eq!(
+            PAWN_PUSHER.status_with_move_limit(position, 3),
+            PositionStatus::Draw,
+        );
+    }
+}
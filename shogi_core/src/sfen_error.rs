@@ -0,0 +1,141 @@
+/// What went wrong in parsing SFEN. See [`SfenParseError`].
+///
+/// Since: 0.1.6
+#[repr(u8)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum SfenParseErrorKind {
+    /// The input ended before the board, side to move and hands.
+    ///
+    /// Discriminant = 1.
+    MissingField = 1,
+    /// A field follows the ply.
+    ///
+    /// Discriminant = 2.
+    TrailingField = 2,
+    /// A character in the board is neither a piece, a digit nor `/`.
+    ///
+    /// Discriminant = 3.
+    InvalidPiece = 3,
+    /// A rank of the board has more than 9 files.
+    ///
+    /// Discriminant = 4.
+    TooManyFiles = 4,
+    /// A rank of the board has fewer than 9 files.
+    ///
+    /// Discriminant = 5.
+    TooFewFiles = 5,
+    /// The board has more than 9 ranks.
+    ///
+    /// Discriminant = 6.
+    TooManyRanks = 6,
+    /// The board has fewer than 9 ranks.
+    ///
+    /// Discriminant = 7.
+    TooFewRanks = 7,
+    /// The side to move is neither `b` nor `w`.
+    ///
+    /// Discriminant = 8.
+    InvalidSide = 8,
+    /// A character in the hands is not a piece that can be in a hand.
+    ///
+    /// Discriminant = 9.
+    InvalidHandPiece = 9,
+    /// A count in the hands is 0, too large, or not followed by a piece.
+    ///
+    /// Discriminant = 10.
+    InvalidHandCount = 10,
+    /// The ply is not a number in `1..=65535`.
+    ///
+    /// Discriminant = 11.
+    InvalidPly = 11,
+}
+
+impl SfenParseErrorKind {
+    /// Returns a short description of `self` in English, e.g. `"invalid ply"`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::SfenParseErrorKind;
+    /// assert_eq!(SfenParseErrorKind::InvalidPly.description(), "invalid ply");
+    /// ```
+    pub const fn description(self) -> &'static str {
+        match self {
+            SfenParseErrorKind::MissingField => "missing field",
+            SfenParseErrorKind::TrailingField => "trailing field",
+            SfenParseErrorKind::InvalidPiece => "invalid piece",
+            SfenParseErrorKind::TooManyFiles => "too many files in a rank",
+            SfenParseErrorKind::TooFewFiles => "too few files in a rank",
+            SfenParseErrorKind::TooManyRanks => "too many ranks",
+            SfenParseErrorKind::TooFewRanks => "too few ranks",
+            SfenParseErrorKind::InvalidSide => "invalid side to move",
+            SfenParseErrorKind::InvalidHandPiece => "invalid piece in hand",
+            SfenParseErrorKind::InvalidHandCount => "invalid count in hand",
+            SfenParseErrorKind::InvalidPly => "invalid ply",
+        }
+    }
+}
+
+impl_ord_for_fieldless_enum!(SfenParseErrorKind);
+impl_hash_for_fieldless_enum!(SfenParseErrorKind);
+
+impl core::fmt::Display for SfenParseErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// An error returned by [`PartialPosition::try_from_sfen`](crate::PartialPosition::try_from_sfen).
+///
+/// It tells what went wrong and where, as a byte offset into the input.
+/// The offset points at the offending character, or at the end of the field
+/// (or of the input) if something is missing there.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{PartialPosition, SfenParseErrorKind};
+/// let error = PartialPosition::try_from_sfen("9/9/9/9/9/9/9/9/4X4 b - 1").unwrap_err();
+/// assert_eq!(error.kind(), SfenParseErrorKind::InvalidPiece);
+/// assert_eq!(error.offset(), 17);
+/// assert_eq!(error.to_string(), "invalid piece at byte 17");
+/// ```
+///
+/// Since: 0.1.6
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct SfenParseError {
+    kind: SfenParseErrorKind,
+    offset: usize,
+}
+
+impl SfenParseError {
+    pub(crate) const fn new(kind: SfenParseErrorKind, offset: usize) -> Self {
+        Self { kind, offset }
+    }
+
+    // Moves the offset by `base`, for errors found in a part of the input starting at `base`.
+    pub(crate) const fn shifted(self, base: usize) -> Self {
+        Self::new(self.kind, self.offset + base)
+    }
+
+    /// Returns what went wrong.
+    pub const fn kind(self) -> SfenParseErrorKind {
+        self.kind
+    }
+
+    /// Returns the byte offset in the input where the error was found.
+    pub const fn offset(self) -> usize {
+        self.offset
+    }
+}
+
+impl_ord_with_fields!(SfenParseError; kind, offset);
+impl_hash_with_fields!(SfenParseError; kind, offset);
+
+impl core::fmt::Display for SfenParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} at byte {}", self.kind, self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for SfenParseError {}
@@ -0,0 +1,268 @@
+//! Conversions between the types of this crate and those of the [`shogi`] crate.
+
+use core::convert::TryFrom;
+
+use alloc::format;
+
+use crate::usi::PositionCommandError;
+use crate::{Color, Move, PartialPosition, Piece, PieceKind, Position, Square};
+
+impl From<Color> for shogi::Color {
+    #[inline]
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black => shogi::Color::Black,
+            Color::White => shogi::Color::White,
+        }
+    }
+}
+
+impl From<shogi::Color> for Color {
+    #[inline]
+    fn from(color: shogi::Color) -> Self {
+        match color {
+            shogi::Color::Black => Color::Black,
+            shogi::Color::White => Color::White,
+        }
+    }
+}
+
+/// Note that files and ranks of [`shogi::Square`] are 0-based.
+impl From<Square> for shogi::Square {
+    #[inline]
+    fn from(square: Square) -> Self {
+        // Both indices are in range 0..81 and go through a file from rank a to rank i.
+        match shogi::Square::from_index(square.array_index() as u8) {
+            Some(square) => square,
+            // Safety: square.array_index() < 81
+            None => unsafe { crate::common::unreachable_unchecked() },
+        }
+    }
+}
+
+impl From<shogi::Square> for Square {
+    #[inline]
+    fn from(square: shogi::Square) -> Self {
+        // Safety: square.index() < 81, so square.index() + 1 is in range 1..=81
+        unsafe { Square::from_u8_unchecked(square.index() as u8 + 1) }
+    }
+}
+
+impl From<PieceKind> for shogi::PieceType {
+    fn from(piece_kind: PieceKind) -> Self {
+        match piece_kind {
+            PieceKind::Pawn => shogi::PieceType::Pawn,
+            PieceKind::Lance => shogi::PieceType::Lance,
+            PieceKind::Knight => shogi::PieceType::Knight,
+            PieceKind::Silver => shogi::PieceType::Silver,
+            PieceKind::Gold => shogi::PieceType::Gold,
+            PieceKind::Bishop => shogi::PieceType::Bishop,
+            PieceKind::Rook => shogi::PieceType::Rook,
+            PieceKind::King => shogi::PieceType::King,
+            PieceKind::ProPawn => shogi::PieceType::ProPawn,
+            PieceKind::ProLance => shogi::PieceType::ProLance,
+            PieceKind::ProKnight => shogi::PieceType::ProKnight,
+            PieceKind::ProSilver => shogi::PieceType::ProSilver,
+            PieceKind::ProBishop => shogi::PieceType::ProBishop,
+            PieceKind::ProRook => shogi::PieceType::ProRook,
+        }
+    }
+}
+
+impl From<shogi::PieceType> for PieceKind {
+    fn from(piece_type: shogi::PieceType) -> Self {
+        match piece_type {
+            shogi::PieceType::Pawn => PieceKind::Pawn,
+            shogi::PieceType::Lance => PieceKind::Lance,
+            shogi::PieceType::Knight => PieceKind::Knight,
+            shogi::PieceType::Silver => PieceKind::Silver,
+            shogi::PieceType::Gold => PieceKind::Gold,
+            shogi::PieceType::Bishop => PieceKind::Bishop,
+            shogi::PieceType::Rook => PieceKind::Rook,
+            shogi::PieceType::King => PieceKind::King,
+            shogi::PieceType::ProPawn => PieceKind::ProPawn,
+            shogi::PieceType::ProLance => PieceKind::ProLance,
+            shogi::PieceType::ProKnight => PieceKind::ProKnight,
+            shogi::PieceType::ProSilver => PieceKind::ProSilver,
+            shogi::PieceType::ProBishop => PieceKind::ProBishop,
+            shogi::PieceType::ProRook => PieceKind::ProRook,
+        }
+    }
+}
+
+impl From<Piece> for shogi::Piece {
+    #[inline]
+    fn from(piece: Piece) -> Self {
+        shogi::Piece {
+            piece_type: piece.piece_kind().into(),
+            color: piece.color().into(),
+        }
+    }
+}
+
+impl From<shogi::Piece> for Piece {
+    #[inline]
+    fn from(piece: shogi::Piece) -> Self {
+        Piece::new(piece.piece_type.into(), piece.color.into())
+    }
+}
+
+/// The color of a dropped piece is lost, because [`shogi::Move::Drop`] does not have one.
+impl From<Move> for shogi::Move {
+    fn from(mv: Move) -> Self {
+        match mv {
+            Move::Normal { from, to, promote } => shogi::Move::Normal {
+                from: from.into(),
+                to: to.into(),
+                promote,
+            },
+            Move::Drop { piece, to } => shogi::Move::Drop {
+                to: to.into(),
+                piece_type: piece.piece_kind().into(),
+            },
+        }
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "shogi")))]
+impl Move {
+    /// Converts a [`shogi::Move`] made by `side`.
+    ///
+    /// [`Move`] does not implement <code>[From]<[shogi::Move]></code>, because [`shogi::Move::Drop`] does not have the color of the dropped piece.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Move, Piece, Square};
+    /// let mv = shogi::Move::Drop { to: shogi::Square::from_sfen("5b").unwrap(), piece_type: shogi::PieceType::Silver };
+    /// assert_eq!(
+    ///     Move::from_shogi(mv, Color::White),
+    ///     Move::Drop { piece: Piece::W_S, to: Square::SQ_5B },
+    /// );
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn from_shogi(mv: shogi::Move, side: Color) -> Self {
+        match mv {
+            shogi::Move::Normal { from, to, promote } => Move::Normal {
+                from: from.into(),
+                to: to.into(),
+                promote,
+            },
+            shogi::Move::Drop { to, piece_type } => Move::Drop {
+                piece: Piece::new(piece_type.into(), side),
+                to: to.into(),
+            },
+        }
+    }
+}
+
+/// Converts the position through SFEN, because the fields of [`shogi::Position`] are private.
+///
+/// Fails if the [`shogi`] crate rejects the position.
+impl TryFrom<&PartialPosition> for shogi::Position {
+    type Error = shogi::SfenError;
+
+    fn try_from(position: &PartialPosition) -> Result<Self, shogi::SfenError> {
+        let mut result = shogi::Position::new();
+        result.set_sfen(&position.to_sfen_owned())?;
+        Ok(result)
+    }
+}
+
+/// Converts the position through the `position` command of USI, because the fields of [`shogi::Position`] are private.
+///
+/// The moves made in `position` are kept.
+impl TryFrom<&shogi::Position> for Position {
+    type Error = PositionCommandError;
+
+    fn try_from(position: &shogi::Position) -> Result<Self, PositionCommandError> {
+        Position::from_usi_position(&format!("sfen {}", position.to_sfen()))
+    }
+}
+
+/// Converts the current position of `position`, discarding the moves made in it.
+impl TryFrom<&shogi::Position> for PartialPosition {
+    type Error = PositionCommandError;
+
+    fn try_from(position: &shogi::Position) -> Result<Self, PositionCommandError> {
+        Position::try_from(position).map(|position| position.inner().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToUsi;
+
+    #[test]
+    fn square_conversions_round_trip() {
+        for square in Square::all() {
+            let converted = shogi::Square::from(square);
+            assert_eq!(converted.to_string(), square.to_usi_owned());
+            assert_eq!(Square::from(converted), square);
+        }
+    }
+
+    #[test]
+    fn piece_conversions_round_trip() {
+        for piece in Piece::all() {
+            let converted = shogi::Piece::from(piece);
+            assert_eq!(converted.to_string(), piece.to_usi_owned());
+            assert_eq!(Piece::from(converted), piece);
+        }
+        for color in Color::all() {
+            assert_eq!(Color::from(shogi::Color::from(color)), color);
+        }
+    }
+
+    #[test]
+    fn move_conversions_round_trip() {
+        for from in Square::all() {
+            for to in Square::all() {
+                for promote in [false, true] {
+                    let mv = Move::Normal { from, to, promote };
+                    let converted = shogi::Move::from(mv);
+                    assert_eq!(converted.to_string(), mv.to_usi_owned());
+                    assert_eq!(Move::from_shogi(converted, Color::Black), mv);
+                }
+            }
+        }
+        for piece in Piece::all() {
+            for to in Square::all() {
+                let mv = Move::Drop { piece, to };
+                let converted = shogi::Move::from(mv);
+                assert_eq!(converted.to_string(), mv.to_usi_owned());
+                assert_eq!(Move::from_shogi(converted, piece.color()), mv);
+            }
+        }
+    }
+
+    #[test]
+    fn position_conversions_round_trip() {
+        for sfen in [
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1",
+            "8l/1l+R2P3/p2pBG1pp/kps1p4/Nn1P2G2/P1P1P2PP/1PS6/1KSG3+r1/LN2+p3L w Sbgn3p 124",
+        ] {
+            let position = PartialPosition::from_sfen(sfen).unwrap();
+            let converted = shogi::Position::try_from(&position).unwrap();
+            assert_eq!(converted.to_sfen(), sfen);
+            assert_eq!(PartialPosition::try_from(&converted), Ok(position));
+        }
+
+        // Moves made in a `shogi::Position` are kept.
+        // The `shogi` crate needs its attack tables to make moves.
+        shogi::bitboard::Factory::init();
+        let mut converted = shogi::Position::new();
+        converted
+            .set_sfen(
+                "lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1 moves 7g7f 3c3d",
+            )
+            .unwrap();
+        let position = Position::try_from(&converted).unwrap();
+        assert_eq!(position.moves().len(), 2);
+        assert_eq!(
+            position.inner().to_sfen_owned(),
+            "lnsgkgsnl/1r5b1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL b - 3"
+        );
+    }
+}
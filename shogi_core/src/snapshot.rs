@@ -0,0 +1,409 @@
+use core::fmt::{Result as FmtResult, Write};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::common::write_u32;
+use crate::usi::{next_number, next_token, PositionCommand};
+use crate::{
+    Color, Game, GameResolution, IllegalMoveKind, MoveAnnotation, ResolutionDetail, TimeControl,
+    ToUsi,
+};
+
+/// A snapshot of a game, possibly still in progress, that can be saved and restored later.
+///
+/// A snapshot is written in a line-based text format. The first line is `shogi_core snapshot 1`,
+/// where `1` is the version of the format ([`GameSnapshot::VERSION`]). Each of the following lines is one of:
+/// - `position <args>`: the initial position and the moves made so far,
+///   where `<args>` is the same as the arguments of the USI `position` command. Required.
+/// - `time_control <main_time> <byoyomi> <increment> <per_move_limit>`: the fields of [`TimeControl`].
+/// - `move_time <index> <time>`: the time in milliseconds spent on the `<index>`-th move (0-based), as in [`Game::move_time`].
+///   The remaining time of each player is not written, because [`Game::remaining_time`] computes it from these lines.
+/// - `annotation <index> <annotation>`: the annotation of the `<index>`-th move (0-based), as in [`Game::annotation`].
+///   `<annotation>` is written by [`MoveAnnotation::to_comment`] and extends to the end of the line.
+/// - `resolution <resolution>`: the resolution of the game,
+///   one of `black_wins`, `white_wins`, `draw`, `rematch` and `aborted`.
+/// - `detail <reason> [<player>] [<kind>]`: the detail of the resolution, which implies `resolution`.
+///   `<reason>` is one of `resignation`, `mated`, `time_up`, `illegal_move`, `perpetual_check`, `declaration`,
+///   `repetition`, `impasse`, `move_limit` and `aborted`. `<player>` is `b` or `w`, and is given if the reason has a player.
+///   `<kind>` is the representation of an [`IllegalMoveKind`], and may be given only for `illegal_move`.
+/// - `meta <key> <value>`: an entry of metadata. `<value>` extends to the end of the line.
+///
+/// Every line ends with `\n`. Lines other than the first line, `move_time` lines, `annotation` lines and `meta` lines may appear at most once.
+/// A `move_time` line and an `annotation` line may appear at most once for each move.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Color, Game, GameSnapshot, Move, MoveAnnotation, Position, Square, TimeControl};
+/// let mut position = Position::startpos();
+/// position.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+/// let mut game = Game::new(position);
+/// game.time_control_set(Some(TimeControl::with_byoyomi(600_000, 10_000)));
+/// assert!(game.move_time_set(0, Some(10_000)));
+/// assert!(game.annotation_set(0, Some(MoveAnnotation::Good)));
+/// let mut snapshot = GameSnapshot::new(game);
+/// snapshot.metadata.push(("black".to_owned(), "Alice".to_owned()));
+/// let text = snapshot.to_text_owned().unwrap();
+/// assert_eq!(
+///     text,
+///     "shogi_core snapshot 1\n\
+///      position startpos moves 7g7f\n\
+///      time_control 600000 10000 0 0\n\
+///      move_time 0 10000\n\
+///      annotation 0 !\n\
+///      meta black Alice\n",
+/// );
+/// let restored = GameSnapshot::from_text(&text).unwrap();
+/// assert_eq!(restored.game.remaining_time(Color::Black, 1), Some(590_000));
+/// assert_eq!(restored, snapshot);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct GameSnapshot {
+    /// The game, including the moves made so far, the time spent on them and their annotations.
+    pub game: Game,
+    /// Arbitrary key-value pairs, such as players' names.
+    /// Keys must be non-empty and must not contain ASCII whitespace. Values must not contain line breaks.
+    pub metadata: Vec<(String, String)>,
+}
+
+impl GameSnapshot {
+    /// The version of the snapshot format, which is written in the first line.
+    /// [`GameSnapshot::from_text`] rejects snapshots of other versions.
+    pub const VERSION: u32 = 1;
+
+    /// Creates a snapshot of `game` without metadata.
+    pub fn new(game: Game) -> Self {
+        Self {
+            game,
+            metadata: Vec::new(),
+        }
+    }
+
+    /// Writes `self` in the snapshot format.
+    ///
+    /// If a key or a value in the metadata is invalid, this function returns an error.
+    pub fn to_text<W: Write>(&self, sink: &mut W) -> FmtResult {
+        sink.write_str("shogi_core snapshot ")?;
+        write_u32(sink, Self::VERSION)?;
+        sink.write_str("\nposition ")?;
+        PositionCommand::from(self.game.position()).to_usi(sink)?;
+        sink.write_char('\n')?;
+        if let Some(time_control) = self.game.time_control() {
+            sink.write_str("time_control")?;
+            for value in [
+                time_control.main_time,
+                time_control.byoyomi,
+                time_control.increment,
+                time_control.per_move_limit,
+            ] {
+                sink.write_char(' ')?;
+                write_u32(sink, value)?;
+            }
+            sink.write_char('\n')?;
+        }
+        for index in 0..self.game.position().moves().len() {
+            if let Some(time) = self.game.move_time(index) {
+                sink.write_str("move_time ")?;
+                write_u32(sink, index as u32)?;
+                sink.write_char(' ')?;
+                write_u32(sink, time)?;
+                sink.write_char('\n')?;
+            }
+        }
+        for (index, annotation) in self.game.annotations() {
+            sink.write_str("annotation ")?;
+            write_u32(sink, index as u32)?;
+            sink.write_char(' ')?;
+            annotation.to_comment(sink)?;
+            sink.write_char('\n')?;
+        }
+        match (self.game.resolution_detail(), self.game.resolution()) {
+            (Some(detail), _) => {
+                sink.write_str("detail ")?;
+                sink.write_str(detail_name(detail))?;
+                if let Some(player) = detail.player() {
+                    sink.write_char(' ')?;
+                    player.to_usi(sink)?;
+                }
+                if let ResolutionDetail::IllegalMove(_, Some(kind)) = detail {
+                    sink.write_char(' ')?;
+                    write_u32(sink, kind as u32)?;
+                }
+                sink.write_char('\n')?;
+            }
+            (None, Some(resolution)) => {
+                sink.write_str("resolution ")?;
+                sink.write_str(resolution_name(resolution))?;
+                sink.write_char('\n')?;
+            }
+            (None, None) => {}
+        }
+        for (key, value) in &self.metadata {
+            let key_is_valid = !key.is_empty() && !key.contains(|c: char| c.is_ascii_whitespace());
+            if !key_is_valid || value.contains(['\n', '\r']) {
+                return Err(core::fmt::Error);
+            }
+            sink.write_str("meta ")?;
+            sink.write_str(key)?;
+            sink.write_char(' ')?;
+            sink.write_str(value)?;
+            sink.write_char('\n')?;
+        }
+        Ok(())
+    }
+
+    /// Returns `self` in the snapshot format.
+    ///
+    /// If a key or a value in the metadata is invalid, this function returns [`None`].
+    pub fn to_text_owned(&self) -> Option<String> {
+        let mut s = String::new();
+        self.to_text(&mut s).ok()?;
+        Some(s)
+    }
+
+    /// Parses a snapshot in the snapshot format.
+    ///
+    /// Returns [`None`] if `s` is not a valid snapshot, the version is not supported or a move does not make sense.
+    /// Legality of moves is not checked.
+    pub fn from_text(s: &str) -> Option<Self> {
+        let mut lines = s.lines();
+        let mut header = lines.next()?;
+        if next_token(&mut header)? != "shogi_core"
+            || next_token(&mut header)? != "snapshot"
+            || next_number::<u32>(&mut header)? != Self::VERSION
+            || next_token(&mut header).is_some()
+        {
+            return None;
+        }
+        let mut position = None;
+        let mut time_control = None;
+        let mut move_times = Vec::new();
+        let mut annotations = Vec::new();
+        let mut resolution = None;
+        let mut detail = None;
+        let mut metadata = Vec::new();
+        for line in lines {
+            let mut rest = line;
+            let keyword = next_token(&mut rest)?;
+            match keyword {
+                "position" if position.is_none() => {
+                    position = Some(PositionCommand::from_usi(rest)?.to_position()?);
+                }
+                "time_control" if time_control.is_none() => {
+                    time_control = Some(TimeControl {
+                        main_time: next_number(&mut rest)?,
+                        byoyomi: next_number(&mut rest)?,
+                        increment: next_number(&mut rest)?,
+                        per_move_limit: next_number(&mut rest)?,
+                    });
+                    end_of_line(&mut rest)?;
+                }
+                "move_time" => {
+                    let index = next_number::<u32>(&mut rest)? as usize;
+                    move_times.push((index, next_number(&mut rest)?));
+                    end_of_line(&mut rest)?;
+                }
+                "annotation" => {
+                    let index = next_number::<u32>(&mut rest)? as usize;
+                    annotations.push((index, MoveAnnotation::from_comment(rest)?));
+                }
+                "resolution" if resolution.is_none() => {
+                    resolution = Some(parse_resolution(next_token(&mut rest)?)?);
+                    end_of_line(&mut rest)?;
+                }
+                "detail" if detail.is_none() => {
+                    detail = Some(parse_detail(&mut rest)?);
+                    end_of_line(&mut rest)?;
+                }
+                "meta" => {
+                    let key = next_token(&mut rest)?;
+                    // A single separator is removed so that values can start with whitespace.
+                    let value = rest.strip_prefix(|c: char| c.is_ascii_whitespace())?;
+                    metadata.push((key.into(), value.into()));
+                }
+                _ => return None,
+            }
+        }
+        let mut game = Game::new(position?);
+        game.time_control_set(time_control);
+        for (index, time) in move_times {
+            if game.move_time(index).is_some() || !game.move_time_set(index, Some(time)) {
+                return None;
+            }
+        }
+        for (index, annotation) in annotations {
+            if game.annotation(index).is_some() || !game.annotation_set(index, Some(annotation)) {
+                return None;
+            }
+        }
+        match (detail, resolution) {
+            (Some(detail), None) => game.resolve_with_detail(detail),
+            (None, Some(resolution)) => game.resolve(resolution),
+            (None, None) => {}
+            (Some(detail), Some(resolution)) => {
+                if detail.resolution() != resolution {
+                    return None;
+                }
+                game.resolve_with_detail(detail);
+            }
+        }
+        Some(Self { game, metadata })
+    }
+}
+
+const fn resolution_name(resolution: GameResolution) -> &'static str {
+    match resolution {
+        GameResolution::BlackWins => "black_wins",
+        GameResolution::WhiteWins => "white_wins",
+        GameResolution::Draw => "draw",
+        GameResolution::Rematch => "rematch",
+        GameResolution::Aborted => "aborted",
+    }
+}
+
+fn parse_resolution(s: &str) -> Option<GameResolution> {
+    Some(match s {
+        "black_wins" => GameResolution::BlackWins,
+        "white_wins" => GameResolution::WhiteWins,
+        "draw" => GameResolution::Draw,
+        "rematch" => GameResolution::Rematch,
+        "aborted" => GameResolution::Aborted,
+        _ => return None,
+    })
+}
+
+const fn detail_name(detail: ResolutionDetail) -> &'static str {
+    match detail {
+        ResolutionDetail::Resignation(_) => "resignation",
+        ResolutionDetail::Mated(_) => "mated",
+        ResolutionDetail::TimeUp(_) => "time_up",
+        ResolutionDetail::IllegalMove(_, _) => "illegal_move",
+        ResolutionDetail::PerpetualCheck(_) => "perpetual_check",
+        ResolutionDetail::Declaration(_) => "declaration",
+        ResolutionDetail::Repetition => "repetition",
+        ResolutionDetail::Impasse => "impasse",
+        ResolutionDetail::MoveLimit => "move_limit",
+        ResolutionDetail::Aborted => "aborted",
+    }
+}
+
+// Parses `<reason> [<player>] [<kind>]`.
+fn parse_detail(rest: &mut &str) -> Option<ResolutionDetail> {
+    let reason = next_token(rest)?;
+    let mut player = || -> Option<Color> {
+        match next_token(rest)? {
+            "b" => Some(Color::Black),
+            "w" => Some(Color::White),
+            _ => None,
+        }
+    };
+    Some(match reason {
+        "resignation" => ResolutionDetail::Resignation(player()?),
+        "mated" => ResolutionDetail::Mated(player()?),
+        "time_up" => ResolutionDetail::TimeUp(player()?),
+        "illegal_move" => {
+            let player = player()?;
+            let kind = match next_token(rest) {
+                Some(kind) => Some(IllegalMoveKind::from_u8(kind.parse().ok()?)?),
+                None => None,
+            };
+            ResolutionDetail::IllegalMove(player, kind)
+        }
+        "perpetual_check" => ResolutionDetail::PerpetualCheck(player()?),
+        "declaration" => ResolutionDetail::Declaration(player()?),
+        "repetition" => ResolutionDetail::Repetition,
+        "impasse" => ResolutionDetail::Impasse,
+        "move_limit" => ResolutionDetail::MoveLimit,
+        "aborted" => ResolutionDetail::Aborted,
+        _ => return None,
+    })
+}
+
+// Succeeds if no tokens are left in `rest`.
+fn end_of_line(rest: &mut &str) -> Option<()> {
+    match next_token(rest) {
+        Some(_) => None,
+        None => Some(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Move, Position, Square};
+
+    #[test]
+    fn snapshot_round_trip_works() {
+        let mut position = Position::startpos();
+        for (from, to) in [
+            (Square::SQ_7G, Square::SQ_7F),
+            (Square::SQ_3C, Square::SQ_3D),
+        ] {
+            let mv = Move::Normal {
+                from,
+                to,
+                promote: false,
+            };
+            position.make_move(mv).unwrap();
+        }
+        let mut game = Game::new(position);
+        game.time_control_set(Some(TimeControl::fischer(60_000, 5_000)));
+        assert!(game.move_time_set(0, Some(3_000)));
+        assert!(game.move_time_set(1, Some(0)));
+        assert!(game.annotation_set(0, Some(MoveAnnotation::Good)));
+        assert!(game.annotation_set(1, Some(MoveAnnotation::Score(-120))));
+        let mut snapshot = GameSnapshot::new(game);
+        let text = snapshot.to_text_owned().unwrap();
+        assert!(text.contains("\nmove_time 0 3000\nmove_time 1 0\n"));
+        assert!(text.contains("\nannotation 0 !\nannotation 1 評価値 -120\n"));
+        let restored = GameSnapshot::from_text(&text).unwrap();
+        assert_eq!(restored.game.remaining_time(Color::Black, 2), Some(62_000));
+        assert_eq!(restored, snapshot);
+        snapshot
+            .metadata
+            .push(("event".into(), " leading space".into()));
+        let details = [
+            ResolutionDetail::IllegalMove(Color::Black, Some(IllegalMoveKind::TwoPawns)),
+            ResolutionDetail::IllegalMove(Color::White, None),
+            ResolutionDetail::Declaration(Color::Black),
+            ResolutionDetail::MoveLimit,
+        ];
+        for detail in details {
+            snapshot.game.resolve_with_detail(detail);
+            let text = snapshot.to_text_owned().unwrap();
+            assert_eq!(GameSnapshot::from_text(&text), Some(snapshot.clone()));
+        }
+        snapshot.game.resolve(GameResolution::Rematch);
+        let text = snapshot.to_text_owned().unwrap();
+        assert!(text.contains("\nresolution rematch\n"));
+        assert_eq!(GameSnapshot::from_text(&text), Some(snapshot.clone()));
+
+        snapshot.metadata.push(("bad key".into(), "value".into()));
+        assert_eq!(snapshot.to_text_owned(), None);
+    }
+
+    #[test]
+    fn from_text_rejects_invalid_input() {
+        let invalid = [
+            "",
+            "shogi_core snapshot 2\nposition startpos\n",
+            "shogi_core snapshot 1\n",
+            "shogi_core snapshot 1\nposition startpos moves 7g7f 7g7f\n",
+            "shogi_core snapshot 1\nposition startpos\nposition startpos\n",
+            "shogi_core snapshot 1\nposition startpos moves 7g7f\nmove_time 0\n",
+            "shogi_core snapshot 1\nposition startpos moves 7g7f\nmove_time 0 1 2\n",
+            "shogi_core snapshot 1\nposition startpos moves 7g7f\nmove_time 1 1000\n",
+            "shogi_core snapshot 1\nposition startpos moves 7g7f\nmove_time 0 1\nmove_time 0 2\n",
+            "shogi_core snapshot 1\nposition startpos moves 7g7f\nannotation 0 great\n",
+            "shogi_core snapshot 1\nposition startpos moves 7g7f\nannotation 1 !\n",
+            "shogi_core snapshot 1\nposition startpos moves 7g7f\nannotation 0 !\nannotation 0 ?\n",
+            "shogi_core snapshot 1\nposition startpos\ndetail resignation\n",
+            "shogi_core snapshot 1\nposition startpos\ndetail repetition\nresolution draw\n",
+            "shogi_core snapshot 1\nposition startpos\nunknown\n",
+        ];
+        for s in invalid {
+            assert_eq!(GameSnapshot::from_text(s), None, "{}", s);
+        }
+    }
+}
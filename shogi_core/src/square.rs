@@ -1,6 +1,7 @@
 use core::num::NonZeroU8;
+use core::ops::{Index, IndexMut};
 
-use crate::{common, Color, ToUsi};
+use crate::{common, Color, OutOfRangeError, ToUsi};
 
 /// A square.
 ///
@@ -22,12 +23,13 @@ impl Square {
         }
         // Safety: file >= 1 && rank >= 1 implies file * 9 + rank - 9 >= 1
         Some(Square(unsafe {
-            NonZeroU8::new_unchecked(file * 9 + rank - 9)
+            crate::common::nonzero_u8_unchecked(file * 9 + rank - 9)
         }))
     }
 
     /// C interface to [`Square::new`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Square_new(file: u8, rank: u8) -> OptionSquare {
         Square::new(file, rank).into()
     }
@@ -50,7 +52,7 @@ impl Square {
         // Safety: file >= 1 && rank >= 1 implies 1 <= file * 9 + rank - 9 <= 81
         let relative_index = file * 9 + rank - 9;
         Some(Square(unsafe {
-            NonZeroU8::new_unchecked(match color {
+            crate::common::nonzero_u8_unchecked(match color {
                 Color::Black => relative_index,
                 Color::White => 82 - relative_index,
             })
@@ -58,7 +60,8 @@ impl Square {
     }
 
     /// C interface to [`Square::new_relative`].
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub extern "C" fn Square_new_relative(file: u8, rank: u8, color: Color) -> OptionSquare {
         Square::new_relative(file, rank, color).into()
     }
@@ -70,8 +73,8 @@ impl Square {
     /// use shogi_core::Square;
     /// assert_eq!(Square::SQ_3D.file(), 3);
     /// ```
-    #[inline(always)]
-    #[export_name = "Square_file"]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_file")]
     pub extern "C" fn file(self) -> u8 {
         self.sanity_check();
         (((self.0.get() + 8) as u32 * 57) >> 9) as u8
@@ -84,8 +87,8 @@ impl Square {
     /// use shogi_core::Square;
     /// assert_eq!(Square::SQ_3D.rank(), 4);
     /// ```
-    #[inline(always)]
-    #[export_name = "Square_rank"]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_rank")]
     pub extern "C" fn rank(self) -> u8 {
         self.sanity_check();
         self.0.get() + 9 - 9 * self.file()
@@ -99,15 +102,58 @@ impl Square {
     /// use shogi_core::Square;
     /// assert_eq!(Square::SQ_3D.index(), 22);
     /// ```
-    #[inline(always)]
-    #[export_name = "Square_index"]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_index")]
     pub extern "C" fn index(self) -> u8 {
         self.sanity_check();
         self.0.get()
     }
 
+    /// Finds the zero-based index of `self` in range `0..81`, which is `self.index() - 1`.
+    ///
+    /// Tables indexed by squares should use this function (or [`Square::array_index`]) rather than [`Square::index`].
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::Square;
+    /// assert_eq!(Square::SQ_1A.index0(), Square::MIN_INDEX0);
+    /// assert_eq!(Square::SQ_3D.index0(), 21);
+    /// assert_eq!(Square::SQ_9I.index0(), Square::MAX_INDEX0);
+    /// ```
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_index0")]
+    pub extern "C" fn index0(self) -> u8 {
+        self.sanity_check();
+        self.0.get() - 1
+    }
+
+    /// Converts a zero-based index to a [`Square`]. This is the inverse of [`Square::index0`].
+    /// If `index0` is not in range `0..81`, this function returns [`None`].
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::Square;
+    /// assert_eq!(Square::from_index0(21), Some(Square::SQ_3D));
+    /// assert_eq!(Square::from_index0(81), None);
+    /// assert_eq!(Square::from_index0(255), None);
+    /// ```
+    #[cfg_attr(not(feature = "c-exports"), inline)]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_from_index0")]
+    pub extern "C" fn from_index0(index0: u8) -> Option<Self> {
+        Self::from_u8(index0.wrapping_add(1))
+    }
+
+    /// The minimum value of [`Square::index`].
+    pub const MIN_INDEX: u8 = 1;
+    /// The maximum value of [`Square::index`].
+    pub const MAX_INDEX: u8 = 81;
+    /// The minimum value of [`Square::index0`].
+    pub const MIN_INDEX0: u8 = 0;
+    /// The maximum value of [`Square::index0`].
+    pub const MAX_INDEX0: u8 = 80;
+
     /// Finds the rank from the perspective of `color`.
-    #[export_name = "Square_relative_rank"]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_relative_rank")]
     pub extern "C" fn relative_rank(self, color: Color) -> u8 {
         let rank = self.rank();
         match color {
@@ -117,7 +163,7 @@ impl Square {
     }
 
     /// Finds the file from the perspective of `color`.
-    #[export_name = "Square_relative_file"]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_relative_file")]
     pub extern "C" fn relative_file(self, color: Color) -> u8 {
         let file = self.file();
         match color {
@@ -126,6 +172,25 @@ impl Square {
         }
     }
 
+    /// Finds the square from the perspective of `color`, i.e., `self` for black and `self.flip()` for white.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::{Color, Square};
+    /// let relative = Square::SQ_2C.relative(Color::White);
+    /// assert_eq!((relative.file(), relative.rank()), (8, 7));
+    /// assert_eq!(relative.absolute(Color::White), Square::SQ_2C);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[inline(always)]
+    pub fn relative(self, color: Color) -> RelativeSquare {
+        match color {
+            Color::Black => RelativeSquare(self),
+            Color::White => RelativeSquare(self.flip()),
+        }
+    }
+
     /// Finds the reflected square of `self`.
     ///
     /// Examples:
@@ -134,8 +199,8 @@ impl Square {
     /// assert_eq!(Square::SQ_1A.flip(), Square::SQ_9I);
     /// assert_eq!(Square::SQ_3D.flip(), Square::SQ_7F);
     /// ```
-    #[inline(always)]
-    #[export_name = "Square_flip"]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_flip")]
     pub extern "C" fn flip(self) -> Self {
         // Safety: self.0.get() is in range 1..=81.
         unsafe { Self::from_u8_unchecked(82 - self.0.get()) }
@@ -150,8 +215,8 @@ impl Square {
     /// assert_eq!(Square::from_u8(0), None);
     /// assert_eq!(Square::from_u8(82), None);
     /// ```
-    #[inline]
-    #[export_name = "Square_from_u8"]
+    #[cfg_attr(not(feature = "c-exports"), inline)]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_from_u8")]
     pub extern "C" fn from_u8(value: u8) -> Option<Self> {
         // The shortest possible machine code for this function in x86_64 (System V AMD64 ABI) is:
         // 31 c0       xorl %eax, %eax
@@ -159,7 +224,7 @@ impl Square {
         // 0f 42 c7    cmovbl %edi, %eax
         if matches!(value as u32, 0..=81) {
             // Safety: `value` is in `0..=81`, which is the range of valid representations.
-            unsafe { core::mem::transmute::<_, OptionSquare>(value) }.into()
+            unsafe { core::mem::transmute::<u8, OptionSquare>(value) }.into()
         } else {
             None
         }
@@ -172,22 +237,23 @@ impl Square {
     #[inline(always)]
     pub const unsafe fn from_u8_unchecked(value: u8) -> Self {
         if !matches!(value, 1..=81) {
-            core::hint::unreachable_unchecked();
+            crate::common::unreachable_unchecked();
         }
-        Self(NonZeroU8::new_unchecked(value))
+        Self(crate::common::nonzero_u8_unchecked(value))
     }
 
     /// C interface to [`Square::from_u8_unchecked`].
     ///
     /// # Safety
     /// `value` must be in range 1..=81
-    #[inline(always)]
-    #[no_mangle]
+    #[allow(non_snake_case)]
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
     pub unsafe extern "C" fn Square_from_u8_unchecked(value: u8) -> Self {
         if !matches!(value, 1..=81) {
-            core::hint::unreachable_unchecked();
+            crate::common::unreachable_unchecked();
         }
-        Self(NonZeroU8::new_unchecked(value))
+        Self(crate::common::nonzero_u8_unchecked(value))
     }
 
     /// Shifts `self` by the given arguments. If the result would be out of the board, this function returns [`None`].
@@ -199,7 +265,7 @@ impl Square {
     /// assert_eq!(Square::SQ_8D.shift(0, -3), Some(Square::SQ_8A));
     /// assert_eq!(Square::SQ_3C.shift(-4, 3), None);
     /// ```
-    #[export_name = "Square_shift"]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_shift")]
     pub extern "C" fn shift(self, file_delta: i8, rank_delta: i8) -> Option<Self> {
         self.sanity_check();
         let file_m1 = (self.file() as i8).wrapping_add(file_delta).wrapping_sub(1);
@@ -211,6 +277,87 @@ impl Square {
         Some(unsafe { Self::from_u8_unchecked((file_m1 * 9 + rank_m1 + 1) as u8) })
     }
 
+    /// Checks if `self` and `other` are on the same file.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::Square;
+    /// assert!(Square::SQ_3C.same_file(Square::SQ_3I));
+    /// assert!(!Square::SQ_3C.same_file(Square::SQ_4C));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_same_file")]
+    pub extern "C" fn same_file(self, other: Square) -> bool {
+        self.file() == other.file()
+    }
+
+    /// Checks if `self` and `other` are on the same rank.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::Square;
+    /// assert!(Square::SQ_3C.same_rank(Square::SQ_9C));
+    /// assert!(!Square::SQ_3C.same_rank(Square::SQ_3D));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_same_rank")]
+    pub extern "C" fn same_rank(self, other: Square) -> bool {
+        self.rank() == other.rank()
+    }
+
+    /// Checks if `self` and `other` are on the same diagonal line, in either direction.
+    /// A square is on the same diagonal as itself.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::Square;
+    /// assert!(Square::SQ_3C.same_diagonal(Square::SQ_7G));
+    /// assert!(Square::SQ_3C.same_diagonal(Square::SQ_1E));
+    /// assert!(!Square::SQ_3C.same_diagonal(Square::SQ_4E));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_same_diagonal")]
+    pub extern "C" fn same_diagonal(self, other: Square) -> bool {
+        self.file_abs_diff(other) == self.rank_abs_diff(other)
+    }
+
+    /// Finds the distance between the files of `self` and `other`, in range `0..=8`.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::Square;
+    /// assert_eq!(Square::SQ_3C.file_abs_diff(Square::SQ_7A), 4);
+    /// assert_eq!(Square::SQ_7A.file_abs_diff(Square::SQ_3C), 4);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_file_abs_diff")]
+    pub extern "C" fn file_abs_diff(self, other: Square) -> u8 {
+        self.file().abs_diff(other.file())
+    }
+
+    /// Finds the distance between the ranks of `self` and `other`, in range `0..=8`.
+    ///
+    /// Examples:
+    /// ```
+    /// use shogi_core::Square;
+    /// assert_eq!(Square::SQ_3C.rank_abs_diff(Square::SQ_7A), 2);
+    /// ```
+    ///
+    /// Since: 0.1.6
+    #[cfg_attr(not(feature = "c-exports"), inline(always))]
+    #[cfg_attr(feature = "c-exports", export_name = "Square_rank_abs_diff")]
+    pub extern "C" fn rank_abs_diff(self, other: Square) -> u8 {
+        self.rank().abs_diff(other.rank())
+    }
+
     /// Returns the index of `self` for array accesses. This function returns an integer in range `0..Square::MAX`.
     ///
     /// Since: 0.1.2
@@ -220,7 +367,7 @@ impl Square {
         let result = (self.0.get() - 1) as usize;
         // Safety: result < Square::NUM always holds
         if result >= Self::NUM {
-            unsafe { core::hint::unreachable_unchecked() };
+            unsafe { crate::common::unreachable_unchecked() };
         }
         result
     }
@@ -249,19 +396,147 @@ impl Square {
         (1..=81).map(|index| unsafe { Self::from_u8_unchecked(index) })
     }
 
+    /// Parses a square in USI format, e.g. `7g`.
+    ///
+    /// Unlike [`FromStr`](core::str::FromStr), this function tells why `s` is not a square.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Square, UsiSquareParseError};
+    /// assert_eq!(Square::from_usi("7g"), Ok(Square::SQ_7G));
+    /// assert_eq!(Square::from_usi("0a"), Err(UsiSquareParseError::InvalidFile));
+    /// assert_eq!(Square::from_usi("7j"), Err(UsiSquareParseError::InvalidRank));
+    /// assert_eq!(Square::from_usi("7g "), Err(UsiSquareParseError::Syntax));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn from_usi(s: &str) -> Result<Self, UsiSquareParseError> {
+        Self::try_parse_usi_bytes(s.as_bytes())
+    }
+
+    /// Parses a square in USI format, e.g. `7g`.
+    pub(crate) fn parse_usi_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::try_parse_usi_bytes(bytes).ok()
+    }
+
+    fn try_parse_usi_bytes(bytes: &[u8]) -> Result<Self, UsiSquareParseError> {
+        let (file, rank) = match *bytes {
+            [file, rank] => (file, rank),
+            _ => return Err(UsiSquareParseError::Syntax),
+        };
+        if !matches!(file, b'1'..=b'9') {
+            return Err(UsiSquareParseError::InvalidFile);
+        }
+        if !matches!(rank, b'a'..=b'i') {
+            return Err(UsiSquareParseError::InvalidRank);
+        }
+        // Safety: file is in 1..=9 and rank is in 1..=9
+        Ok(unsafe { common::unwrap_unchecked(Square::new(file - b'0', rank - b'a' + 1)) })
+    }
+
+    /// C interface of [`ToUsi::to_usi`].
+    /// Writes the USI representation of `self` to `ptr` followed by a nul byte.
+    ///
+    /// Returns the number of bytes written excluding the nul byte.
+    /// If `capacity` is not large enough, this function returns 0. A capacity of 3 is always sufficient.
+    ///
+    /// # Safety
+    /// `ptr` must be valid for writes of `capacity` bytes.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn Square_to_usi_c(self, ptr: *mut u8, capacity: usize) -> usize {
+        common::write_nul_terminated(ptr, capacity, |sink| self.to_usi(sink))
+    }
+
+    /// Parses a square in USI format from `len` bytes starting at `ptr`.
+    /// The bytes need not be nul-terminated.
+    ///
+    /// If the bytes are not a valid square, this function returns `0` (`None`).
+    ///
+    /// # Safety
+    /// `ptr` must be null or valid for reads of `len` bytes.
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn Square_parse_c(ptr: *const u8, len: usize) -> OptionSquare {
+        Self::parse_usi_bytes(common::c_bytes(ptr, len)).into()
+    }
+
     // Check if self.0 is in 1..=81
     #[inline(always)]
     const fn sanity_check(self) {
         debug_assert!(matches!(self.0.get(), 1..=81));
         // Safety: for any valid Square, its representation must be in 1..=81.
         if !matches!(self.0.get(), 1..=81) {
-            unsafe { core::hint::unreachable_unchecked() }
+            unsafe { crate::common::unreachable_unchecked() }
         }
     }
 }
 
 impl_ord_for_single_field!(Square);
 impl_hash_for_single_field!(Square);
+impl_rkyv_as_self!(Square);
+
+/// Converts a [`u8`] to a [`Square`] in the same way as [`Square::from_u8`].
+/// Fails if `value` is not in range `1..=81`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{OutOfRangeError, Square};
+/// assert_eq!(Square::try_from(57u8), Ok(Square::SQ_7C));
+/// assert_eq!(Square::try_from(0u8), Err(OutOfRangeError));
+/// ```
+impl TryFrom<u8> for Square {
+    type Error = OutOfRangeError;
+
+    #[inline]
+    fn try_from(value: u8) -> Result<Self, OutOfRangeError> {
+        Square::from_u8(value).ok_or(OutOfRangeError)
+    }
+}
+
+/// Converts a [`Square`] to its index in range `1..=81`, as [`Square::index`] does.
+impl From<Square> for u8 {
+    #[inline(always)]
+    fn from(square: Square) -> Self {
+        square.index()
+    }
+}
+
+/// Converts a [`usize`] to a [`Square`] in the same way as <code>[TryFrom]<[u8]></code>.
+/// Fails if `value` is not in range `1..=81`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{OutOfRangeError, Square};
+/// assert_eq!(Square::try_from(1usize), Ok(Square::SQ_1A));
+/// assert_eq!(Square::try_from(81usize), Ok(Square::SQ_9I));
+/// assert_eq!(Square::try_from(0usize), Err(OutOfRangeError));
+/// ```
+impl TryFrom<usize> for Square {
+    type Error = OutOfRangeError;
+
+    #[inline]
+    fn try_from(value: usize) -> Result<Self, OutOfRangeError> {
+        let value = u8::try_from(value).map_err(|_| OutOfRangeError)?;
+        Square::try_from(value)
+    }
+}
+
+/// Converts a [`Square`] to its index in range `1..=81`, as [`Square::index`] does.
+/// Use [`Square::array_index`] for an index in range `0..81`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::Square;
+/// assert_eq!(usize::from(Square::SQ_9I), 81);
+/// assert_eq!(usize::from(Square::SQ_9I), usize::from(u8::from(Square::SQ_9I)));
+/// ```
+impl From<Square> for usize {
+    #[inline(always)]
+    fn from(square: Square) -> Self {
+        square.index() as usize
+    }
+}
 
 /// USI representation of a square.
 ///
@@ -276,6 +551,171 @@ impl ToUsi for Square {
     }
 }
 
+/// Displays a square in USI format, e.g. `7g`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::Square;
+/// assert_eq!(format!("{}", Square::SQ_7G), "7g");
+/// ```
+///
+/// Since: 0.1.6
+impl core::fmt::Display for Square {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.to_usi(f)
+    }
+}
+
+/// Parses a square in USI format, e.g. `7g`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::Square;
+/// # use shogi_core::UsiSquareParseError;
+/// assert_eq!("7g".parse::<Square>(), Ok(Square::SQ_7G));
+/// assert_eq!("0a".parse::<Square>(), Err(UsiSquareParseError::InvalidFile));
+/// assert_eq!("7j".parse::<Square>(), Err(UsiSquareParseError::InvalidRank));
+/// ```
+///
+/// Since: 0.1.6
+impl core::str::FromStr for Square {
+    type Err = UsiSquareParseError;
+
+    fn from_str(s: &str) -> Result<Self, UsiSquareParseError> {
+        Self::from_usi(s)
+    }
+}
+
+/// An error returned by [`Square::from_usi`].
+///
+/// Since: 0.1.6
+#[repr(u8)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum UsiSquareParseError {
+    /// The string is not two characters long.
+    ///
+    /// Discriminant = 1.
+    Syntax = 1,
+    /// The file is not in `1`..=`9`.
+    ///
+    /// Discriminant = 2.
+    InvalidFile = 2,
+    /// The rank is not in `a`..=`i`.
+    ///
+    /// Discriminant = 3.
+    InvalidRank = 3,
+}
+
+impl UsiSquareParseError {
+    /// Returns a short description of `self` in English, e.g. `"invalid file"`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::UsiSquareParseError;
+    /// assert_eq!(UsiSquareParseError::InvalidFile.description(), "invalid file");
+    /// ```
+    pub const fn description(self) -> &'static str {
+        match self {
+            UsiSquareParseError::Syntax => "invalid USI square",
+            UsiSquareParseError::InvalidFile => "invalid file",
+            UsiSquareParseError::InvalidRank => "invalid rank",
+        }
+    }
+}
+
+impl_ord_for_fieldless_enum!(UsiSquareParseError);
+impl_hash_for_fieldless_enum!(UsiSquareParseError);
+
+impl core::fmt::Display for UsiSquareParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for UsiSquareParseError {}
+
+/// Formats a square in USI format, e.g. `7g`.
+#[cfg(feature = "defmt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "defmt")))]
+impl defmt::Format for Square {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{=u8}{=char}",
+            self.file(),
+            (b'a' + self.rank() - 1) as char,
+        );
+    }
+}
+
+/// A square from the perspective of a player, whose rank 1 is the farthest rank from the player.
+///
+/// A [`RelativeSquare`] of black is the same as the [`Square`], and that of white is the flipped [`Square`].
+/// Color-symmetric tables (e.g. of evaluation functions) can be indexed by [`RelativeSquare::array_index`] for both players.
+///
+/// Examples:
+/// ```
+/// use shogi_core::{Color, RelativeSquare, Square};
+/// // The square in front of the king in the starting position
+/// let front = RelativeSquare::new(5, 8).unwrap();
+/// assert_eq!(front.absolute(Color::Black), Square::SQ_5H);
+/// assert_eq!(front.absolute(Color::White), Square::SQ_5B);
+/// assert_eq!(Square::SQ_5B.relative(Color::White), front);
+/// ```
+///
+/// Since: 0.1.6
+#[repr(transparent)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct RelativeSquare(Square);
+
+impl RelativeSquare {
+    /// Creates a new [`RelativeSquare`] with given relative `file` and `rank`.
+    ///
+    /// `file` and `rank` must be between 1 and 9 (both inclusive).
+    /// If this condition is not met, this function returns None.
+    #[inline(always)]
+    pub const fn new(file: u8, rank: u8) -> Option<Self> {
+        match Square::new(file, rank) {
+            Some(square) => Some(Self(square)),
+            None => None,
+        }
+    }
+
+    /// Finds the relative file in range `1..=9`.
+    #[inline(always)]
+    pub fn file(self) -> u8 {
+        self.0.file()
+    }
+
+    /// Finds the relative rank in range `1..=9`. Rank 1 is the farthest rank from the player.
+    #[inline(always)]
+    pub fn rank(self) -> u8 {
+        self.0.rank()
+    }
+
+    /// Finds the [`Square`] that `self` represents from the perspective of `color`.
+    /// This is the inverse of [`Square::relative`].
+    #[inline(always)]
+    pub fn absolute(self, color: Color) -> Square {
+        match color {
+            Color::Black => self.0,
+            Color::White => self.0.flip(),
+        }
+    }
+
+    /// Returns the index of `self` for array accesses. This function returns an integer in range `0..Square::NUM`.
+    #[inline(always)]
+    pub const fn array_index(self) -> usize {
+        self.0.array_index()
+    }
+}
+
+impl_ord_for_single_field!(RelativeSquare);
+impl_hash_for_single_field!(RelativeSquare);
+impl_rkyv_as_self!(RelativeSquare);
+
 /// C interface of <code>[Option]<[Square]></code>.
 ///
 /// This type is provided for C interoperability.
@@ -287,9 +727,10 @@ impl ToUsi for Square {
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
 pub struct OptionSquare(u8);
 
-impl From<Option<Square>> for OptionSquare {
+impl OptionSquare {
+    // A `const` version of `From<Option<Square>>`.
     #[inline(always)]
-    fn from(arg: Option<Square>) -> Self {
+    pub(crate) const fn from_option(arg: Option<Square>) -> Self {
         Self(match arg {
             Some(result) => result.0.get(),
             None => 0,
@@ -297,6 +738,13 @@ impl From<Option<Square>> for OptionSquare {
     }
 }
 
+impl From<Option<Square>> for OptionSquare {
+    #[inline(always)]
+    fn from(arg: Option<Square>) -> Self {
+        Self::from_option(arg)
+    }
+}
+
 impl From<OptionSquare> for Option<Square> {
     #[inline(always)]
     fn from(arg: OptionSquare) -> Self {
@@ -304,8 +752,116 @@ impl From<OptionSquare> for Option<Square> {
     }
 }
 
+impl OptionSquare {
+    // Converts `self` to <code>[Option]<[Square]></code>, also returning `None` if `self` holds an out-of-range value.
+    // C callers can create such values because the representation is a plain `uint8_t`.
+    #[inline]
+    pub(crate) fn to_checked_option(self) -> Option<Square> {
+        Square::from_u8(self.0)
+    }
+}
+
+impl OptionSquare {
+    /// Returns true if and only if `self` holds a square, i.e., `self` is not the representation of [`None`].
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn OptionSquare_is_some(self) -> bool {
+        self.0 != 0
+    }
+
+    /// Returns true if and only if `self` is the representation of [`None`].
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub extern "C" fn OptionSquare_is_none(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the [`Square`] held by `self` without checking.
+    ///
+    /// # Safety
+    /// `self` must hold a square, i.e., [`OptionSquare::OptionSquare_is_some`] must return true.
+    ///
+    /// Since: 0.1.6
+    #[allow(non_snake_case)]
+    #[cfg_attr(feature = "c-exports", no_mangle)]
+    pub unsafe extern "C" fn OptionSquare_unwrap_unchecked(self) -> Square {
+        crate::common::unwrap_unchecked(self.into())
+    }
+}
+
 impl_ord_for_single_field!(OptionSquare);
 impl_hash_for_single_field!(OptionSquare);
+impl_rkyv_as_self!(OptionSquare);
+
+/// A value for each [`Square`], indexed by [`Square`].
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{PerSquare, Square};
+/// let mut values = PerSquare::new(0u8);
+/// values[Square::SQ_5E] += 2;
+/// assert_eq!(values[Square::SQ_5E], 2);
+/// assert_eq!(values.as_array()[Square::SQ_5E.array_index()], 2);
+/// assert_eq!(values[Square::SQ_1A], 0);
+/// ```
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct PerSquare<T>([T; Square::NUM]);
+
+impl<T: Copy> PerSquare<T> {
+    /// Creates a [`PerSquare`] with `value` on every square.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self([value; Square::NUM])
+    }
+}
+
+impl<T> PerSquare<T> {
+    /// Creates a [`PerSquare`] from an array indexed by [`Square::array_index`].
+    #[inline]
+    pub const fn from_array(values: [T; Square::NUM]) -> Self {
+        Self(values)
+    }
+
+    /// Returns the values as an array indexed by [`Square::array_index`].
+    #[inline]
+    pub const fn as_array(&self) -> &[T; Square::NUM] {
+        &self.0
+    }
+
+    /// Converts `self` into an array indexed by [`Square::array_index`].
+    #[inline]
+    pub fn into_array(self) -> [T; Square::NUM] {
+        self.0
+    }
+}
+
+impl<T: Copy + Default> Default for PerSquare<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> Index<Square> for PerSquare<T> {
+    type Output = T;
+
+    #[inline(always)]
+    fn index(&self, square: Square) -> &T {
+        // Safety: square.array_index() < Square::NUM
+        unsafe { common::get_unchecked(&self.0, square.array_index()) }
+    }
+}
+
+impl<T> IndexMut<Square> for PerSquare<T> {
+    #[inline(always)]
+    fn index_mut(&mut self, square: Square) -> &mut T {
+        // Safety: square.array_index() < Square::NUM
+        unsafe { common::get_unchecked_mut(&mut self.0, square.array_index()) }
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -366,6 +922,13 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "checked")]
+    #[test]
+    #[should_panic]
+    fn from_u8_unchecked_panics_if_checked() {
+        let _ = unsafe { Square::from_u8_unchecked(82) };
+    }
+
     #[test]
     fn from_u8_works() {
         for value in 0..=255 {
@@ -399,6 +962,50 @@ mod tests {
             }
         }
     }
+    #[test]
+    fn index0_works() {
+        for square in Square::all() {
+            assert_eq!(square.index0() as usize, square.array_index());
+            assert_eq!(Square::from_index0(square.index0()), Some(square));
+        }
+        for index0 in Square::MAX_INDEX0 + 1..=u8::MAX {
+            assert_eq!(Square::from_index0(index0), None);
+        }
+    }
+
+    #[test]
+    fn alignment_works() {
+        for a in Square::all() {
+            for b in Square::all() {
+                assert_eq!(a.same_file(b), a.file() == b.file());
+                assert_eq!(a.same_rank(b), a.rank() == b.rank());
+                // `b` is reachable from `a` by a diagonal shift.
+                let diagonal = (-8..=8).any(|delta| {
+                    a.shift(delta, delta) == Some(b) || a.shift(delta, -delta) == Some(b)
+                });
+                assert_eq!(a.same_diagonal(b), diagonal);
+                assert_eq!(a.file_abs_diff(b), b.file_abs_diff(a));
+                assert_eq!(a.rank_abs_diff(b), b.rank_abs_diff(a));
+            }
+        }
+    }
+
+    #[test]
+    fn relative_works() {
+        for square in Square::all() {
+            for color in Color::all() {
+                let relative = square.relative(color);
+                assert_eq!(relative.absolute(color), square);
+                assert_eq!(relative.file(), square.relative_file(color));
+                assert_eq!(relative.rank(), square.relative_rank(color));
+                assert_eq!(
+                    RelativeSquare::new(relative.file(), relative.rank()),
+                    Some(relative),
+                );
+            }
+        }
+    }
+
     #[test]
     fn array_index_works() {
         for (index, sq) in Square::all().enumerate() {
@@ -406,6 +1013,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn conversions_work() {
+        for square in Square::all() {
+            assert_eq!(Square::try_from(u8::from(square)), Ok(square));
+            assert_eq!(Square::try_from(usize::from(square)), Ok(square));
+        }
+        assert_eq!(Square::try_from(82u8), Err(OutOfRangeError));
+        assert_eq!(Square::try_from(0usize), Err(OutOfRangeError));
+        assert_eq!(Square::try_from(usize::MAX), Err(OutOfRangeError));
+    }
+
+    #[test]
+    fn parse_usi_bytes_works() {
+        for square in Square::all() {
+            let usi = square.to_usi_owned();
+            assert_eq!(Square::parse_usi_bytes(usi.as_bytes()), Some(square));
+            let mut buf = [0u8; 3];
+            let written = unsafe { square.Square_to_usi_c(buf.as_mut_ptr(), buf.len()) };
+            assert_eq!(&buf[..written], usi.as_bytes());
+            assert_eq!(buf[written], 0);
+            assert_eq!(unsafe { square.Square_to_usi_c(buf.as_mut_ptr(), 2) }, 0);
+        }
+        for invalid in [&b""[..], b"0a", b"1j", b"a1", b"1a ", b"1"] {
+            assert_eq!(Square::parse_usi_bytes(invalid), None);
+        }
+        assert_eq!(Square::from_usi(""), Err(UsiSquareParseError::Syntax));
+        assert_eq!(Square::from_usi("1"), Err(UsiSquareParseError::Syntax));
+        assert_eq!(
+            Square::from_usi("a1"),
+            Err(UsiSquareParseError::InvalidFile)
+        );
+        assert_eq!(
+            Square::from_usi("1A"),
+            Err(UsiSquareParseError::InvalidRank)
+        );
+        assert_eq!(
+            "1A".parse::<Square>(),
+            Err(UsiSquareParseError::InvalidRank)
+        );
+        assert_eq!(
+            unsafe { Square::Square_parse_c(core::ptr::null(), 0) },
+            None.into()
+        );
+    }
+
     #[test]
     fn to_usi_works() {
         assert_eq!(Square::SQ_1A.to_usi_owned(), "1a".to_owned());
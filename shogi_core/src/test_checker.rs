@@ -0,0 +1,123 @@
+//! A [`LegalityChecker`] with simplified rules, shared by tests of items that are generic over checkers.
+
+use crate::{
+    Bitboard, IllegalMoveKind, LegalityChecker, Move, PartialPosition, Piece, PieceKind,
+    PositionStatus, Square,
+};
+
+// A checker that allows pseudo-legal moves: checks, two pawns on the same file and so on are not taken into account.
+// - A piece whose kind satisfies `movable` can move to any square it attacks that is not occupied by its owner's piece.
+//   It can promote if it is promotable and either square is in the promotion zone.
+// - If `drops` is true, a piece in hand can be dropped on any vacant square.
+// - The status of a position is given by `status`.
+pub(crate) struct TestChecker {
+    pub(crate) movable: fn(PieceKind) -> bool,
+    pub(crate) drops: bool,
+    pub(crate) status: fn(&PartialPosition) -> PositionStatus,
+}
+
+impl TestChecker {
+    // Every piece can move and be dropped. The game never ends.
+    pub(crate) const PSEUDO_LEGAL: Self = Self {
+        movable: |_| true,
+        drops: true,
+        status: |_| PositionStatus::InProgress,
+    };
+}
+
+impl LegalityChecker for TestChecker {
+    #[cfg(feature = "alloc")]
+    fn status(&self, position: &crate::Position) -> PositionStatus {
+        (self.status)(position.inner())
+    }
+
+    fn status_partial(&self, position: &PartialPosition) -> PositionStatus {
+        (self.status)(position)
+    }
+
+    fn is_legal_partial(
+        &self,
+        position: &PartialPosition,
+        mv: Move,
+    ) -> Result<(), IllegalMoveKind> {
+        if self.is_legal_partial_lite(position, mv) {
+            Ok(())
+        } else {
+            Err(IllegalMoveKind::IncorrectMove)
+        }
+    }
+
+    fn is_legal_partial_lite(&self, position: &PartialPosition, mv: Move) -> bool {
+        let side = position.side_to_move();
+        match mv {
+            Move::Normal { from, to, promote } => {
+                let piece = match position.piece_at(from) {
+                    Some(piece) => piece,
+                    None => return false,
+                };
+                let in_zone = from.relative_rank(side) <= 3 || to.relative_rank(side) <= 3;
+                (!promote || piece.promote().is_some() && in_zone)
+                    && self.normal_from_candidates(position, from).contains(to)
+            }
+            Move::Drop { piece, to } => self.drop_candidates(position, piece).contains(to),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn all_legal_moves_partial(&self, position: &PartialPosition) -> alloc::vec::Vec<Move> {
+        let mut result = alloc::vec::Vec::new();
+        let side = position.side_to_move();
+        for from in position.player_bitboard(side) {
+            for to in self.normal_from_candidates(position, from) {
+                for promote in [false, true] {
+                    let mv = Move::Normal { from, to, promote };
+                    if self.is_legal_partial_lite(position, mv) {
+                        result.push(mv);
+                    }
+                }
+            }
+        }
+        for piece_kind in crate::Hand::all_hand_pieces() {
+            let piece = Piece::new(piece_kind, side);
+            for to in self.drop_candidates(position, piece) {
+                result.push(Move::Drop { piece, to });
+            }
+        }
+        result
+    }
+
+    fn normal_from_candidates(&self, position: &PartialPosition, from: Square) -> Bitboard {
+        let side = position.side_to_move();
+        match position.piece_at(from) {
+            Some(piece) if piece.color() == side && (self.movable)(piece.piece_kind()) => {
+                position.attacks_from(from) & !position.player_bitboard(side)
+            }
+            _ => Bitboard::empty(),
+        }
+    }
+
+    fn normal_to_candidates(
+        &self,
+        position: &PartialPosition,
+        to: Square,
+        piece: Piece,
+    ) -> Bitboard {
+        let mut result = Bitboard::empty();
+        for from in position.piece_bitboard(piece) {
+            if self.normal_from_candidates(position, from).contains(to) {
+                result |= from;
+            }
+        }
+        result
+    }
+
+    fn drop_candidates(&self, position: &PartialPosition, piece: Piece) -> Bitboard {
+        if !self.drops
+            || piece.color() != position.side_to_move()
+            || position.hand(piece).unwrap_or(0) == 0
+        {
+            return Bitboard::empty();
+        }
+        position.vacant_bitboard()
+    }
+}
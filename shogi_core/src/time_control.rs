@@ -0,0 +1,97 @@
+use core::fmt::{Result as FmtResult, Write};
+
+use crate::usi::GoParams;
+use crate::ToUsi;
+
+/// Time control of a game.
+///
+/// All durations are in milliseconds, as in the `go` command of USI. A zero value means the rule is not used:
+/// for example, a [`TimeControl`] whose fields other than `main_time` are all zero is a sudden-death time control.
+/// Both players are given the same time.
+///
+/// Because [`TimeControl`] is cheap to copy, it implements [`Copy`].
+/// Its [`Default`] value has no time at all, where every move must be made in no time.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimeControl {
+    /// The main time (持ち時間, *mochi-jikan*) of each player.
+    pub main_time: u32,
+    /// Byoyomi (秒読み), the time a player can spend for each move after the main time runs out.
+    pub byoyomi: u32,
+    /// Fischer increment, the time added to the remaining time of a player after every move.
+    pub increment: u32,
+    /// The maximum time a player can spend for a move, regardless of the remaining time.
+    pub per_move_limit: u32,
+}
+
+impl TimeControl {
+    /// Creates a sudden-death time control with `main_time` milliseconds.
+    #[inline]
+    pub const fn sudden_death(main_time: u32) -> Self {
+        Self {
+            main_time,
+            byoyomi: 0,
+            increment: 0,
+            per_move_limit: 0,
+        }
+    }
+
+    /// Creates a time control with `main_time` milliseconds and byoyomi of `byoyomi` milliseconds.
+    #[inline]
+    pub const fn with_byoyomi(main_time: u32, byoyomi: u32) -> Self {
+        Self {
+            main_time,
+            byoyomi,
+            increment: 0,
+            per_move_limit: 0,
+        }
+    }
+
+    /// Creates a Fischer time control with `main_time` milliseconds and an increment of `increment` milliseconds.
+    #[inline]
+    pub const fn fischer(main_time: u32, increment: u32) -> Self {
+        Self {
+            main_time,
+            byoyomi: 0,
+            increment,
+            per_move_limit: 0,
+        }
+    }
+
+    /// Checks if this time control is sudden death, i.e., no time is given after the main time runs out.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::TimeControl;
+    /// assert!(TimeControl::sudden_death(600_000).is_sudden_death());
+    /// assert!(!TimeControl::with_byoyomi(600_000, 10_000).is_sudden_death());
+    /// assert!(!TimeControl::fischer(600_000, 10_000).is_sudden_death());
+    /// ```
+    #[inline]
+    pub const fn is_sudden_death(self) -> bool {
+        self.byoyomi == 0 && self.increment == 0
+    }
+
+    /// Writes the time parameters of the USI `go` command, where black and white have `remaining[0]`
+    /// and `remaining[1]` milliseconds left respectively.
+    ///
+    /// This is a shorthand for writing [`GoParams::from_time_control`] in USI format.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::TimeControl;
+    /// let mut s = String::new();
+    /// TimeControl::with_byoyomi(600_000, 10_000).write_usi_go(&mut s, [600_000, 540_000]).unwrap();
+    /// assert_eq!(s, "btime 600000 wtime 540000 byoyomi 10000");
+    /// let mut s = String::new();
+    /// TimeControl::fischer(300_000, 2_000).write_usi_go(&mut s, [300_000, 300_000]).unwrap();
+    /// assert_eq!(s, "btime 300000 wtime 300000 binc 2000 winc 2000");
+    /// ```
+    pub fn write_usi_go<W: Write>(self, sink: &mut W, remaining: [u32; 2]) -> FmtResult {
+        GoParams::from_time_control(self, remaining).to_usi(sink)
+    }
+}
+
+impl_ord_with_fields!(TimeControl; main_time, byoyomi, increment, per_move_limit);
+impl_hash_with_fields!(TimeControl; main_time, byoyomi, increment, per_move_limit);
+impl_rkyv_as_self!(TimeControl);
@@ -19,3 +19,25 @@ pub trait ToUsi {
         s
     }
 }
+
+/// An error returned when a string is not in valid USI format.
+///
+/// This is the error type of the [`FromStr`](core::str::FromStr) implementations of [`PieceKind`](crate::PieceKind) and [`Piece`](crate::Piece).
+/// Other types report what is wrong with their own error types, e.g. [`UsiMoveParseError`](crate::UsiMoveParseError).
+///
+/// Since: 0.1.6
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct UsiParseError;
+
+impl_ord_for_fieldless_struct!(UsiParseError);
+impl_hash_for_fieldless_struct!(UsiParseError);
+
+impl core::fmt::Display for UsiParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("invalid USI string")
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for UsiParseError {}
@@ -0,0 +1,216 @@
+//! Helpers for tsume-shogi (詰将棋) problems.
+//!
+//! By convention, the attacker is black and moves first, and every piece
+//! that is neither on the board nor in the attacker's hand is in the defender's hand.
+
+use crate::{Color, Hand, PartialPosition, Piece, PieceKind, Square};
+
+// The number of pieces of each kind in a game, in the order of `Hand`.
+const PIECE_COUNTS: [u8; Hand::NUM_HAND_PIECES] = [18, 4, 4, 4, 4, 2, 2];
+
+impl PartialPosition {
+    /// Creates a tsume-shogi problem.
+    ///
+    /// Black (the attacker) has `attacker_pieces` on the board and `hand` in hand, and is to move.
+    /// White (the defender) has only a king on `king_square`,
+    /// and every remaining piece is in white's hand.
+    ///
+    /// Returns [`None`] if two pieces share a square, a king is given as an attacker's piece,
+    /// or the pieces outnumber those of a game.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Hand, PartialPosition, PieceKind, Square};
+    /// let hand = Hand::new().added(PieceKind::Gold).unwrap();
+    /// let position = PartialPosition::tsume(&[(Square::SQ_5C, PieceKind::Pawn)], Square::SQ_5A, hand).unwrap();
+    /// assert_eq!(
+    ///     position.to_sfen_owned(),
+    ///     "4k4/9/4P4/9/9/9/9/9/9 b G2r2b3g4s4n4l17p 1",
+    /// );
+    /// assert!(position.is_valid_tsume(true));
+    /// ```
+    pub fn tsume(
+        attacker_pieces: &[(Square, PieceKind)],
+        king_square: Square,
+        hand: Hand,
+    ) -> Option<Self> {
+        let mut result = Self::empty();
+        result.piece_set(king_square, Some(Piece::new(PieceKind::King, Color::White)));
+        for &(square, piece_kind) in attacker_pieces {
+            if piece_kind == PieceKind::King || result.piece_at(square).is_some() {
+                return None;
+            }
+            result.piece_set(square, Some(Piece::new(piece_kind, Color::Black)));
+        }
+        *result.hand_of_a_player_mut(Color::Black) = hand;
+        result.remaining_pieces_to_hand(Color::White)?;
+        Some(result)
+    }
+
+    /// Adds every piece that is neither on the board nor in either hand to `color`'s hand.
+    ///
+    /// Kings are not taken into account. Returns [`None`] if the pieces on the board and in hand
+    /// already outnumber those of a game. In that case `self` is not modified.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, PartialPosition, Piece, PieceKind, Square};
+    /// let mut position = PartialPosition::empty();
+    /// position.piece_set(Square::SQ_1A, Some(Piece::W_K));
+    /// position.piece_set(Square::SQ_2C, Some(Piece::B_PR));
+    /// position.remaining_pieces_to_hand(Color::White).unwrap();
+    /// assert_eq!(position.hand(Piece::W_R), Some(1));
+    /// assert_eq!(position.hand(Piece::W_P), Some(18));
+    /// ```
+    pub fn remaining_pieces_to_hand(&mut self, color: Color) -> Option<()> {
+        let used = self.used_pieces();
+        let mut hand = self.hand_of_a_player(color);
+        for ((piece_kind, &total), &used) in Hand::all_hand_pieces()
+            .zip(PIECE_COUNTS.iter())
+            .zip(used.iter())
+        {
+            let total = u16::from(total);
+            for _ in used..total {
+                hand = hand.added(piece_kind)?;
+            }
+            if used > total {
+                return None;
+            }
+        }
+        *self.hand_of_a_player_mut(color) = hand;
+        Some(())
+    }
+
+    /// Checks if `self` makes sense as a tsume-shogi problem.
+    ///
+    /// It is checked that black is to move, white has exactly one king on the board,
+    /// and the pieces on the board and in hand do not outnumber those of a game.
+    /// If `king_only` is `true`, it is also checked that white has no pieces on the board other than the king.
+    /// Note that this function does not check the legality of the position, e.g., whether white's king is in check.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Hand, PartialPosition, Piece, PieceKind, Square};
+    /// let mut position = PartialPosition::tsume(&[(Square::SQ_2C, PieceKind::Silver)], Square::SQ_1A, Hand::new()).unwrap();
+    /// assert!(position.is_valid_tsume(true));
+    /// position.piece_set(Square::SQ_1B, Some(Piece::W_P));
+    /// assert!(!position.is_valid_tsume(true));
+    /// assert!(!position.is_valid_tsume(false)); // 18 pawns are already in white's hand
+    /// ```
+    pub fn is_valid_tsume(&self, king_only: bool) -> bool {
+        if self.side_to_move() != Color::Black {
+            return false;
+        }
+        let defender = self.player_bitboard(Color::White);
+        let defender_king = self.piece_bitboard(Piece::new(PieceKind::King, Color::White));
+        if defender_king.count() != 1 {
+            return false;
+        }
+        if king_only && defender != defender_king {
+            return false;
+        }
+        self.used_pieces()
+            .iter()
+            .zip(PIECE_COUNTS.iter())
+            .all(|(&used, &total)| used <= u16::from(total))
+    }
+
+    // The number of pieces of each kind on the board and in hand, in the order of `Hand`.
+    // Promoted pieces are counted as unpromoted ones. Kings are not counted.
+    // Counts are summed into `u16`, because a hand can hold up to 255 pieces of a kind.
+    fn used_pieces(&self) -> [u16; Hand::NUM_HAND_PIECES] {
+        let mut used = [0; Hand::NUM_HAND_PIECES];
+        for square in self.occupied_bitboard() {
+            // Safety: `square` is occupied
+            let piece_kind =
+                unsafe { crate::common::unwrap_unchecked(self.piece_at(square)) }.piece_kind();
+            let piece_kind = piece_kind.unpromoted_or_self();
+            if let Some(count) = used.get_mut(piece_kind as usize - 1) {
+                *count += 1;
+            }
+        }
+        for color in Color::all() {
+            let hand = self.hand_of_a_player(color);
+            for (piece_kind, count) in Hand::all_hand_pieces().zip(used.iter_mut()) {
+                if let Some(in_hand) = hand.count(piece_kind) {
+                    *count += u16::from(in_hand);
+                }
+            }
+        }
+        used
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tsume_works() {
+        let hand = Hand::new().added(PieceKind::Gold).unwrap();
+        let pieces = [
+            (Square::SQ_2C, PieceKind::ProRook),
+            (Square::SQ_3C, PieceKind::Silver),
+        ];
+        let position = PartialPosition::tsume(&pieces, Square::SQ_1A, hand).unwrap();
+        assert_eq!(position.hand(Piece::W_R), Some(1));
+        assert_eq!(position.hand(Piece::W_S), Some(3));
+        assert_eq!(position.hand(Piece::W_G), Some(3));
+        assert_eq!(position.hand(Piece::B_G), Some(1));
+        assert!(position.is_valid_tsume(true));
+
+        // Two pieces on the same square
+        let pieces = [(Square::SQ_1A, PieceKind::Gold)];
+        assert_eq!(PartialPosition::tsume(&pieces, Square::SQ_1A, hand), None);
+        // The attacker's king
+        let pieces = [(Square::SQ_5I, PieceKind::King)];
+        assert_eq!(PartialPosition::tsume(&pieces, Square::SQ_1A, hand), None);
+        // Three rooks
+        let pieces = [
+            (Square::SQ_5I, PieceKind::Rook),
+            (Square::SQ_4I, PieceKind::ProRook),
+        ];
+        let hand = Hand::new().added(PieceKind::Rook).unwrap();
+        assert_eq!(PartialPosition::tsume(&pieces, Square::SQ_1A, hand), None);
+    }
+
+    #[test]
+    fn is_valid_tsume_works() {
+        let mut position = PartialPosition::empty();
+        assert!(!position.is_valid_tsume(false));
+        position.piece_set(Square::SQ_1A, Some(Piece::W_K));
+        assert!(position.is_valid_tsume(true));
+        position.piece_set(Square::SQ_9I, Some(Piece::W_K));
+        assert!(!position.is_valid_tsume(false));
+        position.piece_set(Square::SQ_9I, Some(Piece::W_L));
+        assert!(position.is_valid_tsume(false));
+        assert!(!position.is_valid_tsume(true));
+        position.side_to_move_set(Color::White);
+        assert!(!position.is_valid_tsume(false));
+    }
+
+    #[test]
+    fn oversized_hands_are_rejected() {
+        let mut position = PartialPosition::empty();
+        position.piece_set(Square::SQ_1A, Some(Piece::W_K));
+        let mut black = Hand::new();
+        for _ in 0..250 {
+            black = black.added(PieceKind::Pawn).unwrap();
+        }
+        let mut white = Hand::new();
+        for _ in 0..10 {
+            white = white.added(PieceKind::Pawn).unwrap();
+        }
+        *position.hand_of_a_player_mut(Color::Black) = black;
+        *position.hand_of_a_player_mut(Color::White) = white;
+        assert!(!position.is_valid_tsume(true));
+        assert_eq!(position.remaining_pieces_to_hand(Color::White), None);
+        assert_eq!(position.hand_of_a_player(Color::White), white);
+
+        let mut full = Hand::new();
+        for _ in 0..255 {
+            full = full.added(PieceKind::Pawn).unwrap();
+        }
+        assert_eq!(PartialPosition::tsume(&[], Square::SQ_1A, full), None);
+    }
+}
@@ -0,0 +1,1063 @@
+//! Values exchanged between an engine and a GUI in the USI protocol.
+//!
+//! Types in this module represent the arguments of commands, i.e., what follows the command name.
+//! For example, [`GoParams`] represents `btime 60000 wtime 60000 byoyomi 10000` in `go btime 60000 wtime 60000 byoyomi 10000`.
+//! Parsing functions are named `from_usi`, and formatting is done by [`ToUsi`].
+//!
+//! See <http://shogidokoro.starfree.jp/usi.html> for the specification of USI.
+
+use core::fmt::{Result as FmtResult, Write};
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+use crate::common::{write_i32, write_u32, write_u64};
+use crate::{Color, GameResolution, Move, TimeControl, ToUsi};
+#[cfg(feature = "alloc")]
+use crate::{PartialPosition, Position};
+
+// Splits the first token off `rest`. Tokens are separated by ASCII whitespace.
+pub(crate) fn next_token<'a>(rest: &mut &'a str) -> Option<&'a str> {
+    let trimmed = rest.trim_start_matches(|c: char| c.is_ascii_whitespace());
+    if trimmed.is_empty() {
+        *rest = trimmed;
+        return None;
+    }
+    let end = trimmed
+        .find(|c: char| c.is_ascii_whitespace())
+        .unwrap_or(trimmed.len());
+    *rest = &trimmed[end..];
+    Some(&trimmed[..end])
+}
+
+pub(crate) fn next_number<T: core::str::FromStr>(rest: &mut &str) -> Option<T> {
+    let token = next_token(rest)?;
+    if !token.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+// Writes ` name value` if `value` is not `None`.
+fn write_field<W: Write, T: Copy>(
+    sink: &mut W,
+    name: &str,
+    value: Option<T>,
+    write_value: fn(&mut W, T) -> FmtResult,
+) -> FmtResult {
+    if let Some(value) = value {
+        sink.write_str(" ")?;
+        sink.write_str(name)?;
+        sink.write_str(" ")?;
+        write_value(sink, value)?;
+    }
+    Ok(())
+}
+
+/// The result of a game told to an engine by the `gameover` command.
+///
+/// The result is from the perspective of the engine: for example, `gameover win` means the engine won.
+#[repr(u8)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum GameOver {
+    /// `gameover win`.
+    Win = 1,
+    /// `gameover lose`.
+    Lose = 2,
+    /// `gameover draw`.
+    Draw = 3,
+}
+
+impl GameOver {
+    /// Finds the result of a game from the perspective of `color`.
+    ///
+    /// [`GameResolution::Rematch`] is reported as a draw.
+    /// [`GameResolution::Aborted`] cannot be represented in USI and this function returns [`None`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, GameResolution};
+    /// # use shogi_core::usi::GameOver;
+    /// assert_eq!(GameOver::from_resolution(GameResolution::BlackWins, Color::White), Some(GameOver::Lose));
+    /// assert_eq!(GameOver::from_resolution(GameResolution::Rematch, Color::Black), Some(GameOver::Draw));
+    /// assert_eq!(GameOver::from_resolution(GameResolution::Aborted, Color::Black), None);
+    /// ```
+    pub fn from_resolution(resolution: GameResolution, color: Color) -> Option<Self> {
+        Some(match resolution {
+            GameResolution::BlackWins | GameResolution::WhiteWins => {
+                if resolution == GameResolution::win_of(color) {
+                    GameOver::Win
+                } else {
+                    GameOver::Lose
+                }
+            }
+            GameResolution::Draw | GameResolution::Rematch => GameOver::Draw,
+            GameResolution::Aborted => return None,
+        })
+    }
+
+    /// Converts `self`, which is told to the player `color`, to a [`GameResolution`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, GameResolution};
+    /// # use shogi_core::usi::GameOver;
+    /// assert_eq!(GameOver::Win.to_resolution(Color::White), GameResolution::WhiteWins);
+    /// assert_eq!(GameOver::Lose.to_resolution(Color::White), GameResolution::BlackWins);
+    /// assert_eq!(GameOver::Draw.to_resolution(Color::Black), GameResolution::Draw);
+    /// ```
+    pub fn to_resolution(self, color: Color) -> GameResolution {
+        match self {
+            GameOver::Win => GameResolution::win_of(color),
+            GameOver::Lose => GameResolution::win_of(color.flip()),
+            GameOver::Draw => GameResolution::Draw,
+        }
+    }
+
+    /// Parses the argument of the `gameover` command, i.e., `win`, `lose` or `draw`.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::usi::GameOver;
+    /// assert_eq!(GameOver::from_usi("lose"), Some(GameOver::Lose));
+    /// assert_eq!(GameOver::from_usi("resign"), None);
+    /// ```
+    pub fn from_usi(s: &str) -> Option<Self> {
+        match s {
+            "win" => Some(GameOver::Win),
+            "lose" => Some(GameOver::Lose),
+            "draw" => Some(GameOver::Draw),
+            _ => None,
+        }
+    }
+}
+
+impl_ord_for_fieldless_enum!(GameOver);
+impl_hash_for_fieldless_enum!(GameOver);
+
+/// The argument of the `gameover` command, i.e., `win`, `lose` or `draw`.
+impl ToUsi for GameOver {
+    fn to_usi<W: Write>(&self, sink: &mut W) -> FmtResult {
+        sink.write_str(match *self {
+            GameOver::Win => "win",
+            GameOver::Lose => "lose",
+            GameOver::Draw => "draw",
+        })
+    }
+}
+
+/// The argument of the `bestmove` command sent by an engine.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum BestMove {
+    /// The engine makes a move.
+    Move(Move),
+    /// The engine resigns: `bestmove resign`.
+    Resign,
+    /// The engine declares a win by entering king (入玉宣言勝ち): `bestmove win`.
+    Win,
+}
+
+impl BestMove {
+    /// Parses the argument of the `bestmove` command sent by `side`, the player to move.
+    ///
+    /// Arguments after the move (e.g. `ponder 3c3d`) are not accepted.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Move, Piece, Square};
+    /// # use shogi_core::usi::BestMove;
+    /// assert_eq!(
+    ///     BestMove::from_usi("S*5b", Color::White),
+    ///     Some(BestMove::Move(Move::Drop { piece: Piece::W_S, to: Square::SQ_5B })),
+    /// );
+    /// assert_eq!(BestMove::from_usi("resign", Color::Black), Some(BestMove::Resign));
+    /// assert_eq!(BestMove::from_usi("win", Color::Black), Some(BestMove::Win));
+    /// assert_eq!(BestMove::from_usi("lose", Color::Black), None);
+    /// ```
+    pub fn from_usi(s: &str, side: Color) -> Option<Self> {
+        match s {
+            "resign" => Some(BestMove::Resign),
+            "win" => Some(BestMove::Win),
+            _ => Move::parse_usi_bytes(s.as_bytes(), side).map(BestMove::Move),
+        }
+    }
+
+    /// Finds how the game is resolved if `side` sends `self`.
+    ///
+    /// A declaration of win is assumed to be correct.
+    /// If `self` is a move, this function returns [`None`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, GameResolution};
+    /// # use shogi_core::usi::BestMove;
+    /// assert_eq!(BestMove::Resign.resolution(Color::Black), Some(GameResolution::WhiteWins));
+    /// assert_eq!(BestMove::Win.resolution(Color::Black), Some(GameResolution::BlackWins));
+    /// ```
+    pub fn resolution(self, side: Color) -> Option<GameResolution> {
+        match self {
+            BestMove::Move(_) => None,
+            BestMove::Resign => Some(GameResolution::win_of(side.flip())),
+            BestMove::Win => Some(GameResolution::win_of(side)),
+        }
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::PartialOrd for BestMove {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::Ord for BestMove {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        fn key(best_move: BestMove) -> (u8, Option<Move>) {
+            match best_move {
+                BestMove::Move(mv) => (0, Some(mv)),
+                BestMove::Resign => (1, None),
+                BestMove::Win => (2, None),
+            }
+        }
+        key(*self).cmp(&key(*other))
+    }
+}
+
+#[cfg(feature = "hash")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hash")))]
+impl core::hash::Hash for BestMove {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        if let BestMove::Move(mv) = *self {
+            mv.hash(state);
+        }
+    }
+}
+
+/// The argument of the `bestmove` command, e.g. `7g7f`, `resign` or `win`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Move, Square, ToUsi};
+/// # use shogi_core::usi::BestMove;
+/// let mv = Move::Normal { from: Square::SQ_8H, to: Square::SQ_2B, promote: true };
+/// assert_eq!(BestMove::Move(mv).to_usi_owned(), "8h2b+");
+/// assert_eq!(BestMove::Resign.to_usi_owned(), "resign");
+/// ```
+impl ToUsi for BestMove {
+    fn to_usi<W: Write>(&self, sink: &mut W) -> FmtResult {
+        match *self {
+            BestMove::Move(mv) => mv.to_usi(sink),
+            BestMove::Resign => sink.write_str("resign"),
+            BestMove::Win => sink.write_str("win"),
+        }
+    }
+}
+
+// Parses `startpos` or `sfen <sfen>` at the beginning of `rest`.
+#[cfg(feature = "alloc")]
+fn parse_initial_position(rest: &mut &str) -> Option<PartialPosition> {
+    match next_token(rest)? {
+        "startpos" => Some(PartialPosition::startpos()),
+        "sfen" => {
+            let board = next_token(rest)?;
+            let side = next_token(rest)?;
+            let hands = next_token(rest)?;
+            let mut lookahead = *rest;
+            let ply = match next_token(&mut lookahead) {
+                Some("moves") | None => None,
+                Some(ply) => {
+                    *rest = lookahead;
+                    Some(ply)
+                }
+            };
+            PartialPosition::from_sfen_fields(
+                board.as_bytes(),
+                side.as_bytes(),
+                hands.as_bytes(),
+                ply.map(str::as_bytes),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// The arguments of the `position` command, e.g. `startpos moves 7g7f 3c3d`.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct PositionCommand {
+    /// The initial position. It is written as `startpos` if it is equal to [`PartialPosition::startpos`].
+    pub initial: PartialPosition,
+    /// The moves made from `initial`.
+    pub moves: Vec<Move>,
+}
+
+#[cfg(feature = "alloc")]
+impl PositionCommand {
+    /// Parses the arguments of the `position` command, i.e., `startpos` or `sfen <sfen>`, optionally followed by `moves <move>...`.
+    ///
+    /// The moves are not checked to make sense; see [`PositionCommand::to_position`].
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Move, Piece, Square};
+    /// # use shogi_core::usi::PositionCommand;
+    /// let command = PositionCommand::from_usi("sfen 8k/9/9/9/9/9/9/9/K8 w P 2 moves 1a1b P*5e").unwrap();
+    /// assert_eq!(command.initial.to_sfen_owned(), "8k/9/9/9/9/9/9/9/K8 w P 2");
+    /// assert_eq!(command.moves[1], Move::Drop { piece: Piece::B_P, to: Square::SQ_5E });
+    /// assert_eq!(PositionCommand::from_usi("startpos"), Some(PositionCommand::default()));
+    /// ```
+    pub fn from_usi(args: &str) -> Option<Self> {
+        let mut rest = args;
+        let initial = parse_initial_position(&mut rest)?;
+        let mut moves = Vec::new();
+        match next_token(&mut rest) {
+            None => {}
+            Some("moves") => {
+                let mut side = initial.side_to_move();
+                while let Some(token) = next_token(&mut rest) {
+                    moves.push(Move::parse_usi_bytes(token.as_bytes(), side)?);
+                    side = side.flip();
+                }
+            }
+            Some(_) => return None,
+        }
+        Some(Self { initial, moves })
+    }
+
+    /// Makes a [`Position`] by making `self.moves` from `self.initial`.
+    ///
+    /// If a move does not make sense, this function returns [`None`]. Legality of moves is not checked.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::usi::PositionCommand;
+    /// let command = PositionCommand::from_usi("startpos moves 7g7f 3c3d 8h2b+").unwrap();
+    /// let position = command.to_position().unwrap();
+    /// assert_eq!(position.to_sfen_owned(), "lnsgkgsnl/1r5+B1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/7R1/LNSGKGSNL w B 4");
+    /// assert_eq!(PositionCommand::from_usi("startpos moves 7g7f 7g7f").unwrap().to_position(), None);
+    /// ```
+    pub fn to_position(&self) -> Option<Position> {
+        let mut position = Position::arbitrary_position(self.initial.clone());
+        for &mv in &self.moves {
+            position.make_move(mv)?;
+        }
+        Some(position)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&Position> for PositionCommand {
+    fn from(position: &Position) -> Self {
+        Self {
+            initial: position.initial_position().clone(),
+            moves: position.moves().to_vec(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl_ord_with_fields!(PositionCommand; initial, moves);
+#[cfg(feature = "alloc")]
+impl_hash_with_fields!(PositionCommand; initial, moves);
+
+/// The arguments of the `position` command.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Move, Position, Square, ToUsi};
+/// # use shogi_core::usi::PositionCommand;
+/// let mut position = Position::startpos();
+/// position.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+/// assert_eq!(PositionCommand::from(&position).to_usi_owned(), "startpos moves 7g7f");
+/// let position = Position::arbitrary_position(position.inner().clone());
+/// assert_eq!(
+///     PositionCommand::from(&position).to_usi_owned(),
+///     "sfen lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL w - 2",
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+impl ToUsi for PositionCommand {
+    fn to_usi<W: Write>(&self, sink: &mut W) -> FmtResult {
+        if self.initial == PartialPosition::startpos() {
+            sink.write_str("startpos")?;
+        } else {
+            sink.write_str("sfen ")?;
+            self.initial.to_sfen(sink)?;
+        }
+        if !self.moves.is_empty() {
+            sink.write_str(" moves")?;
+            for mv in &self.moves {
+                sink.write_str(" ")?;
+                mv.to_usi(sink)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error returned by [`Position::from_usi_position`].
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum PositionCommandError {
+    /// The command is not `startpos` or `sfen <sfen>` optionally followed by `moves <move>...`.
+    Syntax,
+    /// The move with the given 0-based index in the move list cannot be parsed or does not make sense.
+    Move {
+        /// The index of the move.
+        index: usize,
+    },
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::PartialOrd for PositionCommandError {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::Ord for PositionCommandError {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        fn key(error: PositionCommandError) -> Option<usize> {
+            match error {
+                PositionCommandError::Syntax => None,
+                PositionCommandError::Move { index } => Some(index),
+            }
+        }
+        // `Syntax` is smaller than any `Move`.
+        key(*self).cmp(&key(*other))
+    }
+}
+
+#[cfg(feature = "hash")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hash")))]
+impl core::hash::Hash for PositionCommandError {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        if let PositionCommandError::Move { index } = *self {
+            index.hash(state);
+        }
+    }
+}
+
+impl core::fmt::Display for PositionCommandError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            PositionCommandError::Syntax => f.write_str("invalid position command"),
+            PositionCommandError::Move { index } => write!(f, "invalid move at index {}", index),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for PositionCommandError {}
+
+/// Parsing the `position` command.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl Position {
+    /// Parses the `position` command of USI, e.g. `position startpos moves 7g7f 3c3d`, and makes the moves in it.
+    ///
+    /// The leading `position` may be omitted. Legality of moves is not checked,
+    /// but a move that does not make sense is reported with its index in the move list.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Position};
+    /// # use shogi_core::usi::PositionCommandError;
+    /// let position = Position::from_usi_position("position startpos moves 7g7f 3c3d").unwrap();
+    /// assert_eq!(position.moves().len(), 2);
+    /// let position = Position::from_usi_position("sfen 8k/9/9/9/9/9/9/9/K8 w P 2 moves 1a1b P*5e").unwrap();
+    /// assert_eq!(position.side_to_move(), Color::White);
+    /// assert_eq!(
+    ///     Position::from_usi_position("position startpos moves 7g7f 7g7f"),
+    ///     Err(PositionCommandError::Move { index: 1 }),
+    /// );
+    /// assert_eq!(Position::from_usi_position("position"), Err(PositionCommandError::Syntax));
+    /// ```
+    ///
+    /// Since: 0.1.6
+    pub fn from_usi_position(command: &str) -> Result<Self, PositionCommandError> {
+        let mut rest = command;
+        let mut lookahead = rest;
+        if next_token(&mut lookahead) == Some("position") {
+            rest = lookahead;
+        }
+        let initial = parse_initial_position(&mut rest).ok_or(PositionCommandError::Syntax)?;
+        let mut position = Position::arbitrary_position(initial);
+        match next_token(&mut rest) {
+            None => {}
+            Some("moves") => {
+                let mut index = 0;
+                while let Some(token) = next_token(&mut rest) {
+                    let error = PositionCommandError::Move { index };
+                    let mv = Move::parse_usi_bytes(token.as_bytes(), position.side_to_move())
+                        .ok_or(error)?;
+                    position.make_move(mv).ok_or(error)?;
+                    index += 1;
+                }
+            }
+            Some(_) => return Err(PositionCommandError::Syntax),
+        }
+        Ok(position)
+    }
+}
+
+/// The time limit of the `go mate` command.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum MateLimit {
+    /// `mate <x>`: the engine searches for a mate for `x` milliseconds.
+    Time(u32),
+    /// `mate infinite`: the engine searches for a mate until it is told to stop.
+    Infinite,
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::PartialOrd for MateLimit {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::Ord for MateLimit {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        fn key(limit: MateLimit) -> Option<u32> {
+            match limit {
+                MateLimit::Time(time) => Some(time),
+                MateLimit::Infinite => None,
+            }
+        }
+        // `Infinite` is larger than any `Time`.
+        match (key(*self), key(*other)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        }
+    }
+}
+
+#[cfg(feature = "hash")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hash")))]
+impl core::hash::Hash for MateLimit {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        if let MateLimit::Time(time) = *self {
+            time.hash(state);
+        }
+    }
+}
+
+/// The arguments of the `go` command, e.g. `btime 60000 wtime 50000 byoyomi 10000`.
+///
+/// All times are in milliseconds. Fields that are [`None`] (or `false`) are not written.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct GoParams {
+    /// `ponder`: the engine thinks during the opponent's time.
+    pub ponder: bool,
+    /// `btime <x>`: black's remaining time.
+    pub btime: Option<u32>,
+    /// `wtime <x>`: white's remaining time.
+    pub wtime: Option<u32>,
+    /// `byoyomi <x>`: byoyomi.
+    pub byoyomi: Option<u32>,
+    /// `binc <x>`: black's increment.
+    pub binc: Option<u32>,
+    /// `winc <x>`: white's increment.
+    pub winc: Option<u32>,
+    /// `depth <x>`: the maximum depth of search.
+    pub depth: Option<u32>,
+    /// `nodes <x>`: the maximum number of nodes to search.
+    pub nodes: Option<u64>,
+    /// `mate <x>` or `mate infinite`: the engine searches for a mate.
+    pub mate: Option<MateLimit>,
+    /// `infinite`: the engine searches until it is told to stop.
+    pub infinite: bool,
+}
+
+impl GoParams {
+    /// Creates [`GoParams`] for `time_control`, where black and white have `remaining[0]`
+    /// and `remaining[1]` milliseconds left respectively.
+    ///
+    /// `byoyomi` is set if byoyomi is used, and `binc` and `winc` are set if increments are used.
+    /// `per_move_limit` cannot be expressed in USI and is ignored.
+    pub fn from_time_control(time_control: TimeControl, remaining: [u32; 2]) -> Self {
+        let nonzero = |value: u32| if value == 0 { None } else { Some(value) };
+        Self {
+            btime: Some(remaining[0]),
+            wtime: Some(remaining[1]),
+            byoyomi: nonzero(time_control.byoyomi),
+            binc: nonzero(time_control.increment),
+            winc: nonzero(time_control.increment),
+            ..Self::default()
+        }
+    }
+
+    /// Parses the arguments of the `go` command.
+    ///
+    /// Unknown parameters are rejected.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::usi::{GoParams, MateLimit};
+    /// let params = GoParams::from_usi("btime 60000 wtime 50000 byoyomi 10000").unwrap();
+    /// assert_eq!(params.wtime, Some(50000));
+    /// assert_eq!(params.byoyomi, Some(10000));
+    /// let params = GoParams::from_usi("mate infinite").unwrap();
+    /// assert_eq!(params.mate, Some(MateLimit::Infinite));
+    /// assert_eq!(GoParams::from_usi(""), Some(GoParams::default()));
+    /// assert_eq!(GoParams::from_usi("btime"), None);
+    /// ```
+    pub fn from_usi(args: &str) -> Option<Self> {
+        let mut result = Self::default();
+        let mut rest = args;
+        while let Some(token) = next_token(&mut rest) {
+            match token {
+                "ponder" => result.ponder = true,
+                "btime" => result.btime = Some(next_number(&mut rest)?),
+                "wtime" => result.wtime = Some(next_number(&mut rest)?),
+                "byoyomi" => result.byoyomi = Some(next_number(&mut rest)?),
+                "binc" => result.binc = Some(next_number(&mut rest)?),
+                "winc" => result.winc = Some(next_number(&mut rest)?),
+                "depth" => result.depth = Some(next_number(&mut rest)?),
+                "nodes" => result.nodes = Some(next_number(&mut rest)?),
+                "mate" => {
+                    let mut lookahead = rest;
+                    result.mate = Some(if next_token(&mut lookahead)? == "infinite" {
+                        rest = lookahead;
+                        MateLimit::Infinite
+                    } else {
+                        MateLimit::Time(next_number(&mut rest)?)
+                    });
+                }
+                "infinite" => result.infinite = true,
+                _ => return None,
+            }
+        }
+        Some(result)
+    }
+}
+
+impl_ord_with_fields!(GoParams; ponder, btime, wtime, byoyomi, binc, winc, depth, nodes, mate, infinite);
+impl_hash_with_fields!(GoParams; ponder, btime, wtime, byoyomi, binc, winc, depth, nodes, mate, infinite);
+
+/// The arguments of the `go` command.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::ToUsi;
+/// # use shogi_core::usi::{GoParams, MateLimit};
+/// let params = GoParams { btime: Some(0), wtime: Some(0), byoyomi: Some(5000), ..GoParams::default() };
+/// assert_eq!(params.to_usi_owned(), "btime 0 wtime 0 byoyomi 5000");
+/// let params = GoParams { mate: Some(MateLimit::Time(1000)), ..GoParams::default() };
+/// assert_eq!(params.to_usi_owned(), "mate 1000");
+/// ```
+impl ToUsi for GoParams {
+    fn to_usi<W: Write>(&self, sink: &mut W) -> FmtResult {
+        // Every field is written with a leading space, which is removed afterwards.
+        struct SkipFirstSpace<'a, W> {
+            sink: &'a mut W,
+            first: bool,
+        }
+        impl<W: Write> Write for SkipFirstSpace<'_, W> {
+            fn write_str(&mut self, s: &str) -> FmtResult {
+                if self.first {
+                    self.first = false;
+                    return self.sink.write_str(s.strip_prefix(' ').unwrap_or(s));
+                }
+                self.sink.write_str(s)
+            }
+        }
+        let sink = &mut SkipFirstSpace { sink, first: true };
+        if self.ponder {
+            sink.write_str(" ponder")?;
+        }
+        write_field(sink, "btime", self.btime, write_u32)?;
+        write_field(sink, "wtime", self.wtime, write_u32)?;
+        write_field(sink, "byoyomi", self.byoyomi, write_u32)?;
+        write_field(sink, "binc", self.binc, write_u32)?;
+        write_field(sink, "winc", self.winc, write_u32)?;
+        write_field(sink, "depth", self.depth, write_u32)?;
+        write_field(sink, "nodes", self.nodes, write_u64)?;
+        match self.mate {
+            Some(MateLimit::Time(time)) => write_field(sink, "mate", Some(time), write_u32)?,
+            Some(MateLimit::Infinite) => sink.write_str(" mate infinite")?,
+            None => {}
+        }
+        if self.infinite {
+            sink.write_str(" infinite")?;
+        }
+        Ok(())
+    }
+}
+
+/// The evaluation of a position in the `info` command, from the perspective of the engine.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Score {
+    /// `score cp <x>`: the evaluation in centipawns.
+    Cp(i32),
+    /// `score mate <x>`: the engine mates in `x` plies if `x` is positive, or gets mated in `-x` plies if `x` is negative.
+    Mate(i32),
+    /// `score mate +` or `score mate -`: the engine mates (if `winning` is `true`) or gets mated in an unknown number of plies.
+    MateUnknown {
+        /// `true` for `+`, `false` for `-`.
+        winning: bool,
+    },
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "ord")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ord")))]
+impl core::cmp::Ord for Score {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        fn key(score: Score) -> (u8, i32) {
+            match score {
+                Score::Cp(value) => (0, value),
+                Score::Mate(plies) => (1, plies),
+                Score::MateUnknown { winning } => (2, winning as i32),
+            }
+        }
+        key(*self).cmp(&key(*other))
+    }
+}
+
+#[cfg(feature = "hash")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hash")))]
+impl core::hash::Hash for Score {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        match *self {
+            Score::Cp(value) | Score::Mate(value) => value.hash(state),
+            Score::MateUnknown { winning } => winning.hash(state),
+        }
+    }
+}
+
+/// `score cp <x>`, `score mate <x>`, `score mate +` or `score mate -` without the leading `score`.
+impl ToUsi for Score {
+    fn to_usi<W: Write>(&self, sink: &mut W) -> FmtResult {
+        match *self {
+            Score::Cp(value) => {
+                sink.write_str("cp ")?;
+                write_i32(sink, value)
+            }
+            Score::Mate(plies) => {
+                sink.write_str("mate ")?;
+                write_i32(sink, plies)
+            }
+            Score::MateUnknown { winning } => {
+                sink.write_str(if winning { "mate +" } else { "mate -" })
+            }
+        }
+    }
+}
+
+/// Whether a score in the `info` command is a bound.
+#[repr(u8)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum ScoreBound {
+    /// `lowerbound`: the actual score is at least the given score.
+    Lower = 1,
+    /// `upperbound`: the actual score is at most the given score.
+    Upper = 2,
+}
+
+impl_ord_for_fieldless_enum!(ScoreBound);
+impl_hash_for_fieldless_enum!(ScoreBound);
+
+/// The arguments of the `info` command, e.g. `depth 10 score cp 120 pv 7g7f 3c3d`.
+///
+/// Fields that are [`None`] (or empty) are not written.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct Info {
+    /// `depth <x>`: the depth of search.
+    pub depth: Option<u32>,
+    /// `seldepth <x>`: the selective depth of search.
+    pub seldepth: Option<u32>,
+    /// `time <x>`: the time spent for search in milliseconds.
+    pub time: Option<u64>,
+    /// `nodes <x>`: the number of nodes searched.
+    pub nodes: Option<u64>,
+    /// `nps <x>`: the number of nodes searched per second.
+    pub nps: Option<u64>,
+    /// `hashfull <x>`: how full the hash table is, in permill.
+    pub hashfull: Option<u32>,
+    /// `multipv <x>`: the rank of `pv` in the multi-PV mode.
+    pub multipv: Option<u32>,
+    /// `score ...`: the evaluation of the position.
+    pub score: Option<Score>,
+    /// `lowerbound` or `upperbound` after the score.
+    pub score_bound: Option<ScoreBound>,
+    /// `currmove <x>`: the move currently searched.
+    pub currmove: Option<Move>,
+    /// `pv <x1> <x2> ...`: the principal variation. The first move is made by the engine.
+    pub pv: Vec<Move>,
+    /// `string <x>`: an arbitrary string. It extends to the end of the command.
+    pub string: Option<String>,
+}
+
+#[cfg(feature = "alloc")]
+impl Info {
+    /// Parses the arguments of the `info` command sent by an engine, where `side` is the player to move.
+    ///
+    /// Moves in `pv` are made alternately by `side` and its opponent. Unknown parameters are rejected.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Color, Move, Piece, Square};
+    /// # use shogi_core::usi::{Info, Score, ScoreBound};
+    /// let info = Info::from_usi("depth 3 score cp -50 lowerbound pv 7g7f P*5e string hello world", Color::Black).unwrap();
+    /// assert_eq!(info.depth, Some(3));
+    /// assert_eq!(info.score, Some(Score::Cp(-50)));
+    /// assert_eq!(info.score_bound, Some(ScoreBound::Lower));
+    /// assert_eq!(info.pv[1], Move::Drop { piece: Piece::W_P, to: Square::SQ_5E });
+    /// assert_eq!(info.string.as_deref(), Some("hello world"));
+    /// ```
+    pub fn from_usi(args: &str, side: Color) -> Option<Self> {
+        let mut result = Self::default();
+        let mut rest = args;
+        while let Some(token) = next_token(&mut rest) {
+            match token {
+                "depth" => result.depth = Some(next_number(&mut rest)?),
+                "seldepth" => result.seldepth = Some(next_number(&mut rest)?),
+                "time" => result.time = Some(next_number(&mut rest)?),
+                "nodes" => result.nodes = Some(next_number(&mut rest)?),
+                "nps" => result.nps = Some(next_number(&mut rest)?),
+                "hashfull" => result.hashfull = Some(next_number(&mut rest)?),
+                "multipv" => result.multipv = Some(next_number(&mut rest)?),
+                "score" => {
+                    let kind = next_token(&mut rest)?;
+                    let value = next_token(&mut rest)?;
+                    result.score = Some(match (kind, value) {
+                        ("mate", "+") => Score::MateUnknown { winning: true },
+                        ("mate", "-") => Score::MateUnknown { winning: false },
+                        ("mate", value) => Score::Mate(value.parse().ok()?),
+                        ("cp", value) => Score::Cp(value.parse().ok()?),
+                        _ => return None,
+                    });
+                    let mut lookahead = rest;
+                    result.score_bound = match next_token(&mut lookahead) {
+                        Some("lowerbound") => Some(ScoreBound::Lower),
+                        Some("upperbound") => Some(ScoreBound::Upper),
+                        _ => None,
+                    };
+                    if result.score_bound.is_some() {
+                        rest = lookahead;
+                    }
+                }
+                "currmove" => {
+                    let mv = next_token(&mut rest)?;
+                    result.currmove = Some(Move::parse_usi_bytes(mv.as_bytes(), side)?);
+                }
+                "pv" => {
+                    let mut current = side;
+                    let mut lookahead = rest;
+                    while let Some(token) = next_token(&mut lookahead) {
+                        match Move::parse_usi_bytes(token.as_bytes(), current) {
+                            Some(mv) => result.pv.push(mv),
+                            None => break,
+                        }
+                        rest = lookahead;
+                        current = current.flip();
+                    }
+                }
+                "string" => {
+                    result.string = Some(rest.trim().into());
+                    break;
+                }
+                _ => return None,
+            }
+        }
+        Some(result)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl_ord_with_fields!(Info; depth, seldepth, time, nodes, nps, hashfull, multipv, score, score_bound, currmove, pv, string);
+#[cfg(feature = "alloc")]
+impl_hash_with_fields!(Info; depth, seldepth, time, nodes, nps, hashfull, multipv, score, score_bound, currmove, pv, string);
+
+/// The arguments of the `info` command.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Move, Square, ToUsi};
+/// # use shogi_core::usi::{Info, Score};
+/// let info = Info {
+///     depth: Some(1),
+///     score: Some(Score::Mate(3)),
+///     pv: vec![Move::Normal { from: Square::SQ_2C, to: Square::SQ_2B, promote: true }],
+///     ..Info::default()
+/// };
+/// assert_eq!(info.to_usi_owned(), "depth 1 score mate 3 pv 2c2b+");
+/// ```
+#[cfg(feature = "alloc")]
+impl ToUsi for Info {
+    fn to_usi<W: Write>(&self, sink: &mut W) -> FmtResult {
+        let mut fields = String::new();
+        write_field(&mut fields, "depth", self.depth, write_u32)?;
+        write_field(&mut fields, "seldepth", self.seldepth, write_u32)?;
+        write_field(&mut fields, "time", self.time, write_u64)?;
+        write_field(&mut fields, "nodes", self.nodes, write_u64)?;
+        write_field(&mut fields, "nps", self.nps, write_u64)?;
+        write_field(&mut fields, "hashfull", self.hashfull, write_u32)?;
+        write_field(&mut fields, "multipv", self.multipv, write_u32)?;
+        if let Some(score) = self.score {
+            fields.push_str(" score ");
+            score.to_usi(&mut fields)?;
+            match self.score_bound {
+                Some(ScoreBound::Lower) => fields.push_str(" lowerbound"),
+                Some(ScoreBound::Upper) => fields.push_str(" upperbound"),
+                None => {}
+            }
+        }
+        if let Some(mv) = self.currmove {
+            fields.push_str(" currmove ");
+            mv.to_usi(&mut fields)?;
+        }
+        if !self.pv.is_empty() {
+            fields.push_str(" pv");
+            for mv in &self.pv {
+                fields.push(' ');
+                mv.to_usi(&mut fields)?;
+            }
+        }
+        if let Some(string) = &self.string {
+            fields.push_str(" string ");
+            fields.push_str(string);
+        }
+        sink.write_str(fields.strip_prefix(' ').unwrap_or(&fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_over_round_trips() {
+        for game_over in [GameOver::Win, GameOver::Lose, GameOver::Draw] {
+            assert_eq!(
+                GameOver::from_usi(&game_over.to_usi_owned()),
+                Some(game_over)
+            );
+            for color in Color::all() {
+                let resolution = game_over.to_resolution(color);
+                assert_eq!(
+                    GameOver::from_resolution(resolution, color),
+                    Some(game_over)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn go_params_round_trip() {
+        let params = GoParams {
+            ponder: true,
+            btime: Some(1),
+            wtime: Some(2),
+            byoyomi: Some(3),
+            binc: Some(4),
+            winc: Some(5),
+            depth: Some(6),
+            nodes: Some(7),
+            mate: Some(MateLimit::Infinite),
+            infinite: true,
+        };
+        assert_eq!(
+            params.to_usi_owned(),
+            "ponder btime 1 wtime 2 byoyomi 3 binc 4 winc 5 depth 6 nodes 7 mate infinite infinite",
+        );
+        assert_eq!(GoParams::from_usi(&params.to_usi_owned()), Some(params));
+        assert_eq!(GoParams::default().to_usi_owned(), "");
+    }
+
+    #[test]
+    fn info_round_trip() {
+        let args = "depth 12 seldepth 20 time 1000 nodes 123456 nps 123456 hashfull 10 multipv 2 \
+                    score mate - upperbound currmove 5e5d pv 5e5d 4a5b string pv 5e5d";
+        let info = Info::from_usi(args, Color::White).unwrap();
+        assert_eq!(info.score, Some(Score::MateUnknown { winning: false }));
+        assert_eq!(info.score_bound, Some(ScoreBound::Upper));
+        assert_eq!(info.pv.len(), 2);
+        assert_eq!(info.string.as_deref(), Some("pv 5e5d"));
+        let formatted = info.to_usi_owned();
+        assert_eq!(Info::from_usi(&formatted, Color::White), Some(info));
+        assert_eq!(Info::from_usi("score cp", Color::White), None);
+        assert_eq!(Info::from_usi("unknown 1", Color::White), None);
+    }
+
+    #[test]
+    fn from_usi_position_works() {
+        let position =
+            Position::from_usi_position(" position  startpos moves 7g7f 3c3d 8h2b+ ").unwrap();
+        assert_eq!(
+            position.to_sfen_owned(),
+            "lnsgkgsnl/1r5+B1/pppppp1pp/6p2/9/2P6/PP1PPPPPP/7R1/LNSGKGSNL w B 4",
+        );
+        let position = Position::from_usi_position("sfen 8k/9/9/9/9/9/9/9/K8 b - 1").unwrap();
+        assert_eq!(position.moves(), []);
+        assert_eq!(
+            Position::from_usi_position("position sfen 8k/9/9/9/9/9/9/9/K8 b - moves 9i9h"),
+            Position::from_usi_position("sfen 8k/9/9/9/9/9/9/9/K8 b - 1 moves 9i9h"),
+        );
+        for (command, error) in [
+            ("", PositionCommandError::Syntax),
+            ("position startpos 7g7f", PositionCommandError::Syntax),
+            (
+                "position sfen 9/9/9/9/9/9/9/9 b - 1",
+                PositionCommandError::Syntax,
+            ),
+            ("position position startpos", PositionCommandError::Syntax),
+            (
+                "position startpos moves 7g7f 3c3d 7f7e 3d3",
+                PositionCommandError::Move { index: 3 },
+            ),
+            (
+                "position startpos moves 7g7f P*5e",
+                PositionCommandError::Move { index: 1 },
+            ),
+        ] {
+            assert_eq!(
+                Position::from_usi_position(command),
+                Err(error),
+                "{}",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn position_command_rejects_invalid() {
+        for args in [
+            "",
+            "startpos 7g7f",
+            "startpos moves 7g7",
+            "sfen 9/9/9/9/9/9/9/9/9 b",
+            "sfen 9/9/9/9/9/9/9/9/9 b - 1 2",
+        ] {
+            assert_eq!(PositionCommand::from_usi(args), None, "{}", args);
+        }
+        let command = PositionCommand::from_usi("sfen 9/9/9/9/9/9/9/9/9 b - moves").unwrap();
+        assert_eq!(command.initial.ply(), 1);
+        assert!(command.moves.is_empty());
+    }
+}
@@ -0,0 +1,247 @@
+//! Wrappers of the main types for JavaScript, generated with [`wasm_bindgen`](mod@wasm_bindgen).
+//!
+//! Colors, squares, pieces and moves are passed to and from JavaScript in USI format (e.g. `"b"`, `"7g"`, `"+p"`, `"8h2b+"`),
+//! so that browser-based boards do not need to know the internal representations.
+
+use alloc::string::String;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{Color, Move, PartialPosition, Piece, Square, ToUsi};
+
+/// A wrapper of [`Square`], exported to JavaScript as `Square`.
+#[wasm_bindgen(js_name = Square)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct WasmSquare(Square);
+
+#[wasm_bindgen(js_class = Square)]
+impl WasmSquare {
+    /// Creates a square from its file and rank. Returns `undefined` if they are out of range.
+    #[wasm_bindgen(js_name = fromFileRank)]
+    pub fn from_file_rank(file: u8, rank: u8) -> Option<WasmSquare> {
+        Square::new(file, rank).map(Self)
+    }
+
+    /// Parses a square in USI format, e.g. `"7g"`. Returns `undefined` on failure.
+    #[wasm_bindgen(js_name = fromUsi)]
+    pub fn from_usi(s: &str) -> Option<WasmSquare> {
+        Square::parse_usi_bytes(s.as_bytes()).map(Self)
+    }
+
+    /// Returns the USI representation of `self`.
+    #[wasm_bindgen(js_name = toUsi)]
+    pub fn to_usi(&self) -> String {
+        self.0.to_usi_owned()
+    }
+
+    /// Returns the file of `self`, in `1..=9`.
+    #[wasm_bindgen(getter)]
+    pub fn file(&self) -> u8 {
+        self.0.file()
+    }
+
+    /// Returns the rank of `self`, in `1..=9`.
+    #[wasm_bindgen(getter)]
+    pub fn rank(&self) -> u8 {
+        self.0.rank()
+    }
+
+    /// Returns the index of `self`, in `1..=81`. See [`Square::index`].
+    #[wasm_bindgen(getter)]
+    pub fn index(&self) -> u8 {
+        self.0.index()
+    }
+}
+
+/// A wrapper of [`Piece`], exported to JavaScript as `Piece`.
+#[wasm_bindgen(js_name = Piece)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct WasmPiece(Piece);
+
+#[wasm_bindgen(js_class = Piece)]
+impl WasmPiece {
+    /// Parses a piece in USI format, e.g. `"P"` or `"+r"`. Returns `undefined` on failure.
+    #[wasm_bindgen(js_name = fromUsi)]
+    pub fn from_usi(s: &str) -> Option<WasmPiece> {
+        Piece::parse_usi_bytes(s.as_bytes()).map(Self)
+    }
+
+    /// Returns the USI representation of `self`.
+    #[wasm_bindgen(js_name = toUsi)]
+    pub fn to_usi(&self) -> String {
+        self.0.to_usi_owned()
+    }
+
+    /// Returns the owner of `self`, either `"b"` or `"w"`.
+    #[wasm_bindgen(getter)]
+    pub fn color(&self) -> String {
+        self.0.color().to_usi_owned()
+    }
+}
+
+/// A wrapper of [`Move`], exported to JavaScript as `Move`.
+#[wasm_bindgen(js_name = Move)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct WasmMove(Move);
+
+#[wasm_bindgen(js_class = Move)]
+impl WasmMove {
+    /// Parses a move in USI format, e.g. `"7g7f"` or `"S*5b"`.
+    /// `side` (`"b"` or `"w"`) is the player who makes the move.
+    /// Returns `undefined` on failure.
+    #[wasm_bindgen(js_name = fromUsi)]
+    pub fn from_usi(s: &str, side: &str) -> Option<WasmMove> {
+        Move::parse_usi_bytes(s.as_bytes(), side.parse::<Color>().ok()?).map(Self)
+    }
+
+    /// Returns the USI representation of `self`.
+    #[wasm_bindgen(js_name = toUsi)]
+    pub fn to_usi(&self) -> String {
+        self.0.to_usi_owned()
+    }
+
+    /// Returns the source square, or `undefined` for a drop move.
+    #[wasm_bindgen(getter)]
+    pub fn from(&self) -> Option<WasmSquare> {
+        self.0.from().map(WasmSquare)
+    }
+
+    /// Returns the destination square.
+    #[wasm_bindgen(getter)]
+    pub fn to(&self) -> WasmSquare {
+        WasmSquare(self.0.to())
+    }
+
+    /// Returns whether `self` is a drop move.
+    #[wasm_bindgen(getter, js_name = isDrop)]
+    pub fn is_drop(&self) -> bool {
+        self.0.is_drop()
+    }
+
+    /// Returns whether `self` promotes a piece.
+    #[wasm_bindgen(getter, js_name = isPromoting)]
+    pub fn is_promoting(&self) -> bool {
+        self.0.is_promoting()
+    }
+}
+
+/// A wrapper of [`PartialPosition`], exported to JavaScript as `Position`.
+#[wasm_bindgen(js_name = Position)]
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct WasmPosition(PartialPosition);
+
+#[wasm_bindgen(js_class = Position)]
+impl WasmPosition {
+    /// Creates the starting position of shogi.
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> WasmPosition {
+        Self(PartialPosition::startpos())
+    }
+
+    /// Parses a position in SFEN format, e.g. `"lnsgkgsnl/1r5b1/ppppppppp/9/9/9/PPPPPPPPP/1B5R1/LNSGKGSNL b - 1"`.
+    /// Returns `undefined` on failure.
+    #[wasm_bindgen(js_name = fromSfen)]
+    pub fn from_sfen(s: &str) -> Option<WasmPosition> {
+        PartialPosition::from_sfen(s).map(Self)
+    }
+
+    /// Returns the SFEN representation of `self`.
+    #[wasm_bindgen(js_name = toSfen)]
+    pub fn to_sfen(&self) -> String {
+        self.0.to_sfen_owned()
+    }
+
+    /// Returns the player to move, either `"b"` or `"w"`.
+    #[wasm_bindgen(getter, js_name = sideToMove)]
+    pub fn side_to_move(&self) -> String {
+        self.0.side_to_move().to_usi_owned()
+    }
+
+    /// Returns the ply number. The starting position has ply 1.
+    #[wasm_bindgen(getter)]
+    pub fn ply(&self) -> u16 {
+        self.0.ply()
+    }
+
+    /// Returns the piece on `square`, or `undefined` if it is vacant.
+    #[wasm_bindgen(js_name = pieceAt)]
+    pub fn piece_at(&self, square: &WasmSquare) -> Option<WasmPiece> {
+        self.0.piece_at(square.0).map(WasmPiece)
+    }
+
+    /// Returns how many pieces of kind `piece` (e.g. `"P"` or `"p"`) are in hand.
+    /// Returns `undefined` if `piece` cannot be in hand.
+    #[wasm_bindgen(js_name = handCount)]
+    pub fn hand_count(&self, piece: &WasmPiece) -> Option<u8> {
+        self.0.hand(piece.0)
+    }
+
+    /// Returns the last move, or `undefined` if no moves were made.
+    #[wasm_bindgen(getter, js_name = lastMove)]
+    pub fn last_move(&self) -> Option<WasmMove> {
+        self.0.last_move().map(WasmMove)
+    }
+
+    /// Makes a move. Legality is not checked; see [`PartialPosition::make_move`].
+    ///
+    /// Returns `false` and leaves `self` unchanged if the move does not make sense.
+    #[wasm_bindgen(js_name = makeMove)]
+    pub fn make_move(&mut self, mv: &WasmMove) -> bool {
+        self.0.make_move(mv.0).is_some()
+    }
+}
+
+impl From<PartialPosition> for WasmPosition {
+    fn from(position: PartialPosition) -> Self {
+        Self(position)
+    }
+}
+
+impl From<WasmPosition> for PartialPosition {
+    fn from(position: WasmPosition) -> Self {
+        position.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrappers_work() {
+        let sq77 = WasmSquare::from_usi("7g").unwrap();
+        assert_eq!((sq77.file(), sq77.rank()), (7, 7));
+        assert_eq!(WasmSquare::from_file_rank(7, 7), Some(sq77));
+        assert_eq!(WasmSquare::from_file_rank(0, 7), None);
+
+        let mut position = WasmPosition::new();
+        assert_eq!(position.side_to_move(), "b");
+        let pawn = position.piece_at(&sq77).unwrap();
+        assert_eq!(pawn.to_usi(), "P");
+        assert_eq!(pawn.color(), "b");
+
+        let mv = WasmMove::from_usi("7g7f", "b").unwrap();
+        assert!(position.make_move(&mv));
+        assert_eq!(position.piece_at(&sq77), None);
+        assert_eq!(position.last_move().unwrap().to_usi(), "7g7f");
+        assert_eq!(position.side_to_move(), "w");
+        assert_eq!(
+            position.to_sfen(),
+            "lnsgkgsnl/1r5b1/ppppppppp/9/9/2P6/PP1PPPPPP/1B5R1/LNSGKGSNL w - 2",
+        );
+        assert!(!position.make_move(&mv));
+        assert_eq!(
+            position.hand_count(&WasmPiece::from_usi("p").unwrap()),
+            Some(0)
+        );
+
+        let sfen = position.to_sfen();
+        assert_eq!(WasmPosition::from_sfen(&sfen).unwrap().to_sfen(), sfen);
+        assert_eq!(WasmPosition::from_sfen("9/9/9 b - 1"), None);
+
+        let drop = WasmMove::from_usi("S*5b", "w").unwrap();
+        assert!(drop.is_drop());
+        assert_eq!(drop.from(), None);
+        assert_eq!(WasmMove::from_usi("S*5b", "x"), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! Western notation of moves, as used in English-language books (e.g. `P-7f`, `Bx2b+`, `S*5b`).
+
+use core::fmt::{Error as FmtError, Result as FmtResult, Write};
+
+use crate::{Bitboard, Move, PartialPosition, PieceKind, ToUsi};
+
+// The name of a piece kind in Western notation.
+fn piece_kind_name(piece_kind: PieceKind) -> &'static str {
+    match piece_kind {
+        PieceKind::Pawn => "P",
+        PieceKind::Lance => "L",
+        PieceKind::Knight => "N",
+        PieceKind::Silver => "S",
+        PieceKind::Gold => "G",
+        PieceKind::Bishop => "B",
+        PieceKind::Rook => "R",
+        PieceKind::King => "K",
+        PieceKind::ProPawn => "+P",
+        PieceKind::ProLance => "+L",
+        PieceKind::ProKnight => "+N",
+        PieceKind::ProSilver => "+S",
+        PieceKind::ProBishop => "+B",
+        PieceKind::ProRook => "+R",
+    }
+}
+
+impl PartialPosition {
+    /// Writes a move made in `self` in Western notation.
+    ///
+    /// A move is written as the moved piece, `-` for a move, `x` for a capture or `*` for a drop, and the destination square.
+    /// The source square follows the piece if another piece of the same kind can also move to the destination.
+    /// A promotion is marked with `+`, and a declined promotion with `=`.
+    /// Whether another piece can move to the destination is decided by [`PartialPosition::attacks_from`], so pins are not taken into account.
+    ///
+    /// Returns an error if the source square of `mv` is vacant.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Move, PartialPosition, Piece, Square};
+    /// let position = PartialPosition::startpos();
+    /// let mut s = String::new();
+    /// let mv = Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false };
+    /// position.write_move_western(mv, &mut s).unwrap();
+    /// assert_eq!(s, "P-7f");
+    /// let mut s = String::new();
+    /// // both golds can move to 5h
+    /// let mv = Move::Normal { from: Square::SQ_6I, to: Square::SQ_5H, promote: false };
+    /// position.write_move_western(mv, &mut s).unwrap();
+    /// assert_eq!(s, "G6i-5h");
+    /// ```
+    pub fn write_move_western<W: Write>(&self, mv: Move, sink: &mut W) -> FmtResult {
+        match mv {
+            Move::Normal { from, to, promote } => {
+                let piece = self.piece_at(from).ok_or(FmtError)?;
+                sink.write_str(piece_kind_name(piece.piece_kind()))?;
+                let ambiguous = (self.piece_bitboard(piece) & !Bitboard::single(from))
+                    .any(|other| self.attacks_from(other).contains(to));
+                if ambiguous {
+                    from.to_usi(sink)?;
+                }
+                let separator = if self.piece_at(to).is_some() {
+                    'x'
+                } else {
+                    '-'
+                };
+                sink.write_char(separator)?;
+                to.to_usi(sink)?;
+                let color = piece.color();
+                let in_zone = from.relative_rank(color) <= 3 || to.relative_rank(color) <= 3;
+                if promote {
+                    sink.write_char('+')?;
+                } else if piece.promote().is_some() && in_zone {
+                    sink.write_char('=')?;
+                }
+                Ok(())
+            }
+            Move::Drop { piece, to } => {
+                sink.write_str(piece_kind_name(piece.piece_kind()))?;
+                sink.write_char('*')?;
+                to.to_usi(sink)
+            }
+        }
+    }
+
+    /// Converts a move made in `self` to Western notation. See [`PartialPosition::write_move_western`].
+    ///
+    /// Returns [`None`] if the source square of `mv` is vacant.
+    ///
+    /// Examples:
+    /// ```
+    /// # use shogi_core::{Move, PartialPosition, Piece, Square};
+    /// let mut position = PartialPosition::startpos();
+    /// position.make_move(Move::Normal { from: Square::SQ_7G, to: Square::SQ_7F, promote: false }).unwrap();
+    /// position.make_move(Move::Normal { from: Square::SQ_3C, to: Square::SQ_3D, promote: false }).unwrap();
+    /// let mv = Move::Normal { from: Square::SQ_8H, to: Square::SQ_2B, promote: true };
+    /// assert_eq!(position.move_to_western_owned(mv), Some("Bx2b+".to_string()));
+    /// let mv = Move::Drop { piece: Piece::B_S, to: Square::SQ_5B };
+    /// assert_eq!(position.move_to_western_owned(mv), Some("S*5b".to_string()));
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn move_to_western_owned(&self, mv: Move) -> Option<alloc::string::String> {
+        let mut s = alloc::string::String::new();
+        self.write_move_western(mv, &mut s).ok()?;
+        Some(s)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::{Piece, Square};
+
+    #[test]
+    fn move_to_western_works() {
+        let mut position = PartialPosition::empty();
+        position.piece_set(Square::SQ_5A, Some(Piece::W_K));
+        position.piece_set(Square::SQ_5I, Some(Piece::B_K));
+        position.piece_set(Square::SQ_2D, Some(Piece::B_S));
+        position.piece_set(Square::SQ_4D, Some(Piece::B_S));
+        position.piece_set(Square::SQ_3C, Some(Piece::W_P));
+        position.piece_set(Square::SQ_1G, Some(Piece::B_PB));
+        position.piece_set(Square::SQ_9G, Some(Piece::B_P));
+        let western = |from, to, promote| {
+            position
+                .move_to_western_owned(Move::Normal { from, to, promote })
+                .unwrap()
+        };
+        // Both silvers can capture the pawn on 3c.
+        assert_eq!(western(Square::SQ_2D, Square::SQ_3C, true), "S2dx3c+");
+        assert_eq!(western(Square::SQ_4D, Square::SQ_3C, false), "S4dx3c=");
+        // Only the silver on 4d can move to 5c.
+        assert_eq!(western(Square::SQ_4D, Square::SQ_5C, false), "S-5c=");
+        assert_eq!(western(Square::SQ_4D, Square::SQ_3E, false), "S4d-3e");
+        // Promoted pieces and kings never promote.
+        assert_eq!(western(Square::SQ_1G, Square::SQ_3E, false), "+B-3e");
+        assert_eq!(western(Square::SQ_5I, Square::SQ_5H, false), "K-5h");
+        assert_eq!(western(Square::SQ_9G, Square::SQ_9F, false), "P-9f");
+        assert_eq!(
+            position.move_to_western_owned(Move::Drop {
+                piece: Piece::W_G,
+                to: Square::SQ_5E,
+            }),
+            Some("G*5e".into()),
+        );
+        // The source square is vacant.
+        assert_eq!(
+            position.move_to_western_owned(Move::Normal {
+                from: Square::SQ_5E,
+                to: Square::SQ_5D,
+                promote: false,
+            }),
+            None,
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Zobrist keys of positions.
+//!
+//! The keys returned by [`PartialPosition::zobrist_key`](crate::PartialPosition::zobrist_key) follow
+//! a fixed specification, so that they can be stored (e.g. in opening books or caches) and recomputed by other programs.
+//! The specification is identified by [`VERSION`], and keys never change unless it is incremented,
+//! which happens only in a breaking release.
+//!
+//! Version 1 is defined as follows. Let `splitmix64(n)` be the `n`-th output (0-indexed) of SplitMix64 seeded with [`SEED`],
+//! i.e., the output of the mixing function of SplitMix64 applied to `SEED + (n + 1) * 0x9e3779b97f4a7c15` (wrapping).
+//! - The key of a piece `p` on a square `sq` ([`piece_key`]) is `splitmix64(p.as_u8() * 81 + sq.array_index())`.
+//! - The key of a piece kind `k` in the hand of a player `c` ([`hand_key`]) is `splitmix64(32 * 81 + c.array_index() * 7 + k.array_index())`.
+//! - [`SIDE_KEY`] is `splitmix64(32 * 81 + 14)`.
+//!
+//! The key of a position is the XOR of the keys of all pieces on the board, [`SIDE_KEY`] if white is to move,
+//! and the wrapping sum of the keys of pieces in hand, each multiplied by its count.
+//! Because pieces in hand are hashed additively, a hand with `n` pawns contributes `n` times the key of a pawn.
+//! The ply does not affect the key.
+//!
+//! Examples:
+//! ```
+//! # use shogi_core::{PartialPosition, zobrist};
+//! assert_eq!(zobrist::VERSION, 1);
+//! assert_eq!(PartialPosition::startpos().zobrist_key(), 0x581d_bd65_e263_5aac);
+//! ```
+//!
+//! Since: 0.1.6
+
+use crate::{Color, Hand, Piece, PieceKind, Square};
+
+// The constants below are not a part of the C API. The attributes keep them out of the C header,
+// because the header is generated with `c-exports` enabled.
+
+/// The version of the specification of keys.
+///
+/// Since: 0.1.6
+#[cfg_attr(feature = "c-exports", doc = "cbindgen:ignore")]
+pub const VERSION: u32 = 1;
+
+/// The seed of SplitMix64 from which all keys are generated.
+///
+/// Since: 0.1.6
+#[cfg_attr(feature = "c-exports", doc = "cbindgen:ignore")]
+pub const SEED: u64 = 0x5a0b_1c0e_5a0b_1c0e;
+
+// The `n`-th output of SplitMix64 seeded with `SEED`.
+const fn splitmix64(n: u64) -> u64 {
+    let mut z = SEED.wrapping_add(n.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// The key of a piece on a square, given the representation of the piece and the array index of the square.
+const fn board_key(piece: usize, square: usize) -> u64 {
+    splitmix64((piece * Square::NUM + square) as u64)
+}
+
+// Indexed by the representation of a piece and the array index of a square.
+static BOARD_KEYS: [[u64; Square::NUM]; 32] = {
+    let mut result = [[0; Square::NUM]; 32];
+    let mut piece = 0;
+    while piece < 32 {
+        let mut square = 0;
+        while square < Square::NUM {
+            result[piece][square] = board_key(piece, square);
+            square += 1;
+        }
+        piece += 1;
+    }
+    result
+};
+
+// Indexed by the array index of a color and the index of a piece kind in `Hand`.
+static HAND_KEYS: [[u64; Hand::NUM_HAND_PIECES]; Color::NUM] = {
+    let mut result = [[0; Hand::NUM_HAND_PIECES]; Color::NUM];
+    let offset = 32 * Square::NUM;
+    let mut color = 0;
+    while color < Color::NUM {
+        let mut piece_kind = 0;
+        while piece_kind < Hand::NUM_HAND_PIECES {
+            let index = offset + color * Hand::NUM_HAND_PIECES + piece_kind;
+            result[color][piece_kind] = splitmix64(index as u64);
+            piece_kind += 1;
+        }
+        color += 1;
+    }
+    result
+};
+
+/// The key XORed when white is to move.
+///
+/// Since: 0.1.6
+#[cfg_attr(feature = "c-exports", doc = "cbindgen:ignore")]
+pub const SIDE_KEY: u64 =
+    splitmix64((32 * Square::NUM + Color::NUM * Hand::NUM_HAND_PIECES) as u64);
+
+/// The key of `piece` on `square`.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Piece, Square, zobrist};
+/// assert_ne!(zobrist::piece_key(Piece::B_P, Square::SQ_7G), zobrist::piece_key(Piece::W_P, Square::SQ_7G));
+/// ```
+///
+/// Since: 0.1.6
+#[inline(always)]
+pub fn piece_key(piece: Piece, square: Square) -> u64 {
+    // Safety: piece.as_u8() < 32 and square.array_index() < 81
+    *unsafe {
+        crate::common::get_unchecked(
+            crate::common::get_unchecked(&BOARD_KEYS, piece.as_u8() as usize),
+            square.array_index(),
+        )
+    }
+}
+
+/// The key of `piece` on `square`, computed without the table.
+///
+/// Statics cannot be read in `const` contexts, so `const` functions use this instead of [`piece_key`].
+#[inline(always)]
+pub(crate) const fn piece_key_const(piece: Piece, square: Square) -> u64 {
+    board_key(piece.as_u8() as usize, square.array_index())
+}
+
+/// The key of a piece of `piece_kind` in the hand of `color`.
+/// Returns [`None`] if `piece_kind` cannot be in hand.
+///
+/// Examples:
+/// ```
+/// # use shogi_core::{Color, PieceKind, zobrist};
+/// assert!(zobrist::hand_key(Color::Black, PieceKind::Pawn).is_some());
+/// assert_eq!(zobrist::hand_key(Color::Black, PieceKind::King), None);
+/// ```
+///
+/// Since: 0.1.6
+pub fn hand_key(color: Color, piece_kind: PieceKind) -> Option<u64> {
+    if !Hand::is_hand_piece(piece_kind) {
+        return None;
+    }
+    HAND_KEYS[color.array_index()]
+        .get(piece_kind.array_index())
+        .copied()
+}
+
+/// The key of the hands of both players.
+pub(crate) fn hands_key(hands: [Hand; 2]) -> u64 {
+    let mut result = 0u64;
+    for (hand, keys) in hands.iter().zip(HAND_KEYS.iter()) {
+        for (piece_kind, &key) in Hand::all_hand_pieces().zip(keys.iter()) {
+            // Safety: `piece_kind` is a valid piece in hand
+            let count = unsafe { crate::common::unwrap_unchecked(hand.count(piece_kind)) };
+            result = result.wrapping_add(key.wrapping_mul(count as u64));
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Keys must not change unless `VERSION` is incremented.
+    #[test]
+    fn keys_are_stable() {
+        assert_eq!(splitmix64(0), 0x32be_0633_ce6c_961d);
+        assert_eq!(piece_key(Piece::B_P, Square::SQ_1A), 0x7693_04d1_1ca4_df5d);
+        assert_eq!(piece_key(Piece::W_PR, Square::SQ_9I), 0xbad7_f69b_e9f6_e1c7);
+        assert_eq!(
+            hand_key(Color::Black, PieceKind::Pawn),
+            Some(0x6d7f_ce6e_49ed_5bdf)
+        );
+        assert_eq!(
+            hand_key(Color::White, PieceKind::Rook),
+            Some(0xf07a_c49e_c84d_6ee9)
+        );
+        assert_eq!(SIDE_KEY, 0x0805_c793_9fba_c362);
+    }
+}
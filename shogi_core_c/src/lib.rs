@@ -12,3 +12,34 @@ mod no_std;
 
 #[doc(hidden)]
 pub use shogi_core::*;
+
+/// The version of the C ABI of this library.
+///
+/// It is incremented whenever a change that breaks existing C callers is made,
+/// e.g. a change of the layout of an exported type or the signature of an exported function.
+/// Consumers that load this library dynamically should check it before calling other functions.
+#[no_mangle]
+pub static SHOGI_CORE_ABI_VERSION: u32 = 1;
+
+const VERSION_LEN: usize = shogi_core::VERSION.len() + 1;
+
+static VERSION_NUL_TERMINATED: [u8; VERSION_LEN] = nul_terminated(shogi_core::VERSION);
+
+const fn nul_terminated(s: &str) -> [u8; VERSION_LEN] {
+    let bytes = s.as_bytes();
+    let mut result = [0; VERSION_LEN];
+    let mut i = 0;
+    while i < bytes.len() {
+        result[i] = bytes[i];
+        i += 1;
+    }
+    result
+}
+
+/// Returns the version of `shogi_core` this library is built from (e.g. `"0.1.5"`) as a nul-terminated string.
+///
+/// The returned pointer points to a static buffer and must not be freed.
+#[no_mangle]
+pub extern "C" fn shogi_core_version() -> *const c_compat::c_char {
+    VERSION_NUL_TERMINATED.as_ptr().cast()
+}
@@ -1,5 +1,7 @@
 // unoptimized version of memory-related functions
+// They are declared in <string.h>, so they are marked with `cbindgen:ignore` to be kept out of the header.
 
+/// cbindgen:ignore
 #[no_mangle]
 pub unsafe extern "C" fn memcpy(dest: *mut u8, src: *const u8, n: usize) -> *mut u8 {
     for i in 0..n {
@@ -9,6 +11,7 @@ pub unsafe extern "C" fn memcpy(dest: *mut u8, src: *const u8, n: usize) -> *mut
     dest
 }
 
+/// cbindgen:ignore
 #[no_mangle]
 pub unsafe extern "C" fn memset(b: *mut u8, c: i32, n: usize) -> *mut u8 {
     for i in 0..n {
@@ -17,6 +20,7 @@ pub unsafe extern "C" fn memset(b: *mut u8, c: i32, n: usize) -> *mut u8 {
     b
 }
 
+/// cbindgen:ignore
 #[no_mangle]
 pub unsafe extern "C" fn memcmp(s1: *const u8, s2: *const u8, n: usize) -> i32 {
     for i in 0..n {
@@ -31,6 +35,7 @@ pub unsafe extern "C" fn memcmp(s1: *const u8, s2: *const u8, n: usize) -> i32 {
 
 // Found in https://refspecs.linuxfoundation.org/LSB_1.1.0/gLSB/baselib---bzero-1.html.
 // Some targets (cdylib, rustc 1.60, x86_64-apple-darwin, LLVM version 3.9.0svn) might need this symbol.
+/// cbindgen:ignore
 #[no_mangle]
 pub unsafe extern "C" fn __bzero(s: *mut u8, n: usize) {
     // Really want to suppress all optimizations.
@@ -57,18 +62,40 @@ fn error_handler(_: core::alloc::Layout) -> ! {
 
 // SimpleAllocator found in https://doc.rust-lang.org/core/alloc/trait.GlobalAlloc.html.
 // Modified so that ARENA_BSS is on .bss section and SimpleAllocator doesn't take much space.
-#[cfg(feature = "alloc")]
+// The arena size can be set at compile time with the environment variable SHOGI_CORE_C_ARENA_SIZE (in bytes).
+#[cfg(all(feature = "alloc", not(feature = "external-allocator")))]
 mod simple_alloc {
     use core::alloc::{GlobalAlloc, Layout};
     use core::cell::UnsafeCell;
     use core::ptr::null_mut;
     use core::sync::atomic::{AtomicUsize, Ordering::SeqCst};
-    const ARENA_SIZE: usize = 128 * 1024;
+    const ARENA_SIZE: usize = match option_env!("SHOGI_CORE_C_ARENA_SIZE") {
+        Some(size) => parse_size(size),
+        None => 128 * 1024,
+    };
     const MAX_SUPPORTED_ALIGN: usize = 4096;
     struct SimpleAllocator {
         remaining: AtomicUsize, // we allocate from the top, counting down
     }
 
+    const fn parse_size(s: &str) -> usize {
+        let bytes = s.as_bytes();
+        if bytes.is_empty() {
+            panic!("SHOGI_CORE_C_ARENA_SIZE must not be empty");
+        }
+        let mut result: usize = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            let digit = bytes[i];
+            if !digit.is_ascii_digit() {
+                panic!("SHOGI_CORE_C_ARENA_SIZE must be a decimal number");
+            }
+            result = result * 10 + (digit - b'0') as usize;
+            i += 1;
+        }
+        result
+    }
+
     static mut ARENA_BSS: UnsafeCell<[u8; ARENA_SIZE]> = UnsafeCell::new([0x00; ARENA_SIZE]);
 
     #[global_allocator]
@@ -109,6 +136,53 @@ mod simple_alloc {
             };
             (ARENA_BSS.get() as *mut u8).add(allocated)
         }
-        unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+        // Only the most recently allocated block is reclaimed, so that e.g. a temporary buffer can be reused.
+        // Other blocks are leaked until shogi_core_arena_reset is called.
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            let offset = ptr as usize - ARENA_BSS.get() as usize;
+            let _ = self
+                .remaining
+                .compare_exchange(offset, offset + layout.size(), SeqCst, SeqCst);
+        }
+    }
+
+    /// Returns how many bytes are left in the arena.
+    #[no_mangle]
+    pub extern "C" fn shogi_core_arena_remaining() -> usize {
+        ALLOCATOR.remaining.load(SeqCst)
+    }
+
+    /// Frees everything allocated in the arena at once.
+    ///
+    /// # Safety
+    /// Every object allocated by this library (e.g. `Position`s) must not be used after calling this function.
+    #[no_mangle]
+    pub unsafe extern "C" fn shogi_core_arena_reset() {
+        ALLOCATOR.remaining.store(ARENA_SIZE, SeqCst);
+    }
+}
+
+// An allocator that delegates to functions defined by the user of this library.
+#[cfg(all(feature = "alloc", feature = "external-allocator"))]
+mod external_alloc {
+    use core::alloc::{GlobalAlloc, Layout};
+
+    extern "C" {
+        fn shogi_core_alloc(size: usize, align: usize) -> *mut u8;
+        fn shogi_core_dealloc(ptr: *mut u8, size: usize, align: usize);
+    }
+
+    struct ExternalAllocator;
+
+    #[global_allocator]
+    static ALLOCATOR: ExternalAllocator = ExternalAllocator;
+
+    unsafe impl GlobalAlloc for ExternalAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            shogi_core_alloc(layout.size(), layout.align())
+        }
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            shogi_core_dealloc(ptr, layout.size(), layout.align())
+        }
     }
 }
@@ -0,0 +1,112 @@
+//! Development tasks, run with `cargo xtask <task>` in the repository.
+//!
+//! Tasks:
+//! - `header`: generates `include/shogi_core.h` from the sources of `shogi_core` and `shogi_core_c`.
+//! - `header --check`: checks that `include/shogi_core.h` is up to date without writing it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args[..] {
+        ["header"] => {
+            let header = generate_header();
+            fs::write(header_path(), header).unwrap();
+            ExitCode::SUCCESS
+        }
+        ["header", "--check"] => {
+            let header = generate_header();
+            let path = header_path();
+            if fs::read(&path).ok().as_deref() == Some(&header[..]) {
+                ExitCode::SUCCESS
+            } else {
+                eprintln!(
+                    "{} is out of date. Run `make include` and commit the result.",
+                    path.display(),
+                );
+                ExitCode::FAILURE
+            }
+        }
+        _ => {
+            eprintln!("usage: cargo xtask header [--check]");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+fn header_path() -> PathBuf {
+    root().join("include").join("shogi_core.h")
+}
+
+// Runs cbindgen on the sources of `shogi_core` and `shogi_core_c` and returns the content of the header.
+fn generate_header() -> Vec<u8> {
+    let root = root();
+    let core_src = root.join("shogi_core").join("src");
+
+    // cbindgen does not understand `#[cfg_attr(feature = "c-exports", no_mangle)]`,
+    // so we feed it a copy of the sources where the attributes are unconditional.
+    let copied_src = root.join("target").join("xtask").join("shogi_core_src");
+    if copied_src.exists() {
+        fs::remove_dir_all(&copied_src).unwrap();
+    }
+    copy_unconditionally_exported(&core_src, &copied_src);
+
+    let config = cbindgen::Config::from_file(root.join("cbindgen.toml")).unwrap();
+    let mut header = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(copied_src.join("lib.rs"))
+        .with_src(root.join("shogi_core_c").join("src").join("lib.rs"))
+        .generate()
+        .expect("failed to generate bindings")
+        .write(&mut header);
+    header
+}
+
+fn copy_unconditionally_exported(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let path = entry.unwrap().path();
+        let dest = to.join(path.file_name().unwrap());
+        if path.is_dir() {
+            copy_unconditionally_exported(&path, &dest);
+        } else {
+            let content = fs::read_to_string(&path).unwrap();
+            fs::write(dest, make_exports_unconditional(&content)).unwrap();
+        }
+    }
+}
+
+// Rewrites `#[cfg_attr(feature = "c-exports", attr)]` to `#[attr]`.
+fn make_exports_unconditional(content: &str) -> String {
+    const START: &str = "#[cfg_attr(";
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find(START) {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + START.len()..];
+        let end = after.find(")]").unwrap();
+        let args = after[..end].trim();
+        match args.strip_prefix("feature = \"c-exports\",") {
+            Some(attr) => {
+                result.push_str("#[");
+                result.push_str(attr.trim().trim_end_matches(','));
+                result.push(']');
+            }
+            None => result.push_str(&rest[start..start + START.len() + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    result.push_str(rest);
+    result
+}